@@ -5,9 +5,21 @@ use super::{Element, ViewLimits, FocusRequest, FULL_EXTENT};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
-use crate::view::{MouseButton, KeyInfo, TextInfo, CursorTracking};
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
+
+/// Returns the start and end of a span of `size` placed `align` of the
+/// way along the free space in `extent`, starting at `start`.
+///
+/// A span larger than the extent starts at `start`.
+fn aligned_span(start: f32, extent: f32, size: f32, align: f32) -> (f32, f32) {
+    let start = start + (extent - size).max(0.0) * align;
+    (start, start + size)
+}
 
 /// Horizontal alignment element.
+///
+/// The subject keeps its minimum width and is placed between the left
+/// (0.0) and right (1.0) edges, taking the full height.
 pub struct HAlign<S: Element> {
     subject: S,
     align: f32,
@@ -34,11 +46,12 @@ impl<S: Element> HAlign<S> {
         self.align = align.clamp(0.0, 1.0);
     }
 
-    fn prepare_bounds(&self, ctx: &Context) -> Rect {
-        // This would normally use ctx to get subject limits
-        let bounds = ctx.bounds;
-        // Simplified: just return bounds as-is
-        bounds
+    /// Returns the context for the subject, at its minimum width and
+    /// placed by the alignment.
+    fn subject_ctx<'a>(&self, ctx: &'a Context) -> Context<'a> {
+        let width = self.subject.limits(&BasicContext::new(ctx.view, ctx.canvas)).min.x;
+        let (left, right) = aligned_span(ctx.bounds.left, ctx.bounds.width(), width, self.align);
+        ctx.with_bounds(Rect::new(left, ctx.bounds.top, right, ctx.bounds.bottom))
     }
 }
 
@@ -52,15 +65,15 @@ impl<S: Element + 'static> Element for HAlign<S> {
     }
 
     fn draw(&self, ctx: &Context) {
-        self.subject.draw(ctx);
+        self.subject.draw(&self.subject_ctx(ctx));
     }
 
     fn layout(&mut self, ctx: &Context) {
-        self.subject.layout(ctx);
+        self.subject.layout(&self.subject_ctx(ctx));
     }
 
     fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
-        self.subject.hit_test(ctx, p, leaf, control)
+        self.subject.hit_test(&self.subject_ctx(ctx), p, leaf, control)
     }
 
     fn wants_control(&self) -> bool {
@@ -68,47 +81,65 @@ impl<S: Element + 'static> Element for HAlign<S> {
     }
 
     fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
-        self.subject.click(ctx, btn)
+        self.subject.click(&self.subject_ctx(ctx), btn)
     }
 
     fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
-        self.subject.handle_click(ctx, btn)
+        let subject_ctx = self.subject_ctx(ctx);
+        // Presses in the space around the subject don't reach it; releases
+        // do, so a press that started on it can finish
+        if btn.down && self.subject.hit_test(&subject_ctx, btn.pos, false, false).is_none() {
+            return false;
+        }
+        self.subject.handle_click(&subject_ctx, btn)
     }
 
     fn drag(&mut self, ctx: &Context, btn: MouseButton) {
-        self.subject.drag(ctx, btn);
+        self.subject.drag(&self.subject_ctx(ctx), btn);
     }
 
     fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
-        self.subject.key(ctx, k)
+        self.subject.key(&self.subject_ctx(ctx), k)
     }
 
     fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
-        self.subject.text(ctx, info)
+        self.subject.text(&self.subject_ctx(ctx), info)
     }
 
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
-        self.subject.cursor(ctx, p, status)
+        self.subject.cursor(&self.subject_ctx(ctx), p, status)
     }
 
     fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
-        self.subject.scroll(ctx, dir, p)
+        self.subject.scroll(&self.subject_ctx(ctx), dir, p)
     }
 
     fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
-        self.subject.handle_drag(ctx, btn);
+        self.subject.handle_drag(&self.subject_ctx(ctx), btn);
     }
 
     fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
-        self.subject.handle_key(ctx, k)
+        self.subject.handle_key(&self.subject_ctx(ctx), k)
     }
 
     fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
-        self.subject.handle_text(ctx, info)
+        self.subject.handle_text(&self.subject_ctx(ctx), info)
     }
 
     fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
-        self.subject.handle_scroll(ctx, dir, p)
+        self.subject.handle_scroll(&self.subject_ctx(ctx), dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(&self.subject_ctx(ctx), scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(&self.subject_ctx(ctx), info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(&self.subject_ctx(ctx), info)
     }
 
     fn is_enabled(&self) -> bool {
@@ -143,6 +174,37 @@ impl<S: Element + 'static> Element for HAlign<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(&self.subject_ctx(ctx), element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(&self.subject_ctx(ctx))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(&self.subject_ctx(ctx), p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -153,6 +215,9 @@ impl<S: Element + 'static> Element for HAlign<S> {
 }
 
 /// Vertical alignment element.
+///
+/// The subject keeps its minimum height and is placed between the top
+/// (0.0) and bottom (1.0) edges, taking the full width.
 pub struct VAlign<S: Element> {
     subject: S,
     align: f32,
@@ -178,6 +243,14 @@ impl<S: Element> VAlign<S> {
     pub fn set_align(&mut self, align: f32) {
         self.align = align.clamp(0.0, 1.0);
     }
+
+    /// Returns the context for the subject, at its minimum height and
+    /// placed by the alignment.
+    fn subject_ctx<'a>(&self, ctx: &'a Context) -> Context<'a> {
+        let height = self.subject.limits(&BasicContext::new(ctx.view, ctx.canvas)).min.y;
+        let (top, bottom) = aligned_span(ctx.bounds.top, ctx.bounds.height(), height, self.align);
+        ctx.with_bounds(Rect::new(ctx.bounds.left, top, ctx.bounds.right, bottom))
+    }
 }
 
 impl<S: Element + 'static> Element for VAlign<S> {
@@ -190,15 +263,15 @@ impl<S: Element + 'static> Element for VAlign<S> {
     }
 
     fn draw(&self, ctx: &Context) {
-        self.subject.draw(ctx);
+        self.subject.draw(&self.subject_ctx(ctx));
     }
 
     fn layout(&mut self, ctx: &Context) {
-        self.subject.layout(ctx);
+        self.subject.layout(&self.subject_ctx(ctx));
     }
 
     fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
-        self.subject.hit_test(ctx, p, leaf, control)
+        self.subject.hit_test(&self.subject_ctx(ctx), p, leaf, control)
     }
 
     fn wants_control(&self) -> bool {
@@ -206,47 +279,65 @@ impl<S: Element + 'static> Element for VAlign<S> {
     }
 
     fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
-        self.subject.click(ctx, btn)
+        self.subject.click(&self.subject_ctx(ctx), btn)
     }
 
     fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
-        self.subject.handle_click(ctx, btn)
+        let subject_ctx = self.subject_ctx(ctx);
+        // Presses in the space around the subject don't reach it; releases
+        // do, so a press that started on it can finish
+        if btn.down && self.subject.hit_test(&subject_ctx, btn.pos, false, false).is_none() {
+            return false;
+        }
+        self.subject.handle_click(&subject_ctx, btn)
     }
 
     fn drag(&mut self, ctx: &Context, btn: MouseButton) {
-        self.subject.drag(ctx, btn);
+        self.subject.drag(&self.subject_ctx(ctx), btn);
     }
 
     fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
-        self.subject.key(ctx, k)
+        self.subject.key(&self.subject_ctx(ctx), k)
     }
 
     fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
-        self.subject.text(ctx, info)
+        self.subject.text(&self.subject_ctx(ctx), info)
     }
 
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
-        self.subject.cursor(ctx, p, status)
+        self.subject.cursor(&self.subject_ctx(ctx), p, status)
     }
 
     fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
-        self.subject.scroll(ctx, dir, p)
+        self.subject.scroll(&self.subject_ctx(ctx), dir, p)
     }
 
     fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
-        self.subject.handle_drag(ctx, btn);
+        self.subject.handle_drag(&self.subject_ctx(ctx), btn);
     }
 
     fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
-        self.subject.handle_key(ctx, k)
+        self.subject.handle_key(&self.subject_ctx(ctx), k)
     }
 
     fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
-        self.subject.handle_text(ctx, info)
+        self.subject.handle_text(&self.subject_ctx(ctx), info)
     }
 
     fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
-        self.subject.handle_scroll(ctx, dir, p)
+        self.subject.handle_scroll(&self.subject_ctx(ctx), dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(&self.subject_ctx(ctx), scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(&self.subject_ctx(ctx), info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(&self.subject_ctx(ctx), info)
     }
 
     fn is_enabled(&self) -> bool {
@@ -281,6 +372,37 @@ impl<S: Element + 'static> Element for VAlign<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(&self.subject_ctx(ctx), element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(&self.subject_ctx(ctx))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(&self.subject_ctx(ctx), p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -332,6 +454,17 @@ pub fn align_bottom<S: Element>(subject: S) -> VAlign<S> {
     VAlign::new(1.0, subject)
 }
 
+/// Aligns an element `ax` of the way from left (0.0) to right (1.0) and
+/// `ay` of the way from top (0.0) to bottom (1.0), at its minimum size.
+pub fn align<S: Element>(ax: f32, ay: f32, subject: S) -> HAlign<VAlign<S>> {
+    halign(ax, valign(ay, subject))
+}
+
+/// Centers an element both horizontally and vertically.
+pub fn center<S: Element>(subject: S) -> HAlign<VAlign<S>> {
+    align(0.5, 0.5, subject)
+}
+
 /// Aligns an element both horizontally and vertically.
 pub fn align_left_top<S: Element>(subject: S) -> HAlign<VAlign<S>> {
     align_left(align_top(subject))
@@ -376,3 +509,35 @@ pub fn align_center_bottom<S: Element>(subject: S) -> HAlign<VAlign<S>> {
 pub fn align_right_bottom<S: Element>(subject: S) -> HAlign<VAlign<S>> {
     align_right(align_bottom(subject))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::element::share;
+    use crate::element::button::button;
+    use crate::element::size::fixed_size;
+    use crate::support::point::Extent;
+    use crate::view::TestView;
+
+    #[test]
+    fn test_fractional_alignment() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let sink = clicks.clone();
+        let ok = fixed_size(20.0, 10.0, button("OK").on_click(move || { sink.fetch_add(1, Ordering::SeqCst); }));
+        let aligned = align(0.3, 1.0, ok);
+        let view = TestView::new(share(aligned), Extent::new(100.0, 50.0));
+
+        // 30% of the 80 free pixels from the left, and at the bottom
+        view.click_at(Point::new(10.0, 45.0));
+        view.click_at(Point::new(34.0, 20.0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 0);
+        view.click_at(Point::new(34.0, 45.0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+
+        // Spans larger than the space start at the edge
+        assert_eq!(aligned_span(10.0, 50.0, 20.0, 0.5), (25.0, 45.0));
+        assert_eq!(aligned_span(10.0, 10.0, 20.0, 1.0), (10.0, 30.0));
+    }
+}
@@ -1,15 +1,15 @@
 //! Button elements for user interaction.
 
 use std::any::Any;
-use std::sync::RwLock;
-use super::{Element, ViewLimits};
+use std::sync::{Arc, RwLock};
+use super::{Element, ViewLimits, FocusRequest};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::canvas::CornerRadii;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, CursorTracking};
+use crate::support::canvas::{Canvas, CornerRadii};
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, CursorTracking, KeyInfo, KeyCode, KeyAction};
 
 /// Button state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -24,16 +24,72 @@ pub enum ButtonState {
 /// Callback type for button clicks.
 pub type ClickCallback = Box<dyn Fn() + Send + Sync>;
 
+/// Callback type for toggle buttons, called with the new state.
+pub type ToggleCallback = Box<dyn Fn(bool) + Send + Sync>;
+
+/// Function that draws a vector icon into a rect, in the given color.
+pub type IconPainter = Arc<dyn Fn(&mut Canvas, Rect, Color) + Send + Sync>;
+
+/// Space between a button's icon and its label.
+const ICON_GAP: f32 = 6.0;
+
+/// An icon shown on a button.
+#[derive(Clone)]
+pub enum ButtonIcon {
+    /// A vector icon, drawn in the button's text color.
+    Path(IconPainter),
+    /// An RGBA image, `width * height` pixels row by row.
+    Image {
+        data: Arc<[u8]>,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl ButtonIcon {
+    /// Creates a vector icon from a drawing function.
+    pub fn path<F: Fn(&mut Canvas, Rect, Color) + Send + Sync + 'static>(draw: F) -> Self {
+        ButtonIcon::Path(Arc::new(draw))
+    }
+
+    /// Creates an icon from RGBA image data.
+    pub fn image(data: impl Into<Arc<[u8]>>, width: u32, height: u32) -> Self {
+        ButtonIcon::Image { data: data.into(), width, height }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, bounds: Rect, color: Color) {
+        match self {
+            ButtonIcon::Path(draw) => draw(canvas, bounds, color),
+            ButtonIcon::Image { data, width, height } => canvas.draw_image(data, *width, *height, bounds),
+        }
+    }
+}
+
+/// Where a button's icon sits relative to its label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IconPosition {
+    #[default]
+    Left,
+    Right,
+    Top,
+}
+
 /// A basic button element.
 pub struct BasicButton {
     label: String,
     state: RwLock<ButtonState>,
-    body_color: Color,
-    text_color: Color,
+    body_color: ThemeColor,
+    text_color: ThemeColor,
     corner_radius: f32,
     enabled: bool,
     on_click: Option<ClickCallback>,
-    value: bool, // For toggle buttons
+    toggle: bool,
+    value: RwLock<bool>, // For toggle buttons
+    on_toggle: Option<ToggleCallback>,
+    focused: RwLock<bool>,
+    icon: Option<ButtonIcon>,
+    icon_position: IconPosition,
+    icon_size: f32,
 }
 
 impl BasicButton {
@@ -43,12 +99,18 @@ impl BasicButton {
         Self {
             label: label.into(),
             state: RwLock::new(ButtonState::Normal),
-            body_color: theme.default_button_color,
-            text_color: theme.label_font_color,
+            body_color: ThemeColor::Theme(|theme| theme.default_button_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
             corner_radius: theme.button_corner_radius,
             enabled: true,
             on_click: None,
-            value: false,
+            toggle: false,
+            value: RwLock::new(false),
+            on_toggle: None,
+            focused: RwLock::new(false),
+            icon: None,
+            icon_position: IconPosition::Left,
+            icon_size: theme.label_font_size * 1.2,
         }
     }
 
@@ -58,15 +120,28 @@ impl BasicButton {
         self
     }
 
+    /// Makes the button latch on and off with each click instead of
+    /// springing back.
+    pub fn toggle(mut self, toggle: bool) -> Self {
+        self.toggle = toggle;
+        self
+    }
+
+    /// Sets the callback fired when a toggle button is switched on or off.
+    pub fn on_toggle<F: Fn(bool) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_toggle = Some(Box::new(callback));
+        self
+    }
+
     /// Sets the body color.
     pub fn with_body_color(mut self, color: Color) -> Self {
-        self.body_color = color;
+        self.body_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn with_text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
@@ -76,6 +151,25 @@ impl BasicButton {
         self
     }
 
+    /// Sets the icon. A button with an icon and an empty label is drawn as
+    /// an icon-only square.
+    pub fn icon(mut self, icon: ButtonIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Sets where the icon sits relative to the label.
+    pub fn icon_position(mut self, position: IconPosition) -> Self {
+        self.icon_position = position;
+        self
+    }
+
+    /// Sets the icon size.
+    pub fn icon_size(mut self, size: f32) -> Self {
+        self.icon_size = size;
+        self
+    }
+
     /// Returns the label.
     pub fn label(&self) -> &str {
         &self.label
@@ -93,59 +187,162 @@ impl BasicButton {
 
     /// Returns whether the button is pressed (for toggle buttons).
     pub fn value(&self) -> bool {
-        self.value
+        self.is_on()
     }
 
     /// Sets the value (for toggle buttons).
     pub fn set_value(&mut self, value: bool) {
-        self.value = value;
+        self.set_on(value);
+    }
+
+    /// Returns whether a toggle button is latched on.
+    pub fn is_on(&self) -> bool {
+        *self.value.read().unwrap()
+    }
+
+    /// Latches a toggle button on or off, without firing `on_toggle`.
+    pub fn set_on(&self, on: bool) {
+        *self.value.write().unwrap() = on;
+    }
+
+    /// Fires the button: flips a toggle button, then calls the callbacks.
+    fn activate(&self) {
+        if self.toggle {
+            let on = !self.is_on();
+            self.set_on(on);
+            if let Some(ref callback) = self.on_toggle {
+                callback(on);
+            }
+        }
+        if let Some(ref callback) = self.on_click {
+            callback();
+        }
     }
 
     fn draw_background(&self, ctx: &Context) {
-        let state = *self.state.read().unwrap();
+        let mut state = *self.state.read().unwrap();
+        if self.toggle && self.is_on() && state != ButtonState::Disabled {
+            state = ButtonState::Pressed;
+        }
         let color = match state {
-            ButtonState::Normal => self.body_color,
-            ButtonState::Hover => self.body_color.level(1.2),
-            ButtonState::Pressed => self.body_color.level(0.8),
-            ButtonState::Disabled => self.body_color.with_alpha(0.5),
+            ButtonState::Normal => self.body_color.get(),
+            ButtonState::Hover => self.body_color.get().level(1.2),
+            ButtonState::Pressed => self.body_color.get().level(0.8),
+            ButtonState::Disabled => self.body_color.get().with_alpha(0.5),
         };
 
         let mut canvas = ctx.canvas.borrow_mut();
         canvas.fill_style(color);
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
+
+        // Focus ring
+        if *self.focused.read().unwrap() {
+            let theme = get_theme();
+            canvas.stroke_style(theme.frame_hilite_color);
+            canvas.line_width(2.0);
+            canvas.stroke_round_rect(ctx.bounds.inset(1.0, 1.0), self.corner_radius);
+        }
+    }
+
+    /// Returns the size of the label text, or zero if there is none.
+    fn label_size(&self) -> Point {
+        if self.label.is_empty() {
+            return Point::zero();
+        }
+        let theme = get_theme();
+        Point::new(self.label.len() as f32 * theme.label_font_size * 0.6, theme.label_font_size * 1.2)
+    }
+
+    /// Returns the size of the icon and label laid out together.
+    fn content_size(&self) -> Point {
+        let label = self.label_size();
+        if self.icon.is_none() {
+            return label;
+        }
+
+        let icon = self.icon_size;
+        let gap = if self.label.is_empty() { 0.0 } else { ICON_GAP };
+        match self.icon_position {
+            IconPosition::Left | IconPosition::Right => Point::new(icon + gap + label.x, icon.max(label.y)),
+            IconPosition::Top => Point::new(icon.max(label.x), icon + gap + label.y),
+        }
+    }
+
+    /// Returns the icon and label rects, centered in `bounds`.
+    fn content_rects(&self, bounds: Rect) -> (Rect, Rect) {
+        let size = self.content_size();
+        let label = self.label_size();
+        let icon = self.icon_size;
+        let left = bounds.left + (bounds.width() - size.x) / 2.0;
+        let top = bounds.top + (bounds.height() - size.y) / 2.0;
+        let mid_y = top + size.y / 2.0;
+        let mid_x = left + size.x / 2.0;
+
+        if self.icon.is_none() {
+            return (Rect::zero(), Rect::new(left, top, left + label.x, top + label.y));
+        }
+        match self.icon_position {
+            IconPosition::Left => (
+                Rect::new(left, mid_y - icon / 2.0, left + icon, mid_y + icon / 2.0),
+                Rect::new(left + size.x - label.x, mid_y - label.y / 2.0, left + size.x, mid_y + label.y / 2.0),
+            ),
+            IconPosition::Right => (
+                Rect::new(left + size.x - icon, mid_y - icon / 2.0, left + size.x, mid_y + icon / 2.0),
+                Rect::new(left, mid_y - label.y / 2.0, left + label.x, mid_y + label.y / 2.0),
+            ),
+            IconPosition::Top => (
+                Rect::new(mid_x - icon / 2.0, top, mid_x + icon / 2.0, top + icon),
+                Rect::new(mid_x - label.x / 2.0, top + size.y - label.y, mid_x + label.x / 2.0, top + size.y),
+            ),
+        }
     }
 
     fn draw_label(&self, ctx: &Context) {
         let color = if self.enabled {
-            self.text_color
+            self.text_color.get()
         } else {
-            self.text_color.with_alpha(0.5)
+            self.text_color.get().with_alpha(0.5)
         };
 
         let theme = get_theme();
+        let (icon_rect, label_rect) = self.content_rects(ctx.bounds);
         let mut canvas = ctx.canvas.borrow_mut();
-        canvas.fill_style(color);
-        canvas.font_size(theme.label_font_size);
 
-        // Center the text
-        let text_width = self.label.len() as f32 * theme.label_font_size * 0.6;
-        let text_height = theme.label_font_size;
-        let x = ctx.bounds.left + (ctx.bounds.width() - text_width) / 2.0;
-        let y = ctx.bounds.top + (ctx.bounds.height() - text_height) / 2.0 + text_height * 0.8;
+        if let Some(ref icon) = self.icon {
+            canvas.save();
+            icon.draw(&mut canvas, icon_rect, color);
+            canvas.restore();
+        }
+
+        if !self.label.is_empty() {
+            canvas.fill_style(color);
+            canvas.font_size(theme.label_font_size);
 
-        canvas.fill_text(&self.label, Point::new(x, y));
+            let text_height = theme.label_font_size;
+            let x = label_rect.left;
+            let y = label_rect.top + (label_rect.height() - text_height) / 2.0 + text_height * 0.8;
+            canvas.fill_text(&self.label, Point::new(x, y));
+        }
     }
 }
 
 impl Element for BasicButton {
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
         let theme = get_theme();
-        let text_width = self.label.len() as f32 * theme.label_font_size * 0.6;
-        let text_height = theme.label_font_size * 1.2;
-
         let margin = &theme.button_margin;
-        let width = text_width + margin.left + margin.right;
-        let height = text_height + margin.top + margin.bottom;
+
+        // Icon-only buttons are square
+        if self.icon.is_some() && self.label.is_empty() {
+            let side = self.icon_size + margin.top + margin.bottom;
+            return ViewLimits::fixed(side, side);
+        }
+
+        let mut size = self.content_size();
+        if self.label.is_empty() {
+            size.y = theme.label_font_size * 1.2;
+        }
+        let width = size.x + margin.left + margin.right;
+        let height = size.y + margin.top + margin.bottom;
 
         ViewLimits::fixed(width, height)
     }
@@ -188,7 +385,9 @@ impl Element for BasicButton {
             if *state == ButtonState::Pressed {
                 // Button was clicked - call callback outside of lock
                 drop(state);
-                if let Some(ref callback) = self.on_click {
+                if ctx.bounds.contains(btn.pos) {
+                    self.activate();
+                } else if let Some(ref callback) = self.on_click {
                     callback();
                 }
                 let mut state = self.state.write().unwrap();
@@ -232,6 +431,47 @@ impl Element for BasicButton {
         true
     }
 
+    fn handle_key(&self, _ctx: &Context, k: KeyInfo) -> bool {
+        if !self.enabled || !*self.focused.read().unwrap() {
+            return false;
+        }
+        if !matches!(k.key, KeyCode::Space | KeyCode::Enter) {
+            return false;
+        }
+
+        match k.action {
+            KeyAction::Press => {
+                *self.state.write().unwrap() = ButtonState::Pressed;
+                self.activate();
+            }
+            KeyAction::Repeat => {}
+            KeyAction::Release => {
+                *self.state.write().unwrap() = ButtonState::Normal;
+            }
+        }
+        true
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.enabled
+    }
+
+    fn clear_focus(&self) {
+        *self.focused.write().unwrap() = false;
+    }
+
+    fn has_focus(&self) -> bool {
+        *self.focused.read().unwrap()
+    }
+
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        *self.focused.write().unwrap() = true;
+        true
+    }
+
     fn enable(&mut self, state: bool) {
         self.enabled = state;
         let mut btn_state = self.state.write().unwrap();
@@ -258,38 +498,37 @@ impl Element for BasicButton {
 /// A toggle button that maintains its state.
 pub struct ToggleButton {
     inner: BasicButton,
-    active_color: Color,
+    active_color: ThemeColor,
 }
 
 impl ToggleButton {
     /// Creates a new toggle button.
     pub fn new(label: impl Into<String>) -> Self {
-        let theme = get_theme();
         Self {
             inner: BasicButton::new(label),
-            active_color: theme.indicator_bright_color,
+            active_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
         }
     }
 
     /// Sets the active color.
     pub fn with_active_color(mut self, color: Color) -> Self {
-        self.active_color = color;
+        self.active_color = color.into();
         self
     }
 
     /// Returns whether the button is toggled on.
     pub fn value(&self) -> bool {
-        self.inner.value
+        self.inner.is_on()
     }
 
     /// Sets the toggle state.
     pub fn set_value(&mut self, value: bool) {
-        self.inner.value = value;
+        self.inner.set_on(value);
     }
 
     /// Toggles the state.
     pub fn toggle(&mut self) {
-        self.inner.value = !self.inner.value;
+        self.inner.set_on(!self.inner.is_on());
     }
 }
 
@@ -301,7 +540,7 @@ impl Element for ToggleButton {
     fn draw(&self, ctx: &Context) {
         // Modify color if toggled
         let original_color = self.inner.body_color;
-        if self.inner.value {
+        if self.inner.is_on() {
             // Would need interior mutability here
             // For now, just draw with current color
         }
@@ -393,3 +632,90 @@ pub fn draw_button_base(
     canvas.add_round_rect_varying(bounds, corner_radii);
     canvas.fill();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::share;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_icon_layout() {
+        let limits = |b: BasicButton| TestView::new(share(b), Extent::new(100.0, 100.0)).limits();
+        let icon = ButtonIcon::path(|_, _, _| {});
+
+        let plain = limits(button("Save"));
+        let left = limits(button("Save").icon(icon.clone()).icon_size(16.0));
+        assert_eq!(left.min.x, plain.min.x + 16.0 + ICON_GAP);
+
+        let top = limits(button("Save").icon(icon.clone()).icon_size(16.0).icon_position(IconPosition::Top));
+        assert_eq!(top.min.x, plain.min.x);
+        assert!(top.min.y > plain.min.y);
+
+        // Icon-only buttons are square
+        let only = limits(button("").icon(icon));
+        assert_eq!(only.min.x, only.min.y);
+    }
+
+    #[test]
+    fn test_toggle_latches() {
+        let toggled = Arc::new(RwLock::new(Vec::new()));
+        let t = toggled.clone();
+        let mute = Arc::new(button("Mute").toggle(true).on_toggle(move |on| t.write().unwrap().push(on)));
+        let view = TestView::new(mute.clone(), Extent::new(80.0, 30.0));
+        let center = Point::new(40.0, 15.0);
+
+        view.click_at(center);
+        assert!(mute.is_on());
+        view.click_at(center);
+        assert!(!mute.is_on());
+        assert_eq!(*toggled.read().unwrap(), vec![true, false]);
+
+        // Momentary buttons don't latch
+        let plain = Arc::new(button("Go"));
+        TestView::new(plain.clone(), Extent::new(80.0, 30.0)).click_at(center);
+        assert!(!plain.is_on());
+    }
+
+    #[test]
+    fn test_space_and_enter_activate_focused_button() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let sink = clicks.clone();
+        let go = Arc::new(button("Go").on_click(move || {
+            sink.fetch_add(1, Ordering::SeqCst);
+        }));
+        let view = TestView::new(go.clone(), Extent::new(80.0, 30.0));
+
+        // Keys only reach a focused button
+        assert!(!view.key(KeyCode::Space, 0));
+        assert!(view.focus_first());
+        assert!(view.key(KeyCode::Space, 0));
+        assert!(view.key(KeyCode::Enter, 0));
+        assert!(!view.key(KeyCode::A, 0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 2);
+
+        // The button looks pressed while the key is held, and a repeat
+        // doesn't fire it again
+        let key = |action| KeyInfo { key: KeyCode::Space, action, modifiers: 0 };
+        view.with_context(|ctx| {
+            assert!(go.handle_key(ctx, key(KeyAction::Press)));
+            assert_eq!(go.state(), ButtonState::Pressed);
+            assert!(go.handle_key(ctx, key(KeyAction::Repeat)));
+            assert_eq!(clicks.load(Ordering::SeqCst), 3);
+            assert!(go.handle_key(ctx, key(KeyAction::Release)));
+            assert_eq!(go.state(), ButtonState::Normal);
+        });
+
+        // A toggle latches from the keyboard too
+        let mute = Arc::new(button("Mute").toggle(true));
+        let view = TestView::new(mute.clone(), Extent::new(80.0, 30.0));
+        assert!(view.focus_first());
+        view.key(KeyCode::Space, 0);
+        assert!(mute.is_on());
+        view.key(KeyCode::Enter, 0);
+        assert!(!mute.is_on());
+    }
+}
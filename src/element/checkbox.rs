@@ -7,7 +7,7 @@ use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
+use crate::support::theme::{get_theme, ThemeColor};
 use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
 
 /// Checkbox state.
@@ -28,9 +28,9 @@ pub struct Checkbox {
     label: String,
     checked: RwLock<bool>,
     state: RwLock<CheckboxState>,
-    box_color: Color,
-    check_color: Color,
-    text_color: Color,
+    box_color: ThemeColor,
+    check_color: ThemeColor,
+    text_color: ThemeColor,
     box_size: f32,
     corner_radius: f32,
     enabled: bool,
@@ -40,14 +40,13 @@ pub struct Checkbox {
 impl Checkbox {
     /// Creates a new checkbox with optional label.
     pub fn new(label: impl Into<String>) -> Self {
-        let theme = get_theme();
         Self {
             label: label.into(),
             checked: RwLock::new(false),
             state: RwLock::new(CheckboxState::Normal),
-            box_color: theme.frame_color,
-            check_color: theme.indicator_bright_color,
-            text_color: theme.label_font_color,
+            box_color: ThemeColor::Theme(|theme| theme.frame_color),
+            check_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
             box_size: 18.0,
             corner_radius: 3.0,
             enabled: true,
@@ -63,19 +62,19 @@ impl Checkbox {
 
     /// Sets the box color.
     pub fn box_color(mut self, color: Color) -> Self {
-        self.box_color = color;
+        self.box_color = color.into();
         self
     }
 
     /// Sets the check mark color.
     pub fn check_color(mut self, color: Color) -> Self {
-        self.check_color = color;
+        self.check_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
@@ -122,10 +121,10 @@ impl Checkbox {
         let box_rect = self.box_rect(&ctx.bounds);
 
         let color = match state {
-            CheckboxState::Normal => self.box_color,
-            CheckboxState::Hover => self.box_color.level(1.2),
-            CheckboxState::Pressed => self.box_color.level(0.8),
-            CheckboxState::Disabled => self.box_color.with_alpha(0.5),
+            CheckboxState::Normal => self.box_color.get(),
+            CheckboxState::Hover => self.box_color.get().level(1.2),
+            CheckboxState::Pressed => self.box_color.get().level(0.8),
+            CheckboxState::Disabled => self.box_color.get().with_alpha(0.5),
         };
 
         canvas.fill_style(color);
@@ -142,9 +141,9 @@ impl Checkbox {
         let state = *self.state.read().unwrap();
 
         let color = if state == CheckboxState::Disabled {
-            self.check_color.with_alpha(0.5)
+            self.check_color.get().with_alpha(0.5)
         } else {
-            self.check_color
+            self.check_color.get()
         };
 
         // Draw checkmark
@@ -175,9 +174,9 @@ impl Checkbox {
         let state = *self.state.read().unwrap();
 
         let color = if state == CheckboxState::Disabled {
-            self.text_color.with_alpha(0.5)
+            self.text_color.get().with_alpha(0.5)
         } else {
-            self.text_color
+            self.text_color.get()
         };
 
         canvas.fill_style(color);
@@ -304,9 +303,9 @@ pub struct RadioButton {
     label: String,
     selected: RwLock<bool>,
     state: RwLock<CheckboxState>,
-    circle_color: Color,
-    indicator_color: Color,
-    text_color: Color,
+    circle_color: ThemeColor,
+    indicator_color: ThemeColor,
+    text_color: ThemeColor,
     circle_size: f32,
     enabled: bool,
     on_select: Option<Box<dyn Fn() + Send + Sync>>,
@@ -315,14 +314,13 @@ pub struct RadioButton {
 impl RadioButton {
     /// Creates a new radio button with label.
     pub fn new(label: impl Into<String>) -> Self {
-        let theme = get_theme();
         Self {
             label: label.into(),
             selected: RwLock::new(false),
             state: RwLock::new(CheckboxState::Normal),
-            circle_color: theme.frame_color,
-            indicator_color: theme.indicator_bright_color,
-            text_color: theme.label_font_color,
+            circle_color: ThemeColor::Theme(|theme| theme.frame_color),
+            indicator_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
             circle_size: 18.0,
             enabled: true,
             on_select: None,
@@ -337,19 +335,19 @@ impl RadioButton {
 
     /// Sets the circle color.
     pub fn circle_color(mut self, color: Color) -> Self {
-        self.circle_color = color;
+        self.circle_color = color.into();
         self
     }
 
     /// Sets the indicator color.
     pub fn indicator_color(mut self, color: Color) -> Self {
-        self.indicator_color = color;
+        self.indicator_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
@@ -379,10 +377,10 @@ impl RadioButton {
         );
 
         let color = match state {
-            CheckboxState::Normal => self.circle_color,
-            CheckboxState::Hover => self.circle_color.level(1.2),
-            CheckboxState::Pressed => self.circle_color.level(0.8),
-            CheckboxState::Disabled => self.circle_color.with_alpha(0.5),
+            CheckboxState::Normal => self.circle_color.get(),
+            CheckboxState::Hover => self.circle_color.get().level(1.2),
+            CheckboxState::Pressed => self.circle_color.get().level(0.8),
+            CheckboxState::Disabled => self.circle_color.get().with_alpha(0.5),
         };
 
         canvas.fill_style(color);
@@ -405,9 +403,9 @@ impl RadioButton {
         );
 
         let color = if state == CheckboxState::Disabled {
-            self.indicator_color.with_alpha(0.5)
+            self.indicator_color.get().with_alpha(0.5)
         } else {
-            self.indicator_color
+            self.indicator_color.get()
         };
 
         canvas.fill_style(color);
@@ -426,9 +424,9 @@ impl RadioButton {
         let state = *self.state.read().unwrap();
 
         let color = if state == CheckboxState::Disabled {
-            self.text_color.with_alpha(0.5)
+            self.text_color.get().with_alpha(0.5)
         } else {
-            self.text_color
+            self.text_color.get()
         };
 
         canvas.fill_style(color);
@@ -0,0 +1,378 @@
+//! Collapsible section that shows or hides its body.
+
+use std::any::Any;
+use std::sync::RwLock;
+use std::time::Duration;
+use super::{Element, ElementPtr, ViewLimits, ViewStretch, FocusRequest, share};
+use super::context::{BasicContext, Context};
+use crate::support::animation::Animated;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::ThemeColor;
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, TextInfo, DropInfo, CursorTracking};
+
+/// Width of the chevron column left of the header.
+const CHEVRON_WIDTH: f32 = 20.0;
+
+/// Time the chevron takes to turn.
+const CHEVRON_DURATION: Duration = Duration::from_millis(125);
+
+/// Callback type for expansion changes.
+pub type ToggleCallback = Box<dyn Fn(bool) + Send + Sync>;
+
+/// A section with a clickable header that expands or collapses its body.
+///
+/// The header is drawn after a chevron that points right when collapsed
+/// and down when expanded. A collapsed body takes no space and receives
+/// no events.
+pub struct Collapsible {
+    header: ElementPtr,
+    body: ElementPtr,
+    expanded: RwLock<bool>,
+    /// Chevron rotation, from 0 (collapsed) to 1 (expanded)
+    chevron: RwLock<Animated<f32>>,
+    chevron_color: ThemeColor,
+    on_toggle: Option<ToggleCallback>,
+}
+
+impl Collapsible {
+    /// Creates a collapsed section.
+    pub fn new<H: Element + 'static, B: Element + 'static>(header: H, body: B) -> Self {
+        Self {
+            header: share(header),
+            body: share(body),
+            expanded: RwLock::new(false),
+            chevron: RwLock::new(Animated::new(0.0).duration(CHEVRON_DURATION)),
+            chevron_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            on_toggle: None,
+        }
+    }
+
+    /// Sets whether the section starts expanded.
+    pub fn expanded(self, expanded: bool) -> Self {
+        *self.expanded.write().unwrap() = expanded;
+        self.chevron.write().unwrap().set(if expanded { 1.0 } else { 0.0 });
+        self
+    }
+
+    /// Sets the callback called when the user expands or collapses the section.
+    pub fn on_toggle<F: Fn(bool) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_toggle = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns whether the body is shown.
+    pub fn is_expanded(&self) -> bool {
+        *self.expanded.read().unwrap()
+    }
+
+    /// Expands or collapses the section.
+    ///
+    /// This does not call the `on_toggle` callback.
+    pub fn set_expanded(&self, expanded: bool) {
+        *self.expanded.write().unwrap() = expanded;
+        self.chevron.write().unwrap().set_target(if expanded { 1.0 } else { 0.0 });
+        if !expanded {
+            self.body.clear_focus();
+        }
+    }
+
+    /// Toggles the section on behalf of the user.
+    fn toggle(&self, ctx: &Context) {
+        let expanded = !self.is_expanded();
+        self.set_expanded(expanded);
+        // The section changes size, which moves everything after it
+        ctx.view.refresh();
+        if let Some(ref callback) = self.on_toggle {
+            callback(expanded);
+        }
+    }
+
+    /// Returns the children that can take part in focus traversal.
+    fn visible_children(&self) -> Vec<&dyn Element> {
+        let mut children = vec![self.header.as_ref()];
+        if self.is_expanded() {
+            children.push(self.body.as_ref());
+        }
+        children
+    }
+
+    fn header_height(&self, ctx: &BasicContext) -> f32 {
+        self.header.limits(ctx).min.y.max(CHEVRON_WIDTH)
+    }
+
+    /// Returns the header row, including the chevron.
+    fn header_rect(&self, ctx: &Context) -> Rect {
+        let bounds = ctx.bounds;
+        let height = self.header_height(&BasicContext::new(ctx.view, ctx.canvas));
+        Rect::new(bounds.left, bounds.top, bounds.right, bounds.top + height)
+    }
+
+    /// Returns the header context, to the right of the chevron.
+    fn header_ctx<'a>(&self, ctx: &'a Context) -> Context<'a> {
+        let row = self.header_rect(ctx);
+        ctx.with_bounds(Rect::new(row.left + CHEVRON_WIDTH, row.top, row.right, row.bottom))
+    }
+
+    /// Returns the body context, or `None` when collapsed.
+    fn body_ctx<'a>(&self, ctx: &'a Context) -> Option<Context<'a>> {
+        if !self.is_expanded() {
+            return None;
+        }
+        let row = self.header_rect(ctx);
+        Some(ctx.with_bounds(Rect::new(ctx.bounds.left, row.bottom, ctx.bounds.right, ctx.bounds.bottom)))
+    }
+
+    fn draw_chevron(&self, ctx: &Context) {
+        let row = self.header_rect(ctx);
+        let c = Point::new(row.left + CHEVRON_WIDTH / 2.0, row.center().y);
+        let angle = self.chevron.read().unwrap().get() * std::f32::consts::FRAC_PI_2;
+        let (sin, cos) = angle.sin_cos();
+        let rotate = |x: f32, y: f32| Point::new(c.x + x * cos - y * sin, c.y + x * sin + y * cos);
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.stroke_style(self.chevron_color.get());
+        canvas.line_width(1.5);
+        canvas.begin_path();
+        canvas.move_to(rotate(-2.5, -5.0));
+        canvas.line_to(rotate(2.5, 0.0));
+        canvas.line_to(rotate(-2.5, 5.0));
+        canvas.stroke();
+    }
+}
+
+impl Element for Collapsible {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let header = self.header.limits(ctx);
+        let height = self.header_height(ctx);
+        let mut limits = ViewLimits {
+            min: Point::new(header.min.x + CHEVRON_WIDTH, height),
+            max: Point::new(super::FULL_EXTENT, height),
+        };
+        if self.is_expanded() {
+            let body = self.body.limits(ctx);
+            limits.min.x = limits.min.x.max(body.min.x);
+            limits.min.y += body.min.y;
+            limits.max.y = (limits.max.y + body.max.y).min(super::FULL_EXTENT);
+        }
+        limits
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        if self.is_expanded() {
+            ViewStretch::new(1.0, self.body.stretch().y)
+        } else {
+            ViewStretch::new(1.0, 0.0)
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.draw_chevron(ctx);
+        self.header.draw(&self.header_ctx(ctx));
+        if let Some(body_ctx) = self.body_ctx(ctx) {
+            self.body.draw(&body_ctx);
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        if self.header_rect(ctx).contains(p) {
+            let header_ctx = self.header_ctx(ctx);
+            if let Some(hit) = self.header.hit_test(&header_ctx, p, leaf, control) {
+                return Some(hit);
+            }
+            return Some(self);
+        }
+        if let Some(body_ctx) = self.body_ctx(ctx) {
+            if let Some(hit) = self.body.hit_test(&body_ctx, p, leaf, control) {
+                return Some(hit);
+            }
+        }
+        if ctx.bounds.contains(p) && !leaf {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.header.bounds_of_element(&self.header_ctx(ctx), element)
+            .or_else(|| self.body.bounds_of_element(&self.body_ctx(ctx)?, element))
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.header.focus_bounds(&self.header_ctx(ctx))
+            .or_else(|| self.body.focus_bounds(&self.body_ctx(ctx)?))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let header = self.header.handle_cursor(&self.header_ctx(ctx), p, status);
+        let body = self.body_ctx(ctx).is_some_and(|body_ctx| self.body.handle_cursor(&body_ctx, p, status));
+        header || body
+    }
+
+    fn wants_control(&self) -> bool {
+        true
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if self.header_rect(ctx).contains(btn.pos) {
+            // Controls in the header keep their clicks
+            let header_ctx = self.header_ctx(ctx);
+            if self.header.hit_test(&header_ctx, btn.pos, false, true).is_some()
+                && self.header.handle_click(&header_ctx, btn)
+            {
+                return true;
+            }
+            if btn.button == MouseButtonKind::Left && btn.down {
+                self.toggle(ctx);
+            }
+            return true;
+        }
+        match self.body_ctx(ctx) {
+            Some(body_ctx) => self.body.handle_click(&body_ctx, btn),
+            None => false,
+        }
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if let Some(body_ctx) = self.body_ctx(ctx) {
+            self.body.handle_drag(&body_ctx, btn);
+        }
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.header.handle_key(&self.header_ctx(ctx), k)
+            || self.body_ctx(ctx).is_some_and(|body_ctx| self.body.handle_key(&body_ctx, k))
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.header.handle_text(&self.header_ctx(ctx), info)
+            || self.body_ctx(ctx).is_some_and(|body_ctx| self.body.handle_text(&body_ctx, info))
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        match self.body_ctx(ctx) {
+            Some(body_ctx) if body_ctx.bounds.contains(p) => self.body.handle_scroll(&body_ctx, dir, p),
+            _ => false,
+        }
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        match self.body_ctx(ctx) {
+            Some(body_ctx) if body_ctx.bounds.contains(center) => self.body.handle_magnify(&body_ctx, scale_delta, center),
+            _ => false,
+        }
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.header.handle_track_drop(&self.header_ctx(ctx), info, status);
+        if let Some(body_ctx) = self.body_ctx(ctx) {
+            self.body.handle_track_drop(&body_ctx, info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        if self.header_rect(ctx).contains(info.where_) {
+            return self.header.handle_drop(&self.header_ctx(ctx), info);
+        }
+        match self.body_ctx(ctx) {
+            Some(body_ctx) if body_ctx.bounds.contains(info.where_) => self.body.handle_drop(&body_ctx, info),
+            _ => false,
+        }
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.header.wants_focus() || (self.is_expanded() && self.body.wants_focus())
+    }
+
+    fn clear_focus(&self) {
+        self.header.clear_focus();
+        self.body.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.header.has_focus() || self.body.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        super::begin_child_focus(&self.visible_children(), req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        super::child_focus_next(&self.visible_children(), forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        let mut redraw = self.chevron.write().unwrap().poll();
+        redraw |= self.header.handle_poll();
+        if self.is_expanded() {
+            redraw |= self.body.handle_poll();
+        }
+        redraw
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a collapsed section with the given header and body.
+pub fn collapsible<H: Element + 'static, B: Element + 'static>(header: H, body: B) -> Collapsible {
+    Collapsible::new(header, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::element::size::fixed_size;
+    use crate::element::button::button;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_toggle_shows_and_hides_body() {
+        let toggled = Arc::new(Mutex::new(Vec::new()));
+        let sink = toggled.clone();
+        let section = Arc::new(
+            collapsible(fixed_size(100.0, 24.0, button("Header")), fixed_size(100.0, 50.0, button("Body")))
+                .on_toggle(move |expanded| sink.lock().unwrap().push(expanded)),
+        );
+        let view = TestView::new(section.clone(), Extent::new(200.0, 74.0));
+        assert_eq!(view.limits().min.y, 24.0);
+
+        // Collapsed, the body area is not hit-testable
+        let body_point = Point::new(50.0, 50.0);
+        assert!(view.with_context(|ctx| section.hit_test(ctx, body_point, true, false).is_none()));
+
+        // Clicking the chevron expands the section
+        let chevron_point = Point::new(10.0, 12.0);
+        view.click_at(chevron_point);
+        assert!(section.is_expanded());
+        assert_eq!(view.limits().min.y, 74.0);
+        assert!(view.with_context(|ctx| section.hit_test(ctx, body_point, true, false).is_some()));
+
+        // The chevron turns until it points down
+        let mut chevron = section.chevron.write().unwrap();
+        assert!(chevron.advance(1.0));
+        assert!(!chevron.advance(1.0));
+        assert_eq!(chevron.get(), 1.0);
+        drop(chevron);
+        assert!(view.render().is_some());
+
+        view.click_at(chevron_point);
+        assert!(!section.is_expanded());
+        assert_eq!(*toggled.lock().unwrap(), vec![true, false]);
+    }
+}
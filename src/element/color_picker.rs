@@ -0,0 +1,329 @@
+//! Color picker element.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch};
+use super::context::{BasicContext, Context};
+use crate::support::canvas::LinearGradient;
+use crate::support::color::Color;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind};
+
+/// Width of the hue strip.
+const HUE_STRIP_WIDTH: f32 = 16.0;
+
+/// Space between the parts of the picker.
+const GAP: f32 = 8.0;
+
+/// Height of the preview swatch and hex readout row.
+const FOOTER_HEIGHT: f32 = 20.0;
+
+/// Callback type for color changes.
+pub type ColorChangeCallback = Box<dyn Fn(Color) + Send + Sync>;
+
+/// The part of the picker being dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PickerPart {
+    Square,
+    HueStrip,
+}
+
+/// A color picker with a saturation/value square and a hue strip.
+///
+/// The color is kept as HSV so the hue survives while the color is gray.
+/// The alpha of the color set with [`ColorPicker::color`] is kept as is.
+pub struct ColorPicker {
+    /// Hue in degrees, saturation and value
+    hsv: RwLock<(f32, f32, f32)>,
+    alpha: RwLock<f32>,
+    tracking: RwLock<Option<PickerPart>>,
+    show_preview: bool,
+    frame_color: ThemeColor,
+    text_color: ThemeColor,
+    on_change: Option<ColorChangeCallback>,
+}
+
+impl ColorPicker {
+    /// Creates a color picker set to opaque red.
+    pub fn new() -> Self {
+        Self {
+            hsv: RwLock::new((0.0, 1.0, 1.0)),
+            alpha: RwLock::new(1.0),
+            tracking: RwLock::new(None),
+            show_preview: true,
+            frame_color: ThemeColor::Theme(|theme| theme.frame_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            on_change: None,
+        }
+    }
+
+    /// Sets the initial color.
+    pub fn color(self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    /// Sets whether the preview swatch and hex readout are shown.
+    pub fn preview(mut self, show: bool) -> Self {
+        self.show_preview = show;
+        self
+    }
+
+    /// Sets the callback called when the user picks a color.
+    pub fn on_change<F: Fn(Color) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the current color.
+    pub fn get_color(&self) -> Color {
+        let (h, s, v) = *self.hsv.read().unwrap();
+        Color::from_hsv(h, s, v).with_alpha(*self.alpha.read().unwrap())
+    }
+
+    /// Sets the current color.
+    ///
+    /// This does not call the `on_change` callback.
+    pub fn set_color(&self, color: Color) {
+        let (h, s, v) = color.to_hsv();
+        let mut hsv = self.hsv.write().unwrap();
+        // Gray has no hue of its own; keep the one the user chose
+        let hue = if s == 0.0 { hsv.0 } else { h };
+        *hsv = (hue, s, v);
+        *self.alpha.write().unwrap() = color.alpha;
+    }
+
+    /// Returns the saturation/value square, the hue strip and the footer row.
+    fn parts(&self, bounds: &Rect) -> (Rect, Rect, Rect) {
+        let bottom = if self.show_preview { bounds.bottom - FOOTER_HEIGHT - GAP } else { bounds.bottom };
+        let strip_left = bounds.right - HUE_STRIP_WIDTH;
+        (
+            Rect::new(bounds.left, bounds.top, strip_left - GAP, bottom),
+            Rect::new(strip_left, bounds.top, bounds.right, bottom),
+            Rect::new(bounds.left, bounds.bottom - FOOTER_HEIGHT, bounds.right, bounds.bottom),
+        )
+    }
+
+    /// Updates the color from a point in the tracked part.
+    fn track(&self, ctx: &Context, part: PickerPart, p: Point) {
+        let (square, strip, _) = self.parts(&ctx.bounds);
+        let fraction = |value: f32, start: f32, extent: f32| ((value - start) / extent.max(1.0)).clamp(0.0, 1.0);
+
+        let old = *self.hsv.read().unwrap();
+        let (h, s, v) = old;
+        let new = match part {
+            PickerPart::Square => (
+                h,
+                fraction(p.x, square.left, square.width()),
+                1.0 - fraction(p.y, square.top, square.height()),
+            ),
+            PickerPart::HueStrip => (fraction(p.y, strip.top, strip.height()) * 360.0, s, v),
+        };
+        if new == old {
+            return;
+        }
+        *self.hsv.write().unwrap() = new;
+        self.refresh(ctx, 0);
+        if let Some(ref callback) = self.on_change {
+            callback(self.get_color());
+        }
+    }
+
+    fn draw_square(&self, ctx: &Context, square: Rect) {
+        let (h, s, v) = *self.hsv.read().unwrap();
+        let mut canvas = ctx.canvas.borrow_mut();
+
+        // Saturation runs left to right, value top to bottom
+        let mut saturation = LinearGradient::new(Point::new(square.left, 0.0), Point::new(square.right, 0.0));
+        saturation.add_stop(0.0, Color::from_hsv(h, 0.0, 1.0));
+        saturation.add_stop(1.0, Color::from_hsv(h, 1.0, 1.0));
+        canvas.begin_path();
+        canvas.add_rect(square);
+        canvas.fill_linear_gradient(&saturation);
+
+        let mut value = LinearGradient::new(Point::new(0.0, square.top), Point::new(0.0, square.bottom));
+        value.add_stop(0.0, Color::new(0.0, 0.0, 0.0, 0.0));
+        value.add_stop(1.0, Color::new(0.0, 0.0, 0.0, 1.0));
+        canvas.begin_path();
+        canvas.add_rect(square);
+        canvas.fill_linear_gradient(&value);
+
+        canvas.stroke_style(self.frame_color.get());
+        canvas.line_width(1.0);
+        canvas.stroke_rect(square);
+
+        // Marker, in whichever of black or white stands out from the color
+        let marker = Point::new(
+            square.left + s * square.width(),
+            square.top + (1.0 - v) * square.height(),
+        );
+        canvas.stroke_style(Color::from_hsv(h, s, v).readable_text_color());
+        canvas.line_width(1.5);
+        canvas.begin_path();
+        canvas.arc(marker, 5.0, 0.0, std::f32::consts::TAU, false);
+        canvas.stroke();
+    }
+
+    fn draw_hue_strip(&self, ctx: &Context, strip: Rect) {
+        let hue = self.hsv.read().unwrap().0;
+        let mut canvas = ctx.canvas.borrow_mut();
+
+        let mut gradient = LinearGradient::new(Point::new(0.0, strip.top), Point::new(0.0, strip.bottom));
+        for i in 0..=6 {
+            gradient.add_stop(i as f32 / 6.0, Color::from_hsv(i as f32 * 60.0, 1.0, 1.0));
+        }
+        canvas.begin_path();
+        canvas.add_rect(strip);
+        canvas.fill_linear_gradient(&gradient);
+
+        canvas.stroke_style(self.frame_color.get());
+        canvas.line_width(1.0);
+        canvas.stroke_rect(strip);
+
+        let y = strip.top + hue / 360.0 * strip.height();
+        let marker = Rect::new(strip.left - 2.0, y - 2.0, strip.right + 2.0, y + 2.0);
+        canvas.stroke_style(Color::new(1.0, 1.0, 1.0, 1.0));
+        canvas.line_width(1.5);
+        canvas.stroke_round_rect(marker, 2.0);
+    }
+
+    fn draw_footer(&self, ctx: &Context, footer: Rect) {
+        let theme = get_theme();
+        let color = self.get_color();
+        let mut canvas = ctx.canvas.borrow_mut();
+
+        let swatch = Rect::new(footer.left, footer.top, footer.left + FOOTER_HEIGHT * 2.0, footer.bottom);
+        canvas.fill_style(color);
+        canvas.fill_round_rect(swatch, 3.0);
+        canvas.stroke_style(self.frame_color.get());
+        canvas.line_width(1.0);
+        canvas.stroke_round_rect(swatch, 3.0);
+
+        canvas.fill_style(self.text_color.get());
+        canvas.font_size(theme.label_font_size);
+        canvas.fill_text(
+            &color.to_hex(),
+            Point::new(swatch.right + GAP, footer.center().y + theme.label_font_size * 0.35),
+        );
+    }
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Element for ColorPicker {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        let footer = if self.show_preview { FOOTER_HEIGHT + GAP } else { 0.0 };
+        ViewLimits::min_size(120.0 + GAP + HUE_STRIP_WIDTH, 120.0 + footer)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(1.0, 1.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let (square, strip, footer) = self.parts(&ctx.bounds);
+        self.draw_square(ctx, square);
+        self.draw_hue_strip(ctx, strip);
+        if self.show_preview {
+            self.draw_footer(ctx, footer);
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn wants_control(&self) -> bool {
+        true
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if btn.button != MouseButtonKind::Left {
+            return false;
+        }
+        if !btn.down {
+            *self.tracking.write().unwrap() = None;
+            return true;
+        }
+
+        let (square, strip, _) = self.parts(&ctx.bounds);
+        let part = if square.contains(btn.pos) {
+            PickerPart::Square
+        } else if strip.contains(btn.pos) {
+            PickerPart::HueStrip
+        } else {
+            return false;
+        };
+        *self.tracking.write().unwrap() = Some(part);
+        self.track(ctx, part, btn.pos);
+        true
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        let part = *self.tracking.read().unwrap();
+        if let Some(part) = part {
+            self.track(ctx, part, btn.pos);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a color picker.
+pub fn color_picker() -> ColorPicker {
+    ColorPicker::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_drag_square_and_hue_strip() {
+        let picked = Arc::new(Mutex::new(Vec::new()));
+        let sink = picked.clone();
+        let picker = Arc::new(
+            color_picker()
+                .preview(false)
+                .on_change(move |c| sink.lock().unwrap().push(c.to_hex())),
+        );
+
+        // Square: 0..100 wide, hue strip: 108..124
+        let view = TestView::new(picker.clone(), Extent::new(124.0, 100.0));
+
+        // Top left of the square is white; dragging off the right edge clamps
+        view.drag(Point::new(0.0, 0.0), Point::new(150.0, 0.0));
+
+        // A third of the way down the strip is green
+        view.click_at(Point::new(116.0, 100.0 / 3.0));
+        assert_eq!(*picked.lock().unwrap(), vec!["#ffffff", "#ff0000", "#00ff00"]);
+
+        // Gray keeps the chosen hue
+        picker.set_color(Color::new(0.5, 0.5, 0.5, 1.0));
+        assert!((picker.hsv.read().unwrap().0 - 120.0).abs() < 1e-3);
+        assert!(view.render().is_some());
+    }
+}
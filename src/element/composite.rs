@@ -2,10 +2,11 @@
 
 use std::any::Any;
 use std::collections::HashSet;
-use super::{Element, ElementPtr, ViewLimits, FocusRequest};
+use super::{Element, ElementPtr, ViewLimits, FocusRequest, begin_child_focus, child_focus_next};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
+use crate::view::CursorTracking;
 
 /// Storage trait for accessing elements by index.
 pub trait Storage {
@@ -94,10 +95,8 @@ pub trait CompositeBase: Element + Storage {
             if let Some(element) = self.at(i) {
                 let bounds = self.bounds_of(ctx, i);
                 // Check if bounds intersect with view bounds
-                if crate::support::rect::intersects(&bounds, &ctx.bounds) {
-                    if !f(element, i, bounds) {
-                        break;
-                    }
+                if crate::support::rect::intersects(&bounds, &ctx.bounds) && !f(element, i, bounds) {
+                    break;
                 }
             }
         }
@@ -171,6 +170,11 @@ impl Composite {
         &self.children
     }
 
+    /// Returns the children as element references, in tree order.
+    fn child_refs(&self) -> Vec<&dyn Element> {
+        self.children.iter().map(|c| c.as_ref()).collect()
+    }
+
     /// Returns the focus index.
     pub fn focus_index(&self) -> Option<usize> {
         self.focus_index
@@ -319,6 +323,55 @@ impl Element for Composite {
             .map(|e| e.as_ref())
     }
 
+    fn clear_focus(&self) {
+        for child in &self.children {
+            child.clear_focus();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.children.iter().any(|c| c.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        begin_child_focus(&self.child_refs(), req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        child_focus_next(&self.child_refs(), forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        // Every child gets the tick, even after one asks for a redraw
+        let mut redraw = false;
+        for child in &self.children {
+            redraw |= child.handle_poll();
+        }
+        redraw
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.children.iter().enumerate().find_map(|(i, child)| {
+            child.bounds_of_element(&ctx.with_bounds(self.bounds_of(ctx, i)), element)
+        })
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.children.iter().enumerate().find_map(|(i, child)| {
+            child.focus_bounds(&ctx.with_bounds(self.bounds_of(ctx, i)))
+        })
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Every child sees the move, so the one the mouse left can tell
+        self.children.iter().enumerate().fold(false, |handled, (i, child)| {
+            child.handle_cursor(&ctx.with_bounds(self.bounds_of(ctx, i)), p, status) || handled
+        })
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
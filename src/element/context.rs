@@ -46,6 +46,8 @@ pub struct Context<'a> {
     pub parent: Option<&'a Context<'a>>,
     pub bounds: Rect,
     pub enabled: bool,
+    /// The visible region, if drawing is clipped by an enclosing element.
+    pub clip: Option<Rect>,
 }
 
 impl<'a> Context<'a> {
@@ -58,6 +60,7 @@ impl<'a> Context<'a> {
             parent: None,
             bounds,
             enabled: true,
+            clip: None,
         }
     }
 
@@ -71,6 +74,35 @@ impl<'a> Context<'a> {
             parent: None, // Cannot set parent due to lifetime constraints
             bounds,
             enabled: self.enabled,
+            clip: self.clip,
+        }
+    }
+
+    /// Creates a child context whose visible region is further clipped to `clip`.
+    ///
+    /// This only records the clip; elements that clip their children should
+    /// also push it onto the canvas with `Canvas::push_clip`.
+    pub fn with_clip(&self, clip: Rect) -> Context<'a> {
+        let clip = match self.clip {
+            Some(existing) => existing.intersection(clip).unwrap_or(Rect::zero()),
+            None => clip,
+        };
+        Context {
+            view: self.view,
+            canvas: self.canvas,
+            element: self.element,
+            parent: None,
+            bounds: self.bounds,
+            enabled: self.enabled,
+            clip: Some(clip),
+        }
+    }
+
+    /// Returns the part of the bounds that is visible after clipping.
+    pub fn visible_bounds(&self) -> Option<Rect> {
+        match self.clip {
+            Some(clip) => self.bounds.intersection(clip),
+            None => Some(self.bounds),
         }
     }
 
@@ -97,6 +129,7 @@ pub struct ContextBuilder<'a> {
     parent: Option<&'a Context<'a>>,
     bounds: Rect,
     enabled: bool,
+    clip: Option<Rect>,
 }
 
 impl<'a> ContextBuilder<'a> {
@@ -108,6 +141,7 @@ impl<'a> ContextBuilder<'a> {
             parent: Some(parent),
             bounds: parent.bounds,
             enabled: parent.enabled,
+            clip: parent.clip,
         }
     }
 
@@ -133,6 +167,7 @@ impl<'a> ContextBuilder<'a> {
             parent: self.parent,
             bounds: self.bounds,
             enabled: self.enabled,
+            clip: self.clip,
         }
     }
 }
@@ -0,0 +1,876 @@
+//! Decoration elements that paint a background, border or drop shadow
+//! around their subject, or fade it.
+//!
+//! Decorations draw in their subject's bounds and forward everything else,
+//! so they can wrap any element without affecting layout or events.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
+use super::context::{BasicContext, Context};
+use crate::support::color::Color;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
+
+/// Fills a rounded rectangle behind its subject.
+pub struct Background<S: Element> {
+    subject: S,
+    color: Color,
+    corner_radius: f32,
+}
+
+impl<S: Element> Background<S> {
+    /// Creates a new background element.
+    pub fn new(color: Color, corner_radius: f32, subject: S) -> Self {
+        Self { subject, color, corner_radius }
+    }
+
+    /// Returns a reference to the subject.
+    pub fn subject(&self) -> &S {
+        &self.subject
+    }
+}
+
+impl<S: Element + 'static> Element for Background<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.fill_style(self.color);
+        canvas.fill_round_rect(ctx.bounds, self.corner_radius);
+        canvas.restore();
+        drop(canvas);
+
+        self.subject.draw(ctx);
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.click(ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.subject.drag(ctx, btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.key(ctx, k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.scroll(ctx, dir, p)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Strokes a rounded rectangle over its subject.
+pub struct Border<S: Element> {
+    subject: S,
+    color: Color,
+    width: f32,
+    corner_radius: f32,
+}
+
+impl<S: Element> Border<S> {
+    /// Creates a new border element.
+    pub fn new(color: Color, width: f32, corner_radius: f32, subject: S) -> Self {
+        Self { subject, color, width, corner_radius }
+    }
+
+    /// Returns a reference to the subject.
+    pub fn subject(&self) -> &S {
+        &self.subject
+    }
+}
+
+impl<S: Element + 'static> Element for Border<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.subject.draw(ctx);
+
+        // Inset by half the width so the stroke stays inside the bounds
+        let inset = self.width / 2.0;
+        let bounds = Rect::new(
+            ctx.bounds.left + inset,
+            ctx.bounds.top + inset,
+            ctx.bounds.right - inset,
+            ctx.bounds.bottom - inset,
+        );
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.stroke_style(self.color);
+        canvas.line_width(self.width);
+        canvas.stroke_round_rect(bounds, (self.corner_radius - inset).max(0.0));
+        canvas.restore();
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.click(ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.subject.drag(ctx, btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.key(ctx, k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.scroll(ctx, dir, p)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Draws a soft shadow behind its subject.
+///
+/// The shadow is a blurred copy of the subject's bounds, so it suits
+/// opaque, rectangular subjects such as panels and popups.
+pub struct DropShadow<S: Element> {
+    subject: S,
+    offset: Point,
+    blur: f32,
+    color: Color,
+    corner_radius: f32,
+}
+
+impl<S: Element> DropShadow<S> {
+    /// Creates a new drop shadow element.
+    pub fn new(offset: Point, blur: f32, color: Color, subject: S) -> Self {
+        Self { subject, offset, blur, color, corner_radius: 0.0 }
+    }
+
+    /// Sets the corner radius of the shadow, to match a rounded subject.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Returns a reference to the subject.
+    pub fn subject(&self) -> &S {
+        &self.subject
+    }
+}
+
+impl<S: Element + 'static> Element for DropShadow<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.fill_style(self.color);
+        canvas.fill_shadow(ctx.bounds.translate(self.offset.x, self.offset.y), self.corner_radius, self.blur);
+        canvas.restore();
+        drop(canvas);
+
+        self.subject.draw(ctx);
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.click(ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.subject.drag(ctx, btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.key(ctx, k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.scroll(ctx, dir, p)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Draws its subject at reduced opacity.
+///
+/// The subject is drawn as a group, so its overlapping parts fade together
+/// rather than showing through each other. Events pass through unchanged.
+pub struct Opacity<S: Element> {
+    subject: S,
+    opacity: RwLock<f32>,
+}
+
+impl<S: Element> Opacity<S> {
+    /// Creates a new opacity element.
+    pub fn new(opacity: f32, subject: S) -> Self {
+        Self {
+            subject,
+            opacity: RwLock::new(opacity.clamp(0.0, 1.0)),
+        }
+    }
+
+    /// Returns the opacity.
+    pub fn opacity(&self) -> f32 {
+        *self.opacity.read().unwrap()
+    }
+
+    /// Sets the opacity, for example from a fade animation.
+    pub fn set_opacity(&self, opacity: f32) {
+        *self.opacity.write().unwrap() = opacity.clamp(0.0, 1.0);
+    }
+
+    /// Returns a reference to the subject.
+    pub fn subject(&self) -> &S {
+        &self.subject
+    }
+}
+
+impl<S: Element + 'static> Element for Opacity<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let opacity = self.opacity();
+        if opacity <= 0.0 {
+            return;
+        }
+        if opacity >= 1.0 {
+            self.subject.draw(ctx);
+            return;
+        }
+
+        ctx.canvas.borrow_mut().push_group_opacity(opacity);
+        self.subject.draw(ctx);
+        ctx.canvas.borrow_mut().pop_group();
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.click(ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.subject.drag(ctx, btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.key(ctx, k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.scroll(ctx, dir, p)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// Convenience functions
+
+/// Creates a background element.
+pub fn background<S: Element>(color: Color, corner_radius: f32, subject: S) -> Background<S> {
+    Background::new(color, corner_radius, subject)
+}
+
+/// Creates a border element.
+pub fn border<S: Element>(color: Color, width: f32, corner_radius: f32, subject: S) -> Border<S> {
+    Border::new(color, width, corner_radius, subject)
+}
+
+/// Creates a drop shadow element.
+pub fn drop_shadow<S: Element>(offset: Point, blur: f32, color: Color, subject: S) -> DropShadow<S> {
+    DropShadow::new(offset, blur, color, subject)
+}
+
+/// Creates an opacity element.
+pub fn opacity<S: Element>(opacity: f32, subject: S) -> Opacity<S> {
+    Opacity::new(opacity, subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::element::margin::margin;
+    use crate::element::share;
+    use crate::element::size::fixed_size;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    /// A subject that fills its bounds' top half, to tell it from decorations.
+    struct TopHalf;
+
+    impl Element for TopHalf {
+        fn draw(&self, ctx: &Context) {
+            let b = ctx.bounds;
+            let mut canvas = ctx.canvas.borrow_mut();
+            canvas.fill_style(Color::rgb(0.0, 0.0, 1.0));
+            canvas.fill_rect(Rect::new(b.left, b.top, b.right, (b.top + b.bottom) / 2.0));
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_decorations_draw_around_subject() {
+        let element = border(
+            Color::rgb(0.0, 1.0, 0.0),
+            2.0,
+            0.0,
+            background(Color::rgb(1.0, 0.0, 0.0), 0.0, fixed_size(40.0, 40.0, TopHalf)),
+        );
+        let view = TestView::new(share(margin(10.0, element)), Extent::new(60.0, 60.0));
+        assert_eq!(view.limits(), ViewLimits::fixed(60.0, 60.0));
+
+        let pixmap = view.render().unwrap();
+        let pixel = |x, y| {
+            let p = pixmap.pixel(x, y).unwrap().demultiply();
+            (p.red(), p.green(), p.blue())
+        };
+        assert_eq!(pixel(30, 20), (0, 0, 255));
+        assert_eq!(pixel(30, 40), (255, 0, 0));
+        assert_eq!(pixel(10, 30), (0, 255, 0));
+        assert_eq!(pixel(5, 30), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_opacity_fades_subject_as_a_group() {
+        // The background and subject overlap; as a group they fade together
+        let element = Arc::new(opacity(0.5, background(Color::rgb(1.0, 0.0, 0.0), 0.0, TopHalf)));
+        let view = TestView::new(element.clone(), Extent::new(40.0, 40.0));
+        let pixmap = view.render().unwrap();
+        let alpha = |x, y| pixmap.pixel(x, y).unwrap().alpha();
+        assert!((alpha(20, 10) as i32 - 128).abs() <= 1);
+        assert!((alpha(20, 30) as i32 - 128).abs() <= 1);
+        let blue = pixmap.pixel(20, 10).unwrap().demultiply();
+        assert!(blue.blue() > 250 && blue.red() < 5);
+
+        element.set_opacity(0.0);
+        assert_eq!(view.render().unwrap().pixel(20, 10).unwrap().alpha(), 0);
+    }
+}
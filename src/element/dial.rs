@@ -3,12 +3,14 @@
 use std::any::Any;
 use std::sync::RwLock;
 use std::f32::consts::PI;
-use super::{Element, ViewLimits, ViewStretch};
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
+use crate::support::circle::Circle;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
+use crate::support::canvas::{LineCap, TextAlign, HorizontalAlign, VerticalAlign};
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, KeyAction, KeyCode, KeyInfo};
 
 /// Dial state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -22,6 +24,8 @@ pub enum DialState {
 
 /// Callback type for dial value changes.
 pub type DialChangeCallback = Box<dyn Fn(f64) + Send + Sync>;
+/// Formats a dial value for display.
+pub type DialValueFormatter = Box<dyn Fn(f64) -> String + Send + Sync>;
 
 /// A rotary dial/knob element for selecting values.
 pub struct Dial {
@@ -29,15 +33,22 @@ pub struct Dial {
     min_value: f64,
     max_value: f64,
     state: RwLock<DialState>,
-    dial_color: Color,
-    indicator_color: Color,
-    gauge_color: Color,
+    dial_color: ThemeColor,
+    indicator_color: ThemeColor,
+    gauge_color: ThemeColor,
     gauge_width: f32,
     size: f32,
     /// Start angle in radians (measured from top, clockwise positive)
     start_angle: f32,
     /// End angle in radians
     end_angle: f32,
+    /// Value change per arrow key press or scroll step (defaults to 1% of the range)
+    increment: Option<f64>,
+    focused: RwLock<bool>,
+    /// Value restored on double-click (defaults to the middle of the range)
+    default_value: Option<f64>,
+    show_value: bool,
+    value_formatter: Option<DialValueFormatter>,
     enabled: bool,
     on_change: Option<DialChangeCallback>,
     drag_start_y: RwLock<f32>,
@@ -57,13 +68,18 @@ impl Dial {
             min_value: 0.0,
             max_value: 1.0,
             state: RwLock::new(DialState::Normal),
-            dial_color: theme.dial_color,
-            indicator_color: theme.dial_indicator_color,
-            gauge_color: theme.dial_gauge_color,
+            dial_color: ThemeColor::Theme(|theme| theme.dial_color),
+            indicator_color: ThemeColor::Theme(|theme| theme.dial_indicator_color),
+            gauge_color: ThemeColor::Theme(|theme| theme.dial_gauge_color),
             gauge_width: theme.dial_gauge_width,
             size: 50.0,
             start_angle: -135.0 * PI / 180.0,  // -135 degrees from top
             end_angle: 135.0 * PI / 180.0,     // 135 degrees from top
+            increment: None,
+            focused: RwLock::new(false),
+            default_value: None,
+            show_value: false,
+            value_formatter: None,
             enabled: true,
             on_change: None,
             drag_start_y: RwLock::new(0.0),
@@ -108,19 +124,19 @@ impl Dial {
 
     /// Sets the dial color.
     pub fn dial_color(mut self, color: Color) -> Self {
-        self.dial_color = color;
+        self.dial_color = color.into();
         self
     }
 
     /// Sets the indicator color.
     pub fn indicator_color(mut self, color: Color) -> Self {
-        self.indicator_color = color;
+        self.indicator_color = color.into();
         self
     }
 
     /// Sets the gauge color.
     pub fn gauge_color(mut self, color: Color) -> Self {
-        self.gauge_color = color;
+        self.gauge_color = color.into();
         self
     }
 
@@ -136,6 +152,31 @@ impl Dial {
         self
     }
 
+    /// Sets the value change per arrow key press or scroll step.
+    /// Holding Shift uses a tenth of it.
+    pub fn increment(mut self, increment: f64) -> Self {
+        self.increment = Some(increment);
+        self
+    }
+
+    /// Sets the value restored by a double-click.
+    pub fn default_value(mut self, value: f64) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+
+    /// Shows the value as text below the knob.
+    pub fn show_value(mut self, show: bool) -> Self {
+        self.show_value = show;
+        self
+    }
+
+    /// Sets how the value text is formatted.
+    pub fn value_formatter<F: Fn(f64) -> String + Send + Sync + 'static>(mut self, formatter: F) -> Self {
+        self.value_formatter = Some(Box::new(formatter));
+        self
+    }
+
     /// Sets the value change callback.
     pub fn on_change<F: Fn(f64) + Send + Sync + 'static>(mut self, callback: F) -> Self {
         self.on_change = Some(Box::new(callback));
@@ -168,6 +209,109 @@ impl Dial {
         self.set_value(value);
     }
 
+    /// Moves the value by `steps` increments (a tenth as much if `fine`),
+    /// firing the change callback if it changed. Returns whether it did.
+    fn nudge(&self, steps: f64, fine: bool) -> bool {
+        let mut increment = self.increment.unwrap_or((self.max_value - self.min_value) / 100.0);
+        if fine {
+            increment /= 10.0;
+        }
+
+        let previous = self.get_value();
+        self.set_value(previous + steps * increment);
+        self.notify_change(previous)
+    }
+
+    /// Restores the default value, firing the change callback if it
+    /// changed. Returns whether it did.
+    fn reset_to_default(&self) -> bool {
+        let previous = self.get_value();
+        self.set_value(self.default_value.unwrap_or((self.min_value + self.max_value) / 2.0));
+        self.notify_change(previous)
+    }
+
+    /// Fires the change callback if the value moved from `previous`.
+    /// Returns whether it did.
+    fn notify_change(&self, previous: f64) -> bool {
+        let value = self.get_value();
+        if value == previous {
+            return false;
+        }
+        if let Some(ref callback) = self.on_change {
+            callback(value);
+        }
+        true
+    }
+
+    /// Refreshes the dial after its value changed. The change callback may
+    /// update other elements that don't refresh themselves, so with one
+    /// set the whole view is refreshed.
+    fn refresh_value(&self, ctx: &Context) {
+        if self.on_change.is_some() {
+            ctx.view.refresh();
+        } else {
+            self.refresh(ctx, 0);
+        }
+    }
+
+    /// Returns the value as display text. Without a formatter, the number of
+    /// decimals follows the range: two for a range of 1, none from 100 up.
+    fn format_value(&self) -> String {
+        let value = self.get_value();
+        if let Some(ref formatter) = self.value_formatter {
+            return formatter(value);
+        }
+
+        let span = self.max_value - self.min_value;
+        let decimals = if span > 0.0 {
+            (2.0 - span.log10().floor()).clamp(0.0, 4.0) as usize
+        } else {
+            2
+        };
+        format!("{:.*}", decimals, value)
+    }
+
+    /// Returns the height reserved below the knob for the value text.
+    fn value_text_height(&self) -> f32 {
+        if self.show_value {
+            get_theme().label_font_size * 1.4
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the knob center; the knob and value text are centered together.
+    fn knob_center(&self, ctx: &Context) -> Point {
+        let center = ctx.bounds.center();
+        Point::new(center.x, center.y - self.value_text_height() / 2.0)
+    }
+
+    fn draw_value(&self, ctx: &Context) {
+        if !self.show_value {
+            return;
+        }
+
+        let theme = get_theme();
+        let text = self.format_value();
+        let top = self.knob_center(ctx).y + self.size / 2.0 + theme.label_font_size * 0.2;
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        let color = if *self.state.read().unwrap() == DialState::Disabled {
+            theme.label_font_color.with_alpha(0.5)
+        } else {
+            theme.label_font_color
+        };
+        canvas.fill_style(color);
+        canvas.font_size(theme.label_font_size);
+        canvas.text_align(TextAlign {
+            horizontal: HorizontalAlign::Center,
+            vertical: VerticalAlign::Top,
+        });
+        canvas.fill_text(&text, Point::new(ctx.bounds.center().x, top));
+        canvas.restore();
+    }
+
     /// Returns the angle for the current value.
     fn value_to_angle(&self) -> f32 {
         let norm = self.normalized_value() as f32;
@@ -176,90 +320,69 @@ impl Dial {
 
     fn draw_gauge_background(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        let center = ctx.bounds.center();
+        let center = self.knob_center(ctx);
         let radius = self.size / 2.0 - self.gauge_width / 2.0;
 
         // Draw background arc
         let state = *self.state.read().unwrap();
         let color = match state {
-            DialState::Disabled => self.dial_color.with_alpha(0.3),
-            _ => self.dial_color.with_alpha(0.3),
+            DialState::Disabled => self.dial_color.get().with_alpha(0.3),
+            _ => self.dial_color.get().with_alpha(0.3),
         };
 
         canvas.stroke_style(color);
         canvas.line_width(self.gauge_width);
 
-        // Draw arc from start to end angle
-        let segments = 32;
-        let angle_range = self.end_angle - self.start_angle;
-
+        // Draw arc from start to end angle (angles are measured from the top)
         canvas.begin_path();
-        for i in 0..=segments {
-            let t = i as f32 / segments as f32;
-            let angle = self.start_angle + t * angle_range - PI / 2.0; // Adjust for coordinate system
-            let x = center.x + radius * angle.cos();
-            let y = center.y + radius * angle.sin();
-
-            if i == 0 {
-                canvas.move_to(Point::new(x, y));
-            } else {
-                canvas.line_to(Point::new(x, y));
-            }
-        }
+        canvas.arc(center, radius, self.start_angle - PI / 2.0, self.end_angle - PI / 2.0, false);
         canvas.stroke();
     }
 
     fn draw_gauge_value(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        let center = ctx.bounds.center();
+        let center = self.knob_center(ctx);
         let radius = self.size / 2.0 - self.gauge_width / 2.0;
         let current_angle = self.value_to_angle();
 
         let state = *self.state.read().unwrap();
         let color = match state {
-            DialState::Normal => self.gauge_color,
-            DialState::Hover => self.gauge_color.level(1.2),
-            DialState::Dragging => self.gauge_color.level(1.3),
-            DialState::Disabled => self.gauge_color.with_alpha(0.5),
+            DialState::Normal => self.gauge_color.get(),
+            DialState::Hover => self.gauge_color.get().level(1.2),
+            DialState::Dragging => self.gauge_color.get().level(1.3),
+            DialState::Disabled => self.gauge_color.get().with_alpha(0.5),
         };
 
         canvas.stroke_style(color);
         canvas.line_width(self.gauge_width);
 
         // Draw arc from start to current value
-        let segments = 32;
         let angle_range = current_angle - self.start_angle;
 
         if angle_range.abs() > 0.01 {
             canvas.begin_path();
-            let segment_count = ((segments as f32) * (angle_range.abs() / (self.end_angle - self.start_angle))).ceil() as i32;
-            for i in 0..=segment_count.max(1) {
-                let t = i as f32 / segment_count.max(1) as f32;
-                let angle = self.start_angle + t * angle_range - PI / 2.0;
-                let x = center.x + radius * angle.cos();
-                let y = center.y + radius * angle.sin();
-
-                if i == 0 {
-                    canvas.move_to(Point::new(x, y));
-                } else {
-                    canvas.line_to(Point::new(x, y));
-                }
-            }
+            canvas.arc(
+                center,
+                radius,
+                self.start_angle - PI / 2.0,
+                current_angle - PI / 2.0,
+                angle_range < 0.0,
+            );
             canvas.stroke();
         }
     }
 
     fn draw_indicator(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        let center = ctx.bounds.center();
+        let center = self.knob_center(ctx);
         let current_angle = self.value_to_angle() - PI / 2.0;
 
         let state = *self.state.read().unwrap();
         let color = match state {
-            DialState::Normal => self.indicator_color,
-            DialState::Hover => self.indicator_color.level(1.2),
-            DialState::Dragging => self.indicator_color.level(1.3),
-            DialState::Disabled => self.indicator_color.with_alpha(0.5),
+            DialState::Normal => self.indicator_color.get(),
+            DialState::Hover => self.indicator_color.get().level(1.2),
+            DialState::Dragging => self.indicator_color.get().level(1.3),
+            DialState::Disabled => self.indicator_color.get().with_alpha(0.5),
         };
 
         // Draw indicator line
@@ -271,12 +394,15 @@ impl Dial {
         let outer_x = center.x + outer_radius * current_angle.cos();
         let outer_y = center.y + outer_radius * current_angle.sin();
 
+        canvas.save();
         canvas.stroke_style(color);
         canvas.line_width(2.0);
+        canvas.line_cap(LineCap::Round);
         canvas.begin_path();
         canvas.move_to(Point::new(inner_x, inner_y));
         canvas.line_to(Point::new(outer_x, outer_y));
         canvas.stroke();
+        canvas.restore();
 
         // Draw center dot
         canvas.fill_style(color);
@@ -294,7 +420,7 @@ impl Default for Dial {
 
 impl Element for Dial {
     fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
-        ViewLimits::fixed(self.size, self.size)
+        ViewLimits::fixed(self.size, self.size + self.value_text_height())
     }
 
     fn stretch(&self) -> ViewStretch {
@@ -305,16 +431,13 @@ impl Element for Dial {
         self.draw_gauge_background(ctx);
         self.draw_gauge_value(ctx);
         self.draw_indicator(ctx);
+        self.draw_value(ctx);
     }
 
     fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
         if ctx.bounds.contains(p) && self.enabled {
             // Check if within the circular dial area
-            let center = ctx.bounds.center();
-            let dx = p.x - center.x;
-            let dy = p.y - center.y;
-            let dist = (dx * dx + dy * dy).sqrt();
-            if dist <= self.size / 2.0 {
+            if Circle::new(self.knob_center(ctx), self.size / 2.0).contains(p) {
                 return Some(self);
             }
         }
@@ -331,10 +454,22 @@ impl Element for Dial {
         }
 
         let mut state = self.state.write().unwrap();
+        if btn.down && btn.click_count == 2 {
+            // A double-click resets the dial instead of turning it
+            *state = DialState::Hover;
+            drop(state);
+            if self.reset_to_default() {
+                self.refresh_value(ctx);
+            } else {
+                self.refresh(ctx, 0);
+            }
+            return true;
+        }
         if btn.down {
             *state = DialState::Dragging;
+            *self.focused.write().unwrap() = true;
             // Store dial center for angular calculations
-            let center = ctx.bounds.center();
+            let center = self.knob_center(ctx);
             *self.dial_center.write().unwrap() = center;
             *self.drag_start_y.write().unwrap() = btn.pos.y;
             *self.drag_start_value.write().unwrap() = self.get_value();
@@ -347,7 +482,9 @@ impl Element for Dial {
                 DialState::Normal
             };
         }
+        drop(state);
 
+        self.refresh(ctx, 0);
         true
     }
 
@@ -355,8 +492,8 @@ impl Element for Dial {
         self.handle_drag(ctx, btn);
     }
 
-    fn handle_drag(&self, _ctx: &Context, btn: MouseButton) {
-        if !self.enabled {
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if !self.enabled || *self.state.read().unwrap() != DialState::Dragging {
             return;
         }
 
@@ -384,11 +521,74 @@ impl Element for Dial {
         let start_normalized = (drag_start_value - self.min_value) / (self.max_value - self.min_value);
         let new_normalized = (start_normalized + delta_normalized).clamp(0.0, 1.0);
 
+        let previous = self.get_value();
         self.set_normalized_value(new_normalized);
+        if self.notify_change(previous) {
+            self.refresh_value(ctx);
+        }
+    }
 
-        if let Some(ref callback) = self.on_change {
-            callback(self.get_value());
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if !self.enabled || !*self.focused.read().unwrap() || k.action == KeyAction::Release {
+            return false;
+        }
+
+        let fine = k.modifiers & crate::view::modifiers::SHIFT != 0;
+        let changed = match k.key {
+            KeyCode::Up | KeyCode::Right => self.nudge(1.0, fine),
+            KeyCode::Down | KeyCode::Left => self.nudge(-1.0, fine),
+            _ => return false,
+        };
+        if changed {
+            self.refresh_value(ctx);
         }
+        true
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, _p: Point) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        // Scrolling up (or right) turns the dial clockwise by one step,
+        // whether the delta is in wheel notches or trackpad pixels
+        let delta = if dir.y != 0.0 { dir.y } else { dir.x };
+        if delta == 0.0 {
+            return false;
+        }
+        let fine = ctx.view.modifiers() & crate::view::modifiers::SHIFT != 0;
+        if self.nudge(delta.signum() as f64, fine) {
+            self.refresh_value(ctx);
+        }
+        true
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.enabled
+    }
+
+    fn clear_focus(&self) {
+        *self.focused.write().unwrap() = false;
+    }
+
+    fn has_focus(&self) -> bool {
+        *self.focused.read().unwrap()
+    }
+
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        *self.focused.write().unwrap() = true;
+        true
     }
 
     fn cursor(&mut self, _ctx: &Context, _p: Point, status: CursorTracking) -> bool {
@@ -445,3 +645,122 @@ pub fn dial() -> Dial {
 pub fn dial_with_range(min: f64, max: f64) -> Dial {
     Dial::with_range(min, max)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::support::rect::Rect;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_turning_refreshes_only_the_dial() {
+        let dial = Arc::new(dial());
+        let view = TestView::new(dial.clone(), Extent::new(400.0, 300.0));
+        let bounds = Rect::new(100.0, 50.0, 160.0, 110.0);
+
+        view.with_context(|ctx| {
+            assert!(dial.handle_scroll(&ctx.with_bounds(bounds), Point::new(0.0, 1.0), bounds.center()));
+        });
+        assert_eq!(view.take_dirty(), Some(bounds));
+        assert_eq!(view.take_dirty(), None);
+
+        // Refreshes accumulate until the host collects them
+        view.with_context(|ctx| {
+            ctx.view.refresh_area(Rect::new(0.0, 0.0, 10.0, 10.0));
+            ctx.view.refresh_area(Rect::new(390.0, 290.0, 500.0, 500.0));
+        });
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 0.0, 400.0, 300.0)));
+    }
+
+    #[test]
+    fn test_keys_and_scroll_step() {
+        use std::sync::Mutex;
+        use crate::view::modifiers;
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        let dial = Arc::new(dial_with_range(0.0, 100.0).value(50.0).on_change(move |v| sink.lock().unwrap().push(v)));
+        let view = TestView::new(dial.clone(), Extent::new(60.0, 60.0));
+        let value = || (dial.get_value() * 1000.0).round() / 1000.0;
+
+        // Keys only turn a focused dial
+        assert!(!view.key(KeyCode::Up, 0));
+        assert!(view.focus_first());
+        assert!(view.key(KeyCode::Up, 0));
+        assert_eq!(value(), 51.0);
+        assert!(view.key(KeyCode::Left, modifiers::SHIFT));
+        assert_eq!(value(), 50.9);
+
+        // A scroll event is one step, however far it scrolled
+        assert!(view.scroll(Point::new(0.0, 3.0), Point::new(30.0, 30.0)));
+        assert_eq!(value(), 51.9);
+        assert!(view.scroll(Point::new(0.0, -120.0), Point::new(30.0, 30.0)));
+        assert_eq!(value(), 50.9);
+        assert!(view.scroll_with_modifiers(Point::new(0.0, 1.0), Point::new(30.0, 30.0), modifiers::SHIFT));
+        assert_eq!(value(), 51.0);
+
+        // The value stops at the ends of the range, without reporting a change
+        dial.set_value(99.5);
+        changes.lock().unwrap().clear();
+        view.key(KeyCode::Right, 0);
+        view.key(KeyCode::Right, 0);
+        assert_eq!(dial.get_value(), 100.0);
+        assert_eq!(*changes.lock().unwrap(), vec![100.0]);
+    }
+
+    #[test]
+    fn test_change_callback_refreshes_the_view() {
+        let dial = Arc::new(dial_with_range(0.0, 100.0).value(50.0).on_change(|_| {}));
+        let view = TestView::new(dial.clone(), Extent::new(400.0, 300.0));
+        let bounds = Rect::new(100.0, 50.0, 160.0, 110.0);
+
+        // The callback may have changed anything, so everything repaints
+        view.with_context(|ctx| {
+            assert!(dial.handle_scroll(&ctx.with_bounds(bounds), Point::new(0.0, 1.0), bounds.center()));
+        });
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 0.0, 400.0, 300.0)));
+
+        // Nothing changes at the end of the range
+        dial.set_value(100.0);
+        view.with_context(|ctx| {
+            assert!(dial.handle_scroll(&ctx.with_bounds(bounds), Point::new(0.0, 1.0), bounds.center()));
+        });
+        assert_eq!(view.take_dirty(), None);
+    }
+
+    #[test]
+    fn test_double_click_resets_without_turning() {
+        use std::sync::Mutex;
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        let dial = Arc::new(
+            dial_with_range(0.0, 100.0)
+                .value(20.0)
+                .default_value(20.0)
+                .on_change(move |v| sink.lock().unwrap().push(v)),
+        );
+        let view = TestView::new(dial.clone(), Extent::new(60.0, 60.0));
+        let reset = |view: &TestView| {
+            view.click_at(Point::new(30.0, 10.0));
+            let mut btn = MouseButton::new(true, MouseButtonKind::Left, Point::new(30.0, 10.0));
+            btn.click_count = 2;
+            view.mouse(btn);
+        };
+
+        // Already at the default: no change is reported, and dragging on
+        // from the second press leaves the value alone
+        reset(&view);
+        view.drag_to(Point::new(55.0, 30.0));
+        assert_eq!(dial.get_value(), 20.0);
+        assert!(changes.lock().unwrap().is_empty());
+        view.release(Point::new(55.0, 30.0));
+
+        dial.set_value(70.0);
+        reset(&view);
+        assert_eq!(dial.get_value(), 20.0);
+        assert_eq!(*changes.lock().unwrap(), vec![20.0]);
+    }
+}
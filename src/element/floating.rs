@@ -2,13 +2,16 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, share};
+use super::{Element, ElementPtr, FocusRequest, ViewLimits, ViewStretch, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind};
+use crate::support::theme::ThemeColor;
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, DropInfo, KeyInfo, TextInfo};
+
+/// How much of a dragged floating element must stay inside the view.
+const MIN_VISIBLE: f32 = 24.0;
 
 /// A floating element that can be positioned freely and dragged.
 pub struct Floating {
@@ -17,29 +20,31 @@ pub struct Floating {
     size: RwLock<Point>,
     dragging: RwLock<bool>,
     drag_offset: RwLock<Point>,
-    background_color: Color,
-    border_color: Color,
+    background_color: ThemeColor,
+    border_color: ThemeColor,
     corner_radius: f32,
     shadow: bool,
     draggable: bool,
+    /// Area that starts a drag, relative to the element's top-left corner
+    drag_handle: Option<Rect>,
     visible: RwLock<bool>,
 }
 
 impl Floating {
     /// Creates a new floating element.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             content: None,
             position: RwLock::new(Point::new(100.0, 100.0)),
             size: RwLock::new(Point::new(200.0, 150.0)),
             dragging: RwLock::new(false),
             drag_offset: RwLock::new(Point::zero()),
-            background_color: theme.element_background_color,
-            border_color: theme.frame_color,
+            background_color: ThemeColor::Theme(|theme| theme.element_background_color),
+            border_color: ThemeColor::Theme(|theme| theme.frame_color),
             corner_radius: 8.0,
             shadow: true,
             draggable: true,
+            drag_handle: None,
             visible: RwLock::new(true),
         }
     }
@@ -64,7 +69,7 @@ impl Floating {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
@@ -74,6 +79,14 @@ impl Floating {
         self
     }
 
+    /// Restricts dragging to `handle`, given relative to the element's
+    /// top-left corner (such as a title strip). By default the whole
+    /// element is a handle.
+    pub fn drag_handle(mut self, handle: Rect) -> Self {
+        self.drag_handle = Some(handle);
+        self
+    }
+
     /// Sets whether to show a shadow.
     pub fn shadow(mut self, shadow: bool) -> Self {
         self.shadow = shadow;
@@ -110,6 +123,24 @@ impl Floating {
         let size = *self.size.read().unwrap();
         Rect::new(pos.x, pos.y, pos.x + size.x, pos.y + size.y)
     }
+
+    /// Returns the area that starts a drag, in view coordinates.
+    fn drag_handle_bounds(&self) -> Rect {
+        let bounds = self.floating_bounds();
+        match self.drag_handle {
+            Some(handle) => handle.translate(bounds.left, bounds.top),
+            None => bounds,
+        }
+    }
+
+    /// Moves the element to `pos`, keeping enough of it inside `view` to
+    /// grab it again. The top edge always stays inside.
+    fn move_to(&self, pos: Point, view: Rect) {
+        let size = *self.size.read().unwrap();
+        let x = pos.x.min(view.right - MIN_VISIBLE).max(view.left + MIN_VISIBLE - size.x);
+        let y = pos.y.min(view.bottom - MIN_VISIBLE).max(view.top);
+        *self.position.write().unwrap() = Point::new(x, y);
+    }
 }
 
 impl Default for Floating {
@@ -144,11 +175,11 @@ impl Element for Floating {
         }
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(bounds, self.corner_radius);
 
         // Border
-        canvas.stroke_style(self.border_color);
+        canvas.stroke_style(self.border_color.get());
         canvas.line_width(1.0);
         canvas.begin_path();
         canvas.add_round_rect(bounds, self.corner_radius);
@@ -210,7 +241,7 @@ impl Element for Floating {
                 }
 
                 // Start dragging
-                if self.draggable {
+                if self.draggable && self.drag_handle_bounds().contains(btn.pos) {
                     *self.dragging.write().unwrap() = true;
                     let pos = *self.position.read().unwrap();
                     *self.drag_offset.write().unwrap() = Point::new(btn.pos.x - pos.x, btn.pos.y - pos.y);
@@ -234,13 +265,128 @@ impl Element for Floating {
         bounds.contains(btn.pos)
     }
 
-    fn drag(&mut self, _ctx: &Context, btn: MouseButton) {
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
         if *self.dragging.read().unwrap() {
             let offset = *self.drag_offset.read().unwrap();
-            *self.position.write().unwrap() = Point::new(btn.pos.x - offset.x, btn.pos.y - offset.y);
+            self.move_to(Point::new(btn.pos.x - offset.x, btn.pos.y - offset.y), ctx.view_bounds());
+        } else if let Some(ref content) = self.content {
+            let content_ctx = ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0));
+            content.handle_drag(&content_ctx, btn);
         }
     }
 
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        let content_ctx = ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0));
+        match self.content {
+            Some(ref content) if self.is_visible() && content_ctx.bounds.contains(p) => {
+                content.handle_scroll(&content_ctx, dir, p)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        let content_ctx = ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0));
+        match self.content {
+            Some(ref content) if self.is_visible() && content_ctx.bounds.contains(center) => {
+                content.handle_magnify(&content_ctx, scale_delta, center)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        match self.content {
+            Some(ref content) if self.is_visible() => {
+                content.handle_key(&ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0)), k)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        match self.content {
+            Some(ref content) if self.is_visible() => {
+                content.handle_text(&ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0)), info)
+            }
+            _ => false,
+        }
+    }
+
+    // A hidden panel is skipped when tabbing
+    fn wants_focus(&self) -> bool {
+        self.is_visible() && self.content.as_ref().is_some_and(|content| content.wants_focus())
+    }
+
+    fn clear_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.clear_focus();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.is_visible() && self.content.as_ref().is_some_and(|content| content.handle_begin_focus(req))
+    }
+
+    fn handle_end_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.handle_end_focus();
+        }
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_focus_next(forward))
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_poll())
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        let bounds = self.floating_bounds();
+        if std::ptr::addr_eq(self, element) {
+            return Some(bounds);
+        }
+        self.content.as_ref()?.bounds_of_element(&ctx.with_bounds(bounds.inset(8.0, 8.0)), element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.content.as_ref()?.focus_bounds(&ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0)))
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        let status = if self.is_visible() { status } else { CursorTracking::Leaving };
+        if let Some(ref content) = self.content {
+            content.handle_track_drop(&ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0)), info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        let content_ctx = ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0));
+        match self.content {
+            Some(ref content) if self.is_visible() && content_ctx.bounds.contains(info.where_) => {
+                content.handle_drop(&content_ctx, info)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // The mouse is never over the content of a hidden panel
+        let status = if self.is_visible() { status } else { CursorTracking::Leaving };
+        self.content.as_ref().is_some_and(|content| {
+            content.handle_cursor(&ctx.with_bounds(self.floating_bounds().inset(8.0, 8.0)), p, status)
+        })
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -254,3 +400,114 @@ impl Element for Floating {
 pub fn floating() -> Floating {
     Floating::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_drag_by_handle_and_clamp() {
+        let panel = Arc::new(
+            floating()
+                .position(100.0, 100.0)
+                .size(200.0, 150.0)
+                .drag_handle(Rect::new(0.0, 0.0, 200.0, 24.0)),
+        );
+        let view = TestView::new(panel.clone(), Extent::new(800.0, 600.0));
+
+        // Only the handle starts a drag
+        view.drag(Point::new(150.0, 200.0), Point::new(250.0, 300.0));
+        assert_eq!(panel.get_position(), Point::new(100.0, 100.0));
+
+        view.press(Point::new(150.0, 110.0));
+        view.drag_to(Point::new(250.0, 210.0));
+        assert_eq!(panel.get_position(), Point::new(200.0, 200.0));
+
+        // Dragging far off the view leaves a strip of the panel inside it
+        view.drag_to(Point::new(-1000.0, -1000.0));
+        assert_eq!(panel.get_position(), Point::new(MIN_VISIBLE - 200.0, 0.0));
+        view.drag_to(Point::new(5000.0, 5000.0));
+        assert_eq!(panel.get_position(), Point::new(800.0 - MIN_VISIBLE, 600.0 - MIN_VISIBLE));
+    }
+
+    #[test]
+    fn test_tab_skips_hidden_panel() {
+        use crate::view::KeyCode;
+        use crate::element::text_box::text_box;
+        use crate::element::layer::Layer;
+        use crate::element::size::fixed_size;
+
+        let field = Arc::new(fixed_size(200.0, 20.0, text_box()));
+        let panel = Arc::new(floating().position(100.0, 100.0).content(text_box()));
+        let view = TestView::new(Arc::new(Layer::from_vec(vec![field.clone(), panel.clone()])), Extent::new(800.0, 600.0));
+        assert!(view.focus_first());
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(panel.has_focus() && !field.has_focus());
+        assert!(view.type_text("x"));
+
+        panel.hide();
+        assert!(!view.type_text("x"));
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(field.has_focus() && !panel.has_focus());
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(field.has_focus());
+    }
+
+    #[test]
+    fn test_drop_reaches_content() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::element::proxy::{on_drop, DropTarget};
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let sink = drops.clone();
+        let target = on_drop(crate::element::empty(), move |_: &DropInfo| {
+            sink.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+        let panel = Arc::new(floating().position(100.0, 100.0).size(200.0, 150.0).content(target));
+        let view = TestView::new(panel.clone(), Extent::new(800.0, 600.0));
+        let content = panel.content.as_ref().unwrap().as_any().downcast_ref::<DropTarget>().unwrap();
+
+        let inside = DropInfo::new(Point::new(150.0, 150.0)).with_text("x");
+        view.track_drop(&inside, CursorTracking::Entering);
+        assert!(content.is_hovering());
+        assert!(view.perform_drop(&inside));
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+
+        // Drops outside the panel, or on a hidden one, are not taken
+        assert!(!view.perform_drop(&DropInfo::new(Point::new(500.0, 500.0)).with_text("x")));
+        panel.hide();
+        view.track_drop(&inside, CursorTracking::Hovering);
+        assert!(!content.is_hovering());
+        assert!(!view.perform_drop(&inside));
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_scroll_and_pinch_reach_content() {
+        use crate::element::dial::{dial_with_range, Dial};
+        use crate::element::zoom::{zoomable, Zoomable};
+        use crate::element::Empty;
+
+        let panel = Arc::new(floating().position(100.0, 100.0).size(76.0, 76.0).content(dial_with_range(0.0, 100.0)));
+        let view = TestView::new(panel.clone(), Extent::new(800.0, 600.0));
+        let knob = panel.content.as_ref().unwrap().as_any().downcast_ref::<Dial>().unwrap();
+        assert!(view.scroll(Point::new(0.0, 1.0), Point::new(138.0, 138.0)));
+        assert_eq!(knob.get_value(), 1.0);
+        assert!(!view.scroll(Point::new(0.0, 1.0), Point::new(500.0, 500.0)));
+
+        let panel = Arc::new(floating().position(100.0, 100.0).content(zoomable(crate::element::empty())));
+        let view = TestView::new(panel.clone(), Extent::new(800.0, 600.0));
+        let zoom = panel.content.as_ref().unwrap().as_any().downcast_ref::<Zoomable<Empty>>().unwrap();
+        assert!(view.magnify(1.0, Point::new(150.0, 150.0)));
+        assert_eq!(zoom.get_scale(), 2.0);
+        assert!(!view.magnify(1.0, Point::new(500.0, 500.0)));
+
+        panel.hide();
+        assert!(!view.magnify(1.0, Point::new(150.0, 150.0)));
+        assert_eq!(zoom.get_scale(), 2.0);
+    }
+}
@@ -2,18 +2,52 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, FULL_EXTENT};
+use super::{Element, ElementPtr, ViewLimits, ViewStretch, FocusRequest, FULL_EXTENT};
 use super::context::{BasicContext, Context};
 use super::composite::{Storage, CompositeBase, Composite};
+use super::proxy::Proxy;
 use crate::support::point::Point;
 use crate::support::rect::Rect;
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
+
+/// Position of a child in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    row: usize,
+    col: usize,
+    span: usize,
+}
+
+/// Column and row sizes computed for a given bounds.
+#[derive(Debug, Clone)]
+struct GridLayout {
+    bounds: Rect,
+    cells: Vec<Cell>,
+    col_widths: Vec<f32>,
+    row_heights: Vec<f32>,
+}
+
+/// Minimum and maximum sizes and stretches of the grid's columns and rows.
+#[derive(Debug, Clone)]
+struct GridSizes {
+    col_min: Vec<f32>,
+    col_max: Vec<f32>,
+    col_stretch: Vec<f32>,
+    row_min: Vec<f32>,
+    row_max: Vec<f32>,
+    row_stretch: Vec<f32>,
+}
 
 /// A grid layout element that arranges children in rows and columns.
+///
+/// Children fill the grid left to right, top to bottom. A child whose
+/// [`Element::span`] is greater than one covers that many columns; if it
+/// doesn't fit in the rest of the current row it starts on the next one.
 pub struct Grid {
     inner: Composite,
     columns: usize,
-    row_heights: RwLock<Vec<f32>>,
-    col_widths: RwLock<Vec<f32>>,
+    fixed_row_heights: Vec<f32>,
+    layout: RwLock<Option<GridLayout>>,
     h_gap: f32,
     v_gap: f32,
 }
@@ -21,14 +55,7 @@ pub struct Grid {
 impl Grid {
     /// Creates a new grid with the specified number of columns.
     pub fn new(columns: usize) -> Self {
-        Self {
-            inner: Composite::new(),
-            columns: columns.max(1),
-            row_heights: RwLock::new(Vec::new()),
-            col_widths: RwLock::new(Vec::new()),
-            h_gap: 4.0,
-            v_gap: 4.0,
-        }
+        Self::from_vec(columns, Vec::new())
     }
 
     /// Creates a grid from a vector of elements.
@@ -36,13 +63,22 @@ impl Grid {
         Self {
             inner: Composite::from_vec(children),
             columns: columns.max(1),
-            row_heights: RwLock::new(Vec::new()),
-            col_widths: RwLock::new(Vec::new()),
+            fixed_row_heights: Vec::new(),
+            layout: RwLock::new(None),
             h_gap: 4.0,
             v_gap: 4.0,
         }
     }
 
+    /// Sets explicit row heights.
+    ///
+    /// Row `i` gets `heights[i]` instead of a height computed from its
+    /// children. Rows beyond the list are sized from their children.
+    pub fn row_heights(mut self, heights: Vec<f32>) -> Self {
+        self.fixed_row_heights = heights;
+        self
+    }
+
     /// Sets the horizontal gap between columns.
     pub fn h_gap(mut self, gap: f32) -> Self {
         self.h_gap = gap;
@@ -65,72 +101,162 @@ impl Grid {
     /// Adds an element.
     pub fn push(&mut self, element: ElementPtr) {
         self.inner.push(element);
+        *self.layout.write().unwrap() = None;
     }
 
-    /// Returns the number of rows.
-    fn row_count(&self) -> usize {
-        let count = self.inner.len();
-        (count + self.columns - 1) / self.columns
-    }
+    /// Assigns each child a row, column and column span.
+    fn place_cells(&self) -> Vec<Cell> {
+        let mut cells = Vec::with_capacity(self.inner.len());
+        let (mut row, mut col) = (0, 0);
 
-    fn compute_layout(&self, ctx: &BasicContext, bounds: &Rect) {
-        let count = self.inner.len();
-        if count == 0 {
-            return;
+        for i in 0..self.inner.len() {
+            let span = self.inner.at(i)
+                .map_or(1, |child| child.span() as usize)
+                .clamp(1, self.columns);
+            if col + span > self.columns {
+                row += 1;
+                col = 0;
+            }
+            cells.push(Cell { row, col, span });
+            col += span;
+            if col == self.columns {
+                row += 1;
+                col = 0;
+            }
         }
 
-        let rows = self.row_count();
-
-        // Calculate minimum sizes for each row and column
-        let mut col_min_widths = vec![0.0f32; self.columns];
-        let mut col_stretches = vec![0.0f32; self.columns];
-        let mut row_min_heights = vec![0.0f32; rows];
-        let mut row_stretches = vec![0.0f32; rows];
+        cells
+    }
 
-        for i in 0..count {
-            let row = i / self.columns;
-            let col = i % self.columns;
+    /// Returns the number of rows needed for the given cells.
+    fn row_count(cells: &[Cell]) -> usize {
+        cells.last().map_or(0, |cell| cell.row + 1)
+    }
 
-            if let Some(child) = self.inner.at(i) {
-                let limits = child.limits(ctx);
-                let stretch = child.stretch();
+    /// Returns the fixed height of a row, if one was given.
+    fn fixed_row_height(&self, row: usize) -> Option<f32> {
+        self.fixed_row_heights.get(row).copied()
+    }
 
-                col_min_widths[col] = col_min_widths[col].max(limits.min.x);
-                col_stretches[col] = col_stretches[col].max(stretch.x);
-                row_min_heights[row] = row_min_heights[row].max(limits.min.y);
-                row_stretches[row] = row_stretches[row].max(stretch.y);
+    /// Grows the columns covered by `cell` so together they are at least
+    /// `size` wide, spreading any shortfall evenly.
+    fn fit_span(&self, sizes: &mut [f32], cell: Cell, size: f32) {
+        let columns = &mut sizes[cell.col..cell.col + cell.span];
+        let current = columns.iter().sum::<f32>() + self.h_gap * (cell.span - 1) as f32;
+        if size > current {
+            let extra = (size - current) / cell.span as f32;
+            for width in columns {
+                *width += extra;
             }
         }
+    }
+
+    /// Computes the minimum and maximum sizes and stretches of every column and row.
+    fn measure(&self, ctx: &BasicContext, cells: &[Cell]) -> GridSizes {
+        let rows = Self::row_count(cells);
+        let mut col_min = vec![0.0f32; self.columns];
+        let mut col_max = vec![0.0f32; self.columns];
+        let mut col_stretch = vec![0.0f32; self.columns];
+        let mut row_min = vec![0.0f32; rows];
+        let mut row_max = vec![0.0f32; rows];
+        let mut row_stretch = vec![0.0f32; rows];
+
+        // Single-column cells first, so spanning cells only add what's missing
+        let mut spanning = Vec::new();
+        for (i, cell) in cells.iter().enumerate() {
+            let Some(child) = self.inner.at(i) else { continue };
+            let limits = child.limits(ctx);
+            let stretch = child.stretch();
+
+            if cell.span == 1 {
+                col_min[cell.col] = col_min[cell.col].max(limits.min.x);
+                col_max[cell.col] = col_max[cell.col].max(limits.max.x);
+            } else {
+                spanning.push((*cell, limits));
+            }
+            for s in &mut col_stretch[cell.col..cell.col + cell.span] {
+                *s = s.max(stretch.x);
+            }
 
-        // Calculate total minimum sizes
-        let total_min_width: f32 = col_min_widths.iter().sum::<f32>() + self.h_gap * (self.columns - 1) as f32;
-        let total_min_height: f32 = row_min_heights.iter().sum::<f32>() + self.v_gap * (rows - 1) as f32;
+            row_min[cell.row] = row_min[cell.row].max(limits.min.y);
+            row_max[cell.row] = row_max[cell.row].max(limits.max.y);
+            row_stretch[cell.row] = row_stretch[cell.row].max(stretch.y);
+        }
 
-        // Calculate extra space
-        let extra_width = (bounds.width() - total_min_width).max(0.0);
-        let extra_height = (bounds.height() - total_min_height).max(0.0);
+        for (cell, limits) in spanning {
+            self.fit_span(&mut col_min, cell, limits.min.x);
+            self.fit_span(&mut col_max, cell, limits.max.x);
+        }
 
-        let total_col_stretch: f32 = col_stretches.iter().sum();
-        let total_row_stretch: f32 = row_stretches.iter().sum();
+        for row in 0..rows {
+            if let Some(height) = self.fixed_row_height(row) {
+                row_min[row] = height;
+                row_max[row] = height;
+                row_stretch[row] = 0.0;
+            }
+        }
 
-        // Distribute extra space
-        let mut col_widths = col_min_widths.clone();
-        let mut row_heights = row_min_heights.clone();
+        GridSizes { col_min, col_max, col_stretch, row_min, row_max, row_stretch }
+    }
 
-        if total_col_stretch > 0.0 {
-            for (i, stretch) in col_stretches.iter().enumerate() {
-                col_widths[i] += extra_width * (stretch / total_col_stretch);
+    /// Distributes `extra` space over `sizes` in proportion to `stretches`.
+    fn distribute(sizes: &mut [f32], stretches: &[f32], extra: f32) {
+        let total: f32 = stretches.iter().sum();
+        if total > 0.0 {
+            for (size, stretch) in sizes.iter_mut().zip(stretches) {
+                *size += extra * (stretch / total);
             }
         }
+    }
+
+    fn compute_layout(&self, ctx: &BasicContext, bounds: &Rect) -> GridLayout {
+        let cells = self.place_cells();
+        let rows = Self::row_count(&cells);
+        let sizes = self.measure(ctx, &cells);
+        let mut col_widths = sizes.col_min;
+        let mut row_heights = sizes.row_min;
+
+        let total_min_width: f32 = col_widths.iter().sum::<f32>() + self.h_gap * (self.columns - 1) as f32;
+        let total_min_height: f32 = row_heights.iter().sum::<f32>() + self.v_gap * rows.saturating_sub(1) as f32;
+
+        Self::distribute(&mut col_widths, &sizes.col_stretch, (bounds.width() - total_min_width).max(0.0));
+        Self::distribute(&mut row_heights, &sizes.row_stretch, (bounds.height() - total_min_height).max(0.0));
 
-        if total_row_stretch > 0.0 {
-            for (i, stretch) in row_stretches.iter().enumerate() {
-                row_heights[i] += extra_height * (stretch / total_row_stretch);
+        GridLayout {
+            bounds: *bounds,
+            cells,
+            col_widths,
+            row_heights,
+        }
+    }
+
+    /// Runs `f` with the layout for the context's bounds, recomputing it if
+    /// the bounds changed.
+    fn with_layout<R>(&self, ctx: &Context, f: impl FnOnce(&GridLayout) -> R) -> R {
+        {
+            let layout = self.layout.read().unwrap();
+            if let Some(layout) = layout.as_ref().filter(|l| l.bounds == ctx.bounds) {
+                return f(layout);
             }
         }
+        let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
+        let layout = self.compute_layout(&basic_ctx, &ctx.bounds);
+        let result = f(&layout);
+        *self.layout.write().unwrap() = Some(layout);
+        result
+    }
 
-        *self.col_widths.write().unwrap() = col_widths;
-        *self.row_heights.write().unwrap() = row_heights;
+    /// Returns the rect of a cell within the layout.
+    fn cell_bounds(&self, layout: &GridLayout, cell: Cell) -> Rect {
+        let x = layout.bounds.left
+            + layout.col_widths[..cell.col].iter().map(|w| w + self.h_gap).sum::<f32>();
+        let y = layout.bounds.top
+            + layout.row_heights[..cell.row].iter().map(|h| h + self.v_gap).sum::<f32>();
+        let width = layout.col_widths[cell.col..cell.col + cell.span].iter().sum::<f32>()
+            + self.h_gap * (cell.span - 1) as f32;
+        let height = layout.row_heights[cell.row];
+
+        Rect::new(x, y, x + width, y + height)
     }
 }
 
@@ -156,81 +282,35 @@ impl Storage for Grid {
 
 impl CompositeBase for Grid {
     fn bounds_of(&self, ctx: &Context, index: usize) -> Rect {
-        let count = self.inner.len();
-        if index >= count {
+        if index >= self.inner.len() {
             return Rect::zero();
         }
 
-        // Ensure layout is computed
-        {
-            let col_widths = self.col_widths.read().unwrap();
-            if col_widths.is_empty() || col_widths.len() != self.columns {
-                drop(col_widths);
-                let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
-                self.compute_layout(&basic_ctx, &ctx.bounds);
-            }
-        }
-
-        let col_widths = self.col_widths.read().unwrap();
-        let row_heights = self.row_heights.read().unwrap();
-
-        let row = index / self.columns;
-        let col = index % self.columns;
-
-        let mut x = ctx.bounds.left;
-        for i in 0..col {
-            x += col_widths.get(i).copied().unwrap_or(0.0) + self.h_gap;
-        }
-
-        let mut y = ctx.bounds.top;
-        for i in 0..row {
-            y += row_heights.get(i).copied().unwrap_or(0.0) + self.v_gap;
-        }
-
-        let width = col_widths.get(col).copied().unwrap_or(0.0);
-        let height = row_heights.get(row).copied().unwrap_or(0.0);
-
-        Rect::new(x, y, x + width, y + height)
+        self.with_layout(ctx, |layout| self.cell_bounds(layout, layout.cells[index]))
     }
 }
 
 impl Element for Grid {
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
-        let count = self.inner.len();
-        if count == 0 {
+        if self.inner.is_empty() {
             return ViewLimits::fixed(0.0, 0.0);
         }
 
-        let rows = self.row_count();
-
-        let mut col_min_widths = vec![0.0f32; self.columns];
-        let mut col_max_widths = vec![0.0f32; self.columns];
-        let mut row_min_heights = vec![0.0f32; rows];
-        let mut row_max_heights = vec![0.0f32; rows];
+        let cells = self.place_cells();
+        let rows = Self::row_count(&cells);
+        let sizes = self.measure(ctx, &cells);
 
-        for i in 0..count {
-            let row = i / self.columns;
-            let col = i % self.columns;
-
-            if let Some(child) = self.inner.at(i) {
-                let limits = child.limits(ctx);
-                col_min_widths[col] = col_min_widths[col].max(limits.min.x);
-                col_max_widths[col] = col_max_widths[col].max(limits.max.x);
-                row_min_heights[row] = row_min_heights[row].max(limits.min.y);
-                row_max_heights[row] = row_max_heights[row].max(limits.max.y);
-            }
-        }
-
-        let total_min_width: f32 = col_min_widths.iter().sum::<f32>() + self.h_gap * (self.columns.saturating_sub(1)) as f32;
-        let total_max_width: f32 = col_max_widths.iter().sum::<f32>() + self.h_gap * (self.columns.saturating_sub(1)) as f32;
-        let total_min_height: f32 = row_min_heights.iter().sum::<f32>() + self.v_gap * rows.saturating_sub(1) as f32;
-        let total_max_height: f32 = row_max_heights.iter().sum::<f32>() + self.v_gap * rows.saturating_sub(1) as f32;
+        let h_gaps = self.h_gap * (self.columns - 1) as f32;
+        let v_gaps = self.v_gap * rows.saturating_sub(1) as f32;
 
         ViewLimits {
-            min: Point::new(total_min_width, total_min_height),
+            min: Point::new(
+                sizes.col_min.iter().sum::<f32>() + h_gaps,
+                sizes.row_min.iter().sum::<f32>() + v_gaps,
+            ),
             max: Point::new(
-                total_max_width.min(FULL_EXTENT),
-                total_max_height.min(FULL_EXTENT),
+                (sizes.col_max.iter().sum::<f32>() + h_gaps).min(FULL_EXTENT),
+                (sizes.row_max.iter().sum::<f32>() + v_gaps).min(FULL_EXTENT),
             ),
         }
     }
@@ -252,16 +332,6 @@ impl Element for Grid {
     }
 
     fn draw(&self, ctx: &Context) {
-        // Ensure layout is computed
-        {
-            let col_widths = self.col_widths.read().unwrap();
-            if col_widths.is_empty() {
-                drop(col_widths);
-                let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
-                self.compute_layout(&basic_ctx, &ctx.bounds);
-            }
-        }
-
         for i in 0..self.inner.len() {
             if let Some(child) = self.inner.at(i) {
                 let bounds = self.bounds_of(ctx, i);
@@ -293,7 +363,7 @@ impl Element for Grid {
         if leaf { None } else { Some(self) }
     }
 
-    fn handle_click(&self, ctx: &Context, btn: crate::view::MouseButton) -> bool {
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
         for i in 0..self.inner.len() {
             let bounds = self.bounds_of(ctx, i);
             if bounds.contains(btn.pos) {
@@ -308,6 +378,73 @@ impl Element for Grid {
         false
     }
 
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        for i in 0..self.inner.len() {
+            if let Some(child) = self.inner.at(i) {
+                let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+                if child.hit_test(&child_ctx, btn.pos, false, false).is_some() {
+                    child.handle_drag(&child_ctx, btn);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        (0..self.inner.len()).any(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| {
+                child.hit_test(&child_ctx, p, false, false).is_some() && child.handle_scroll(&child_ctx, dir, p)
+            })
+        })
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        (0..self.inner.len()).any(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| {
+                child.hit_test(&child_ctx, center, false, false).is_some()
+                    && child.handle_magnify(&child_ctx, scale_delta, center)
+            })
+        })
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        (0..self.inner.len()).any(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| child.handle_key(&child_ctx, k))
+        })
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        (0..self.inner.len()).any(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| child.handle_text(&child_ctx, info))
+        })
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        for i in 0..self.inner.len() {
+            if let Some(child) = self.inner.at(i) {
+                child.handle_track_drop(&ctx.with_bounds(self.bounds_of(ctx, i)), info, status);
+            }
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if bounds.contains(info.where_) {
+                if let Some(child) = self.inner.at(i) {
+                    if child.handle_drop(&ctx.with_bounds(bounds), info) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
     fn wants_control(&self) -> bool {
         self.inner.wants_control()
     }
@@ -320,6 +457,55 @@ impl Element for Grid {
         self.inner.enable(state);
     }
 
+    fn wants_focus(&self) -> bool {
+        self.inner.wants_focus()
+    }
+
+    fn clear_focus(&self) {
+        self.inner.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.inner.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.inner.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.inner.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.inner.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.bounds_of_element(&child_ctx, element)
+        })
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.focus_bounds(&child_ctx)
+        })
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Every child sees the move, so the one the mouse left can tell
+        (0..self.inner.len()).fold(false, |handled, i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| child.handle_cursor(&child_ctx, p, status)) || handled
+        })
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -329,11 +515,25 @@ impl Element for Grid {
     }
 }
 
+/// Makes its subject span several columns of a [`Grid`]: a [`Proxy`]
+/// with a span, forwarding everything else to the subject.
+pub type GridSpan<S> = Proxy<S>;
+
 /// Creates a grid with the specified number of columns.
 pub fn grid(columns: usize) -> Grid {
     Grid::new(columns)
 }
 
+/// Creates a grid with the specified number of columns and explicit row heights.
+pub fn grid_with_rows(columns: usize, row_heights: Vec<f32>) -> Grid {
+    Grid::new(columns).row_heights(row_heights)
+}
+
+/// Makes an element span several columns of a grid.
+pub fn grid_span<S: Element>(span: u32, subject: S) -> GridSpan<S> {
+    Proxy::new(subject).with_span(span)
+}
+
 /// Macro for creating grids.
 #[macro_export]
 macro_rules! grid {
@@ -345,3 +545,101 @@ macro_rules! grid {
         g
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::element::share;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    struct Fixed {
+        width: f32,
+        height: f32,
+    }
+
+    impl Element for Fixed {
+        fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+            ViewLimits::new(Point::new(self.width, self.height), Point::new(FULL_EXTENT, FULL_EXTENT))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    fn cell(width: f32) -> Fixed {
+        Fixed { width, height: 20.0 }
+    }
+
+    #[test]
+    fn test_span_wraps_to_next_row() {
+        let mut g = grid(3).gap(0.0);
+        g.push(share(cell(10.0)));
+        g.push(share(cell(10.0)));
+        g.push(share(grid_span(2, cell(50.0))));
+        g.push(share(cell(10.0)));
+        g.push(share(cell(10.0)));
+        let g = Arc::new(g);
+        let view = TestView::new(g.clone(), Extent::new(90.0, 60.0));
+
+        // The span doesn't fit after two cells, so it starts row 1 and the
+        // following child fills the remaining column
+        assert_eq!(g.place_cells(), vec![
+            Cell { row: 0, col: 0, span: 1 },
+            Cell { row: 0, col: 1, span: 1 },
+            Cell { row: 1, col: 0, span: 2 },
+            Cell { row: 1, col: 2, span: 1 },
+            Cell { row: 2, col: 0, span: 1 },
+        ]);
+
+        // The span widens its columns to 25 each; the 30 extra pixels are
+        // then shared evenly, giving columns of 35, 35 and 20
+        view.with_context(|ctx| {
+            assert_eq!(g.bounds_of(ctx, 1), Rect::new(35.0, 0.0, 70.0, 20.0));
+            assert_eq!(g.bounds_of(ctx, 2), Rect::new(0.0, 20.0, 70.0, 40.0));
+            assert_eq!(g.bounds_of(ctx, 3), Rect::new(70.0, 20.0, 90.0, 40.0));
+            assert_eq!(g.bounds_of(ctx, 4), Rect::new(0.0, 40.0, 35.0, 60.0));
+        });
+        assert_eq!(view.limits().min, Point::new(60.0, 60.0));
+    }
+
+    #[test]
+    fn test_span_forwards_to_subject() {
+        use crate::element::text_box::{text_box, TextBox};
+        use crate::view::KeyCode;
+
+        let mut g = grid(2).gap(0.0);
+        g.push(share(grid_span(2, text_box())));
+        let g = Arc::new(g);
+        let view = TestView::new(g.clone(), Extent::new(200.0, 30.0));
+
+        // Focus and typing pass through the span to the text box
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(view.type_text("ok"));
+        let span = g.at(0).unwrap().as_any().downcast_ref::<GridSpan<TextBox>>().unwrap();
+        assert_eq!(span.span(), 2);
+        assert!(span.has_focus());
+        assert_eq!(span.actual_subject().get_text(), "ok");
+    }
+
+    #[test]
+    fn test_explicit_row_heights() {
+        let mut g = grid_with_rows(2, vec![50.0]).gap(0.0);
+        for _ in 0..4 {
+            g.push(share(cell(10.0)));
+        }
+        let g = Arc::new(g);
+        let view = TestView::new(g.clone(), Extent::new(20.0, 100.0));
+
+        view.with_context(|ctx| {
+            assert_eq!(g.bounds_of(ctx, 0), Rect::new(0.0, 0.0, 10.0, 50.0));
+            assert_eq!(g.bounds_of(ctx, 3), Rect::new(10.0, 50.0, 20.0, 100.0));
+        });
+    }
+}
@@ -0,0 +1,410 @@
+//! Vector icon element drawn from path data.
+
+use std::any::Any;
+use std::sync::Arc;
+use super::{Element, ViewLimits, ViewStretch};
+use super::context::{BasicContext, Context};
+use super::button::ButtonIcon;
+use crate::support::canvas::Canvas;
+use crate::support::color::Color;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::ThemeColor;
+
+/// A single path command, in view box coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Point),
+    LineTo(Point),
+    /// Quadratic curve: control point, end point.
+    QuadTo(Point, Point),
+    /// Cubic curve: two control points, end point.
+    CurveTo(Point, Point, Point),
+    Close,
+}
+
+/// Path data for a vector icon.
+///
+/// Coordinates are in the path's view box, which is scaled to fit the
+/// bounds the icon is drawn in.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IconPath {
+    commands: Vec<PathCommand>,
+    view_box: Option<Rect>,
+}
+
+impl IconPath {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses SVG path data, such as `"M4 12 L10 18 L20 6"`.
+    ///
+    /// Supports the `M`, `L`, `H`, `V`, `C`, `S`, `Q`, `T` and `Z` commands
+    /// in absolute and relative form. Returns `None` if the data is
+    /// malformed or uses an unsupported command, such as arcs.
+    pub fn parse(d: &str) -> Option<Self> {
+        let mut parser = PathParser { bytes: d.as_bytes(), pos: 0 };
+        let mut path = Self::new();
+        let mut command = None;
+        let mut current = Point::zero();
+        let mut start = Point::zero();
+        // Last control points, for the smooth curve commands
+        let mut last_cubic = None;
+        let mut last_quad = None;
+
+        while !parser.at_end() {
+            if let Some(c) = parser.command() {
+                command = Some(c);
+            }
+            let c = command?;
+            let relative = c.is_ascii_lowercase();
+            let origin = if relative { current } else { Point::zero() };
+            let reflect = |cp: Option<Point>| cp.map_or(current, |cp| current * 2.0 - cp);
+
+            let mut next = (None, None);
+            match c.to_ascii_uppercase() {
+                b'M' => {
+                    current = origin + parser.point()?;
+                    start = current;
+                    path.commands.push(PathCommand::MoveTo(current));
+                    // Further coordinate pairs are implicit line-tos
+                    command = Some(if relative { b'l' } else { b'L' });
+                }
+                b'L' => {
+                    current = origin + parser.point()?;
+                    path.commands.push(PathCommand::LineTo(current));
+                }
+                b'H' => {
+                    current.x = origin.x + parser.number()?;
+                    path.commands.push(PathCommand::LineTo(current));
+                }
+                b'V' => {
+                    current.y = origin.y + parser.number()?;
+                    path.commands.push(PathCommand::LineTo(current));
+                }
+                b'C' | b'S' => {
+                    let cp1 = match c.to_ascii_uppercase() {
+                        b'C' => origin + parser.point()?,
+                        _ => reflect(last_cubic),
+                    };
+                    let cp2 = origin + parser.point()?;
+                    current = origin + parser.point()?;
+                    path.commands.push(PathCommand::CurveTo(cp1, cp2, current));
+                    next.0 = Some(cp2);
+                }
+                b'Q' | b'T' => {
+                    let cp = match c.to_ascii_uppercase() {
+                        b'Q' => origin + parser.point()?,
+                        _ => reflect(last_quad),
+                    };
+                    current = origin + parser.point()?;
+                    path.commands.push(PathCommand::QuadTo(cp, current));
+                    next.1 = Some(cp);
+                }
+                b'Z' => {
+                    current = start;
+                    path.commands.push(PathCommand::Close);
+                    // Close takes no arguments, so a number may not follow
+                    command = None;
+                }
+                _ => return None,
+            }
+            (last_cubic, last_quad) = next;
+        }
+        Some(path)
+    }
+
+    /// Sets the view box the coordinates are given in.
+    ///
+    /// Without one, the path's own bounds are used.
+    pub fn view_box(mut self, view_box: Rect) -> Self {
+        self.view_box = Some(view_box);
+        self
+    }
+
+    /// Starts a new subpath at `p`.
+    pub fn move_to(mut self, p: Point) -> Self {
+        self.commands.push(PathCommand::MoveTo(p));
+        self
+    }
+
+    /// Adds a line to `p`.
+    pub fn line_to(mut self, p: Point) -> Self {
+        self.commands.push(PathCommand::LineTo(p));
+        self
+    }
+
+    /// Adds a quadratic curve to `p`, with control point `cp`.
+    pub fn quad_to(mut self, cp: Point, p: Point) -> Self {
+        self.commands.push(PathCommand::QuadTo(cp, p));
+        self
+    }
+
+    /// Adds a cubic curve to `p`, with control points `cp1` and `cp2`.
+    pub fn curve_to(mut self, cp1: Point, cp2: Point, p: Point) -> Self {
+        self.commands.push(PathCommand::CurveTo(cp1, cp2, p));
+        self
+    }
+
+    /// Closes the current subpath.
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Returns the path commands.
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// Returns the view box, or the bounds of all points if none was set.
+    pub fn bounds(&self) -> Rect {
+        if let Some(view_box) = self.view_box {
+            return view_box;
+        }
+        let mut points = self.commands.iter().flat_map(|command| match *command {
+            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => vec![p],
+            PathCommand::QuadTo(cp, p) => vec![cp, p],
+            PathCommand::CurveTo(cp1, cp2, p) => vec![cp1, cp2, p],
+            PathCommand::Close => vec![],
+        });
+        let Some(first) = points.next() else {
+            return Rect::zero();
+        };
+        points.fold(Rect::from_points(first, first), |r, p| {
+            Rect::new(r.left.min(p.x), r.top.min(p.y), r.right.max(p.x), r.bottom.max(p.y))
+        })
+    }
+
+    /// Adds the path to the canvas, scaled to fit `bounds` and centered.
+    ///
+    /// The path keeps its aspect ratio.
+    pub fn add_to(&self, canvas: &mut Canvas, bounds: Rect) {
+        let view_box = self.bounds();
+        let scale = (bounds.width() / view_box.width().max(f32::EPSILON))
+            .min(bounds.height() / view_box.height().max(f32::EPSILON));
+        let offset = Point::new(
+            bounds.left + (bounds.width() - view_box.width() * scale) / 2.0,
+            bounds.top + (bounds.height() - view_box.height() * scale) / 2.0,
+        );
+        let map = |p: Point| offset + Point::new(p.x - view_box.left, p.y - view_box.top) * scale;
+
+        canvas.begin_path();
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(p) => canvas.move_to(map(p)),
+                PathCommand::LineTo(p) => canvas.line_to(map(p)),
+                PathCommand::QuadTo(cp, p) => canvas.quadratic_curve_to(map(cp), map(p)),
+                PathCommand::CurveTo(cp1, cp2, p) => canvas.bezier_curve_to(map(cp1), map(cp2), map(p)),
+                PathCommand::Close => canvas.close_path(),
+            }
+        }
+    }
+}
+
+/// Filled icons draw in the button's text color.
+impl From<IconPath> for ButtonIcon {
+    fn from(path: IconPath) -> Self {
+        ButtonIcon::path(move |canvas, bounds, color| {
+            canvas.fill_style(color);
+            path.add_to(canvas, bounds);
+            canvas.fill();
+        })
+    }
+}
+
+/// Tokenizer for SVG path data.
+struct PathParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl PathParser<'_> {
+    fn skip_separators(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(|b| b.is_ascii_whitespace() || *b == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.bytes.len()
+    }
+
+    /// Consumes a command letter, if one is next.
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let c = *self.bytes.get(self.pos)?;
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            self.pos += 1;
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            while parser.bytes.get(parser.pos).is_some_and(u8::is_ascii_digit) {
+                parser.pos += 1;
+            }
+        };
+        if matches!(self.bytes.get(self.pos), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        digits(self);
+        // A second '.' starts the next number, as in "0.5.5"
+        if self.bytes.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            digits(self);
+        }
+        if matches!(self.bytes.get(self.pos), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.bytes.get(self.pos), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            digits(self);
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos]).ok()?.parse().ok()
+    }
+
+    fn point(&mut self) -> Option<Point> {
+        Some(Point::new(self.number()?, self.number()?))
+    }
+}
+
+/// A vector icon, scaled to fit its bounds.
+pub struct Icon {
+    path: Arc<IconPath>,
+    size: f32,
+    fill_color: Option<ThemeColor>,
+    stroke: Option<(ThemeColor, f32)>,
+}
+
+impl Icon {
+    /// Creates a filled icon in the theme's icon color.
+    pub fn new(path: IconPath) -> Self {
+        Self {
+            path: Arc::new(path),
+            size: 16.0,
+            fill_color: Some(ThemeColor::Theme(|theme| theme.icon_color)),
+            stroke: None,
+        }
+    }
+
+    /// Sets the icon size.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the fill color.
+    pub fn color(mut self, color: Color) -> Self {
+        self.fill_color = Some(color.into());
+        self
+    }
+
+    /// Strokes the outline with the given color and line width.
+    pub fn stroke(mut self, color: Color, width: f32) -> Self {
+        self.stroke = Some((color.into(), width));
+        self
+    }
+
+    /// Draws the outline only, without filling.
+    pub fn no_fill(mut self) -> Self {
+        self.fill_color = None;
+        self
+    }
+}
+
+impl Element for Icon {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        ViewLimits::fixed(self.size, self.size)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(0.0, 0.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        // Inset strokes so they aren't clipped at the edges
+        let inset = self.stroke.as_ref().map_or(0.0, |(_, width)| width / 2.0);
+        let bounds = ctx.bounds.inset(inset, inset);
+
+        if let Some(ref color) = self.fill_color {
+            canvas.fill_style(color.get());
+            self.path.add_to(&mut canvas, bounds);
+            canvas.fill();
+        }
+        if let Some((ref color, width)) = self.stroke {
+            canvas.stroke_style(color.get());
+            canvas.line_width(width);
+            self.path.add_to(&mut canvas, bounds);
+            canvas.stroke();
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates an icon from path data.
+pub fn icon(path: IconPath) -> Icon {
+    Icon::new(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_svg_path() {
+        use PathCommand::*;
+
+        let path = IconPath::parse("M4,12 l6 6L20-6.5e0 h-2v.5.5Z m1 1 Q2 2 3 3 T5 5").unwrap();
+        assert_eq!(path.commands(), &[
+            MoveTo(Point::new(4.0, 12.0)),
+            LineTo(Point::new(10.0, 18.0)),
+            LineTo(Point::new(20.0, -6.5)),
+            LineTo(Point::new(18.0, -6.5)),
+            LineTo(Point::new(18.0, -6.0)),
+            LineTo(Point::new(18.0, -5.5)),
+            Close,
+            MoveTo(Point::new(5.0, 13.0)),
+            QuadTo(Point::new(2.0, 2.0), Point::new(3.0, 3.0)),
+            QuadTo(Point::new(4.0, 4.0), Point::new(5.0, 5.0)),
+        ]);
+        assert_eq!(path.bounds(), Rect::new(2.0, -6.5, 20.0, 18.0));
+
+        assert!(IconPath::parse("M0 0 A1 1 0 0 1 2 2").is_none());
+        assert!(IconPath::parse("10 10").is_none());
+        assert!(IconPath::parse("M0 0 L1").is_none());
+    }
+
+    #[test]
+    fn test_icon_fills_its_bounds() {
+        use crate::element::share;
+        use crate::support::point::Extent;
+        use crate::view::testing::TestView;
+
+        // A square in a 24 unit view box, drawn at 48 pixels
+        let square = IconPath::parse("M6 6 H18 V18 H6 Z").unwrap().view_box(Rect::new(0.0, 0.0, 24.0, 24.0));
+        let icon = icon(square).size(48.0).color(Color::new(1.0, 0.0, 0.0, 1.0));
+
+        let pixmap = TestView::new(share(icon), Extent::new(48.0, 48.0)).render().unwrap();
+        let red = |x: u32, y: u32| pixmap.pixel(x, y).unwrap().red();
+        assert_eq!(red(24, 24), 255);
+        assert_eq!(red(13, 24), 255);
+        assert_eq!(red(11, 24), 0);
+    }
+}
@@ -0,0 +1,182 @@
+//! Image element for showing decoded bitmaps.
+
+use std::any::Any;
+use std::path::Path;
+use super::{Element, ViewLimits, ViewStretch};
+use super::context::{BasicContext, Context};
+use crate::support::point::{Extent, Point};
+use crate::support::rect::Rect;
+
+/// How an image is scaled into its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// Cover the bounds, keeping the aspect ratio and cropping the overflow.
+    Fill,
+    /// Fit inside the bounds, keeping the aspect ratio.
+    #[default]
+    Fit,
+    /// Stretch to the bounds, ignoring the aspect ratio.
+    Stretch,
+    /// Draw at natural size, centered and cropped to the bounds.
+    Center,
+}
+
+/// An image, decoded once and drawn scaled into its bounds.
+///
+/// By default the element asks for the image's natural size; wrap it in a
+/// size element, or set [`Image::size`], to constrain it.
+pub struct Image {
+    /// Premultiplied pixels, ready to draw
+    pixmap: tiny_skia::Pixmap,
+    size: Option<Extent>,
+    scale_mode: ScaleMode,
+}
+
+impl Image {
+    /// Creates an image from straight-alpha RGBA8 pixels, row by row.
+    ///
+    /// Returns `None` if the image is empty or `data` is too short.
+    pub fn from_rgba(data: &[u8], width: u32, height: u32) -> Option<Self> {
+        let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+        if data.len() < pixmap.data().len() {
+            return None;
+        }
+        for (dst, src) in pixmap.pixels_mut().iter_mut().zip(data.chunks_exact(4)) {
+            *dst = tiny_skia::ColorU8::from_rgba(src[0], src[1], src[2], src[3]).premultiply();
+        }
+        Some(Self {
+            pixmap,
+            size: None,
+            scale_mode: ScaleMode::default(),
+        })
+    }
+
+    /// Decodes a PNG or JPEG image from memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ::image::ImageError> {
+        Self::from_decoded(::image::load_from_memory(bytes)?)
+    }
+
+    /// Loads and decodes a PNG or JPEG image file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ::image::ImageError> {
+        Self::from_decoded(::image::open(path)?)
+    }
+
+    fn from_decoded(decoded: ::image::DynamicImage) -> Result<Self, ::image::ImageError> {
+        let rgba = decoded.into_rgba8();
+        Self::from_rgba(rgba.as_raw(), rgba.width(), rgba.height()).ok_or_else(|| {
+            ::image::ImageError::Limits(::image::error::LimitError::from_kind(
+                ::image::error::LimitErrorKind::DimensionError,
+            ))
+        })
+    }
+
+    /// Sets the size the element asks for, instead of the natural size.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Some(Extent::new(width, height));
+        self
+    }
+
+    /// Sets how the image is scaled into its bounds.
+    pub fn scale_mode(mut self, mode: ScaleMode) -> Self {
+        self.scale_mode = mode;
+        self
+    }
+
+    /// Returns the natural size of the image, in pixels.
+    pub fn natural_size(&self) -> Extent {
+        Extent::new(self.pixmap.width() as f32, self.pixmap.height() as f32)
+    }
+
+    /// Returns where the image is drawn for the given bounds.
+    fn dest_rect(&self, bounds: Rect) -> Rect {
+        let natural = self.natural_size();
+        let scale = match self.scale_mode {
+            ScaleMode::Stretch => return bounds,
+            ScaleMode::Fit => (bounds.width() / natural.width()).min(bounds.height() / natural.height()),
+            ScaleMode::Fill => (bounds.width() / natural.width()).max(bounds.height() / natural.height()),
+            ScaleMode::Center => 1.0,
+        };
+        let (width, height) = (natural.width() * scale, natural.height() * scale);
+        let c = bounds.center();
+        Rect::new(c.x - width / 2.0, c.y - height / 2.0, c.x + width / 2.0, c.y + height / 2.0)
+    }
+}
+
+impl Element for Image {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        let size = self.size.unwrap_or_else(|| self.natural_size());
+        ViewLimits::fixed(size.width(), size.height())
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(0.0, 0.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let crop = matches!(self.scale_mode, ScaleMode::Fill | ScaleMode::Center);
+        if crop {
+            canvas.push_clip(ctx.bounds);
+        }
+        canvas.draw_pixmap(self.pixmap.as_ref(), self.dest_rect(ctx.bounds));
+        if crop {
+            canvas.pop_clip();
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) && !leaf {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Loads an image file.
+pub fn image_file(path: impl AsRef<Path>) -> Result<Image, ::image::ImageError> {
+    Image::from_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::share;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_decode_and_scale_modes() {
+        // Encode a 4x2 PNG: left half red, right half blue
+        let rgba = ::image::RgbaImage::from_fn(4, 2, |x, _| {
+            if x < 2 { ::image::Rgba([255, 0, 0, 255]) } else { ::image::Rgba([0, 0, 255, 255]) }
+        });
+        let mut png = std::io::Cursor::new(Vec::new());
+        rgba.write_to(&mut png, ::image::ImageFormat::Png).unwrap();
+
+        let image = Image::from_bytes(png.get_ref()).unwrap();
+        assert_eq!(image.natural_size(), Extent::new(4.0, 2.0));
+        assert!(Image::from_bytes(b"not an image").is_err());
+
+        let bounds = Rect::new(0.0, 0.0, 40.0, 40.0);
+        assert_eq!(image.dest_rect(bounds), Rect::new(0.0, 10.0, 40.0, 30.0));
+        let image = image.scale_mode(ScaleMode::Fill);
+        assert_eq!(image.dest_rect(bounds), Rect::new(-20.0, 0.0, 60.0, 40.0));
+        let image = image.scale_mode(ScaleMode::Center);
+        assert_eq!(image.dest_rect(bounds), Rect::new(18.0, 19.0, 22.0, 21.0));
+
+        // Filling crops the sides; the halves still meet in the middle
+        let image = image.scale_mode(ScaleMode::Fill);
+        let pixmap = TestView::new(share(image), bounds.size()).render().unwrap();
+        let pixel = |x, y| pixmap.pixel(x, y).unwrap();
+        assert_eq!((pixel(5, 5).red(), pixel(5, 5).blue()), (255, 0));
+        assert_eq!((pixel(35, 35).red(), pixel(35, 35).blue()), (0, 255));
+    }
+}
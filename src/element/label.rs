@@ -1,19 +1,78 @@
 //! Label elements for displaying text.
 
 use std::any::Any;
-use super::{Element, ViewLimits};
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
+use crate::support::canvas::Canvas;
 use crate::support::color::Color;
-use crate::support::font::Font;
+use crate::support::font::{Font, FontStyle, FontWeight};
 use crate::support::point::Point;
-use crate::support::theme::get_theme;
+use crate::support::theme::{get_theme, ThemeColor};
+
+/// Measures the width of `text` in the given font using the context's canvas.
+fn measure_text_width(ctx: &BasicContext, text: &str, font: &Font, font_size: f32) -> f32 {
+    let mut canvas = ctx.canvas.borrow_mut();
+    canvas.save();
+    canvas.font(font.clone());
+    canvas.font_size(font_size);
+    let width = canvas.measure_text(text).width;
+    canvas.restore();
+    width
+}
+
+/// Breaks `text` into lines no wider than `width`, at word boundaries.
+///
+/// Explicit newlines always break. A word too long for a line gets a line
+/// of its own.
+fn wrap_lines(canvas: &Canvas, text: &str, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() {
+                let candidate = format!("{line} {word}");
+                if canvas.text_width(&candidate) <= width {
+                    line = candidate;
+                    continue;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Shortens `text` to fit in `width`, ending it with an ellipsis if
+/// anything had to be cut.
+fn ellipsize_line(canvas: &Canvas, text: &str, width: f32) -> String {
+    if canvas.text_width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if canvas.text_width(&candidate) <= width {
+            return candidate;
+        }
+    }
+    "…".to_string()
+}
 
 /// A simple text label element.
 pub struct Label {
     text: String,
     font: Font,
     font_size: f32,
-    color: Color,
+    color: ThemeColor,
+    wrap: bool,
+    ellipsize: bool,
+    /// Width the label was last drawn at, which wrapped labels lay out for
+    layout_width: RwLock<Option<f32>>,
 }
 
 impl Label {
@@ -24,7 +83,10 @@ impl Label {
             text: text.into(),
             font: theme.label_font.clone(),
             font_size: theme.label_font_size,
-            color: theme.label_font_color,
+            color: ThemeColor::Theme(|theme| theme.label_font_color),
+            wrap: false,
+            ellipsize: false,
+            layout_width: RwLock::new(None),
         }
     }
 
@@ -52,10 +114,35 @@ impl Label {
 
     /// Sets the color.
     pub fn with_color(mut self, color: Color) -> Self {
-        self.color = color;
+        self.color = color.into();
+        self
+    }
+
+    /// Sets whether the text wraps onto multiple lines at word boundaries
+    /// to fit the available width.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
         self
     }
 
+    /// Sets whether text that doesn't fit on one line is cut short with an
+    /// ellipsis.
+    pub fn ellipsize(mut self, ellipsize: bool) -> Self {
+        self.ellipsize = ellipsize;
+        self
+    }
+
+    /// Returns the lines to draw in `width`.
+    fn lines(&self, canvas: &Canvas, width: f32) -> Vec<String> {
+        if self.wrap {
+            wrap_lines(canvas, &self.text, width)
+        } else if self.ellipsize {
+            vec![ellipsize_line(canvas, &self.text, width)]
+        } else {
+            vec![self.text.clone()]
+        }
+    }
+
     /// Returns the font.
     pub fn font(&self) -> &Font {
         &self.font
@@ -68,36 +155,63 @@ impl Label {
 
     /// Returns the color.
     pub fn color(&self) -> Color {
-        self.color
+        self.color.get()
     }
 }
 
 impl Element for Label {
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
-        // Estimate text size based on font size and character count
-        // In a real implementation, this would use proper text measurement
-        let estimated_width = self.text.len() as f32 * self.font_size * 0.6;
-        let estimated_height = self.font_size * 1.2;
+        let line_height = self.font_size * 1.2;
+        if !self.wrap && !self.ellipsize {
+            let width = measure_text_width(ctx, &self.text, &self.font, self.font_size);
+            return ViewLimits::fixed(width, line_height);
+        }
 
-        ViewLimits::fixed(estimated_width, estimated_height)
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.font(self.font.clone());
+        canvas.font_size(self.font_size);
+        let natural_width = self.text.split('\n').map(|line| canvas.text_width(line)).fold(0.0, f32::max);
+        let limits = if self.wrap {
+            // Wrap to the width last drawn at, or the natural width at first
+            let width = self.layout_width.read().unwrap().unwrap_or(natural_width);
+            let widest_word = self.text.split_whitespace().map(|word| canvas.text_width(word)).fold(0.0, f32::max);
+            let height = self.lines(&canvas, width).len() as f32 * line_height;
+            ViewLimits::new(Point::new(widest_word, height), Point::new(natural_width.max(widest_word), height))
+        } else {
+            ViewLimits::new(Point::new(canvas.text_width("…"), line_height), Point::new(natural_width, line_height))
+        };
+        canvas.restore();
+        limits
     }
 
-    fn stretch(&self) -> super::ViewStretch {
-        // Label has fixed size, so no stretch
-        super::ViewStretch::new(0.0, 0.0)
+    fn stretch(&self) -> ViewStretch {
+        if self.wrap || self.ellipsize {
+            ViewStretch::new(1.0, 0.0)
+        } else {
+            // Label has fixed size, so no stretch
+            ViewStretch::new(0.0, 0.0)
+        }
     }
 
     fn draw(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        canvas.fill_style(self.color);
+        canvas.save();
+        canvas.fill_style(self.color.get());
         canvas.font(self.font.clone());
         canvas.font_size(self.font_size);
-        // Position text with baseline offset (ascent is roughly 80% of font size)
-        let text_pos = Point::new(
-            ctx.bounds.left,
-            ctx.bounds.top + self.font_size * 0.8,
-        );
-        canvas.fill_text(&self.text, text_pos);
+
+        let width = ctx.bounds.width();
+        *self.layout_width.write().unwrap() = Some(width);
+        for (i, line) in self.lines(&canvas, width).iter().enumerate() {
+            // Position text with baseline offset (ascent is roughly 80% of font size)
+            let text_pos = Point::new(
+                ctx.bounds.left,
+                ctx.bounds.top + self.font_size * (0.8 + 1.2 * i as f32),
+            );
+            canvas.fill_text(line, text_pos);
+        }
+        canvas.restore();
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -118,12 +232,11 @@ impl Heading {
     /// Creates a new heading.
     pub fn new(text: impl Into<String>) -> Self {
         let theme = get_theme();
-        Self {
-            label: Label::new(text)
-                .with_font(theme.heading_font.clone())
-                .with_font_size(theme.heading_font_size)
-                .with_color(theme.heading_font_color),
-        }
+        let mut label = Label::new(text)
+            .with_font(theme.heading_font.clone())
+            .with_font_size(theme.heading_font_size);
+        label.color = ThemeColor::Theme(|theme| theme.heading_font_color);
+        Self { label }
     }
 
     /// Sets the text.
@@ -138,6 +251,10 @@ impl Heading {
 }
 
 impl Element for Heading {
+    fn stretch(&self) -> ViewStretch {
+        self.label.stretch()
+    }
+
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
         self.label.limits(ctx)
     }
@@ -155,6 +272,261 @@ impl Element for Heading {
     }
 }
 
+/// A run of text drawn in one style within a [`RichLabel`].
+#[derive(Debug, Clone)]
+pub struct TextSpan {
+    pub text: String,
+    pub color: ThemeColor,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+}
+
+impl TextSpan {
+    /// Creates a span in the label color, regular weight and upright.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: ThemeColor::Theme(|theme| theme.label_font_color),
+            weight: FontWeight::Regular,
+            style: FontStyle::Normal,
+        }
+    }
+
+    /// Sets the color.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the font weight.
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Sets the font weight to bold.
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::Bold)
+    }
+
+    /// Sets the font style.
+    pub fn style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the font style to italic.
+    pub fn italic(self) -> Self {
+        self.style(FontStyle::Italic)
+    }
+
+    /// Sets up `canvas` to measure and draw this span.
+    fn apply(&self, canvas: &mut Canvas) {
+        canvas.font_weight(self.weight.value());
+        canvas.font_style(self.style);
+    }
+}
+
+impl<T: Into<String>> From<T> for TextSpan {
+    fn from(text: T) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A piece of a span placed on a line of a [`RichLabel`].
+#[derive(Debug, Clone, PartialEq)]
+struct SpanRun {
+    span: usize,
+    text: String,
+    x: f32,
+}
+
+/// A label made of spans in different colors, weights and styles, such as
+/// a bold red "ERROR:" followed by normal text.
+///
+/// The spans follow each other on one line, each starting where the last
+/// one ended, or wrap at whitespace to fit the available width.
+pub struct RichLabel {
+    spans: Vec<TextSpan>,
+    font: Font,
+    font_size: f32,
+    wrap: bool,
+    /// Width the label was last drawn at, which wrapped labels lay out for
+    layout_width: RwLock<Option<f32>>,
+}
+
+impl RichLabel {
+    /// Creates a rich label from spans.
+    pub fn new(spans: Vec<TextSpan>) -> Self {
+        let theme = get_theme();
+        Self {
+            spans,
+            font: theme.label_font.clone(),
+            font_size: theme.label_font_size,
+            wrap: false,
+            layout_width: RwLock::new(None),
+        }
+    }
+
+    /// Adds a span at the end.
+    pub fn span(mut self, span: impl Into<TextSpan>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+
+    /// Sets the spans.
+    pub fn set_spans(&mut self, spans: Vec<TextSpan>) {
+        self.spans = spans;
+    }
+
+    /// Returns the spans.
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// Sets the font family the spans are drawn in.
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Sets the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets whether the text wraps onto multiple lines at whitespace to fit
+    /// the available width.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Splits the spans into lines of runs, wrapping at `width` if given.
+    ///
+    /// Lines break only after whitespace, so a word split across spans
+    /// stays together. Explicit newlines always break.
+    fn layout(&self, canvas: &mut Canvas, width: Option<f32>) -> Vec<Vec<SpanRun>> {
+        canvas.save();
+        canvas.font(self.font.clone());
+        canvas.font_size(self.font_size);
+
+        let mut lines = vec![Vec::new()];
+        let mut x = 0.0;
+        let mut can_break = false;
+        for (index, span) in self.spans.iter().enumerate() {
+            span.apply(canvas);
+            for (i, paragraph) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(Vec::new());
+                    x = 0.0;
+                    can_break = false;
+                }
+                for piece in paragraph.split_inclusive(char::is_whitespace) {
+                    let fits = width.is_none_or(|width| x + canvas.text_width(piece.trim_end()) <= width);
+                    if !fits && can_break {
+                        lines.push(Vec::new());
+                        x = 0.0;
+                    }
+                    let line = lines.last_mut().unwrap();
+                    line.push(SpanRun { span: index, text: piece.to_string(), x });
+                    x += canvas.text_width(piece);
+                    can_break = piece.ends_with(char::is_whitespace);
+                }
+            }
+        }
+        canvas.restore();
+
+        // Pieces of the same span that stayed on one line draw as one run
+        for line in &mut lines {
+            line.dedup_by(|next, run| {
+                let joined = next.span == run.span;
+                if joined {
+                    run.text.push_str(&next.text);
+                }
+                joined
+            });
+        }
+        lines
+    }
+
+    /// Returns the width of the widest line in `lines`.
+    fn lines_width(&self, canvas: &mut Canvas, lines: &[Vec<SpanRun>]) -> f32 {
+        canvas.save();
+        canvas.font(self.font.clone());
+        canvas.font_size(self.font_size);
+        let width = lines
+            .iter()
+            .filter_map(|line| {
+                let last = line.last()?;
+                self.spans[last.span].apply(canvas);
+                Some(last.x + canvas.text_width(last.text.trim_end()))
+            })
+            .fold(0.0, f32::max);
+        canvas.restore();
+        width
+    }
+}
+
+impl Element for RichLabel {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let line_height = self.font_size * 1.2;
+        let mut canvas = ctx.canvas.borrow_mut();
+        let natural = self.layout(&mut canvas, None);
+        let natural_width = self.lines_width(&mut canvas, &natural);
+        if !self.wrap {
+            return ViewLimits::fixed(natural_width, natural.len() as f32 * line_height);
+        }
+
+        // Wrap to the width last drawn at, or the natural width at first.
+        // Laying out at zero width puts each word on a line of its own.
+        let width = self.layout_width.read().unwrap().unwrap_or(natural_width);
+        let words = self.layout(&mut canvas, Some(0.0));
+        let widest_word = self.lines_width(&mut canvas, &words);
+        let height = self.layout(&mut canvas, Some(width)).len() as f32 * line_height;
+        ViewLimits::new(Point::new(widest_word, height), Point::new(natural_width.max(widest_word), height))
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        if self.wrap {
+            ViewStretch::new(1.0, 0.0)
+        } else {
+            ViewStretch::new(0.0, 0.0)
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let width = ctx.bounds.width();
+        *self.layout_width.write().unwrap() = Some(width);
+        let lines = self.layout(&mut canvas, self.wrap.then_some(width));
+
+        canvas.save();
+        canvas.font(self.font.clone());
+        canvas.font_size(self.font_size);
+        for (i, line) in lines.iter().enumerate() {
+            // Baseline offset as in Label (ascent is roughly 80% of font size)
+            let baseline = ctx.bounds.top + self.font_size * (0.8 + 1.2 * i as f32);
+            for run in line {
+                let span = &self.spans[run.span];
+                span.apply(&mut canvas);
+                canvas.fill_style(span.color.get());
+                canvas.fill_text(&run.text, Point::new(ctx.bounds.left + run.x, baseline));
+            }
+        }
+        canvas.restore();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 /// A static text element (label that doesn't change).
 pub struct StaticText {
     text: &'static str,
@@ -187,9 +559,8 @@ impl StaticText {
 
 impl Element for StaticText {
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
-        let estimated_width = self.text.len() as f32 * self.font_size * 0.6;
-        let estimated_height = self.font_size * 1.2;
-        ViewLimits::fixed(estimated_width, estimated_height)
+        let width = measure_text_width(ctx, self.text, &Font::default(), self.font_size);
+        ViewLimits::fixed(width, self.font_size * 1.2)
     }
 
     fn draw(&self, ctx: &Context) {
@@ -225,7 +596,101 @@ pub fn heading(text: impl Into<String>) -> Heading {
     Heading::new(text)
 }
 
+/// Creates a rich label from spans.
+pub fn rich_label(spans: Vec<TextSpan>) -> RichLabel {
+    RichLabel::new(spans)
+}
+
 /// Creates static text.
 pub const fn static_text(text: &'static str) -> StaticText {
     StaticText::new(text)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::support::point::Extent;
+    use crate::support::theme::{set_theme, Theme};
+    use crate::view::render_to_pixmap;
+
+    /// Draws `label` and returns the color of its most opaque pixel.
+    fn ink_color(label: &Label) -> Option<(u8, u8, u8)> {
+        let pixmap = render_to_pixmap(label, Extent::new(100.0, 30.0))?;
+        let ink = pixmap.pixels().iter().max_by_key(|p| p.alpha())?.demultiply();
+        (ink.alpha() > 0).then(|| (ink.red(), ink.green(), ink.blue()))
+    }
+
+    #[test]
+    fn test_wrap_lines_at_words() {
+        let mut canvas = Canvas::new(1, 1).unwrap();
+        canvas.font_size(14.0);
+        let width = canvas.text_width("alpha beta").max(canvas.text_width("gamma delta"));
+
+        let lines = wrap_lines(&canvas, "alpha beta gamma delta\nepsilon", width);
+        assert_eq!(lines, vec!["alpha beta", "gamma delta", "epsilon"]);
+
+        // Overlong words stay whole on their own line
+        let lines = wrap_lines(&canvas, "a supercalifragilistic word", canvas.text_width("a wor"));
+        assert_eq!(lines, vec!["a", "supercalifragilistic", "word"]);
+    }
+
+    #[test]
+    fn test_ellipsize_line() {
+        let mut canvas = Canvas::new(1, 1).unwrap();
+        canvas.font_size(14.0);
+        let text = "A rather long label";
+
+        assert_eq!(ellipsize_line(&canvas, text, canvas.text_width(text)), text);
+        let short = ellipsize_line(&canvas, text, canvas.text_width("A rather…"));
+        assert_eq!(short, "A rather…");
+        assert_eq!(ellipsize_line(&canvas, text, 0.0), "…");
+    }
+
+    #[test]
+    fn test_label_follows_theme_switch() {
+        let label = label("Themed");
+
+        set_theme(Theme::dark());
+        let Some(dark) = ink_color(&label) else {
+            // No font available to draw with
+            set_theme(Theme::default());
+            return;
+        };
+        set_theme(Theme::light());
+        let light = ink_color(&label).unwrap();
+        set_theme(Theme::default());
+
+        // Dark themes draw light text and light themes dark text
+        assert!(dark.0 > 150 && light.0 < 100, "dark {dark:?}, light {light:?}");
+
+        // Explicit colors ignore the theme
+        let fixed = label.with_color(Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(fixed.color(), Color::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_rich_label_layout() {
+        let rich = rich_label(vec![
+            TextSpan::new("ERROR: ").bold().color(Color::new(1.0, 0.0, 0.0, 1.0)),
+            TextSpan::new("disk full"),
+        ]);
+        let mut canvas = Canvas::new(1, 1).unwrap();
+        canvas.font(rich.font.clone());
+        canvas.font_size(rich.font_size);
+        canvas.font_weight(700);
+        let prefix = canvas.text_width("ERROR: ");
+
+        // The second span starts where the bold one ends
+        let lines = rich.layout(&mut canvas, None);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0][1], SpanRun { span: 1, text: "disk full".into(), x: prefix });
+
+        // Too narrow for the prefix and the rest on one line
+        canvas.font_weight(400);
+        let width = prefix.max(canvas.text_width("disk full")) + 1.0;
+        let lines = rich.layout(&mut canvas, Some(width));
+        let text: Vec<Vec<&str>> = lines.iter().map(|l| l.iter().map(|r| r.text.as_str()).collect()).collect();
+        assert_eq!(text, vec![vec!["ERROR: "], vec!["disk full"]]);
+        assert_eq!(lines[1][0].x, 0.0);
+    }
+}
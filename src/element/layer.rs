@@ -6,7 +6,7 @@ use super::context::{BasicContext, Context};
 use super::composite::{Storage, CompositeBase, Composite};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
-use crate::view::{MouseButton, KeyInfo, TextInfo};
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
 
 /// Layer element - stacks children on top of each other.
 ///
@@ -204,6 +204,37 @@ impl Element for Layer {
         false
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        for i in (0..self.inner.len()).rev() {
+            if let Some(child) = self.inner.at(i) {
+                if child.handle_magnify(ctx, scale_delta, center) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        for i in 0..self.inner.len() {
+            if let Some(child) = self.inner.at(i) {
+                child.handle_track_drop(ctx, info, status);
+            }
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        // The topmost child under the drop gets it first
+        for i in (0..self.inner.len()).rev() {
+            if let Some(child) = self.inner.at(i) {
+                if child.hit_test(ctx, info.where_, false, false).is_some() && child.handle_drop(ctx, info) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
     fn is_enabled(&self) -> bool {
         self.inner.is_enabled()
     }
@@ -236,6 +267,42 @@ impl Element for Layer {
         }
     }
 
+    fn has_focus(&self) -> bool {
+        self.inner.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.inner.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.inner.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.inner.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        (0..self.inner.len()).find_map(|i| self.inner.at(i)?.bounds_of_element(ctx, element))
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        (0..self.inner.len()).find_map(|i| self.inner.at(i)?.focus_bounds(ctx))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Every child sees the move, so the one the mouse left can tell
+        let mut handled = false;
+        for child in (0..self.inner.len()).filter_map(|i| self.inner.at(i)) {
+            handled |= child.handle_cursor(ctx, p, status);
+        }
+        handled
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -391,6 +458,28 @@ impl Element for Deck {
         }
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        if let Some(child) = self.inner.at(self.active_index) {
+            child.handle_magnify(ctx, scale_delta, center)
+        } else {
+            false
+        }
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        if let Some(child) = self.inner.at(self.active_index) {
+            child.handle_track_drop(ctx, info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        if let Some(child) = self.inner.at(self.active_index) {
+            child.handle_drop(ctx, info)
+        } else {
+            false
+        }
+    }
+
     fn is_enabled(&self) -> bool {
         self.inner.is_enabled()
     }
@@ -415,6 +504,38 @@ impl Element for Deck {
         }
     }
 
+    fn has_focus(&self) -> bool {
+        self.inner.at(self.active_index).is_some_and(|c| c.has_focus())
+    }
+
+    // Only the visible card takes part in focus traversal
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.inner.at(self.active_index).is_some_and(|c| c.handle_begin_focus(req))
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.inner.at(self.active_index).is_some_and(|c| c.handle_focus_next(forward))
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.inner.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.inner.at(self.active_index)?.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.inner.at(self.active_index)?.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.inner.at(self.active_index).is_some_and(|child| child.handle_cursor(ctx, p, status))
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -2,13 +2,16 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ViewLimits, ViewStretch};
+use std::time::{Duration, Instant};
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
 use super::context::{BasicContext, Context};
+use super::text_box::{TextBox, TextChangeCallback};
+use super::scroll::{MOMENTUM_DECAY, MOMENTUM_MIN_SPEED};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, CursorType, KeyAction, KeyCode, KeyInfo, TextInfo};
 
 /// List selection mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -19,6 +22,13 @@ pub enum SelectionMode {
     None,
 }
 
+/// Stiffness of the spring pulling an overscrolled list back to its edge,
+/// in 1/s². The spring is critically damped, so it settles without bouncing.
+const OVERSCROLL_STIFFNESS: f32 = 400.0;
+
+/// Longest step the momentum is integrated over at once, in seconds.
+const MOMENTUM_STEP: f32 = 1.0 / 120.0;
+
 /// Callback type for selection changes.
 pub type SelectionCallback = Box<dyn Fn(usize) + Send + Sync>;
 pub type MultiSelectionCallback = Box<dyn Fn(&[usize]) + Send + Sync>;
@@ -46,19 +56,63 @@ impl ListItem {
     }
 }
 
+/// Prefix typed for type-ahead search. It starts over after a pause in typing.
+#[derive(Debug, Default)]
+struct TypeAhead {
+    prefix: String,
+    last_input: Option<Instant>,
+}
+
+impl TypeAhead {
+    /// How long typing may pause before the prefix starts over.
+    const RESET_DELAY: Duration = Duration::from_millis(1000);
+
+    /// Appends a typed character and returns the prefix to search for.
+    fn push(&mut self, c: char) -> &str {
+        let now = Instant::now();
+        if self.last_input.is_some_and(|last| now.duration_since(last) > Self::RESET_DELAY) {
+            self.prefix.clear();
+        }
+        self.last_input = Some(now);
+        self.prefix.extend(c.to_lowercase());
+        &self.prefix
+    }
+
+    /// Returns true if nothing has been typed yet (or typing paused).
+    fn is_idle(&self) -> bool {
+        self.prefix.is_empty()
+            || self.last_input.is_none_or(|last| last.elapsed() > Self::RESET_DELAY)
+    }
+
+    /// Returns the index of the first label starting with `prefix`, ignoring case.
+    fn find<'a>(prefix: &str, labels: impl Iterator<Item = &'a str>) -> Option<usize> {
+        labels.into_iter().position(|label| label.to_lowercase().starts_with(prefix))
+    }
+}
+
 /// A list element for displaying and selecting items.
 pub struct List {
     items: RwLock<Vec<ListItem>>,
     selected: RwLock<Vec<usize>>,
     selection_mode: SelectionMode,
     hovered_index: RwLock<Option<usize>>,
+    /// Keyboard cursor in multiple selection mode
+    cursor_index: RwLock<Option<usize>>,
+    focused: RwLock<bool>,
+    type_ahead: RwLock<TypeAhead>,
     scroll_offset: RwLock<f32>,
-    background_color: Color,
+    smooth: bool,
+    /// Momentum scrolling velocity in pixels per second
+    velocity: RwLock<f32>,
+    last_tick: RwLock<Option<Instant>>,
+    /// Furthest the content scrolled at the last wheel event
+    max_scroll: RwLock<f32>,
+    background_color: ThemeColor,
     item_color: Color,
-    selected_color: Color,
-    hover_color: Color,
-    text_color: Color,
-    selected_text_color: Color,
+    selected_color: ThemeColor,
+    hover_color: ThemeColor,
+    text_color: ThemeColor,
+    selected_text_color: ThemeColor,
     item_height: f32,
     width: f32,
     height: f32,
@@ -72,19 +126,25 @@ pub struct List {
 impl List {
     /// Creates a new list.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             items: RwLock::new(Vec::new()),
             selected: RwLock::new(Vec::new()),
             selection_mode: SelectionMode::Single,
             hovered_index: RwLock::new(None),
+            cursor_index: RwLock::new(None),
+            focused: RwLock::new(false),
+            type_ahead: RwLock::new(TypeAhead::default()),
             scroll_offset: RwLock::new(0.0),
-            background_color: theme.input_box_color,
+            smooth: false,
+            velocity: RwLock::new(0.0),
+            last_tick: RwLock::new(None),
+            max_scroll: RwLock::new(0.0),
+            background_color: ThemeColor::Theme(|theme| theme.input_box_color),
             item_color: Color::new(0.0, 0.0, 0.0, 0.0),
-            selected_color: theme.selection_hilite_color,
-            hover_color: theme.frame_hilite_color.with_alpha(0.3),
-            text_color: theme.label_font_color,
-            selected_text_color: theme.label_font_color,
+            selected_color: ThemeColor::Theme(|theme| theme.selection_hilite_color),
+            hover_color: ThemeColor::Theme(|theme| theme.frame_hilite_color.with_alpha(0.3)),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            selected_text_color: ThemeColor::Theme(|theme| theme.label_font_color),
             item_height: 28.0,
             width: 200.0,
             height: 200.0,
@@ -129,13 +189,21 @@ impl List {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
     /// Sets the selected color.
     pub fn selected_color(mut self, color: Color) -> Self {
-        self.selected_color = color;
+        self.selected_color = color.into();
+        self
+    }
+
+    /// Enables momentum scrolling: wheel and trackpad deltas set the items
+    /// moving, and they slow to a stop on the view's idle ticks. Flinging
+    /// past the top or bottom briefly overscrolls, then springs back.
+    pub fn smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
         self
     }
 
@@ -209,9 +277,107 @@ impl List {
         )
     }
 
+    /// Scrolls so that the item at `index` is fully visible.
+    fn scroll_into_view(&self, ctx: &Context, index: usize) {
+        let visible_height = ctx.bounds.height();
+        let max_scroll = (self.total_content_height() - visible_height).max(0.0);
+        let top = index as f32 * self.item_height;
+        let bottom = top + self.item_height + self.padding * 2.0;
+
+        // Stop any momentum, which would carry the item away again
+        *self.velocity.write().unwrap() = 0.0;
+        let mut scroll = self.scroll_offset.write().unwrap();
+        if top < *scroll {
+            *scroll = top;
+        } else if bottom > *scroll + visible_height {
+            *scroll = bottom - visible_height;
+        }
+        *scroll = scroll.clamp(0.0, max_scroll);
+    }
+
+    /// Moves the items along with their momentum for `dt` seconds.
+    ///
+    /// Inside the content the velocity decays as in a smooth
+    /// [`ScrollView`](super::scroll::ScrollView); past an edge a spring
+    /// pulls the items back. Returns true if they moved.
+    fn advance_momentum(&self, dt: f32) -> bool {
+        let max_scroll = *self.max_scroll.read().unwrap();
+        let mut velocity = self.velocity.write().unwrap();
+        let mut scroll = self.scroll_offset.write().unwrap();
+        let start = *scroll;
+
+        let steps = (dt / MOMENTUM_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        let damping = 2.0 * OVERSCROLL_STIFFNESS.sqrt();
+        for _ in 0..steps as u32 {
+            let overshoot = *scroll - scroll.clamp(0.0, max_scroll);
+            if overshoot == 0.0 {
+                // Exponential decay, integrated exactly over the step
+                let decay = (-h / MOMENTUM_DECAY).exp();
+                *scroll += *velocity * MOMENTUM_DECAY * (1.0 - decay);
+                *velocity *= decay;
+            } else {
+                *velocity -= (OVERSCROLL_STIFFNESS * overshoot + damping * *velocity) * h;
+                *scroll += *velocity * h;
+            }
+        }
+
+        // Settle once slow enough, snapping back to the edge if overscrolled
+        let edge = scroll.clamp(0.0, max_scroll);
+        if velocity.abs() < MOMENTUM_MIN_SPEED && (*scroll - edge).abs() < 0.5 {
+            *velocity = 0.0;
+            *scroll = edge;
+            *self.last_tick.write().unwrap() = None;
+        }
+        *scroll != start
+    }
+
+    /// Returns the item the keyboard acts on: the selection in single mode,
+    /// the cursor in multiple mode.
+    fn key_index(&self) -> Option<usize> {
+        match self.selection_mode {
+            SelectionMode::Multiple => *self.cursor_index.read().unwrap(),
+            _ => self.selected.read().unwrap().first().copied(),
+        }
+    }
+
+    /// Moves the keyboard to `index`, selecting it in single mode.
+    fn move_key_index(&self, ctx: &Context, index: usize) {
+        match self.selection_mode {
+            SelectionMode::Single => {
+                if self.key_index() != Some(index) {
+                    self.set_selected(index);
+                    if let Some(ref callback) = self.on_select {
+                        callback(index);
+                    }
+                }
+            }
+            SelectionMode::Multiple => {
+                *self.cursor_index.write().unwrap() = Some(index);
+            }
+            SelectionMode::None => {}
+        }
+        self.scroll_into_view(ctx, index);
+    }
+
+    /// Toggles the selection of the item at `index` (multiple mode).
+    fn toggle_selected(&self, index: usize) {
+        let mut selected = self.selected.write().unwrap();
+        if let Some(pos) = selected.iter().position(|&x| x == index) {
+            selected.remove(pos);
+        } else {
+            selected.push(index);
+        }
+        let selection = selected.clone();
+        drop(selected);
+        if let Some(ref callback) = self.on_multi_select {
+            callback(&selection);
+        }
+    }
+
     fn draw_background(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
     }
 
@@ -219,13 +385,19 @@ impl List {
         let items = self.items.read().unwrap();
         let selected = self.selected.read().unwrap();
         let hovered = *self.hovered_index.read().unwrap();
+        let cursor = if *self.focused.read().unwrap() {
+            *self.cursor_index.read().unwrap()
+        } else {
+            None
+        };
         let theme = get_theme();
 
         for (i, item) in items.iter().enumerate() {
             let bounds = self.item_bounds(ctx, i);
 
-            // Skip if completely outside visible area (optimization)
-            if bounds.bottom < ctx.bounds.top - self.item_height || bounds.top > ctx.bounds.bottom + self.item_height {
+            // Skip items that are completely clipped
+            let visible = ctx.clip.unwrap_or(ctx.bounds);
+            if bounds.bottom < visible.top || bounds.top > visible.bottom {
                 continue;
             }
 
@@ -236,20 +408,20 @@ impl List {
 
             // Background
             if is_selected {
-                canvas.fill_style(self.selected_color);
+                canvas.fill_style(self.selected_color.get());
                 canvas.fill_round_rect(bounds, 3.0);
             } else if is_hovered {
-                canvas.fill_style(self.hover_color);
+                canvas.fill_style(self.hover_color.get());
                 canvas.fill_round_rect(bounds, 3.0);
             }
 
             // Text
             let text_color = if !self.enabled {
-                self.text_color.with_alpha(0.5)
+                self.text_color.get().with_alpha(0.5)
             } else if is_selected {
-                self.selected_text_color
+                self.selected_text_color.get()
             } else {
-                self.text_color
+                self.text_color.get()
             };
 
             canvas.fill_style(text_color);
@@ -258,6 +430,15 @@ impl List {
             let x = bounds.left + 8.0;
             let y = bounds.center().y + theme.label_font_size * 0.35;
             canvas.fill_text(&item.label, Point::new(x, y));
+
+            // Keyboard cursor
+            if cursor == Some(i) {
+                canvas.stroke_style(theme.frame_hilite_color);
+                canvas.line_width(1.0);
+                canvas.begin_path();
+                canvas.add_round_rect(bounds, 3.0);
+                canvas.stroke();
+            }
         }
     }
 
@@ -270,7 +451,8 @@ impl List {
         }
 
         let theme = get_theme();
-        let scroll = *self.scroll_offset.read().unwrap();
+        // The thumb stays in its track while the items overscroll
+        let scroll = self.scroll_offset.read().unwrap().clamp(0.0, total_height - visible_height);
 
         let scrollbar_height = (visible_height / total_height * visible_height).max(20.0);
         let scrollbar_y = scroll / (total_height - visible_height) * (visible_height - scrollbar_height);
@@ -306,26 +488,11 @@ impl Element for List {
     fn draw(&self, ctx: &Context) {
         self.draw_background(ctx);
 
-        // Set clip rect for items (inset to account for corner radius)
-        {
-            let mut canvas = ctx.canvas.borrow_mut();
-            canvas.save();
-            let clip_bounds = Rect::new(
-                ctx.bounds.left + self.corner_radius,
-                ctx.bounds.top + self.corner_radius,
-                ctx.bounds.right - self.corner_radius,
-                ctx.bounds.bottom - self.corner_radius,
-            );
-            canvas.clip(clip_bounds);
-        }
-
-        self.draw_items(ctx);
-
-        // Restore canvas state (removes clipping)
-        {
-            let mut canvas = ctx.canvas.borrow_mut();
-            canvas.restore();
-        }
+        // Clip items to the viewport (inset to account for corner radius)
+        let viewport = ctx.bounds.inset(self.corner_radius, self.corner_radius);
+        ctx.canvas.borrow_mut().push_clip(viewport);
+        self.draw_items(&ctx.with_clip(viewport));
+        ctx.canvas.borrow_mut().pop_clip();
 
         self.draw_scrollbar(ctx);
     }
@@ -351,6 +518,7 @@ impl Element for List {
             return true;
         }
 
+        *self.focused.write().unwrap() = true;
         if self.selection_mode == SelectionMode::None {
             return true;
         }
@@ -374,16 +542,9 @@ impl Element for List {
                         }
                     }
                     SelectionMode::Multiple => {
-                        if let Some(pos) = selected.iter().position(|&x| x == i) {
-                            selected.remove(pos);
-                        } else {
-                            selected.push(i);
-                        }
-                        let selection = selected.clone();
                         drop(selected);
-                        if let Some(ref callback) = self.on_multi_select {
-                            callback(&selection);
-                        }
+                        *self.cursor_index.write().unwrap() = Some(i);
+                        self.toggle_selected(i);
                     }
                     SelectionMode::None => {}
                 }
@@ -395,6 +556,91 @@ impl Element for List {
         true
     }
 
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if !self.enabled || !*self.focused.read().unwrap() || k.action == KeyAction::Release {
+            return false;
+        }
+
+        let count = self.items.read().unwrap().len();
+        if count == 0 || self.selection_mode == SelectionMode::None {
+            return false;
+        }
+
+        let last = count - 1;
+        let page = ((ctx.bounds.height() - self.padding * 2.0) / self.item_height).max(1.0) as usize;
+        let current = self.key_index();
+
+        let target = match (k.key, current) {
+            (KeyCode::Up, Some(i)) => i.saturating_sub(1),
+            (KeyCode::Down, Some(i)) => (i + 1).min(last),
+            (KeyCode::PageUp, Some(i)) => i.saturating_sub(page),
+            (KeyCode::PageDown, Some(i)) => (i + page).min(last),
+            (KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown, None) => 0,
+            (KeyCode::Home, _) => 0,
+            (KeyCode::End, _) => last,
+            (KeyCode::Space, Some(i)) if self.selection_mode == SelectionMode::Multiple => {
+                self.toggle_selected(i);
+                return true;
+            }
+            _ => return false,
+        };
+
+        self.move_key_index(ctx, target);
+        true
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.handle_text(ctx, info)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        if !self.enabled || !*self.focused.read().unwrap() || self.selection_mode == SelectionMode::None {
+            return false;
+        }
+
+        let c = info.codepoint;
+        let mut type_ahead = self.type_ahead.write().unwrap();
+        // A leading space is the Space key toggling the selection
+        if c.is_control() || (c == ' ' && type_ahead.is_idle()) {
+            return false;
+        }
+
+        let prefix = type_ahead.push(c);
+        let items = self.items.read().unwrap();
+        let found = TypeAhead::find(prefix, items.iter().map(|item| item.label.as_str()));
+        drop(items);
+        drop(type_ahead);
+
+        if let Some(index) = found {
+            self.move_key_index(ctx, index);
+        }
+        true
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.enabled
+    }
+
+    fn clear_focus(&self) {
+        *self.focused.write().unwrap() = false;
+    }
+
+    fn has_focus(&self) -> bool {
+        *self.focused.read().unwrap()
+    }
+
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        *self.focused.write().unwrap() = true;
+        true
+    }
+
     fn scroll(&mut self, ctx: &Context, dir: Point, _p: Point) -> bool {
         self.handle_scroll(ctx, dir, _p)
     }
@@ -411,12 +657,29 @@ impl Element for List {
             return false;
         }
 
+        if self.smooth {
+            // Add enough speed for the items to coast the usual step
+            *self.max_scroll.write().unwrap() = total_height - visible_height;
+            *self.velocity.write().unwrap() -= dir.y * 20.0 / MOMENTUM_DECAY;
+            self.last_tick.write().unwrap().get_or_insert_with(Instant::now);
+            return true;
+        }
+
         let mut scroll = self.scroll_offset.write().unwrap();
         *scroll = (*scroll - dir.y * 20.0).clamp(0.0, total_height - visible_height);
 
         true
     }
 
+    fn handle_poll(&self) -> bool {
+        let Some(last_tick) = *self.last_tick.read().unwrap() else {
+            return false;
+        };
+        let now = Instant::now();
+        *self.last_tick.write().unwrap() = Some(now);
+        self.advance_momentum(now.duration_since(last_tick).as_secs_f32())
+    }
+
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
         if !self.enabled {
             return false;
@@ -461,45 +724,63 @@ impl Element for List {
     }
 }
 
+/// Width of the arrow at the right end of a dropdown.
+const DROPDOWN_ARROW_WIDTH: f32 = 24.0;
+
 /// A dropdown/combo box element.
+///
+/// An [editable](Dropdown::editable) dropdown is a combo box: its collapsed
+/// control is a text box whose contents filter the list, and Enter commits
+/// whatever was typed, even if it is not one of the items.
 pub struct Dropdown {
     items: Vec<String>,
     selected: RwLock<Option<usize>>,
     expanded: RwLock<bool>,
     hovered_index: RwLock<Option<usize>>,
-    background_color: Color,
-    hover_color: Color,
-    text_color: Color,
-    arrow_color: Color,
+    background_color: ThemeColor,
+    hover_color: ThemeColor,
+    text_color: ThemeColor,
+    arrow_color: ThemeColor,
     width: f32,
     height: f32,
     dropdown_height: f32,
     corner_radius: f32,
     enabled: bool,
     placeholder: String,
+    type_ahead: RwLock<TypeAhead>,
+    /// Scroll position of the open list
+    scroll_offset: RwLock<f32>,
     on_select: Option<SelectionCallback>,
+    editor: Option<TextBox>,
+    /// Text the list is filtered by, while the user is typing
+    query: RwLock<Option<String>>,
+    on_text: Option<TextChangeCallback>,
 }
 
 impl Dropdown {
     /// Creates a new dropdown.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             items: Vec::new(),
             selected: RwLock::new(None),
             expanded: RwLock::new(false),
             hovered_index: RwLock::new(None),
-            background_color: theme.default_button_color,
-            hover_color: theme.frame_hilite_color,
-            text_color: theme.label_font_color,
-            arrow_color: theme.label_font_color,
+            background_color: ThemeColor::Theme(|theme| theme.default_button_color),
+            hover_color: ThemeColor::Theme(|theme| theme.frame_hilite_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            arrow_color: ThemeColor::Theme(|theme| theme.label_font_color),
             width: 150.0,
             height: 28.0,
             dropdown_height: 150.0,
             corner_radius: 4.0,
             enabled: true,
             placeholder: String::from("Select..."),
+            type_ahead: RwLock::new(TypeAhead::default()),
+            scroll_offset: RwLock::new(0.0),
             on_select: None,
+            editor: None,
+            query: RwLock::new(None),
+            on_text: None,
         }
     }
 
@@ -512,6 +793,13 @@ impl Dropdown {
     /// Sets the placeholder text.
     pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
         self.placeholder = placeholder.into();
+        self.editor = self.editor.take().map(|editor| editor.placeholder(self.placeholder.clone()));
+        self
+    }
+
+    /// Makes the dropdown editable, turning it into a combo box.
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editor = editable.then(|| TextBox::new().placeholder(self.placeholder.clone()));
         self
     }
 
@@ -527,6 +815,21 @@ impl Dropdown {
         self
     }
 
+    /// Sets the callback for values committed in an editable dropdown,
+    /// either typed or picked from the list.
+    pub fn on_text<F: Fn(&str) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_text = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the text in an editable dropdown, or the selected item text.
+    pub fn get_text(&self) -> Option<String> {
+        match &self.editor {
+            Some(editor) => Some(editor.get_text()),
+            None => self.get_selected_text(),
+        }
+    }
+
     /// Returns the selected index.
     pub fn get_selected(&self) -> Option<usize> {
         *self.selected.read().unwrap()
@@ -541,15 +844,186 @@ impl Dropdown {
     /// Sets the selected index.
     pub fn set_selected(&self, index: Option<usize>) {
         *self.selected.write().unwrap() = index;
+        if let Some(ref editor) = self.editor {
+            editor.set_text(index.and_then(|i| self.items.get(i)).map_or("", String::as_str));
+        }
     }
 
     fn item_height(&self) -> f32 {
         self.height
     }
 
+    /// Returns the indices of the items shown in the list, in order. While
+    /// the user types in an editable dropdown, these are the items
+    /// containing the typed text.
+    fn shown_items(&self) -> Vec<usize> {
+        match &*self.query.read().unwrap() {
+            Some(query) if !query.is_empty() => {
+                let query = query.to_lowercase();
+                (0..self.items.len())
+                    .filter(|&i| self.items[i].to_lowercase().contains(&query))
+                    .collect()
+            }
+            _ => (0..self.items.len()).collect(),
+        }
+    }
+
+    /// Returns the item under `p` in the expanded list.
+    fn item_at(&self, ctx: &Context, p: Point) -> Option<usize> {
+        let dropdown_rect = self.dropdown_bounds(ctx);
+        if !dropdown_rect.contains(p) {
+            return None;
+        }
+        let scroll = *self.scroll_offset.read().unwrap();
+        let row = ((p.y - dropdown_rect.top + scroll) / self.item_height()) as usize;
+        self.shown_items().get(row).copied()
+    }
+
+    /// Returns the height of the open list: up to five rows, and at most
+    /// the dropdown height.
+    fn list_height(&self) -> f32 {
+        let rows = self.shown_items().len().min(5);
+        (rows as f32 * self.item_height()).min(self.dropdown_height)
+    }
+
+    fn max_scroll(&self) -> f32 {
+        let content_height = self.shown_items().len() as f32 * self.item_height();
+        (content_height - self.list_height()).max(0.0)
+    }
+
+    /// Scrolls the open list so that the item at `index` is fully visible.
+    fn scroll_into_view(&self, index: usize) {
+        let Some(row) = self.shown_items().iter().position(|&i| i == index) else {
+            return;
+        };
+        let top = row as f32 * self.item_height();
+        let bottom = top + self.item_height();
+        let visible_height = self.list_height();
+
+        let mut scroll = self.scroll_offset.write().unwrap();
+        if top < *scroll {
+            *scroll = top;
+        } else if bottom > *scroll + visible_height {
+            *scroll = bottom - visible_height;
+        }
+        *scroll = scroll.clamp(0.0, self.max_scroll());
+    }
+
+    /// Opens the list, scrolled to the selected item.
+    fn expand(&self) {
+        *self.expanded.write().unwrap() = true;
+        *self.scroll_offset.write().unwrap() = 0.0;
+        let selected = *self.selected.read().unwrap();
+        if let Some(index) = selected {
+            self.scroll_into_view(index);
+        }
+    }
+
+    /// Returns the context of the editor in an editable dropdown.
+    fn editor_context<'a>(&self, ctx: &Context<'a>) -> Context<'a> {
+        let bounds = ctx.bounds;
+        ctx.with_bounds(Rect::new(bounds.left, bounds.top, bounds.right - DROPDOWN_ARROW_WIDTH, bounds.bottom))
+    }
+
+    fn collapse(&self) {
+        *self.expanded.write().unwrap() = false;
+        *self.hovered_index.write().unwrap() = None;
+        *self.query.write().unwrap() = None;
+    }
+
+    /// Selects an item picked from the list, and closes the list.
+    fn select(&self, index: usize) {
+        *self.selected.write().unwrap() = Some(index);
+        self.collapse();
+        if let Some(ref callback) = self.on_select {
+            callback(index);
+        }
+        if let Some(ref editor) = self.editor {
+            editor.set_text(self.items[index].as_str());
+            if let Some(ref callback) = self.on_text {
+                callback(&self.items[index]);
+            }
+        }
+    }
+
+    /// Commits the text typed in an editable dropdown. Text naming an item
+    /// also selects it.
+    fn commit(&self, editor: &TextBox) {
+        let text = editor.get_text();
+        let index = self.items.iter().position(|item| *item == text);
+        let changed = *self.selected.read().unwrap() != index;
+        *self.selected.write().unwrap() = index;
+        self.collapse();
+        if let (true, Some(index), Some(callback)) = (changed, index, &self.on_select) {
+            callback(index);
+        }
+        if let Some(ref callback) = self.on_text {
+            callback(&text);
+        }
+    }
+
+    /// Filters the list by the editor text after an edit changed it.
+    fn edited(&self, editor: &TextBox, before: &str) {
+        let text = editor.get_text();
+        if text != before {
+            *self.query.write().unwrap() = Some(text);
+            *self.hovered_index.write().unwrap() = None;
+            self.expand();
+        }
+    }
+
+    /// Handles keys for an editable dropdown: arrows move through the list,
+    /// Enter commits and Escape closes it. Other keys edit the text.
+    fn editor_key(&self, ctx: &Context, editor: &TextBox, k: KeyInfo) -> bool {
+        if !matches!(k.action, KeyAction::Press | KeyAction::Repeat) {
+            return editor.handle_key(&self.editor_context(ctx), k);
+        }
+
+        let expanded = *self.expanded.read().unwrap();
+        match k.key {
+            KeyCode::Up | KeyCode::Down => {
+                let shown = self.shown_items();
+                let hovered = *self.hovered_index.read().unwrap();
+                let row = hovered.and_then(|i| shown.iter().position(|&s| s == i));
+                let last = shown.len().saturating_sub(1);
+                let row = match (k.key, row) {
+                    (KeyCode::Up, Some(r)) => r.saturating_sub(1),
+                    (KeyCode::Down, Some(r)) => (r + 1).min(last),
+                    _ => 0,
+                };
+                if !expanded {
+                    self.expand();
+                }
+                let hovered = shown.get(row).copied();
+                *self.hovered_index.write().unwrap() = hovered;
+                if let Some(index) = hovered {
+                    self.scroll_into_view(index);
+                }
+                true
+            }
+            KeyCode::Enter => {
+                let hovered = *self.hovered_index.read().unwrap();
+                match hovered {
+                    Some(index) if expanded => self.select(index),
+                    _ => self.commit(editor),
+                }
+                true
+            }
+            KeyCode::Escape if expanded => {
+                self.collapse();
+                true
+            }
+            _ => {
+                let before = editor.get_text();
+                let handled = editor.handle_key(&self.editor_context(ctx), k);
+                self.edited(editor, &before);
+                handled
+            }
+        }
+    }
+
     fn dropdown_bounds(&self, ctx: &Context) -> Rect {
-        let item_count = self.items.len().min(5);
-        let height = (item_count as f32 * self.item_height()).min(self.dropdown_height);
+        let height = self.list_height();
 
         Rect::new(
             ctx.bounds.left,
@@ -565,35 +1039,43 @@ impl Dropdown {
         let expanded = *self.expanded.read().unwrap();
 
         let color = if expanded {
-            self.background_color.level(1.2)
+            self.background_color.get().level(1.2)
         } else {
-            self.background_color
+            self.background_color.get()
         };
 
         canvas.fill_style(color);
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
 
-        // Text
-        let selected = *self.selected.read().unwrap();
-        let text = selected
-            .and_then(|i| self.items.get(i))
-            .unwrap_or(&self.placeholder);
-
-        let text_color = if selected.is_none() {
-            self.text_color.with_alpha(0.6)
+        if let Some(ref editor) = self.editor {
+            drop(canvas);
+            editor.draw(&self.editor_context(ctx));
+            canvas = ctx.canvas.borrow_mut();
         } else {
-            self.text_color
-        };
+            // Text
+            let selected = *self.selected.read().unwrap();
+            let text = selected
+                .and_then(|i| self.items.get(i))
+                .unwrap_or(&self.placeholder);
 
-        canvas.fill_style(text_color);
-        canvas.font_size(theme.label_font_size);
+            let text_color = if selected.is_none() {
+                self.text_color.get().with_alpha(0.6)
+            } else {
+                self.text_color.get()
+            };
 
-        let x = ctx.bounds.left + 10.0;
-        let y = ctx.bounds.center().y + theme.label_font_size * 0.35;
-        canvas.fill_text(text, Point::new(x, y));
+            canvas.fill_style(text_color);
+            canvas.font_size(theme.label_font_size);
+
+            let x = ctx.bounds.left + 10.0;
+            let y = ctx.bounds.center().y + theme.label_font_size * 0.35;
+            canvas.fill_text(text, Point::new(x, y));
+        }
 
         // Arrow
-        canvas.fill_style(self.arrow_color);
+        canvas.fill_style(self.arrow_color.get());
+        canvas.font_size(theme.label_font_size);
+        let y = ctx.bounds.center().y + theme.label_font_size * 0.35;
         let arrow = if expanded { "▲" } else { "▼" };
         let arrow_x = ctx.bounds.right - 20.0;
         canvas.fill_text(arrow, Point::new(arrow_x, y));
@@ -617,40 +1099,58 @@ impl Dropdown {
         canvas.fill_round_rect(shadow_rect, self.corner_radius);
 
         // Background
-        canvas.fill_style(self.background_color.level(1.1));
+        canvas.fill_style(self.background_color.get().level(1.1));
         canvas.fill_round_rect(dropdown_rect, self.corner_radius);
 
-        // Items
-        for (i, item) in self.items.iter().enumerate() {
-            let item_rect = Rect::new(
-                dropdown_rect.left,
-                dropdown_rect.top + i as f32 * self.item_height(),
-                dropdown_rect.right,
-                dropdown_rect.top + (i + 1) as f32 * self.item_height(),
-            );
+        // Items, clipped to the dropdown
+        let scroll = *self.scroll_offset.read().unwrap();
+        canvas.push_clip(dropdown_rect);
+        for (row, i) in self.shown_items().into_iter().enumerate() {
+            let item = &self.items[i];
+            let top = dropdown_rect.top + row as f32 * self.item_height() - scroll;
+            let item_rect = Rect::new(dropdown_rect.left, top, dropdown_rect.right, top + self.item_height());
 
-            if item_rect.bottom > dropdown_rect.bottom {
-                break;
+            if item_rect.bottom < dropdown_rect.top || item_rect.top > dropdown_rect.bottom {
+                continue;
             }
 
             let is_selected = selected == Some(i);
             let is_hovered = hovered == Some(i);
 
             if is_selected {
-                canvas.fill_style(self.hover_color);
+                canvas.fill_style(self.hover_color.get());
                 canvas.fill_rect(item_rect);
             } else if is_hovered {
-                canvas.fill_style(self.hover_color.with_alpha(0.5));
+                canvas.fill_style(self.hover_color.get().with_alpha(0.5));
                 canvas.fill_rect(item_rect);
             }
 
-            canvas.fill_style(self.text_color);
+            canvas.fill_style(self.text_color.get());
             canvas.font_size(theme.label_font_size);
 
             let x = item_rect.left + 10.0;
             let y = item_rect.center().y + theme.label_font_size * 0.35;
             canvas.fill_text(item, Point::new(x, y));
         }
+
+        // Scrollbar
+        let max_scroll = self.max_scroll();
+        if max_scroll > 0.0 {
+            let visible_height = dropdown_rect.height();
+            let thumb_height = (visible_height / (visible_height + max_scroll) * visible_height).max(20.0);
+            let thumb_y = scroll / max_scroll * (visible_height - thumb_height);
+            canvas.fill_style(theme.scrollbar_color);
+            canvas.fill_round_rect(
+                Rect::new(
+                    dropdown_rect.right - 6.0,
+                    dropdown_rect.top + thumb_y,
+                    dropdown_rect.right - 2.0,
+                    dropdown_rect.top + thumb_y + thumb_height,
+                ),
+                2.0,
+            );
+        }
+        canvas.pop_clip();
     }
 }
 
@@ -693,6 +1193,12 @@ impl Element for Dropdown {
         None
     }
 
+    fn cursor_type(&self, ctx: &Context, p: Point) -> Option<CursorType> {
+        // The editable part takes text; the list and arrow are clicked
+        let over_editor = self.editor.is_some() && self.editor_context(ctx).bounds.contains(p);
+        over_editor.then_some(CursorType::IBeam)
+    }
+
     fn wants_control(&self) -> bool {
         self.enabled
     }
@@ -709,35 +1215,71 @@ impl Element for Dropdown {
         let expanded = *self.expanded.read().unwrap();
 
         // Check dropdown area FIRST when expanded (dropdown is below button)
-        if expanded {
-            let dropdown_rect = self.dropdown_bounds(ctx);
-            if dropdown_rect.contains(btn.pos) {
-                // Find clicked item
-                let rel_y = btn.pos.y - dropdown_rect.top;
-                let index = (rel_y / self.item_height()) as usize;
-
-                if index < self.items.len() {
-                    *self.selected.write().unwrap() = Some(index);
-                    *self.expanded.write().unwrap() = false;
-                    if let Some(ref callback) = self.on_select {
-                        callback(index);
-                    }
-                }
-                return true;
+        if expanded && self.dropdown_bounds(ctx).contains(btn.pos) {
+            if let Some(index) = self.item_at(ctx, btn.pos) {
+                self.select(index);
+            }
+            return true;
+        }
+
+        if let Some(ref editor) = self.editor {
+            let editor_ctx = self.editor_context(ctx);
+            if editor_ctx.bounds.contains(btn.pos) {
+                return editor.handle_click(&editor_ctx, btn);
             }
         }
 
         if ctx.bounds.contains(btn.pos) {
+            // The arrow of an editable dropdown also focuses the editor
+            if let Some(ref editor) = self.editor {
+                editor.handle_begin_focus(FocusRequest::FromTop);
+            }
             // Toggle dropdown
-            *self.expanded.write().unwrap() = !expanded;
+            if expanded {
+                self.collapse();
+            } else {
+                self.expand();
+            }
             return true;
         }
 
         if expanded {
             // Click outside closes dropdown
-            *self.expanded.write().unwrap() = false;
+            self.collapse();
+        }
+
+        true
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if let Some(ref editor) = self.editor {
+            editor.handle_drag(&self.editor_context(ctx), btn);
+        }
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        match &self.editor {
+            Some(editor) if self.enabled && editor.has_focus() => self.editor_key(ctx, editor, k),
+            _ => false,
+        }
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        if !self.enabled || !*self.expanded.read().unwrap() || !self.dropdown_bounds(ctx).contains(p) {
+            return false;
         }
 
+        let max_scroll = self.max_scroll();
+        let mut scroll = self.scroll_offset.write().unwrap();
+        *scroll = (*scroll - dir.y * 20.0).clamp(0.0, max_scroll);
         true
     }
 
@@ -753,18 +1295,7 @@ impl Element for Dropdown {
                 *self.hovered_index.write().unwrap() = None;
             }
             _ if expanded => {
-                let dropdown_rect = self.dropdown_bounds(ctx);
-                if dropdown_rect.contains(p) {
-                    let rel_y = p.y - dropdown_rect.top;
-                    let index = (rel_y / self.item_height()) as usize;
-                    *self.hovered_index.write().unwrap() = if index < self.items.len() {
-                        Some(index)
-                    } else {
-                        None
-                    };
-                } else {
-                    *self.hovered_index.write().unwrap() = None;
-                }
+                *self.hovered_index.write().unwrap() = self.item_at(ctx, p);
             }
             _ => {
                 *self.hovered_index.write().unwrap() = None;
@@ -774,6 +1305,68 @@ impl Element for Dropdown {
         true
     }
 
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.handle_text(ctx, info)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        if let Some(ref editor) = self.editor {
+            let before = editor.get_text();
+            let handled = self.enabled && editor.handle_text(&self.editor_context(ctx), info);
+            self.edited(editor, &before);
+            return handled;
+        }
+
+        if !self.enabled || !*self.expanded.read().unwrap() || info.codepoint.is_control() {
+            return false;
+        }
+
+        let mut type_ahead = self.type_ahead.write().unwrap();
+        let prefix = type_ahead.push(info.codepoint);
+        if let Some(index) = TypeAhead::find(prefix, self.items.iter().map(String::as_str)) {
+            *self.hovered_index.write().unwrap() = Some(index);
+            self.scroll_into_view(index);
+
+            if *self.selected.read().unwrap() != Some(index) {
+                *self.selected.write().unwrap() = Some(index);
+                if let Some(ref callback) = self.on_select {
+                    callback(index);
+                }
+            }
+        }
+        true
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.enabled && self.editor.is_some()
+    }
+
+    fn clear_focus(&self) {
+        if let Some(ref editor) = self.editor {
+            editor.clear_focus();
+        }
+    }
+
+    // An open dropdown holds the focus, so tabbing away closes it
+    fn has_focus(&self) -> bool {
+        *self.expanded.read().unwrap() || self.editor.as_ref().is_some_and(|editor| editor.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.enabled && self.editor.as_ref().is_some_and(|editor| editor.handle_begin_focus(req))
+    }
+
+    fn handle_end_focus(&self) {
+        self.collapse();
+        if let Some(ref editor) = self.editor {
+            editor.handle_end_focus();
+        }
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.editor.as_ref().is_some_and(|editor| editor.handle_poll())
+    }
+
     fn enable(&mut self, state: bool) {
         self.enabled = state;
     }
@@ -800,3 +1393,188 @@ pub fn list() -> List {
 pub fn dropdown() -> Dropdown {
     Dropdown::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_type_ahead_selects_and_scrolls() {
+        let l = Arc::new(
+            list()
+                .items_from_strings(vec!["apple", "banana", "Blueberry", "cherry", "date", "elderberry"])
+                .item_height(20.0)
+                .size(100.0, 48.0),
+        );
+        let view = TestView::new(l.clone(), Extent::new(100.0, 48.0));
+        assert!(view.focus_first());
+
+        view.type_text("b");
+        assert_eq!(l.get_selected(), vec![1]);
+        view.type_text("l");
+        assert_eq!(l.get_selected(), vec![2]);
+
+        // Typing "e" makes "ble", which matches nothing: the selection stays
+        view.type_text("e");
+        assert_eq!(l.get_selected(), vec![2]);
+
+        // A new search after a pause
+        l.type_ahead.write().unwrap().last_input = Some(Instant::now() - Duration::from_secs(2));
+        view.type_text("e");
+        assert_eq!(l.get_selected(), vec![5]);
+
+        // Item 5 spans 104..124 inside the padding; a 48 tall view scrolls to 80
+        assert_eq!(*l.scroll_offset.read().unwrap(), 80.0);
+    }
+
+    #[test]
+    fn test_keys_move_selection_and_scroll() {
+        let l = Arc::new(list().items_from_strings(vec!["a"; 10]).item_height(20.0).size(100.0, 48.0));
+        let view = TestView::new(l.clone(), Extent::new(100.0, 48.0));
+        let offset = || *l.scroll_offset.read().unwrap();
+        assert!(view.focus_first());
+
+        // With nothing selected, the first key selects the first item
+        assert!(view.key(KeyCode::Down, 0));
+        assert_eq!((l.get_selected(), offset()), (vec![0], 0.0));
+        view.key(KeyCode::Down, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![1], 0.0));
+        view.key(KeyCode::Down, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![2], 20.0));
+
+        // Two items fit inside the padding, so a page is two items
+        view.key(KeyCode::PageDown, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![4], 60.0));
+        view.key(KeyCode::End, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![9], 160.0));
+        view.key(KeyCode::PageDown, 0);
+        assert_eq!(l.get_selected(), vec![9]);
+        view.key(KeyCode::PageUp, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![7], 140.0));
+        view.key(KeyCode::Up, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![6], 120.0));
+        view.key(KeyCode::Home, 0);
+        assert_eq!((l.get_selected(), offset()), (vec![0], 0.0));
+        view.key(KeyCode::Up, 0);
+        assert_eq!(l.get_selected(), vec![0]);
+
+        // Space only toggles items in multiple mode
+        assert!(!view.key(KeyCode::Space, 0));
+    }
+
+    #[test]
+    fn test_keys_move_cursor_in_multiple_mode() {
+        let l = Arc::new(
+            list()
+                .items_from_strings(vec!["a"; 10])
+                .item_height(20.0)
+                .size(100.0, 48.0)
+                .selection_mode(SelectionMode::Multiple),
+        );
+        let view = TestView::new(l.clone(), Extent::new(100.0, 48.0));
+        assert!(view.focus_first());
+
+        // Moving leaves the selection alone; Space toggles the item
+        view.key(KeyCode::Down, 0);
+        view.key(KeyCode::Down, 0);
+        assert!(l.get_selected().is_empty());
+        assert!(view.key(KeyCode::Space, 0));
+        view.key(KeyCode::End, 0);
+        assert!(view.key(KeyCode::Space, 0));
+        assert_eq!(l.get_selected(), vec![1, 9]);
+        assert_eq!(*l.scroll_offset.read().unwrap(), 160.0);
+        view.key(KeyCode::Home, 0);
+        view.key(KeyCode::Down, 0);
+        assert!(view.key(KeyCode::Space, 0));
+        assert_eq!(l.get_selected(), vec![9]);
+    }
+
+    #[test]
+    fn test_fling_overscrolls_and_springs_back() {
+        let l = Arc::new(
+            list()
+                .items_from_strings(vec!["a"; 10])
+                .item_height(20.0)
+                .size(100.0, 48.0)
+                .smooth(true),
+        );
+        let view = TestView::new(l.clone(), Extent::new(100.0, 48.0));
+        let offset = || *l.scroll_offset.read().unwrap();
+
+        // One wheel step coasts about 20 pixels in total
+        assert!(view.scroll(Point::new(0.0, -1.0), Point::zero()));
+        while l.advance_momentum(1.0 / 60.0) {}
+        assert!((offset() - 20.0).abs() < 1.0);
+
+        // A fling back past the top overshoots a little, then settles there
+        view.scroll(Point::new(0.0, 10.0), Point::zero());
+        let mut lowest = offset();
+        while l.advance_momentum(1.0 / 60.0) {
+            lowest = lowest.min(offset());
+        }
+        assert!(lowest < 0.0 && lowest > -40.0, "overscrolled to {lowest}");
+        assert_eq!(offset(), 0.0);
+        assert_eq!(*l.velocity.read().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_editable_dropdown_filters_and_commits() {
+        use std::sync::Mutex;
+
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let log = committed.clone();
+        let d = Arc::new(
+            dropdown()
+                .items(vec!["Apple", "Banana", "Cherry"])
+                .editable(true)
+                .on_text(move |text| log.lock().unwrap().push(text.to_string())),
+        );
+        let view = TestView::new(d.clone(), Extent::new(150.0, 28.0));
+        assert!(view.focus_first());
+
+        // Typing opens the list, filtered to the matching items
+        view.type_text("an");
+        assert!(*d.expanded.read().unwrap());
+        assert_eq!(d.shown_items(), vec![1]);
+
+        // Enter commits free text that is not in the list
+        view.key(KeyCode::Enter, 0);
+        assert!(!*d.expanded.read().unwrap());
+        assert_eq!(d.get_selected(), None);
+
+        // Picking a match from the filtered list selects it
+        view.type_text("e");
+        assert_eq!(d.shown_items(), Vec::<usize>::new());
+        view.key(KeyCode::Backspace, 0);
+        view.key(KeyCode::Down, 0);
+        view.key(KeyCode::Enter, 0);
+        assert_eq!(d.get_selected(), Some(1));
+        assert_eq!(d.get_text().as_deref(), Some("Banana"));
+        assert_eq!(*committed.lock().unwrap(), vec!["an", "Banana"]);
+    }
+
+    #[test]
+    fn test_long_dropdown_scrolls() {
+        let d = Arc::new(dropdown().items(vec!["a", "b", "c", "d", "e", "f", "g", "h"]));
+        let view = TestView::new(d.clone(), Extent::new(200.0, 400.0));
+        let bounds = Rect::new(0.0, 0.0, 150.0, 28.0);
+
+        // Opening scrolls the selection into view: five 28 tall rows show
+        d.set_selected(Some(7));
+        d.expand();
+        assert_eq!(*d.scroll_offset.read().unwrap(), 3.0 * 28.0);
+        let last_row = Point::new(10.0, 30.0 + 4.5 * 28.0);
+        view.with_context(|ctx| {
+            let ctx = ctx.with_bounds(bounds);
+            assert_eq!(d.item_at(&ctx, last_row), Some(7));
+
+            // The wheel scrolls back to the top, and no further
+            assert!(d.handle_scroll(&ctx, Point::new(0.0, 10.0), last_row));
+            assert_eq!(*d.scroll_offset.read().unwrap(), 0.0);
+            assert_eq!(d.item_at(&ctx, last_row), Some(4));
+        });
+    }
+}
@@ -5,7 +5,7 @@ use super::{Element, ViewLimits, FocusRequest};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
-use crate::view::{MouseButton, KeyInfo, TextInfo, CursorTracking};
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
 
 /// Margin values for all four sides.
 #[derive(Debug, Clone, Copy, Default)]
@@ -122,12 +122,18 @@ impl<S: Element> MarginElement<S> {
         &mut self.subject
     }
 
+    /// Returns the subject's bounds within `bounds`.
+    ///
+    /// Bounds too small for the margins leave the subject an empty rect
+    /// after the left and top margins, rather than an inverted one.
     fn adjust_bounds(&self, bounds: Rect) -> Rect {
+        let left = bounds.left + self.margin.left;
+        let top = bounds.top + self.margin.top;
         Rect {
-            left: bounds.left + self.margin.left,
-            top: bounds.top + self.margin.top,
-            right: bounds.right - self.margin.right,
-            bottom: bounds.bottom - self.margin.bottom,
+            left,
+            top,
+            right: (bounds.right - self.margin.right).max(left),
+            bottom: (bounds.bottom - self.margin.bottom).max(top),
         }
     }
 }
@@ -222,6 +228,22 @@ impl<S: Element + 'static> Element for MarginElement<S> {
         self.subject.handle_scroll(&adjusted_ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.handle_magnify(&adjusted_ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_track_drop(&adjusted_ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_drop(&adjusted_ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -254,6 +276,40 @@ impl<S: Element + 'static> Element for MarginElement<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.bounds_of_element(&adjusted_ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.focus_bounds(&adjusted_ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_cursor(&adjusted_ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -266,6 +322,9 @@ impl<S: Element + 'static> Element for MarginElement<S> {
 // Convenience functions
 
 /// Adds margin to an element.
+///
+/// The margin can be a single value for all sides, a `(horizontal,
+/// vertical)` pair, a `(left, top, right, bottom)` tuple or a [`Margin`].
 pub fn margin<S: Element>(margin: impl Into<Margin>, subject: S) -> MarginElement<S> {
     MarginElement::new(margin, subject)
 }
@@ -299,3 +358,45 @@ pub fn margin_horizontal<S: Element>(value: f32, subject: S) -> MarginElement<S>
 pub fn margin_vertical<S: Element>(value: f32, subject: S) -> MarginElement<S> {
     MarginElement::new(Margin::vertical(value), subject)
 }
+
+/// Adds `horizontal` margin to the left and right of an element and
+/// `vertical` margin above and below it.
+pub fn margin_hv<S: Element>(horizontal: f32, vertical: f32, subject: S) -> MarginElement<S> {
+    MarginElement::new(Margin::new(horizontal, vertical, horizontal, vertical), subject)
+}
+
+/// Adds a different margin to each side of an element.
+pub fn margin_sides<S: Element>(left: f32, top: f32, right: f32, bottom: f32, subject: S) -> MarginElement<S> {
+    MarginElement::new(Margin::new(left, top, right, bottom), subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::element::size::fixed_size;
+    use crate::element::button::button;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_asymmetric_margins() {
+        let padded = Arc::new(margin_sides(10.0, 2.0, 4.0, 8.0, fixed_size(50.0, 20.0, button("OK"))));
+        let view = TestView::new(padded.clone(), Extent::new(64.0, 30.0));
+
+        let limits = view.limits();
+        assert_eq!(limits.min, Point::new(64.0, 30.0));
+        assert_eq!(limits.max, Point::new(64.0, 30.0));
+
+        // The subject sits inside the margins and only it is hit
+        assert_eq!(padded.adjust_bounds(Rect::new(0.0, 0.0, 64.0, 30.0)), Rect::new(10.0, 2.0, 60.0, 22.0));
+        let hit = |x, y| view.with_context(|ctx| padded.hit_test(ctx, Point::new(x, y), false, false).is_some());
+        assert!(hit(12.0, 10.0));
+        assert!(!hit(5.0, 10.0));
+        assert!(!hit(30.0, 26.0));
+
+        // Bounds smaller than the margins leave an empty subject in place
+        assert_eq!(padded.adjust_bounds(Rect::new(0.0, 0.0, 8.0, 8.0)), Rect::new(10.0, 2.0, 10.0, 2.0));
+        assert_eq!(margin_hv(3.0, 1.0, button("OK")).adjust_bounds(Rect::new(0.0, 0.0, 20.0, 10.0)), Rect::new(3.0, 1.0, 17.0, 9.0));
+    }
+}
@@ -2,13 +2,13 @@
 
 use std::any::Any;
 use std::sync::{RwLock, Arc, OnceLock};
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, share};
+use super::{Element, ElementPtr, FocusRequest, ViewLimits, ViewStretch, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, DropInfo, KeyAction, KeyCode, KeyInfo, TextInfo};
 
 /// Menu item callback type.
 pub type MenuItemCallback = Box<dyn Fn() + Send + Sync>;
@@ -138,7 +138,7 @@ pub struct MenuItem {
     shortcut: Option<String>,
     enabled: bool,
     checked: bool,
-    submenu: Option<Vec<MenuItem>>,
+    submenu: Option<Box<Menu>>,
     on_select: Option<MenuItemCallback>,
     hover: RwLock<bool>,
 }
@@ -190,7 +190,7 @@ impl MenuItem {
 
     /// Adds a submenu.
     pub fn submenu(mut self, items: Vec<MenuItem>) -> Self {
-        self.submenu = Some(items);
+        self.submenu = Some(Box::new(Menu::new(items)));
         self
     }
 
@@ -222,42 +222,44 @@ impl MenuItem {
 /// A popup menu element.
 pub struct Menu {
     items: Vec<MenuItem>,
-    background_color: Color,
-    hover_color: Color,
-    text_color: Color,
-    disabled_color: Color,
-    check_color: Color,
-    separator_color: Color,
+    background_color: ThemeColor,
+    hover_color: ThemeColor,
+    text_color: ThemeColor,
+    disabled_color: ThemeColor,
+    check_color: ThemeColor,
+    separator_color: ThemeColor,
     corner_radius: f32,
     padding: f32,
     min_width: f32,
     visible: RwLock<bool>,
     hovered_index: RwLock<Option<usize>>,
+    /// Index of the item whose submenu is open
+    open_submenu: RwLock<Option<usize>>,
 }
 
 impl Menu {
     /// Creates a new menu.
     pub fn new(items: Vec<MenuItem>) -> Self {
-        let theme = get_theme();
         Self {
             items,
-            background_color: theme.menu_background_color,
-            hover_color: theme.menu_item_hilite_color,
-            text_color: theme.menu_font_color,
-            disabled_color: theme.menu_font_color.with_alpha(0.5),
-            check_color: theme.indicator_bright_color,
-            separator_color: theme.menu_separator_color,
+            background_color: ThemeColor::Theme(|theme| theme.menu_background_color),
+            hover_color: ThemeColor::Theme(|theme| theme.menu_item_hilite_color),
+            text_color: ThemeColor::Theme(|theme| theme.menu_font_color),
+            disabled_color: ThemeColor::Theme(|theme| theme.menu_font_color.with_alpha(0.5)),
+            check_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            separator_color: ThemeColor::Theme(|theme| theme.menu_separator_color),
             corner_radius: 6.0,
             padding: 4.0,
             min_width: 150.0,
             visible: RwLock::new(false),
             hovered_index: RwLock::new(None),
+            open_submenu: RwLock::new(None),
         }
     }
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
@@ -274,6 +276,7 @@ impl Menu {
 
     /// Hides the menu.
     pub fn hide(&self) {
+        self.close_submenu();
         *self.visible.write().unwrap() = false;
         *self.hovered_index.write().unwrap() = None;
     }
@@ -322,6 +325,79 @@ impl Menu {
         Rect::zero()
     }
 
+    /// Returns the open submenu and its item index.
+    fn submenu(&self) -> Option<(usize, &Menu)> {
+        let index = (*self.open_submenu.read().unwrap())?;
+        self.items.get(index)?.submenu.as_deref().map(|menu| (index, menu))
+    }
+
+    /// Opens the submenu of the item at `index`, closing any other.
+    fn open_submenu(&self, index: usize) {
+        if *self.open_submenu.read().unwrap() == Some(index) {
+            return;
+        }
+        self.close_submenu();
+        if let Some(menu) = self.items.get(index).and_then(|item| item.submenu.as_deref()) {
+            menu.show();
+            *self.open_submenu.write().unwrap() = Some(index);
+        }
+    }
+
+    /// Closes the open submenu, if any.
+    fn close_submenu(&self) {
+        if let Some((_, menu)) = self.submenu() {
+            menu.hide();
+        }
+        *self.open_submenu.write().unwrap() = None;
+    }
+
+    /// Returns the next item the keyboard can land on after `from`, skipping
+    /// separators and disabled items and wrapping around at either end.
+    fn next_selectable(&self, from: Option<usize>, forward: bool) -> Option<usize> {
+        let count = self.items.len();
+        let selectable = |i: &usize| {
+            let item = &self.items[*i];
+            !item.is_separator() && item.enabled
+        };
+
+        match (from, forward) {
+            (None, true) => (0..count).find(selectable),
+            (None, false) => (0..count).rev().find(selectable),
+            (Some(from), true) => (from + 1..count).chain(0..=from).find(selectable),
+            (Some(from), false) => (0..from).rev().chain((from..count).rev()).find(selectable),
+        }
+    }
+
+    /// Opens the submenu of the item at `index` with its first item lit.
+    fn enter_submenu(&self, index: usize) {
+        self.open_submenu(index);
+        if let Some((_, menu)) = self.submenu() {
+            *menu.hovered_index.write().unwrap() = menu.next_selectable(None, true);
+        }
+    }
+
+    /// Returns the bounds of the submenu for the item at `index`: to the
+    /// right of the menu, or flipped to the left if it would leave the view.
+    fn submenu_bounds(&self, ctx: &Context, index: usize, menu: &Menu) -> Rect {
+        let (width, height) = menu.calculate_size();
+        let item = self.item_bounds(ctx, index);
+        let top = item.top - menu.padding;
+
+        let mut left = ctx.bounds.right - self.padding;
+        if left + width > ctx.view_bounds().right {
+            left = ctx.bounds.left + self.padding - width;
+        }
+
+        Rect::new(left, top, left + width, top + height)
+    }
+
+    /// Returns the open submenu with its context, if `p` is inside it.
+    fn submenu_at<'a>(&'a self, ctx: &Context<'a>, p: Point) -> Option<(&'a Menu, Context<'a>)> {
+        let (index, menu) = self.submenu()?;
+        let sub_ctx = ctx.with_bounds(self.submenu_bounds(ctx, index, menu));
+        menu.hit_test(&sub_ctx, p, false, false).is_some().then_some((menu, sub_ctx))
+    }
+
     fn draw_background(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
 
@@ -331,7 +407,7 @@ impl Menu {
         canvas.fill_round_rect(shadow_rect, self.corner_radius);
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
     }
 
@@ -342,7 +418,7 @@ impl Menu {
         if item.is_separator() {
             // Draw separator line
             let y = bounds.center().y;
-            canvas.stroke_style(self.separator_color);
+            canvas.stroke_style(self.separator_color.get());
             canvas.line_width(1.0);
             canvas.begin_path();
             canvas.move_to(Point::new(bounds.left + 8.0, y));
@@ -353,19 +429,19 @@ impl Menu {
 
         // Highlight if hovered
         if hovered && item.enabled {
-            canvas.fill_style(self.hover_color);
+            canvas.fill_style(self.hover_color.get());
             canvas.fill_round_rect(bounds, 4.0);
         }
 
         let text_color = if item.enabled {
-            self.text_color
+            self.text_color.get()
         } else {
-            self.disabled_color
+            self.disabled_color.get()
         };
 
         // Draw checkmark if checked
         if item.checked {
-            canvas.fill_style(self.check_color);
+            canvas.fill_style(self.check_color.get());
             let check_x = bounds.left + 8.0;
             let check_y = bounds.center().y;
             canvas.fill_text("✓", Point::new(check_x, check_y + 4.0));
@@ -411,16 +487,27 @@ impl Element for Menu {
 
         self.draw_background(ctx);
 
+        // Clip long labels and shortcuts to the menu
+        ctx.canvas.borrow_mut().push_clip(ctx.bounds);
         let hovered = *self.hovered_index.read().unwrap();
         for (i, item) in self.items.iter().enumerate() {
             let bounds = self.item_bounds(ctx, i);
             let is_hovered = hovered == Some(i);
             self.draw_item(ctx, item, bounds, is_hovered);
         }
+        ctx.canvas.borrow_mut().pop_clip();
+
+        if let Some((index, menu)) = self.submenu() {
+            menu.draw(&ctx.with_bounds(self.submenu_bounds(ctx, index, menu)));
+        }
     }
 
     fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
-        if self.is_visible() && ctx.bounds.contains(p) {
+        if !self.is_visible() {
+            return None;
+        }
+
+        if ctx.bounds.contains(p) || self.submenu_at(ctx, p).is_some() {
             Some(self)
         } else {
             None
@@ -436,12 +523,25 @@ impl Element for Menu {
             return false;
         }
 
+        if let Some((menu, sub_ctx)) = self.submenu_at(ctx, btn.pos) {
+            menu.handle_click(&sub_ctx, btn);
+            // Choosing a submenu item closes the whole menu
+            if !menu.is_visible() {
+                self.hide();
+            }
+            return true;
+        }
+
         if !btn.down {
             // Find clicked item
             for (i, item) in self.items.iter().enumerate() {
                 if !item.is_separator() && item.enabled {
                     let bounds = self.item_bounds(ctx, i);
                     if bounds.contains(btn.pos) {
+                        if item.submenu.is_some() {
+                            self.open_submenu(i);
+                            return true;
+                        }
                         if let Some(ref callback) = item.on_select {
                             callback();
                         }
@@ -460,28 +560,120 @@ impl Element for Menu {
         true
     }
 
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if !self.is_visible() || k.action == KeyAction::Release {
+            return false;
+        }
+
+        // Keys go to a submenu the keyboard has entered; Left and Escape
+        // leave the innermost one
+        if let Some((index, menu)) = self.submenu() {
+            if menu.hovered_index.read().unwrap().is_some() {
+                let leaving = matches!(k.key, KeyCode::Left | KeyCode::Escape);
+                let nested = menu.submenu().is_some_and(|(_, sub)| sub.hovered_index.read().unwrap().is_some());
+                if leaving && !nested {
+                    self.close_submenu();
+                    *self.hovered_index.write().unwrap() = Some(index);
+                    return true;
+                }
+
+                let sub_ctx = ctx.with_bounds(self.submenu_bounds(ctx, index, menu));
+                let handled = menu.handle_key(&sub_ctx, k);
+                if !menu.is_visible() {
+                    self.hide();
+                }
+                return handled;
+            }
+        }
+
+        let hovered = *self.hovered_index.read().unwrap();
+        match k.key {
+            KeyCode::Up | KeyCode::Down => {
+                self.close_submenu();
+                *self.hovered_index.write().unwrap() = self.next_selectable(hovered, k.key == KeyCode::Down);
+            }
+            KeyCode::Right => {
+                if let Some(i) = hovered.filter(|&i| self.items[i].submenu.is_some()) {
+                    self.enter_submenu(i);
+                }
+            }
+            KeyCode::Enter | KeyCode::Space => {
+                let Some(i) = hovered else {
+                    return true;
+                };
+                let item = &self.items[i];
+                if item.submenu.is_some() {
+                    self.enter_submenu(i);
+                } else {
+                    if let Some(ref callback) = item.on_select {
+                        callback();
+                    }
+                    self.hide();
+                }
+            }
+            KeyCode::Escape => self.hide(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.is_visible()
+    }
+
+    // A visible menu holds the focus, so tabbing away closes it
+    fn has_focus(&self) -> bool {
+        self.is_visible()
+    }
+
+    fn handle_end_focus(&self) {
+        self.hide();
+    }
+
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
         if !self.is_visible() {
             return false;
         }
 
-        match status {
-            CursorTracking::Leaving => {
-                *self.hovered_index.write().unwrap() = None;
+        // Track the cursor in the open submenu, keeping its parent item lit
+        if let Some((index, menu)) = self.submenu() {
+            let sub_bounds = self.submenu_bounds(ctx, index, menu);
+            if status != CursorTracking::Leaving && sub_bounds.contains(p) {
+                menu.handle_cursor(&ctx.with_bounds(sub_bounds), p, status);
+                return true;
             }
-            _ => {
-                let mut hovered = self.hovered_index.write().unwrap();
-                *hovered = None;
-
-                for (i, item) in self.items.iter().enumerate() {
-                    if !item.is_separator() {
-                        let bounds = self.item_bounds(ctx, i);
-                        if bounds.contains(p) {
-                            *hovered = Some(i);
-                            break;
-                        }
-                    }
+        }
+
+        let hovered = match status {
+            CursorTracking::Leaving => None,
+            _ => self.items.iter().enumerate().position(|(i, item)| {
+                !item.is_separator() && self.item_bounds(ctx, i).contains(p)
+            }),
+        };
+
+        match hovered {
+            Some(i) => {
+                // Moving to another item closes the open submenu
+                if *self.open_submenu.read().unwrap() != Some(i) {
+                    self.close_submenu();
+                }
+                if self.items[i].enabled {
+                    self.open_submenu(i);
                 }
+                *self.hovered_index.write().unwrap() = Some(i);
+            }
+            None => {
+                // Outside the items, an open submenu stays open with its parent lit
+                *self.hovered_index.write().unwrap() = *self.open_submenu.read().unwrap();
             }
         }
 
@@ -501,7 +693,7 @@ impl Element for Menu {
 pub struct Popup {
     content: Option<ElementPtr>,
     visible: RwLock<bool>,
-    background_color: Color,
+    background_color: ThemeColor,
     corner_radius: f32,
     shadow: bool,
 }
@@ -509,11 +701,10 @@ pub struct Popup {
 impl Popup {
     /// Creates a new popup.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             content: None,
             visible: RwLock::new(false),
-            background_color: theme.menu_background_color,
+            background_color: ThemeColor::Theme(|theme| theme.menu_background_color),
             corner_radius: 8.0,
             shadow: true,
         }
@@ -527,7 +718,7 @@ impl Popup {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
@@ -593,7 +784,7 @@ impl Element for Popup {
         }
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
 
         drop(canvas);
@@ -664,6 +855,216 @@ impl Element for Popup {
     }
 }
 
+/// Wraps an element with a menu that opens at the cursor on a right-click.
+///
+/// While the menu is open it takes all clicks and keys: a click outside it
+/// or Escape dismisses it.
+pub struct ContextMenu {
+    content: ElementPtr,
+    menu: Menu,
+    /// Where the menu was opened
+    position: RwLock<Point>,
+}
+
+impl ContextMenu {
+    /// Creates a context menu with `items` for `content`.
+    pub fn new<E: Element + 'static>(content: E, items: Vec<MenuItem>) -> Self {
+        Self {
+            content: share(content),
+            menu: Menu::new(items),
+            position: RwLock::new(Point::zero()),
+        }
+    }
+
+    /// Opens the menu at `position`.
+    pub fn show(&self, position: Point) {
+        *self.position.write().unwrap() = position;
+        self.menu.show();
+    }
+
+    /// Closes the menu.
+    pub fn hide(&self) {
+        self.menu.hide();
+    }
+
+    /// Returns whether the menu is open.
+    pub fn is_visible(&self) -> bool {
+        self.menu.is_visible()
+    }
+
+    /// Returns the context of the menu: below and to the right of where it
+    /// was opened, flipped or shifted to stay inside the view.
+    fn menu_ctx<'a>(&self, ctx: &Context<'a>) -> Context<'a> {
+        let (width, height) = self.menu.calculate_size();
+        let position = *self.position.read().unwrap();
+        let view = ctx.view_bounds();
+
+        let mut left = position.x;
+        if left + width > view.right {
+            left = position.x - width;
+        }
+        let mut top = position.y;
+        if top + height > view.bottom {
+            top = position.y - height;
+        }
+        left = left.min(view.right - width).max(view.left);
+        top = top.min(view.bottom - height).max(view.top);
+        ctx.with_bounds(Rect::new(left, top, left + width, top + height))
+    }
+}
+
+impl Element for ContextMenu {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.content.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.content.stretch()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.content.draw(ctx);
+        if self.is_visible() {
+            self.menu.draw(&self.menu_ctx(ctx));
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        // An open menu takes every click, to dismiss itself on those outside it
+        if self.is_visible() {
+            return Some(self);
+        }
+        self.content.hit_test(ctx, p, leaf, control)
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.content.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.content.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // An open menu covers the content, so only the menu tracks the mouse
+        if self.is_visible() {
+            return self.menu.handle_cursor(&self.menu_ctx(ctx), p, status);
+        }
+        self.content.handle_cursor(ctx, p, status)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.is_visible() || self.content.wants_control()
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if self.is_visible() {
+            let menu_ctx = self.menu_ctx(ctx);
+            if self.menu.hit_test(&menu_ctx, btn.pos, false, false).is_some() {
+                self.menu.handle_click(&menu_ctx, btn);
+            } else if btn.down {
+                self.hide();
+            }
+            return true;
+        }
+
+        if btn.button == MouseButtonKind::Right && ctx.bounds.contains(btn.pos) {
+            if btn.down {
+                self.show(btn.pos);
+            }
+            return true;
+        }
+
+        self.content.handle_click(ctx, btn)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if !self.is_visible() {
+            self.content.handle_drag(ctx, btn);
+        }
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if self.is_visible() {
+            self.menu.handle_key(&self.menu_ctx(ctx), k);
+            return true;
+        }
+        self.content.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        !self.is_visible() && self.content.handle_text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        !self.is_visible() && self.content.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        !self.is_visible() && self.content.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.content.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.content.handle_drop(ctx, info)
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.content.wants_focus()
+    }
+
+    // A click outside an open menu closes it, so a press clearing the
+    // focus must leave it open for the click to land on its items
+    fn clear_focus(&self) {
+        self.content.clear_focus();
+    }
+
+    // An open menu holds the focus, so tabbing away closes it
+    fn has_focus(&self) -> bool {
+        self.is_visible() || self.content.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.content.handle_begin_focus(req)
+    }
+
+    fn handle_end_focus(&self) {
+        self.hide();
+        self.content.handle_end_focus();
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.hide();
+        self.content.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.content.handle_poll()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 // =============================================================================
 // Native Menu Bar API
 // =============================================================================
@@ -985,6 +1386,11 @@ pub fn popup() -> Popup {
     Popup::new()
 }
 
+/// Wraps `content` with a menu of `items` that opens on a right-click.
+pub fn context_menu<E: Element + 'static>(content: E, items: Vec<MenuItem>) -> ContextMenu {
+    ContextMenu::new(content, items)
+}
+
 /// Creates a native menu item.
 pub fn native_menu_item(label: impl Into<String>) -> NativeMenuItem {
     NativeMenuItem::new(label)
@@ -1004,3 +1410,150 @@ pub fn native_menu(title: impl Into<String>) -> NativeMenu {
 pub fn native_menu_bar() -> NativeMenuBar {
     NativeMenuBar::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let chosen = Arc::new(AtomicUsize::new(0));
+        let sink = chosen.clone();
+        let m = Arc::new(menu(vec![
+            menu_item("Open"),
+            menu_separator(),
+            menu_item("Close").enabled(false),
+            menu_item("Recent").submenu(vec![
+                menu_item("a.txt"),
+                menu_item("b.txt").on_select(move || sink.store(2, Ordering::SeqCst)),
+            ]),
+        ]));
+        m.show();
+        let view = TestView::new(m.clone(), Extent::new(150.0, 100.0));
+        let press = |key| view.key(key, 0);
+
+        // The separator and the disabled item are skipped, wrapping at the ends
+        press(KeyCode::Down);
+        assert_eq!(*m.hovered_index.read().unwrap(), Some(0));
+        press(KeyCode::Down);
+        assert_eq!(*m.hovered_index.read().unwrap(), Some(3));
+        press(KeyCode::Down);
+        assert_eq!(*m.hovered_index.read().unwrap(), Some(0));
+        press(KeyCode::Up);
+        assert_eq!(*m.hovered_index.read().unwrap(), Some(3));
+
+        // Right enters the submenu and Left leaves it
+        press(KeyCode::Right);
+        assert_eq!(*m.open_submenu.read().unwrap(), Some(3));
+        press(KeyCode::Left);
+        assert_eq!(*m.open_submenu.read().unwrap(), None);
+        assert!(m.is_visible());
+
+        // Enter in the submenu fires the item and closes the whole menu
+        press(KeyCode::Right);
+        press(KeyCode::Down);
+        press(KeyCode::Enter);
+        assert_eq!(chosen.load(Ordering::SeqCst), 2);
+        assert!(!m.is_visible());
+    }
+
+    #[test]
+    fn test_hover_opens_submenu() {
+        use crate::element::align::{align_left_top, align_right_top};
+        use crate::element::proxy::RefProxy;
+
+        let recent = || menu_item("Recent").submenu(vec![menu_item("a.txt"), menu_item("b.txt")]);
+        let m = Arc::new(menu(vec![menu_item("Open"), recent()]));
+        m.show();
+        let view = TestView::new(share(align_left_top(RefProxy::new(m.clone()))), Extent::new(400.0, 200.0));
+        let submenu_bounds = |m: &Arc<Menu>, view: &TestView| {
+            view.with_context(|ctx| {
+                let bounds = view.content().bounds_of_element(ctx, m.as_ref()).unwrap();
+                let (index, sub) = m.submenu().unwrap();
+                m.submenu_bounds(&ctx.with_bounds(bounds), index, sub)
+            })
+        };
+
+        // Hovering "Recent" (32..60) opens its submenu to the right
+        assert!(view.hover(Point::new(50.0, 45.0)));
+        assert_eq!(*m.open_submenu.read().unwrap(), Some(1));
+        assert!(m.items[1].submenu.as_ref().unwrap().is_visible());
+        assert_eq!(submenu_bounds(&m, &view).left, 146.0);
+
+        // Moving into the submenu lights its item and keeps the parent lit
+        assert!(view.hover(Point::new(200.0, 45.0)));
+        let sub = m.items[1].submenu.as_deref().unwrap();
+        assert_eq!(*sub.hovered_index.read().unwrap(), Some(0));
+        assert_eq!(*m.hovered_index.read().unwrap(), Some(1));
+
+        // Another item closes it
+        assert!(view.hover(Point::new(50.0, 20.0)));
+        assert_eq!(*m.open_submenu.read().unwrap(), None);
+        assert!(!sub.is_visible());
+
+        // Near the right edge of the view it opens to the left instead
+        let m = Arc::new(menu(vec![menu_item("Open"), recent()]));
+        m.show();
+        let view = TestView::new(share(align_right_top(RefProxy::new(m.clone()))), Extent::new(400.0, 200.0));
+        assert!(view.hover(Point::new(300.0, 45.0)));
+        assert_eq!(*m.open_submenu.read().unwrap(), Some(1));
+        assert_eq!(submenu_bounds(&m, &view).right, 254.0);
+    }
+
+    #[test]
+    fn test_context_menu_opens_on_right_click() {
+        let chosen = Arc::new(AtomicUsize::new(0));
+        let sink = chosen.clone();
+        let c = Arc::new(context_menu(super::super::empty(), vec![
+            menu_item("Cut"),
+            menu_item("Copy").on_select(move || sink.store(1, Ordering::SeqCst)),
+        ]));
+        let view = TestView::new(c.clone(), Extent::new(200.0, 200.0));
+        let click = |down, button, x, y| view.mouse(MouseButton::new(down, button, Point::new(x, y)));
+        let menu_bounds = || view.with_context(|ctx| c.menu_ctx(ctx).bounds);
+
+        // Left clicks don't open it; a right-click opens it at the cursor
+        click(true, MouseButtonKind::Left, 50.0, 50.0);
+        assert!(!c.is_visible());
+        click(true, MouseButtonKind::Right, 50.0, 50.0);
+        click(false, MouseButtonKind::Right, 50.0, 50.0);
+        assert!(c.is_visible());
+        assert_eq!(menu_bounds().top_left(), Point::new(50.0, 50.0));
+
+        // Items 28 tall start 4 down: "Copy" spans 82..110
+        click(true, MouseButtonKind::Left, 80.0, 90.0);
+        click(false, MouseButtonKind::Left, 80.0, 90.0);
+        assert_eq!(chosen.load(Ordering::SeqCst), 1);
+        assert!(!c.is_visible());
+
+        // A click outside or Escape dismisses it
+        click(true, MouseButtonKind::Right, 390.0, 390.0);
+        assert!(!c.is_visible());
+        click(true, MouseButtonKind::Right, 150.0, 150.0);
+        assert!(menu_bounds().right <= 200.0);
+        click(true, MouseButtonKind::Left, 10.0, 10.0);
+        assert!(!c.is_visible());
+        click(true, MouseButtonKind::Right, 150.0, 150.0);
+        view.key(KeyCode::Escape, 0);
+        assert!(!c.is_visible());
+    }
+
+    #[test]
+    fn test_context_menu_tracks_hover() {
+        let c = Arc::new(context_menu(super::super::empty(), vec![menu_item("Cut"), menu_item("Copy")]));
+        let view = TestView::new(c.clone(), Extent::new(200.0, 200.0));
+
+        // A closed menu leaves the mouse to the content
+        assert!(!view.hover(Point::new(80.0, 90.0)));
+
+        view.mouse(MouseButton::new(true, MouseButtonKind::Right, Point::new(50.0, 50.0)));
+        view.mouse(MouseButton::new(false, MouseButtonKind::Right, Point::new(50.0, 50.0)));
+        assert!(view.hover(Point::new(80.0, 90.0)));
+        assert_eq!(*c.menu.hovered_index.read().unwrap(), Some(1));
+        assert!(view.leave(Point::new(80.0, 90.0)));
+        assert_eq!(*c.menu.hovered_index.read().unwrap(), None);
+    }
+}
@@ -10,12 +10,18 @@
 //! - [`margin`]: Margin elements
 //! - [`size`]: Size constraint elements
 //! - [`layer`]: Layered elements
+//! - [`decoration`]: Background, border and drop shadow elements
+//! - [`icon`]: Vector icon elements
+//! - [`image`]: Image elements
 //! - [`slider`]: Slider elements for value selection
 //! - [`checkbox`]: Checkbox and radio button elements
 //! - [`switch`]: Toggle switch elements
 //! - [`dial`]: Rotary dial/knob elements
+//! - [`color_picker`]: Color picker element
 //! - [`text_box`]: Text input elements
-//! - [`menu`]: Menu and popup elements
+//! - [`text_area`]: Multi-line text input element
+//! - [`number_input`]: Numeric input with stepper buttons
+//! - [`menu`]: Menu, popup and context menu elements
 //! - [`list`]: List and dropdown elements
 //! - [`grid`]: Grid layout element
 //! - [`floating`]: Floating/draggable elements
@@ -23,6 +29,9 @@
 //! - [`thumbwheel`]: Thumbwheel element
 //! - [`scroll`]: Scrollable container element
 //! - [`tabs`]: Tab bar element
+//! - [`collapsible`]: Collapsible section element
+//! - [`splitter`]: Resizable two-pane splitter
+//! - [`segmented`]: Segmented control element
 //! - [`tooltip`]: Tooltip element
 //! - [`progress`]: Progress bar element
 
@@ -34,13 +43,19 @@ pub mod align;
 pub mod margin;
 pub mod size;
 pub mod layer;
+pub mod decoration;
+pub mod icon;
+pub mod image;
 pub mod label;
 pub mod button;
 pub mod slider;
 pub mod checkbox;
 pub mod switch;
 pub mod dial;
+pub mod color_picker;
 pub mod text_box;
+pub mod text_area;
+pub mod number_input;
 pub mod menu;
 pub mod list;
 pub mod grid;
@@ -49,14 +64,19 @@ pub mod status_bar;
 pub mod thumbwheel;
 pub mod scroll;
 pub mod tabs;
+pub mod collapsible;
+pub mod splitter;
+pub mod segmented;
 pub mod tooltip;
 pub mod progress;
+pub mod zoom;
 
 use std::sync::{Arc, Weak};
 use std::any::Any;
 
 use crate::support::point::{Point, Axis};
-use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
+use crate::support::rect::Rect;
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking, CursorType};
 
 /// The maximum extent value (effectively infinite).
 pub const FULL_EXTENT: f32 = 1e30;
@@ -204,6 +224,19 @@ pub trait Element: Send + Sync + Any {
         None
     }
 
+    /// Returns the bounds of `element` if it is this element or one of its
+    /// descendants, given this element's context.
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        std::ptr::addr_eq(self, element).then_some(ctx.bounds)
+    }
+
+    /// Returns the bounds of the element holding the keyboard focus if it
+    /// is this element or one of its descendants, given this element's
+    /// context.
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.has_focus().then_some(ctx.bounds)
+    }
+
     /// Returns true if the element contains the given point (within current bounds).
     fn contains(&self, ctx: &Context, p: Point) -> bool {
         ctx.bounds.contains(p)
@@ -215,8 +248,22 @@ pub trait Element: Send + Sync + Any {
     /// Performs layout calculations.
     fn layout(&mut self, ctx: &Context) {}
 
-    /// Refreshes the element, triggering a redraw.
-    fn refresh(&self, ctx: &Context, outward: i32) {}
+    /// Refreshes the element, triggering a redraw of its bounds.
+    ///
+    /// `outward` widens the refresh to that many enclosing elements, where
+    /// the context knows its parents.
+    fn refresh(&self, ctx: &Context, outward: i32) {
+        let mut ctx = ctx;
+        for _ in 0..outward {
+            match ctx.parent {
+                Some(parent) => ctx = parent,
+                None => break,
+            }
+        }
+        if let Some(area) = ctx.visible_bounds() {
+            ctx.view.refresh_area(area);
+        }
+    }
 
     // --- Control ---
 
@@ -278,6 +325,25 @@ pub trait Element: Send + Sync + Any {
         false
     }
 
+    /// Tracks the mouse moving over the view with no button held.
+    ///
+    /// Containers pass the move to every child, not just the one under
+    /// the mouse, so an element can tell when the mouse leaves it. When the
+    /// mouse leaves the view, `status` is [`CursorTracking::Leaving`].
+    /// Returns true if the element responded to the move.
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        false
+    }
+
+    /// Returns the cursor shape to show while the mouse is over `p`, or
+    /// `None` for the arrow.
+    ///
+    /// The view asks the topmost element under the mouse, with that
+    /// element's bounds in `ctx`.
+    fn cursor_type(&self, _ctx: &Context, _p: Point) -> Option<CursorType> {
+        None
+    }
+
     /// Handles scroll events.
     ///
     /// Returns true if the event was handled.
@@ -290,6 +356,14 @@ pub trait Element: Send + Sync + Any {
         false
     }
 
+    /// Handles a trackpad pinch, centered on `center`.
+    ///
+    /// `scale_delta` is the change in magnification, such as 0.1 for a
+    /// pinch that zooms in by 10%. Returns true if the event was handled.
+    fn handle_magnify(&self, _ctx: &Context, _scale_delta: f32, _center: Point) -> bool {
+        false
+    }
+
     /// Enables or disables the element.
     fn enable(&mut self, state: bool) {}
 
@@ -329,6 +403,46 @@ pub trait Element: Send + Sync + Any {
     /// This is used when clicking elsewhere to unfocus text inputs, etc.
     fn clear_focus(&self) {}
 
+    /// Returns true if this element, or one of its children, holds the focus.
+    fn has_focus(&self) -> bool {
+        false
+    }
+
+    /// Gives focus to this element, or to its first (`FromTop`) or last
+    /// (`FromBottom`) focusable child (immutable version).
+    ///
+    /// Returns true if an element took focus.
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        false
+    }
+
+    /// Takes focus away from this element (immutable version).
+    fn handle_end_focus(&self) {
+        self.clear_focus();
+    }
+
+    /// Moves focus to the next (or previous) focusable element in this
+    /// subtree, ending focus on the current one (immutable version).
+    ///
+    /// Returns false if focus moved past the end of the subtree, so the
+    /// parent should continue with its next child.
+    fn handle_focus_next(&self, _forward: bool) -> bool {
+        if self.has_focus() {
+            self.handle_end_focus();
+        }
+        false
+    }
+
+    // --- Idle ---
+
+    /// Advances time-based state, such as animations, on the view's idle
+    /// tick (immutable version).
+    ///
+    /// Returns true if the element needs to be redrawn.
+    fn handle_poll(&self) -> bool {
+        false
+    }
+
     // --- Drag and Drop ---
 
     /// Handles drag tracking events.
@@ -341,6 +455,20 @@ pub trait Element: Send + Sync + Any {
         false
     }
 
+    /// Tracks a drag entering, moving over or leaving the element
+    /// (immutable version).
+    ///
+    /// Containers pass this on to all their children, which check
+    /// `info.where_` against their own bounds.
+    fn handle_track_drop(&self, _ctx: &Context, _info: &DropInfo, _status: CursorTracking) {}
+
+    /// Handles a drop on the element (immutable version).
+    ///
+    /// Returns true if the drop was accepted.
+    fn handle_drop(&self, _ctx: &Context, _info: &DropInfo) -> bool {
+        false
+    }
+
     // --- Type info ---
 
     /// Returns the class name of this element (for debugging).
@@ -355,6 +483,32 @@ pub trait Element: Send + Sync + Any {
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// Gives focus to the first (or, for `FromBottom`, last) child that accepts it.
+pub(crate) fn begin_child_focus(children: &[&dyn Element], req: FocusRequest) -> bool {
+    match req {
+        FocusRequest::FromBottom => children.iter().rev().any(|c| c.handle_begin_focus(req)),
+        _ => children.iter().any(|c| c.handle_begin_focus(req)),
+    }
+}
+
+/// Moves focus from the focused child to the next (or previous) child that
+/// accepts it. Returns false if no child is focused or focus ran off the end.
+pub(crate) fn child_focus_next(children: &[&dyn Element], forward: bool) -> bool {
+    let Some(focused) = children.iter().position(|c| c.has_focus()) else {
+        return false;
+    };
+
+    if children[focused].handle_focus_next(forward) {
+        return true;
+    }
+
+    if forward {
+        children[focused + 1..].iter().any(|c| c.handle_begin_focus(FocusRequest::FromTop))
+    } else {
+        children[..focused].iter().rev().any(|c| c.handle_begin_focus(FocusRequest::FromBottom))
+    }
+}
+
 /// A shared pointer to an element.
 pub type ElementPtr = Arc<dyn Element>;
 
@@ -387,5 +541,5 @@ pub fn empty() -> Empty {
 
 // Re-exports
 pub use context::{BasicContext, Context};
-pub use proxy::{Proxy, ProxyBase};
+pub use proxy::{Proxy, ProxyBase, DoubleClick, on_double_click, DropTarget, on_drop};
 pub use composite::{Composite, CompositeBase, Storage};
@@ -0,0 +1,508 @@
+//! Numeric input element with stepper buttons.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
+use super::context::{BasicContext, Context};
+use super::text_box::TextBox;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::get_theme;
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, KeyAction, KeyCode, TextInfo, CursorTracking, CursorType};
+
+/// Width of the stepper buttons column.
+const STEPPER_WIDTH: f32 = 18.0;
+
+/// Callback type for value changes.
+pub type NumberChangeCallback = Box<dyn Fn(f64) + Send + Sync>;
+/// Formats a value for display.
+pub type NumberFormatter = Box<dyn Fn(f64) -> String + Send + Sync>;
+
+/// A text box for editing a number, with up/down stepper buttons.
+///
+/// Only numeric characters can be typed. Typed values are committed on
+/// Enter or when focus leaves, clamped to the range; text that doesn't
+/// parse reverts to the current value. The Up and Down keys and the
+/// stepper buttons change the value by one step.
+pub struct NumberInput {
+    text_box: TextBox,
+    value: RwLock<f64>,
+    min_value: f64,
+    max_value: f64,
+    step: f64,
+    precision: usize,
+    formatter: Option<NumberFormatter>,
+    /// Whether stepping past either end wraps around to the other.
+    wrap: bool,
+    /// Direction of the stepper button under the mouse: 1 up, -1 down
+    hovered_step: RwLock<Option<f64>>,
+    on_change: Option<NumberChangeCallback>,
+}
+
+impl NumberInput {
+    /// Creates a new number input with range [0.0, 1.0] and step 0.01.
+    pub fn new() -> Self {
+        let input = Self {
+            text_box: TextBox::new()
+                .width(80.0)
+                .filter(|c| c.is_ascii_digit() || c == '.' || c == '-'),
+            value: RwLock::new(0.0),
+            min_value: 0.0,
+            max_value: 1.0,
+            step: 0.01,
+            precision: 2,
+            formatter: None,
+            wrap: false,
+            hovered_step: RwLock::new(None),
+            on_change: None,
+        };
+        input.update_text();
+        input
+    }
+
+    /// Creates a number input with the specified range.
+    pub fn with_range(min: f64, max: f64) -> Self {
+        let mut input = Self::new();
+        input.min_value = min;
+        input.max_value = max;
+        input.set_value(min);
+        input
+    }
+
+    /// Sets the initial value.
+    pub fn value(self, value: f64) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Sets the amount the stepper buttons and arrow keys change the value by.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets the number of decimal places shown.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self.update_text();
+        self
+    }
+
+    /// Sets a custom display format, replacing the fixed precision.
+    ///
+    /// The formatted text should still parse as a number, so that an
+    /// untouched value commits unchanged.
+    pub fn format<F: Fn(f64) -> String + Send + Sync + 'static>(mut self, formatter: F) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self.update_text();
+        self
+    }
+
+    /// Sets whether stepping past either end wraps around to the other.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Sets the width of the text field.
+    pub fn width(mut self, width: f32) -> Self {
+        self.text_box = self.text_box.width(width);
+        self
+    }
+
+    /// Sets the value change callback.
+    pub fn on_change<F: Fn(f64) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the current value.
+    pub fn get_value(&self) -> f64 {
+        *self.value.read().unwrap()
+    }
+
+    /// Sets the current value, clamped to the range.
+    pub fn set_value(&self, value: f64) {
+        *self.value.write().unwrap() = value.clamp(self.min_value, self.max_value);
+        self.update_text();
+    }
+
+    /// Returns the display text for `value`.
+    fn format_value(&self, value: f64) -> String {
+        match self.formatter {
+            Some(ref formatter) => formatter(value),
+            None => format!("{value:.*}", self.precision),
+        }
+    }
+
+    /// Shows the current value in the text field, unless it already does
+    /// (which would move the caret).
+    fn update_text(&self) {
+        let text = self.format_value(self.get_value());
+        if self.text_box.get_text() != text {
+            self.text_box.set_text(text);
+        }
+    }
+
+    /// Sets the value, firing the change callback if it changed.
+    fn change_value(&self, value: f64) {
+        let previous = self.get_value();
+        self.set_value(value);
+        let value = self.get_value();
+        if value != previous {
+            if let Some(ref callback) = self.on_change {
+                callback(value);
+            }
+        }
+    }
+
+    /// Parses the typed text and commits it. Text that isn't a number
+    /// reverts to the current value.
+    fn commit(&self) {
+        match self.text_box.get_text().trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => self.change_value(value),
+            _ => self.update_text(),
+        }
+    }
+
+    /// Changes the value by `steps` steps, wrapping or clamping at the ends.
+    fn step_by(&self, steps: f64) {
+        // Start from any typed value, so stepping doesn't discard it
+        self.commit();
+        let value = self.get_value() + steps * self.step;
+        let value = if self.wrap && value > self.max_value {
+            self.min_value
+        } else if self.wrap && value < self.min_value {
+            self.max_value
+        } else {
+            value
+        };
+        self.change_value(value);
+    }
+
+    /// Returns the bounds of the text field.
+    fn text_bounds(bounds: &Rect) -> Rect {
+        Rect::new(bounds.left, bounds.top, bounds.right - STEPPER_WIDTH, bounds.bottom)
+    }
+
+    /// Returns the bounds of the stepper buttons column.
+    fn stepper_bounds(bounds: &Rect) -> Rect {
+        Rect::new(bounds.right - STEPPER_WIDTH, bounds.top, bounds.right, bounds.bottom)
+    }
+
+    /// Returns the direction of the stepper button at `p`: 1 for up, -1
+    /// for down.
+    fn step_at(bounds: &Rect, p: Point) -> Option<f64> {
+        let stepper = Self::stepper_bounds(bounds);
+        stepper.contains(p).then(|| if p.y < stepper.center().y { 1.0 } else { -1.0 })
+    }
+
+    fn draw_stepper(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let theme = get_theme();
+        let bounds = Self::stepper_bounds(&ctx.bounds);
+        let middle = bounds.center().y;
+
+        canvas.fill_style(theme.input_box_color);
+        canvas.fill_round_rect(bounds, 4.0);
+        if let Some(step) = *self.hovered_step.read().unwrap() {
+            let button = if step > 0.0 {
+                Rect::new(bounds.left, bounds.top, bounds.right, middle)
+            } else {
+                Rect::new(bounds.left, middle, bounds.right, bounds.bottom)
+            };
+            canvas.fill_style(theme.frame_hilite_color.with_alpha(0.3));
+            canvas.fill_round_rect(button, 4.0);
+        }
+        canvas.stroke_style(theme.frame_color);
+        canvas.line_width(1.0);
+        canvas.begin_path();
+        canvas.move_to(Point::new(bounds.left + 3.0, middle));
+        canvas.line_to(Point::new(bounds.right - 3.0, middle));
+        canvas.stroke();
+
+        // Up and down arrows
+        let color = if self.is_enabled() {
+            theme.label_font_color
+        } else {
+            theme.label_font_color.with_alpha(theme.disabled_opacity)
+        };
+        let center_x = bounds.center().x;
+        let half = 4.0;
+        let offset = (bounds.height() / 4.0).min(8.0);
+        canvas.fill_style(color);
+        for (y, dir) in [(middle - offset, -1.0), (middle + offset, 1.0)] {
+            canvas.begin_path();
+            canvas.move_to(Point::new(center_x - half, y - dir * half / 2.0));
+            canvas.line_to(Point::new(center_x + half, y - dir * half / 2.0));
+            canvas.line_to(Point::new(center_x, y + dir * half / 2.0));
+            canvas.close_path();
+            canvas.fill();
+        }
+    }
+}
+
+impl Default for NumberInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Element for NumberInput {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let limits = self.text_box.limits(ctx);
+        ViewLimits::new(
+            Point::new(limits.min.x + STEPPER_WIDTH, limits.min.y),
+            Point::new(limits.max.x + STEPPER_WIDTH, limits.max.y),
+        )
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.text_box.stretch()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.text_box.draw(&ctx.with_bounds(Self::text_bounds(&ctx.bounds)));
+        self.draw_stepper(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) && self.is_enabled() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn cursor_type(&self, ctx: &Context, p: Point) -> Option<CursorType> {
+        Self::text_bounds(&ctx.bounds).contains(p).then_some(CursorType::IBeam)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.is_enabled()
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.text_box.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.text_box.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.commit();
+        self.text_box.end_focus()
+    }
+
+    fn clear_focus(&self) {
+        if self.text_box.has_focus() {
+            self.commit();
+        }
+        self.text_box.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.text_box.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.text_box.handle_begin_focus(req)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        if let Some(step) = Self::step_at(&ctx.bounds, btn.pos) {
+            if btn.down && btn.button == MouseButtonKind::Left {
+                self.step_by(step);
+            }
+            return true;
+        }
+
+        self.text_box.handle_click(&ctx.with_bounds(Self::text_bounds(&ctx.bounds)), btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.text_box.handle_drag(&ctx.with_bounds(Self::text_bounds(&ctx.bounds)), btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if !self.is_enabled() || !self.text_box.has_focus() {
+            return false;
+        }
+
+        if k.action != KeyAction::Release {
+            match k.key {
+                KeyCode::Up => {
+                    self.step_by(1.0);
+                    return true;
+                }
+                KeyCode::Down => {
+                    self.step_by(-1.0);
+                    return true;
+                }
+                KeyCode::Enter => {
+                    self.commit();
+                    return true;
+                }
+                KeyCode::Escape => {
+                    // Drop the edit
+                    self.update_text();
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        self.text_box.handle_key(&ctx.with_bounds(Self::text_bounds(&ctx.bounds)), k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.handle_text(ctx, info)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.text_box.handle_text(&ctx.with_bounds(Self::text_bounds(&ctx.bounds)), info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let text_ctx = ctx.with_bounds(Self::text_bounds(&ctx.bounds));
+        let in_text = self.text_box.handle_cursor(&text_ctx, p, status);
+
+        let hovered = match status {
+            CursorTracking::Leaving => None,
+            _ if self.is_enabled() => Self::step_at(&ctx.bounds, p),
+            _ => None,
+        };
+        let mut current = self.hovered_step.write().unwrap();
+        if *current != hovered {
+            *current = hovered;
+            drop(current);
+            self.refresh(ctx, 0);
+        }
+        in_text || hovered.is_some()
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.text_box.handle_poll()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.text_box.enable(state);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.text_box.is_enabled()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a number input.
+pub fn number_input() -> NumberInput {
+    NumberInput::new()
+}
+
+/// Creates a number input with range.
+pub fn number_input_with_range(min: f64, max: f64) -> NumberInput {
+    NumberInput::with_range(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::support::point::Extent;
+    use crate::view::modifiers;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_typing_commits_clamped_numbers() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let input = Arc::new(
+            number_input_with_range(0.0, 100.0)
+                .precision(1)
+                .on_change(move |v| sink.lock().unwrap().push(v)),
+        );
+        let view = TestView::new(input.clone(), Extent::new(98.0, 28.0));
+        assert!(view.focus_first());
+
+        // Letters are rejected; the number is clamped on Enter
+        view.key(KeyCode::A, modifiers::CONTROL);
+        view.type_text("1x5y0");
+        assert_eq!(input.text_box.get_text(), "150");
+        view.key(KeyCode::Enter, 0);
+        assert_eq!(input.get_value(), 100.0);
+        assert_eq!(input.text_box.get_text(), "100.0");
+
+        // Text that doesn't parse reverts
+        view.key(KeyCode::A, modifiers::CONTROL);
+        view.type_text("-.-");
+        input.clear_focus();
+        assert_eq!(input.text_box.get_text(), "100.0");
+
+        assert_eq!(*reported.lock().unwrap(), vec![100.0]);
+    }
+
+    #[test]
+    fn test_stepping_clamps_or_wraps() {
+        let size = Extent::new(98.0, 28.0);
+        let (up, down) = (Point::new(90.0, 5.0), Point::new(90.0, 23.0));
+
+        let input = Arc::new(number_input_with_range(0.0, 10.0).step(4.0).value(6.0));
+        let view = TestView::new(input.clone(), size);
+        view.click_at(up);
+        assert_eq!(input.get_value(), 10.0);
+        view.click_at(up);
+        assert_eq!(input.get_value(), 10.0);
+        view.click_at(down);
+        assert_eq!(input.get_value(), 6.0);
+
+        let input = Arc::new(number_input_with_range(0.0, 10.0).step(4.0).value(2.0).wrap(true));
+        let view = TestView::new(input.clone(), size);
+        view.focus_first();
+        view.key(KeyCode::Down, 0);
+        assert_eq!(input.get_value(), 10.0);
+        view.key(KeyCode::Up, 0);
+        assert_eq!(input.get_value(), 0.0);
+    }
+
+    #[test]
+    fn test_hover_tracks_stepper_button() {
+        let input = Arc::new(number_input_with_range(0.0, 10.0));
+        let view = TestView::new(input.clone(), Extent::new(98.0, 28.0));
+
+        view.hover(Point::new(90.0, 5.0));
+        assert_eq!(*input.hovered_step.read().unwrap(), Some(1.0));
+        view.hover(Point::new(90.0, 23.0));
+        assert_eq!(*input.hovered_step.read().unwrap(), Some(-1.0));
+        view.hover(Point::new(20.0, 14.0));
+        assert_eq!(*input.hovered_step.read().unwrap(), None);
+
+        view.hover(Point::new(90.0, 5.0));
+        view.leave(Point::new(200.0, 5.0));
+        assert_eq!(*input.hovered_step.read().unwrap(), None);
+    }
+}
@@ -2,12 +2,15 @@
 
 use std::any::Any;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
+use crate::support::animation::{Animated, Easing};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
+use crate::support::canvas::ConicGradient;
+use crate::support::theme::{get_theme, ThemeColor};
 
 /// Progress bar style.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -17,37 +20,49 @@ pub enum ProgressStyle {
     Circular,
 }
 
+/// Time the indeterminate segment takes to sweep across once.
+const SWEEP_PERIOD: f32 = 1.5;
+
 /// A progress bar element.
 pub struct ProgressBar {
     value: RwLock<f32>,
     style: ProgressStyle,
-    background_color: Color,
-    fill_color: Color,
-    text_color: Color,
+    background_color: ThemeColor,
+    fill_color: ThemeColor,
+    /// Colors the circular arc sweeps through, from the top around
+    arc_gradient: Option<(Color, Color)>,
+    text_color: ThemeColor,
     show_percentage: bool,
     width: f32,
     height: f32,
     corner_radius: f32,
     indeterminate: bool,
     animation_offset: RwLock<f32>,
+    animated: bool,
+    /// The value currently drawn, which eases toward `value` when animated
+    displayed_value: RwLock<Animated<f32>>,
+    last_tick: RwLock<Option<Instant>>,
 }
 
 impl ProgressBar {
     /// Creates a new progress bar.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             value: RwLock::new(0.0),
             style: ProgressStyle::Linear,
-            background_color: theme.slider_slot_color,
-            fill_color: theme.indicator_bright_color,
-            text_color: theme.label_font_color,
+            background_color: ThemeColor::Theme(|theme| theme.slider_slot_color),
+            fill_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            arc_gradient: None,
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
             show_percentage: false,
             width: 200.0,
             height: 8.0,
             corner_radius: 4.0,
             indeterminate: false,
             animation_offset: RwLock::new(0.0),
+            animated: false,
+            displayed_value: RwLock::new(Animated::new(0.0).easing(Easing::EaseOut)),
+            last_tick: RwLock::new(None),
         }
     }
 
@@ -68,13 +83,20 @@ impl ProgressBar {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
     /// Sets the fill color.
     pub fn fill_color(mut self, color: Color) -> Self {
-        self.fill_color = color;
+        self.fill_color = color.into();
+        self
+    }
+
+    /// Colors the circular arc by angle, blending from `start` at the top
+    /// to `end` after a full turn, instead of the fill color.
+    pub fn arc_gradient(mut self, start: Color, end: Color) -> Self {
+        self.arc_gradient = Some((start, end));
         self
     }
 
@@ -93,6 +115,20 @@ impl ProgressBar {
         self
     }
 
+    /// Sets whether value changes ease toward the new value instead of
+    /// jumping to it. The indeterminate sweep always animates.
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
+    /// Sets how long an animated value change takes.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        let displayed = self.displayed_value.get_mut().unwrap();
+        *displayed = displayed.duration(duration);
+        self
+    }
+
     /// Sets the dimensions.
     pub fn size(mut self, width: f32, height: f32) -> Self {
         self.width = width;
@@ -107,7 +143,14 @@ impl ProgressBar {
 
     /// Sets the current value.
     pub fn set_value(&self, value: f32) {
-        *self.value.write().unwrap() = value.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        *self.value.write().unwrap() = value;
+        let mut displayed = self.displayed_value.write().unwrap();
+        if self.animated {
+            displayed.set_target(value);
+        } else {
+            displayed.set(value);
+        }
     }
 
     /// Increments the value.
@@ -116,19 +159,33 @@ impl ProgressBar {
         self.set_value(current + delta);
     }
 
+    /// Advances the animation by `dt` seconds.
+    ///
+    /// Returns true if anything moved.
+    fn advance(&self, dt: f32) -> bool {
+        if self.indeterminate {
+            let mut offset = self.animation_offset.write().unwrap();
+            *offset = (*offset + dt / SWEEP_PERIOD).fract();
+            return true;
+        }
+
+        self.displayed_value.write().unwrap().advance(dt)
+    }
+
     fn draw_linear(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        let value = self.get_value();
+        let value = self.displayed_value.read().unwrap().get();
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(ctx.bounds, self.corner_radius);
 
         if self.indeterminate {
-            // Animated indeterminate bar
+            // Animated indeterminate bar, sweeping in from the left edge
+            // and out past the right one
             let offset = *self.animation_offset.read().unwrap();
             let bar_width = ctx.bounds.width() * 0.3;
-            let x = ctx.bounds.left + (ctx.bounds.width() - bar_width) * offset;
+            let x = ctx.bounds.left - bar_width + (ctx.bounds.width() + bar_width) * offset;
 
             let fill_rect = Rect::new(
                 x.max(ctx.bounds.left),
@@ -137,8 +194,10 @@ impl ProgressBar {
                 ctx.bounds.bottom,
             );
 
-            canvas.fill_style(self.fill_color);
-            canvas.fill_round_rect(fill_rect, self.corner_radius);
+            if fill_rect.width() > 0.0 {
+                canvas.fill_style(self.fill_color.get());
+                canvas.fill_round_rect(fill_rect, self.corner_radius);
+            }
         } else {
             // Determinate progress bar
             if value > 0.0 {
@@ -150,7 +209,7 @@ impl ProgressBar {
                     ctx.bounds.bottom,
                 );
 
-                canvas.fill_style(self.fill_color);
+                canvas.fill_style(self.fill_color.get());
                 canvas.fill_round_rect(fill_rect, self.corner_radius);
             }
 
@@ -159,7 +218,7 @@ impl ProgressBar {
                 let text = format!("{}%", (value * 100.0) as i32);
                 let theme = get_theme();
 
-                canvas.fill_style(self.text_color);
+                canvas.fill_style(self.text_color.get());
                 canvas.font_size(theme.label_font_size * 0.8);
 
                 let x = ctx.bounds.center().x - text.len() as f32 * theme.label_font_size * 0.2;
@@ -171,7 +230,7 @@ impl ProgressBar {
 
     fn draw_circular(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
-        let value = self.get_value();
+        let value = self.displayed_value.read().unwrap().get();
         let theme = get_theme();
 
         let center = ctx.bounds.center();
@@ -179,7 +238,7 @@ impl ProgressBar {
         let stroke_width = 6.0;
 
         // Background circle
-        canvas.stroke_style(self.background_color);
+        canvas.stroke_style(self.background_color.get());
         canvas.line_width(stroke_width);
         canvas.begin_path();
         canvas.add_circle(crate::support::circle::Circle::new(center, radius));
@@ -191,7 +250,7 @@ impl ProgressBar {
             let start_angle = offset * std::f32::consts::PI * 2.0 - std::f32::consts::PI / 2.0;
             let end_angle = start_angle + std::f32::consts::PI * 0.75;
 
-            canvas.stroke_style(self.fill_color);
+            canvas.stroke_style(self.fill_color.get());
             canvas.line_width(stroke_width);
             canvas.begin_path();
 
@@ -215,31 +274,43 @@ impl ProgressBar {
                 let start_angle = -std::f32::consts::PI / 2.0;
                 let end_angle = start_angle + value * std::f32::consts::PI * 2.0;
 
-                canvas.stroke_style(self.fill_color);
-                canvas.line_width(stroke_width);
-                canvas.begin_path();
-
-                let segments = (value * 40.0).max(2.0) as i32;
-                for i in 0..=segments {
-                    let t = i as f32 / segments as f32;
-                    let angle = start_angle + t * (end_angle - start_angle);
-                    let x = center.x + radius * angle.cos();
-                    let y = center.y + radius * angle.sin();
-
-                    if i == 0 {
-                        canvas.move_to(Point::new(x, y));
-                    } else {
-                        canvas.line_to(Point::new(x, y));
+                if let Some((start, end)) = self.arc_gradient {
+                    // Fill the band the stroke would cover, colored by angle
+                    let mut grad = ConicGradient::new(center, start_angle);
+                    grad.add_stop(0.0, start);
+                    grad.add_stop(1.0, end);
+                    canvas.begin_path();
+                    canvas.arc(center, radius + stroke_width / 2.0, start_angle, end_angle, false);
+                    canvas.arc(center, radius - stroke_width / 2.0, end_angle, start_angle, true);
+                    canvas.close_path();
+                    canvas.fill_conic_gradient(&grad);
+                } else {
+                    canvas.stroke_style(self.fill_color.get());
+                    canvas.line_width(stroke_width);
+                    canvas.begin_path();
+
+                    let segments = (value * 40.0).max(2.0) as i32;
+                    for i in 0..=segments {
+                        let t = i as f32 / segments as f32;
+                        let angle = start_angle + t * (end_angle - start_angle);
+                        let x = center.x + radius * angle.cos();
+                        let y = center.y + radius * angle.sin();
+
+                        if i == 0 {
+                            canvas.move_to(Point::new(x, y));
+                        } else {
+                            canvas.line_to(Point::new(x, y));
+                        }
                     }
+                    canvas.stroke();
                 }
-                canvas.stroke();
             }
 
             // Percentage text in center
             if self.show_percentage {
                 let text = format!("{}%", (value * 100.0) as i32);
 
-                canvas.fill_style(self.text_color);
+                canvas.fill_style(self.text_color.get());
                 canvas.font_size(theme.label_font_size);
 
                 let x = center.x - text.len() as f32 * theme.label_font_size * 0.25;
@@ -275,6 +346,20 @@ impl Element for ProgressBar {
         }
     }
 
+    fn handle_poll(&self) -> bool {
+        let now = Instant::now();
+        let last_tick = self.last_tick.write().unwrap().replace(now);
+        let dt = last_tick.map_or(0.0, |t| now.duration_since(t).as_secs_f32());
+
+        if self.advance(dt) {
+            return true;
+        }
+
+        // Nothing to animate; restart the clock on the next change
+        *self.last_tick.write().unwrap() = None;
+        false
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -305,3 +390,52 @@ pub fn circular_progress() -> ProgressBar {
 pub fn indeterminate_progress() -> ProgressBar {
     ProgressBar::new().indeterminate(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indeterminate_sweep_wraps() {
+        let bar = indeterminate_progress();
+        assert!(bar.advance(SWEEP_PERIOD * 0.5));
+        assert!((*bar.animation_offset.read().unwrap() - 0.5).abs() < 1e-4);
+        assert!(bar.advance(SWEEP_PERIOD * 0.75));
+        assert!((*bar.animation_offset.read().unwrap() - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_circular_arc_gradient() {
+        let bar = circular_progress()
+            .value(1.0)
+            .arc_gradient(Color::rgb(1.0, 0.0, 0.0), Color::rgb(0.0, 0.0, 1.0));
+        let pixmap = crate::view::render_to_pixmap(&bar, crate::support::point::Extent::new(100.0, 100.0)).unwrap();
+
+        // Red just past the top, three quarters of the way to blue on the left
+        let top = pixmap.pixel(52, 4).unwrap();
+        assert!(top.red() > 240 && top.blue() < 15);
+        let left = pixmap.pixel(4, 50).unwrap();
+        assert!((55..75).contains(&left.red()) && (180..200).contains(&left.blue()));
+    }
+
+    #[test]
+    fn test_animated_value_eases_to_target() {
+        let bar = progress_bar().animated(true).duration(Duration::from_millis(200));
+        bar.set_value(0.8);
+        assert_eq!(bar.get_value(), 0.8);
+        assert_eq!(bar.displayed_value.read().unwrap().get(), 0.0);
+
+        assert!(bar.advance(0.05));
+        let halfway = bar.displayed_value.read().unwrap().get();
+        assert!(halfway > 0.0 && halfway < 0.8);
+
+        while bar.advance(1.0 / 60.0) {}
+        assert_eq!(bar.displayed_value.read().unwrap().get(), 0.8);
+
+        // Without animation the value jumps
+        let bar = progress_bar();
+        bar.set_value(0.5);
+        assert_eq!(bar.displayed_value.read().unwrap().get(), 0.5);
+        assert!(!bar.advance(1.0 / 60.0));
+    }
+}
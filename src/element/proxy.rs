@@ -5,10 +5,13 @@
 //! overriding certain behaviors.
 
 use std::any::Any;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, FocusRequest};
+use std::sync::RwLock;
+use super::{Element, ElementPtr, ViewLimits, ViewStretch, FocusRequest, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
-use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
+use crate::support::rect::Rect;
+use crate::support::theme::get_theme;
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, TextInfo, DropInfo, CursorTracking};
 
 /// Base trait for proxy elements.
 pub trait ProxyBase: Element {
@@ -28,12 +31,20 @@ pub trait ProxyBase: Element {
 /// A generic proxy that wraps any element.
 pub struct Proxy<S: Element> {
     subject: S,
+    /// Overrides the subject's grid span when set
+    span: Option<u32>,
 }
 
 impl<S: Element> Proxy<S> {
     /// Creates a new proxy wrapping the given subject.
     pub fn new(subject: S) -> Self {
-        Self { subject }
+        Self { subject, span: None }
+    }
+
+    /// Makes the subject span `span` columns of a grid.
+    pub fn with_span(mut self, span: u32) -> Self {
+        self.span = Some(span.max(1));
+        self
     }
 
     /// Returns a reference to the actual subject type.
@@ -67,7 +78,7 @@ impl<S: Element + 'static> Element for Proxy<S> {
     }
 
     fn span(&self) -> u32 {
-        self.subject.span()
+        self.span.unwrap_or_else(|| self.subject.span())
     }
 
     fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
@@ -134,6 +145,18 @@ impl<S: Element + 'static> Element for Proxy<S> {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn enable(&mut self, state: bool) {
         self.subject.enable(state);
     }
@@ -166,6 +189,37 @@ impl<S: Element + 'static> Element for Proxy<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
     fn track_drop(&mut self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
         self.subject.track_drop(ctx, info, status);
     }
@@ -245,6 +299,18 @@ impl Element for RefProxy {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -261,6 +327,37 @@ impl Element for RefProxy {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -269,3 +366,394 @@ impl Element for RefProxy {
         self
     }
 }
+
+/// Callback type for double-clicks.
+pub type DoubleClickCallback = Box<dyn Fn() + Send + Sync>;
+
+/// A proxy that calls back when its subject is double-clicked with the
+/// left button.
+///
+/// Clicks still reach the subject. The callback runs on the press that
+/// completes the double-click, as counted by the host against the
+/// [double-click threshold](crate::view::set_double_click_threshold).
+pub struct DoubleClick {
+    subject: ElementPtr,
+    on_double_click: DoubleClickCallback,
+}
+
+impl DoubleClick {
+    /// Creates a proxy calling `callback` when `subject` is double-clicked.
+    pub fn new<F: Fn() + Send + Sync + 'static>(subject: ElementPtr, callback: F) -> Self {
+        Self {
+            subject,
+            on_double_click: Box::new(callback),
+        }
+    }
+}
+
+impl Element for DoubleClick {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        // Clicks anywhere in the bounds count, even where the subject has
+        // nothing to hit
+        self.subject.hit_test(ctx, p, leaf, control)
+            .or_else(|| ctx.bounds.contains(p).then_some(self as &dyn Element))
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.subject.draw(ctx);
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        let handled = self.subject.handle_click(ctx, btn);
+        let double_click = btn.down && btn.click_count == 2 && btn.button == MouseButtonKind::Left;
+        if double_click && ctx.bounds.contains(btn.pos) {
+            (self.on_double_click)();
+            return true;
+        }
+        handled
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Callback type for drops; returns whether the drop was accepted.
+pub type DropCallback = Box<dyn Fn(&DropInfo) -> bool + Send + Sync>;
+
+/// A proxy that accepts data dragged onto its subject, such as files from
+/// the desktop.
+///
+/// The subject is outlined while a drag hovers over it. Drops the subject
+/// handles itself do not reach the callback.
+pub struct DropTarget {
+    subject: ElementPtr,
+    on_drop: DropCallback,
+    /// Whether a drag is over the subject
+    hovering: RwLock<bool>,
+}
+
+impl DropTarget {
+    /// Creates a proxy calling `callback` with what is dropped on `subject`.
+    pub fn new<F: Fn(&DropInfo) -> bool + Send + Sync + 'static>(subject: ElementPtr, callback: F) -> Self {
+        Self {
+            subject,
+            on_drop: Box::new(callback),
+            hovering: RwLock::new(false),
+        }
+    }
+
+    /// Returns whether a drag is over the subject.
+    pub fn is_hovering(&self) -> bool {
+        *self.hovering.read().unwrap()
+    }
+
+    fn set_hovering(&self, ctx: &Context, hovering: bool) {
+        let mut current = self.hovering.write().unwrap();
+        if *current != hovering {
+            *current = hovering;
+            drop(current);
+            self.refresh(ctx, 0);
+        }
+    }
+}
+
+impl Element for DropTarget {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+            .or_else(|| ctx.bounds.contains(p).then_some(self as &dyn Element))
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.subject.draw(ctx);
+        if self.is_hovering() {
+            let mut canvas = ctx.canvas.borrow_mut();
+            canvas.stroke_style(get_theme().frame_hilite_color);
+            canvas.line_width(2.0);
+            canvas.stroke_rect(ctx.bounds.inset(1.0, 1.0));
+        }
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+        self.set_hovering(ctx, status != CursorTracking::Leaving && ctx.bounds.contains(info.where_));
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.set_hovering(ctx, false);
+        if !ctx.bounds.contains(info.where_) {
+            return false;
+        }
+        self.subject.handle_drop(ctx, info) || (self.on_drop)(info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Wraps `subject` to call `callback` when it is double-clicked.
+pub fn on_double_click<E: Element + 'static, F: Fn() + Send + Sync + 'static>(subject: E, callback: F) -> DoubleClick {
+    DoubleClick::new(share(subject), callback)
+}
+
+/// Wraps `subject` to call `callback` with data dropped on it.
+pub fn on_drop<E: Element + 'static, F: Fn(&DropInfo) -> bool + Send + Sync + 'static>(subject: E, callback: F) -> DropTarget {
+    DropTarget::new(share(subject), callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use crate::support::point::Extent;
+    use crate::element::composite::Storage;
+    use crate::element::share;
+    use crate::view::{ClickCounter, MouseButtonKind};
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_double_click() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let sink = count.clone();
+        let element = on_double_click(super::super::empty(), move || {
+            sink.fetch_add(1, Ordering::SeqCst);
+        });
+        let view = TestView::new(share(element), Extent::new(100.0, 100.0));
+
+        // Presses close in time and space count up; slow or far ones restart
+        let mut clicks = ClickCounter::default();
+        let mut press = |ms, x| {
+            let pos = Point::new(x, 10.0);
+            let mut btn = MouseButton::new(true, MouseButtonKind::Left, pos);
+            btn.click_count = clicks.press(btn.button, Duration::from_millis(ms), pos);
+            view.mouse(btn);
+            btn.click_count
+        };
+        assert_eq!(press(0, 10.0), 1);
+        assert_eq!(press(200, 12.0), 2);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(press(1000, 12.0), 1);
+        assert_eq!(press(1100, 30.0), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_drop_reaches_target_under_the_cursor() {
+        let dropped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (top_sink, bottom_sink) = (dropped.clone(), dropped.clone());
+        let tile = Arc::new(crate::vtile![
+            on_drop(super::super::empty(), move |info: &DropInfo| {
+                top_sink.lock().unwrap().push(("top", info.paths()));
+                true
+            }),
+            on_drop(super::super::empty(), move |info: &DropInfo| {
+                bottom_sink.lock().unwrap().push(("bottom", info.paths()));
+                true
+            }),
+        ]);
+        let view = TestView::new(tile.clone(), Extent::new(100.0, 100.0));
+        let target = |i: usize| tile.at(i).unwrap().as_any().downcast_ref::<DropTarget>().unwrap();
+
+        // Only the target under the drag is outlined
+        let info = DropInfo::new(Point::new(50.0, 80.0)).with_paths(["/tmp/a b.wav", "/tmp/c.wav"]);
+        view.track_drop(&info, CursorTracking::Entering);
+        assert!(!target(0).is_hovering());
+        assert!(target(1).is_hovering());
+
+        assert!(view.perform_drop(&info));
+        assert!(!target(1).is_hovering());
+        let dropped = dropped.lock().unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].0, "bottom");
+        assert_eq!(dropped[0].1, vec![std::path::PathBuf::from("/tmp/a b.wav"), std::path::PathBuf::from("/tmp/c.wav")]);
+    }
+}
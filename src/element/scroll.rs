@@ -2,13 +2,14 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, share};
+use std::time::Instant;
+use super::{Element, ElementPtr, FocusRequest, ViewLimits, ViewStretch, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind};
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, DropInfo, KeyInfo, TextInfo};
 
 /// Scrollbar visibility options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -19,6 +20,13 @@ pub enum ScrollbarVisibility {
     Never,
 }
 
+/// How fast momentum scrolling slows down: the velocity falls by a factor
+/// of e every `MOMENTUM_DECAY` seconds.
+pub(crate) const MOMENTUM_DECAY: f32 = 0.2;
+
+/// Speed (in pixels per second) below which momentum scrolling stops.
+pub(crate) const MOMENTUM_MIN_SPEED: f32 = 5.0;
+
 /// A scrollable container element.
 pub struct ScrollView {
     content: Option<ElementPtr>,
@@ -26,8 +34,8 @@ pub struct ScrollView {
     content_size: RwLock<Point>,
     h_scrollbar: ScrollbarVisibility,
     v_scrollbar: ScrollbarVisibility,
-    scrollbar_color: Color,
-    scrollbar_hover_color: Color,
+    scrollbar_color: ThemeColor,
+    scrollbar_hover_color: ThemeColor,
     scrollbar_width: f32,
     width: f32,
     height: f32,
@@ -35,6 +43,13 @@ pub struct ScrollView {
     dragging_h: RwLock<bool>,
     drag_start: RwLock<Point>,
     drag_start_scroll: RwLock<Point>,
+    smooth: bool,
+    /// Momentum scrolling velocity in pixels per second
+    velocity: RwLock<Point>,
+    last_tick: RwLock<Option<Instant>>,
+    /// Set when the focus moves into the content, so the next draw scrolls
+    /// the focused element into view
+    reveal_focus: RwLock<bool>,
 }
 
 impl ScrollView {
@@ -47,8 +62,8 @@ impl ScrollView {
             content_size: RwLock::new(Point::new(400.0, 400.0)),
             h_scrollbar: ScrollbarVisibility::Auto,
             v_scrollbar: ScrollbarVisibility::Auto,
-            scrollbar_color: theme.scrollbar_color,
-            scrollbar_hover_color: theme.scrollbar_color.level(1.3),
+            scrollbar_color: ThemeColor::Theme(|theme| theme.scrollbar_color),
+            scrollbar_hover_color: ThemeColor::Theme(|theme| theme.scrollbar_color.level(1.3)),
             scrollbar_width: theme.scrollbar_width,
             width: 200.0,
             height: 200.0,
@@ -56,6 +71,10 @@ impl ScrollView {
             dragging_h: RwLock::new(false),
             drag_start: RwLock::new(Point::zero()),
             drag_start_scroll: RwLock::new(Point::zero()),
+            smooth: false,
+            velocity: RwLock::new(Point::zero()),
+            last_tick: RwLock::new(None),
+            reveal_focus: RwLock::new(false),
         }
     }
 
@@ -90,9 +109,16 @@ impl ScrollView {
         self
     }
 
+    /// Enables momentum scrolling: wheel and trackpad deltas set the content
+    /// moving, and it slows to a stop on the view's idle ticks.
+    pub fn smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        self
+    }
+
     /// Sets the scrollbar color.
     pub fn scrollbar_color(mut self, color: Color) -> Self {
-        self.scrollbar_color = color;
+        self.scrollbar_color = color.into();
         self
     }
 
@@ -134,6 +160,96 @@ impl ScrollView {
         self.set_scroll(new_scroll);
     }
 
+    /// Scrolls by the smallest amount that brings `rect`, given in content
+    /// coordinates, fully into view. A rect larger than the viewport is
+    /// aligned to its top-left corner.
+    pub fn scroll_to_rect(&self, ctx: &Context, rect: Rect) {
+        fn reveal(scroll: f32, start: f32, end: f32, extent: f32) -> f32 {
+            if start < scroll {
+                start
+            } else if end > scroll + extent {
+                (end - extent).min(start)
+            } else {
+                scroll
+            }
+        }
+
+        let viewport = self.viewport_rect(ctx);
+        let scroll = self.get_scroll();
+        self.set_scroll(Point::new(
+            reveal(scroll.x, rect.left, rect.right, viewport.width()),
+            reveal(scroll.y, rect.top, rect.bottom, viewport.height()),
+        ));
+    }
+
+    /// Scrolls so that `element`, somewhere inside the content, is fully
+    /// visible, moving as little as possible.
+    ///
+    /// Returns false if `element` is not part of the content.
+    pub fn scroll_to_element(&self, ctx: &Context, element: &dyn Element) -> bool {
+        self.reveal(ctx, |content, content_ctx| content.bounds_of_element(content_ctx, element))
+    }
+
+    /// Scrolls so that the focused element inside the content is fully
+    /// visible, moving as little as possible.
+    ///
+    /// Returns false if nothing in the content has the focus.
+    pub fn scroll_to_focus(&self, ctx: &Context) -> bool {
+        self.reveal(ctx, |content, content_ctx| content.focus_bounds(content_ctx))
+    }
+
+    /// Scrolls to the rect `locate` finds in the content, which it is
+    /// given at the origin.
+    fn reveal(&self, ctx: &Context, locate: impl FnOnce(&dyn Element, &Context) -> Option<Rect>) -> bool {
+        let Some(ref content) = self.content else {
+            return false;
+        };
+
+        let content_size = *self.content_size.read().unwrap();
+        let content_ctx = ctx.with_bounds(Rect::new(0.0, 0.0, content_size.x, content_size.y));
+        match locate(content.as_ref(), &content_ctx) {
+            Some(bounds) => {
+                self.scroll_to_rect(ctx, bounds);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Notes whether the content took the focus, so the next draw can
+    /// bring the focused element into view.
+    fn track_focus(&self, focused: bool) -> bool {
+        if focused {
+            *self.reveal_focus.write().unwrap() = true;
+        }
+        focused
+    }
+
+    /// Moves the content along with its momentum for `dt` seconds.
+    ///
+    /// Returns true if the content moved.
+    fn advance_momentum(&self, dt: f32) -> bool {
+        let mut velocity = self.velocity.write().unwrap();
+        if velocity.x.hypot(velocity.y) < MOMENTUM_MIN_SPEED {
+            *velocity = Point::zero();
+            *self.last_tick.write().unwrap() = None;
+            return false;
+        }
+
+        // Exponential decay, integrated exactly over the tick
+        let decay = (-dt / MOMENTUM_DECAY).exp();
+        let travel = MOMENTUM_DECAY * (1.0 - decay);
+        let current = self.get_scroll();
+        let target = Point::new(current.x + velocity.x * travel, current.y + velocity.y * travel);
+        self.set_scroll(target);
+
+        // Stop at the content edges
+        let scroll = self.get_scroll();
+        velocity.x = if scroll.x == target.x { velocity.x * decay } else { 0.0 };
+        velocity.y = if scroll.y == target.y { velocity.y * decay } else { 0.0 };
+        scroll != current
+    }
+
     fn needs_v_scrollbar(&self) -> bool {
         match self.v_scrollbar {
             ScrollbarVisibility::Always => true,
@@ -156,6 +272,19 @@ impl ScrollView {
         }
     }
 
+    /// Returns the bounds of the content at the current scroll offset.
+    fn content_bounds(&self, ctx: &Context) -> Rect {
+        let viewport = self.viewport_rect(ctx);
+        let scroll = *self.scroll_offset.read().unwrap();
+        let content_size = *self.content_size.read().unwrap();
+        Rect::new(
+            viewport.left - scroll.x,
+            viewport.top - scroll.y,
+            viewport.left - scroll.x + content_size.x,
+            viewport.top - scroll.y + content_size.y,
+        )
+    }
+
     fn viewport_rect(&self, ctx: &Context) -> Rect {
         let has_v = self.needs_v_scrollbar();
         let has_h = self.needs_h_scrollbar();
@@ -265,14 +394,14 @@ impl ScrollView {
             let thumb = self.v_thumb_rect(ctx);
 
             // Track background
-            canvas.fill_style(self.scrollbar_color.with_alpha(0.2));
+            canvas.fill_style(self.scrollbar_color.get().with_alpha(0.2));
             canvas.fill_rect(track);
 
             // Thumb
             let color = if *self.dragging_v.read().unwrap() {
-                self.scrollbar_hover_color
+                self.scrollbar_hover_color.get()
             } else {
-                self.scrollbar_color
+                self.scrollbar_color.get()
             };
             canvas.fill_style(color);
             canvas.fill_round_rect(thumb, 3.0);
@@ -284,14 +413,14 @@ impl ScrollView {
             let thumb = self.h_thumb_rect(ctx);
 
             // Track background
-            canvas.fill_style(self.scrollbar_color.with_alpha(0.2));
+            canvas.fill_style(self.scrollbar_color.get().with_alpha(0.2));
             canvas.fill_rect(track);
 
             // Thumb
             let color = if *self.dragging_h.read().unwrap() {
-                self.scrollbar_hover_color
+                self.scrollbar_hover_color.get()
             } else {
-                self.scrollbar_color
+                self.scrollbar_color.get()
             };
             canvas.fill_style(color);
             canvas.fill_round_rect(thumb, 3.0);
@@ -305,7 +434,7 @@ impl ScrollView {
                 ctx.bounds.right,
                 ctx.bounds.bottom,
             );
-            canvas.fill_style(self.scrollbar_color.with_alpha(0.3));
+            canvas.fill_style(self.scrollbar_color.get().with_alpha(0.3));
             canvas.fill_rect(corner);
         }
     }
@@ -328,22 +457,21 @@ impl Element for ScrollView {
 
     fn draw(&self, ctx: &Context) {
         let viewport = self.viewport_rect(ctx);
-        let scroll = *self.scroll_offset.read().unwrap();
-        let content_size = *self.content_size.read().unwrap();
+
+        // Tabbing to an element out of view scrolls it in before it is shown
+        if std::mem::take(&mut *self.reveal_focus.write().unwrap()) {
+            self.scroll_to_focus(ctx);
+        }
 
         // Draw content
         if let Some(ref content) = self.content {
-            // Content bounds (scrolled)
-            let content_bounds = Rect::new(
-                viewport.left - scroll.x,
-                viewport.top - scroll.y,
-                viewport.left - scroll.x + content_size.x,
-                viewport.top - scroll.y + content_size.y,
-            );
+            let content_bounds = self.content_bounds(ctx);
 
-            // Clip to viewport (simplified - would need proper clipping)
-            let content_ctx = ctx.with_bounds(content_bounds);
+            // Clip to viewport so scrolled content doesn't overdraw the scrollbars
+            ctx.canvas.borrow_mut().push_clip(viewport);
+            let content_ctx = ctx.with_bounds(content_bounds).with_clip(viewport);
             content.draw(&content_ctx);
+            ctx.canvas.borrow_mut().pop_clip();
         }
 
         self.draw_scrollbars(ctx);
@@ -363,14 +491,7 @@ impl Element for ScrollView {
         let viewport = self.viewport_rect(ctx);
         if viewport.contains(p) {
             if let Some(ref content) = self.content {
-                let scroll = *self.scroll_offset.read().unwrap();
-                let content_size = *self.content_size.read().unwrap();
-                let content_bounds = Rect::new(
-                    viewport.left - scroll.x,
-                    viewport.top - scroll.y,
-                    viewport.left - scroll.x + content_size.x,
-                    viewport.top - scroll.y + content_size.y,
-                );
+                let content_bounds = self.content_bounds(ctx);
                 let content_ctx = ctx.with_bounds(content_bounds);
                 if let Some(hit) = content.hit_test(&content_ctx, p, leaf, control) {
                     return Some(hit);
@@ -411,14 +532,7 @@ impl Element for ScrollView {
             let viewport = self.viewport_rect(ctx);
             if viewport.contains(btn.pos) {
                 if let Some(ref content) = self.content {
-                    let scroll = *self.scroll_offset.read().unwrap();
-                    let content_size = *self.content_size.read().unwrap();
-                    let content_bounds = Rect::new(
-                        viewport.left - scroll.x,
-                        viewport.top - scroll.y,
-                        viewport.left - scroll.x + content_size.x,
-                        viewport.top - scroll.y + content_size.y,
-                    );
+                    let content_bounds = self.content_bounds(ctx);
                     let content_ctx = ctx.with_bounds(content_bounds);
                     if content.handle_click(&content_ctx, btn) {
                         return true;
@@ -433,14 +547,7 @@ impl Element for ScrollView {
             let viewport = self.viewport_rect(ctx);
             if viewport.contains(btn.pos) {
                 if let Some(ref content) = self.content {
-                    let scroll = *self.scroll_offset.read().unwrap();
-                    let content_size = *self.content_size.read().unwrap();
-                    let content_bounds = Rect::new(
-                        viewport.left - scroll.x,
-                        viewport.top - scroll.y,
-                        viewport.left - scroll.x + content_size.x,
-                        viewport.top - scroll.y + content_size.y,
-                    );
+                    let content_bounds = self.content_bounds(ctx);
                     let content_ctx = ctx.with_bounds(content_bounds);
                     if content.handle_click(&content_ctx, btn) {
                         return true;
@@ -498,6 +605,15 @@ impl Element for ScrollView {
     }
 
     fn handle_scroll(&self, _ctx: &Context, dir: Point, _p: Point) -> bool {
+        if self.smooth {
+            // Add enough speed for the content to coast the usual step
+            let mut velocity = self.velocity.write().unwrap();
+            velocity.x -= dir.x * 20.0 / MOMENTUM_DECAY;
+            velocity.y -= dir.y * 20.0 / MOMENTUM_DECAY;
+            self.last_tick.write().unwrap().get_or_insert_with(Instant::now);
+            return true;
+        }
+
         let current = *self.scroll_offset.read().unwrap();
         let new_scroll = Point::new(
             current.x - dir.x * 20.0,
@@ -507,6 +623,100 @@ impl Element for ScrollView {
         true
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        match self.content {
+            Some(ref content) if self.viewport_rect(ctx).contains(center) => {
+                content.handle_magnify(&ctx.with_bounds(self.content_bounds(ctx)), scale_delta, center)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        if let Some(ref content) = self.content {
+            // Content scrolled out of the viewport can't be dropped on
+            let status = if self.viewport_rect(ctx).contains(info.where_) { status } else { CursorTracking::Leaving };
+            content.handle_track_drop(&ctx.with_bounds(self.content_bounds(ctx)), info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        match self.content {
+            Some(ref content) if self.viewport_rect(ctx).contains(info.where_) => {
+                content.handle_drop(&ctx.with_bounds(self.content_bounds(ctx)), info)
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_key(&ctx.with_bounds(self.content_bounds(ctx)), k))
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_text(&ctx.with_bounds(self.content_bounds(ctx)), info))
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.wants_focus())
+    }
+
+    fn clear_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.clear_focus();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.track_focus(self.content.as_ref().is_some_and(|content| content.handle_begin_focus(req)))
+    }
+
+    fn handle_end_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.handle_end_focus();
+        }
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.track_focus(self.content.as_ref().is_some_and(|content| content.handle_focus_next(forward)))
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        let content_ctx = ctx.with_bounds(self.content_bounds(ctx));
+        self.content.as_ref()?.bounds_of_element(&content_ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        let content_ctx = ctx.with_bounds(self.content_bounds(ctx));
+        self.content.as_ref()?.focus_bounds(&content_ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Content scrolled out of view isn't under the mouse
+        let status = if self.viewport_rect(ctx).contains(p) { status } else { CursorTracking::Leaving };
+        let content_ctx = ctx.with_bounds(self.content_bounds(ctx));
+        self.content.as_ref().is_some_and(|content| content.handle_cursor(&content_ctx, p, status))
+    }
+
+    fn handle_poll(&self) -> bool {
+        let content_redraw = self.content.as_ref().is_some_and(|c| c.handle_poll());
+
+        let Some(last_tick) = *self.last_tick.read().unwrap() else {
+            return content_redraw;
+        };
+        let now = Instant::now();
+        *self.last_tick.write().unwrap() = Some(now);
+
+        self.advance_momentum(now.duration_since(last_tick).as_secs_f32()) || content_redraw
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -530,3 +740,139 @@ pub fn vscroll_view() -> ScrollView {
 pub fn hscroll_view() -> ScrollView {
     ScrollView::new().v_scrollbar(ScrollbarVisibility::Never)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+    use crate::element::{empty, size::fixed_size, tile::VTile};
+    use crate::element::text_box::text_box;
+    use crate::view::{KeyCode, modifiers};
+
+    #[test]
+    fn test_tab_moves_through_content() {
+        let first = Arc::new(text_box());
+        let sv = Arc::new(scroll_view().content(text_box()));
+        let view = TestView::new(Arc::new(VTile::from_vec(vec![first.clone(), sv.clone()])), Extent::new(200.0, 100.0));
+        assert!(view.focus_first());
+        assert!(first.has_focus());
+
+        // Tab reaches the field inside the scroll view, and typing goes there
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(sv.has_focus() && !first.has_focus());
+        assert!(view.type_text("x"));
+
+        // Past the last field it wraps around to the first
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(first.has_focus() && !sv.has_focus());
+
+        // Shift-Tab goes back, wrapping the other way
+        assert!(view.key(KeyCode::Tab, modifiers::SHIFT));
+        assert!(sv.has_focus() && !first.has_focus());
+        assert!(view.key(KeyCode::Tab, modifiers::SHIFT));
+        assert!(first.has_focus());
+    }
+
+    #[test]
+    fn test_momentum_decays_and_stops_at_edge() {
+        let sv = Arc::new(scroll_view().size(100.0, 100.0).content_size(100.0, 1000.0).smooth(true));
+        let view = TestView::new(sv.clone(), Extent::new(100.0, 100.0));
+
+        // One wheel step coasts about 20 pixels in total
+        view.scroll(Point::new(0.0, -1.0), Point::zero());
+        assert_eq!(sv.get_scroll(), Point::zero());
+        while sv.advance_momentum(1.0 / 60.0) {}
+        assert!((sv.get_scroll().y - 20.0).abs() < 1.0);
+
+        // Scrolling back past the top stops there
+        view.scroll(Point::new(0.0, 5.0), Point::zero());
+        assert!(sv.advance_momentum(1.0));
+        assert_eq!(sv.get_scroll().y, 0.0);
+        assert_eq!(*sv.velocity.read().unwrap(), Point::zero());
+        assert!(!sv.advance_momentum(1.0 / 60.0));
+    }
+
+    #[test]
+    fn test_idle_ticks_drive_momentum() {
+        let sv = Arc::new(scroll_view().size(100.0, 100.0).content_size(100.0, 1000.0).smooth(true));
+        let view = TestView::new(sv.clone(), Extent::new(100.0, 100.0));
+        assert!(!view.poll());
+
+        // The host's idle tick carries the scroll on until it comes to rest
+        view.scroll(Point::new(0.0, -1.0), Point::zero());
+        let mut ticks = 0;
+        while view.poll() {
+            ticks += 1;
+            assert!(ticks < 1000, "momentum never settled");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(ticks > 1);
+        assert!((sv.get_scroll().y - 20.0).abs() < 1.0);
+        assert!(!view.poll());
+    }
+
+    #[test]
+    fn test_scroll_to_element() {
+        let rows: Vec<ElementPtr> = (0..10).map(|_| share(fixed_size(100.0, 50.0, empty()))).collect();
+        let sv = Arc::new(
+            scroll_view()
+                .size(100.0, 100.0)
+                .content_size(100.0, 500.0)
+                .v_scrollbar(ScrollbarVisibility::Never)
+                .content(VTile::from_vec(rows.clone())),
+        );
+        let view = TestView::new(sv.clone(), Extent::new(100.0, 100.0));
+        let scroll_to = |element: &dyn Element| view.with_context(|ctx| sv.scroll_to_element(ctx, element));
+
+        // Scrolling down aligns the row's bottom with the viewport's bottom
+        assert!(scroll_to(rows[4].as_ref()));
+        assert_eq!(sv.get_scroll().y, 150.0);
+
+        // A row that is already visible doesn't move the content
+        assert!(scroll_to(rows[3].as_ref()));
+        assert_eq!(sv.get_scroll().y, 150.0);
+
+        // Scrolling up aligns the row's top with the viewport's top
+        assert!(scroll_to(rows[1].as_ref()));
+        assert_eq!(sv.get_scroll().y, 50.0);
+
+        assert!(!scroll_to(&empty()));
+    }
+
+    #[test]
+    fn test_tab_scrolls_focus_into_view() {
+        let rows: Vec<ElementPtr> = (0..10).map(|_| share(fixed_size(100.0, 50.0, text_box()))).collect();
+        let sv = Arc::new(
+            scroll_view()
+                .size(100.0, 100.0)
+                .content_size(100.0, 500.0)
+                .v_scrollbar(ScrollbarVisibility::Never)
+                .content(VTile::from_vec(rows.clone())),
+        );
+        let view = TestView::new(sv.clone(), Extent::new(100.0, 100.0));
+        let tab = |mods| {
+            assert!(view.key(KeyCode::Tab, mods));
+            view.render();
+        };
+
+        assert!(view.focus_first());
+        for _ in 0..4 {
+            tab(0);
+        }
+        assert!(rows[4].has_focus());
+        assert_eq!(sv.get_scroll().y, 150.0);
+
+        // Going back to a visible row leaves the content where it is
+        tab(modifiers::SHIFT);
+        assert_eq!(sv.get_scroll().y, 150.0);
+
+        // Wrapping around brings the first row back
+        for _ in 0..7 {
+            tab(0);
+        }
+        assert!(rows[0].has_focus());
+        assert_eq!(sv.get_scroll().y, 0.0);
+    }
+}
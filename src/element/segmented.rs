@@ -0,0 +1,368 @@
+//! Segmented control for choosing one of a few options.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch};
+use super::context::{BasicContext, Context};
+use crate::support::color::Color;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, KeyAction, KeyCode, CursorTracking};
+
+/// Callback type for segment selection.
+pub type SegmentSelectCallback = Box<dyn Fn(usize) + Send + Sync>;
+
+/// A row of mutually exclusive segments drawn as a single pill.
+///
+/// Exactly one segment is selected at a time.
+pub struct SegmentedControl {
+    segments: Vec<String>,
+    selected: RwLock<usize>,
+    hovered: RwLock<Option<usize>>,
+    selected_color: ThemeColor,
+    background_color: ThemeColor,
+    hover_color: ThemeColor,
+    frame_color: ThemeColor,
+    text_color: ThemeColor,
+    height: f32,
+    padding: f32,
+    corner_radius: f32,
+    on_select: Option<SegmentSelectCallback>,
+}
+
+impl SegmentedControl {
+    /// Creates an empty segmented control.
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            selected: RwLock::new(0),
+            hovered: RwLock::new(None),
+            selected_color: ThemeColor::Theme(|theme| theme.active_tab_color),
+            background_color: ThemeColor::Theme(|theme| theme.inactive_tab_color),
+            hover_color: ThemeColor::Theme(|theme| theme.tab_hilite_color),
+            frame_color: ThemeColor::Theme(|theme| theme.frame_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            height: 26.0,
+            padding: 12.0,
+            corner_radius: 6.0,
+            on_select: None,
+        }
+    }
+
+    /// Sets the segment labels.
+    pub fn segments(mut self, segments: Vec<String>) -> Self {
+        let last = segments.len().saturating_sub(1);
+        let selected = self.selected.get_mut().unwrap();
+        *selected = (*selected).min(last);
+        self.segments = segments;
+        self
+    }
+
+    /// Sets the color of the selected segment.
+    pub fn selected_color(mut self, color: Color) -> Self {
+        self.selected_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the unselected segments.
+    pub fn background_color(mut self, color: Color) -> Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the text color.
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Sets the control height.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the corner radius of the pill.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets the callback called when the user selects another segment.
+    pub fn on_select<F: Fn(usize) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the index of the selected segment.
+    pub fn selected(&self) -> usize {
+        *self.selected.read().unwrap()
+    }
+
+    /// Selects a segment. Out of range indices are ignored.
+    ///
+    /// This does not call the `on_select` callback.
+    pub fn set_selected(&self, index: usize) {
+        if index < self.segments.len() {
+            *self.selected.write().unwrap() = index;
+        }
+    }
+
+    /// Selects a segment on behalf of the user, calling `on_select` if the
+    /// selection changed.
+    fn select(&self, ctx: &Context, index: usize) {
+        if index >= self.segments.len() || index == self.selected() {
+            return;
+        }
+        self.set_selected(index);
+        self.refresh(ctx, 0);
+        if let Some(ref callback) = self.on_select {
+            callback(index);
+        }
+    }
+
+    /// Returns the bounds of the segment at `index`.
+    fn segment_rect(&self, bounds: &Rect, index: usize) -> Rect {
+        let width = bounds.width() / self.segments.len().max(1) as f32;
+        let left = bounds.left + width * index as f32;
+        Rect::new(left, bounds.top, left + width, bounds.bottom)
+    }
+
+    /// Returns the segment under `p`, if any.
+    fn segment_at(&self, bounds: &Rect, p: Point) -> Option<usize> {
+        if !bounds.contains(p) {
+            return None;
+        }
+        (0..self.segments.len()).find(|&i| self.segment_rect(bounds, i).contains(p))
+    }
+}
+
+impl Default for SegmentedControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Element for SegmentedControl {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        // Segments share the width equally, so size for the longest label
+        let theme = get_theme();
+        let longest = self.segments.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+        let segment_width = longest as f32 * theme.label_font_size * 0.6 + self.padding * 2.0;
+        ViewLimits {
+            min: Point::new(segment_width * self.segments.len() as f32, self.height),
+            max: Point::new(super::FULL_EXTENT, self.height),
+        }
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(1.0, 0.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        if self.segments.is_empty() {
+            return;
+        }
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        let theme = get_theme();
+        let bounds = ctx.bounds;
+        let selected = self.selected();
+        let hovered = *self.hovered.read().unwrap();
+
+        canvas.fill_style(self.background_color.get());
+        canvas.fill_round_rect(bounds, self.corner_radius);
+
+        // Fill segments clipped to the pill shape so the ends stay rounded
+        for i in 0..self.segments.len() {
+            let color = if i == selected {
+                self.selected_color.get()
+            } else if hovered == Some(i) {
+                self.hover_color.get()
+            } else {
+                continue;
+            };
+            canvas.push_clip(self.segment_rect(&bounds, i));
+            canvas.fill_style(color);
+            canvas.fill_round_rect(bounds, self.corner_radius);
+            canvas.pop_clip();
+        }
+
+        // Dividers, except next to the selected segment
+        canvas.stroke_style(self.frame_color.get());
+        canvas.line_width(1.0);
+        for i in 1..self.segments.len() {
+            if i == selected || i - 1 == selected {
+                continue;
+            }
+            let x = self.segment_rect(&bounds, i).left;
+            canvas.begin_path();
+            canvas.move_to(Point::new(x, bounds.top + 4.0));
+            canvas.line_to(Point::new(x, bounds.bottom - 4.0));
+            canvas.stroke();
+        }
+        canvas.stroke_round_rect(bounds.inset(0.5, 0.5), self.corner_radius);
+
+        // Labels, centered in their segments
+        canvas.font_size(theme.label_font_size);
+        for (i, label) in self.segments.iter().enumerate() {
+            let rect = self.segment_rect(&bounds, i);
+            let width = canvas.measure_text(label).width;
+            let color = if i == selected {
+                self.text_color.get()
+            } else {
+                self.text_color.get().with_alpha(0.7)
+            };
+            canvas.fill_style(color);
+            canvas.fill_text(label, Point::new(
+                rect.center().x - width / 2.0,
+                rect.center().y + theme.label_font_size * 0.35,
+            ));
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        ctx.bounds.contains(p).then_some(self as &dyn Element)
+    }
+
+    fn wants_control(&self) -> bool {
+        true
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if btn.button != MouseButtonKind::Left {
+            return false;
+        }
+        if btn.down {
+            if let Some(i) = self.segment_at(&ctx.bounds, btn.pos) {
+                self.select(ctx, i);
+            }
+        }
+        true
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if k.action == KeyAction::Release {
+            return false;
+        }
+        let selected = self.selected();
+        match k.key {
+            KeyCode::Left if selected > 0 => self.select(ctx, selected - 1),
+            KeyCode::Right => self.select(ctx, selected + 1),
+            _ => return false,
+        }
+        true
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let hovered = match status {
+            CursorTracking::Leaving => None,
+            _ => self.segment_at(&ctx.bounds, p),
+        };
+        let mut current = self.hovered.write().unwrap();
+        let changed = *current != hovered;
+        if changed {
+            *current = hovered;
+            drop(current);
+            self.refresh(ctx, 0);
+        }
+        changed || hovered.is_some()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a segmented control with the given segment labels.
+pub fn segmented_control<S: Into<String>>(segments: impl IntoIterator<Item = S>) -> SegmentedControl {
+    SegmentedControl::new().segments(segments.into_iter().map(Into::into).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_click_selects_segment() {
+        let selected = Arc::new(Mutex::new(Vec::new()));
+        let sink = selected.clone();
+        let control = Arc::new(
+            segmented_control(["Day", "Week", "Month"])
+                .on_select(move |i| sink.lock().unwrap().push(i)),
+        );
+        let view = TestView::new(control.clone(), Extent::new(300.0, 26.0));
+        let click = |x: f32| view.click_at(Point::new(x, 13.0));
+
+        click(250.0);
+        assert_eq!(control.selected(), 2);
+
+        // Clicking the selected segment again doesn't report a change
+        click(220.0);
+        click(150.0);
+        assert_eq!(control.selected(), 1);
+
+        // Keys move the selection but stop at the ends
+        view.key(KeyCode::Left, 0);
+        view.key(KeyCode::Left, 0);
+        assert_eq!(control.selected(), 0);
+
+        // Programmatic selection is silent
+        control.set_selected(2);
+        control.set_selected(5);
+        assert_eq!(control.selected(), 2);
+        assert!(view.render().is_some());
+
+        assert_eq!(*selected.lock().unwrap(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_click_inside_tile() {
+        use crate::element::tile::VTile;
+        use crate::element::size::fixed_size;
+
+        let control = Arc::new(segmented_control(["Day", "Week"]));
+        let tile = VTile::from_vec(vec![Arc::new(fixed_size(200.0, 30.0, crate::element::empty())), control.clone()]);
+        let view = TestView::new(Arc::new(tile), Extent::new(200.0, 60.0));
+        assert!(view.click_at(Point::new(150.0, 45.0)));
+        assert_eq!(control.selected(), 1);
+    }
+
+    #[test]
+    fn test_hover_highlights_segment() {
+        use crate::element::tile::VTile;
+        use crate::element::size::fixed_size;
+
+        let control = Arc::new(segmented_control(["Day", "Week"]));
+        let tile = VTile::from_vec(vec![Arc::new(fixed_size(200.0, 30.0, crate::element::empty())), control.clone()]);
+        let view = TestView::new(Arc::new(tile), Extent::new(200.0, 60.0));
+
+        assert!(view.hover(Point::new(150.0, 45.0)));
+        assert_eq!(*control.hovered.read().unwrap(), Some(1));
+        assert!(view.take_dirty().is_some());
+
+        // Moving off the control, or out of the view, clears it
+        assert!(view.hover(Point::new(150.0, 15.0)));
+        assert_eq!(*control.hovered.read().unwrap(), None);
+        assert!(!view.hover(Point::new(150.0, 15.0)));
+        view.hover(Point::new(50.0, 45.0));
+        view.leave(Point::new(50.0, 45.0));
+        assert_eq!(*control.hovered.read().unwrap(), None);
+    }
+}
@@ -3,8 +3,9 @@
 use std::any::Any;
 use super::{Element, ViewLimits, ViewStretch, FocusRequest};
 use super::context::{BasicContext, Context};
-use crate::support::point::Point;
-use crate::view::{MouseButton, KeyInfo, TextInfo, CursorTracking};
+use crate::support::point::{Axis, Point};
+use crate::support::rect::Rect;
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking};
 
 /// Fixed size element.
 pub struct FixedSize<S: Element> {
@@ -101,6 +102,18 @@ impl<S: Element + 'static> Element for FixedSize<S> {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -133,6 +146,37 @@ impl<S: Element + 'static> Element for FixedSize<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -164,7 +208,11 @@ impl<S: Element + 'static> Element for MinSize<S> {
                 e_limits.min.x.max(self.min_width),
                 e_limits.min.y.max(self.min_height),
             ),
-            max: e_limits.max,
+            // A subject smaller than the minimum may grow to it
+            max: Point::new(
+                e_limits.max.x.max(self.min_width),
+                e_limits.max.y.max(self.min_height),
+            ),
         }
     }
 
@@ -204,6 +252,18 @@ impl<S: Element + 'static> Element for MinSize<S> {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -232,6 +292,187 @@ impl<S: Element + 'static> Element for MinSize<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Element with a fixed size along one axis.
+///
+/// The other axis keeps the subject's own limits.
+pub struct AxisSize<S: Element> {
+    subject: S,
+    axis: Axis,
+    size: f32,
+}
+
+impl<S: Element> AxisSize<S> {
+    /// Creates a new element fixing the size along `axis`.
+    pub fn new(axis: Axis, size: f32, subject: S) -> Self {
+        Self { subject, axis, size }
+    }
+
+    /// Returns the axis the size is fixed along.
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// Returns the fixed size.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+}
+
+impl<S: Element + 'static> Element for AxisSize<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let mut limits = self.subject.limits(ctx);
+        limits.min[self.axis] = self.size;
+        limits.max[self.axis] = self.size;
+        limits
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.subject.draw(ctx);
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        self.subject.hit_test(ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, btn)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, btn);
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -259,7 +500,11 @@ impl<S: Element + 'static> Element for MaxSize<S> {
     fn limits(&self, ctx: &BasicContext) -> ViewLimits {
         let e_limits = self.subject.limits(ctx);
         ViewLimits {
-            min: e_limits.min,
+            // A subject larger than the maximum has to shrink to it
+            min: Point::new(
+                e_limits.min.x.min(self.max_width),
+                e_limits.min.y.min(self.max_height),
+            ),
             max: Point::new(
                 e_limits.max.x.min(self.max_width),
                 e_limits.max.y.min(self.max_height),
@@ -303,6 +548,18 @@ impl<S: Element + 'static> Element for MaxSize<S> {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -331,6 +588,37 @@ impl<S: Element + 'static> Element for MaxSize<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -409,6 +697,18 @@ impl<S: Element + 'static> Element for Stretch<S> {
         self.subject.handle_scroll(ctx, dir, p)
     }
 
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.subject.handle_magnify(ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        self.subject.handle_track_drop(ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.subject.handle_drop(ctx, info)
+    }
+
     fn is_enabled(&self) -> bool {
         self.subject.is_enabled()
     }
@@ -437,6 +737,262 @@ impl<S: Element + 'static> Element for Stretch<S> {
         self.subject.clear_focus();
     }
 
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.subject.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.subject.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, p, status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Keeps its subject at a fixed aspect ratio.
+///
+/// The subject gets the largest rect of the ratio that fits the bounds,
+/// centered in them; the rest of the bounds is left empty.
+pub struct AspectRatio<S: Element> {
+    subject: S,
+    ratio: f32,
+}
+
+impl<S: Element> AspectRatio<S> {
+    /// Creates a new aspect ratio element. `ratio` is width over height,
+    /// so 16:9 is `16.0 / 9.0`, and must be positive.
+    pub fn new(ratio: f32, subject: S) -> Self {
+        Self { subject, ratio }
+    }
+
+    /// Returns the aspect ratio, width over height.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Returns the bounds given to the subject.
+    fn adjust_bounds(&self, bounds: Rect) -> Rect {
+        let (mut width, mut height) = (bounds.width(), bounds.height());
+        if width > height * self.ratio {
+            width = height * self.ratio;
+        } else {
+            height = width / self.ratio;
+        }
+        let c = bounds.center();
+        Rect::new(c.x - width / 2.0, c.y - height / 2.0, c.x + width / 2.0, c.y + height / 2.0)
+    }
+}
+
+impl<S: Element + 'static> Element for AspectRatio<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let e_limits = self.subject.limits(ctx);
+        let ratio = self.ratio;
+        // The smallest rect of the ratio that fits the subject's minimum,
+        // and the largest that fits within its maximum
+        let min = Point::new(
+            e_limits.min.x.max(e_limits.min.y * ratio),
+            e_limits.min.y.max(e_limits.min.x / ratio),
+        );
+        let max = Point::new(
+            e_limits.max.x.min(e_limits.max.y * ratio).max(min.x),
+            e_limits.max.y.min(e_limits.max.x / ratio).max(min.y),
+        );
+        ViewLimits { min, max }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.draw(&adjusted_ctx);
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.layout(&adjusted_ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        // Let the subject determine if it handles the point
+        // This allows popups/dropdowns that extend beyond bounds to receive hits
+        self.subject.hit_test(&adjusted_ctx, p, leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.click(&adjusted_ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        // Presses in the empty bands beside the subject don't reach it;
+        // releases do, so a press that started on it can finish
+        if btn.down && self.subject.hit_test(&adjusted_ctx, btn.pos, false, false).is_none() {
+            return false;
+        }
+        self.subject.handle_click(&adjusted_ctx, btn)
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.drag(&adjusted_ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.handle_drag(&adjusted_ctx, btn);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.text(ctx, info)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.subject.scroll(ctx, dir, p)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.handle_scroll(&adjusted_ctx, dir, p)
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        let adjusted_bounds = self.adjust_bounds(ctx.bounds);
+        let adjusted_ctx = ctx.with_bounds(adjusted_bounds);
+        self.subject.handle_magnify(&adjusted_ctx, scale_delta, center)
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_track_drop(&adjusted_ctx, info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_drop(&adjusted_ctx, info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.bounds_of_element(&adjusted_ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.focus_bounds(&adjusted_ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let adjusted_ctx = ctx.with_bounds(self.adjust_bounds(ctx.bounds));
+        self.subject.handle_cursor(&adjusted_ctx, p, status)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -453,6 +1009,16 @@ pub fn fixed_size<S: Element>(width: f32, height: f32, subject: S) -> FixedSize<
     FixedSize::new(width, height, subject)
 }
 
+/// Creates an element with a fixed width, keeping the subject's height limits.
+pub fn hsize<S: Element>(width: f32, subject: S) -> AxisSize<S> {
+    AxisSize::new(Axis::X, width, subject)
+}
+
+/// Creates an element with a fixed height, keeping the subject's width limits.
+pub fn vsize<S: Element>(height: f32, subject: S) -> AxisSize<S> {
+    AxisSize::new(Axis::Y, height, subject)
+}
+
 /// Creates a minimum size element.
 pub fn min_size<S: Element>(min_width: f32, min_height: f32, subject: S) -> MinSize<S> {
     MinSize::new(min_width, min_height, subject)
@@ -463,6 +1029,11 @@ pub fn max_size<S: Element>(max_width: f32, max_height: f32, subject: S) -> MaxS
     MaxSize::new(max_width, max_height, subject)
 }
 
+/// Creates an element keeping its subject at `ratio`, width over height.
+pub fn aspect_ratio<S: Element>(ratio: f32, subject: S) -> AspectRatio<S> {
+    AspectRatio::new(ratio, subject)
+}
+
 /// Creates a horizontal stretch element.
 pub fn hstretch<S: Element>(factor: f32, subject: S) -> Stretch<S> {
     Stretch::new(factor, 1.0, subject)
@@ -482,3 +1053,62 @@ pub fn stretch<S: Element>(x: f32, y: f32, subject: S) -> Stretch<S> {
 pub fn no_stretch<S: Element>(subject: S) -> Stretch<S> {
     Stretch::new(0.0, 0.0, subject)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::element::{empty, share, FULL_EXTENT};
+    use crate::element::button::button;
+    use crate::support::point::Extent;
+    use crate::view::TestView;
+
+    fn limits_of(element: impl Element + 'static) -> ViewLimits {
+        TestView::new(share(element), Extent::new(100.0, 100.0)).limits()
+    }
+
+    #[test]
+    fn test_size_limits() {
+        let limits = |min: (f32, f32), max: (f32, f32)| {
+            ViewLimits::new(Point::new(min.0, min.1), Point::new(max.0, max.1))
+        };
+
+        assert_eq!(limits_of(fixed_size(40.0, 20.0, empty())), ViewLimits::fixed(40.0, 20.0));
+        assert_eq!(limits_of(hsize(40.0, empty())), limits((40.0, 0.0), (40.0, FULL_EXTENT)));
+        assert_eq!(limits_of(vsize(20.0, empty())), limits((0.0, 20.0), (FULL_EXTENT, 20.0)));
+        assert_eq!(
+            limits_of(hsize(40.0, fixed_size(10.0, 10.0, empty()))),
+            ViewLimits::fixed(40.0, 10.0),
+        );
+
+        assert_eq!(limits_of(min_size(40.0, 20.0, empty())), ViewLimits::min_size(40.0, 20.0));
+        assert_eq!(limits_of(max_size(40.0, 20.0, empty())), limits((0.0, 0.0), (40.0, 20.0)));
+
+        // Constraints the subject can't meet clamp both ends, so min never
+        // exceeds max
+        let small = fixed_size(10.0, 10.0, empty());
+        assert_eq!(limits_of(min_size(40.0, 5.0, small)), limits((40.0, 10.0), (40.0, 10.0)));
+        let large = fixed_size(60.0, 60.0, empty());
+        assert_eq!(limits_of(max_size(40.0, 80.0, large)), limits((40.0, 60.0), (40.0, 60.0)));
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let wide = aspect_ratio(2.0, min_size(40.0, 10.0, empty()));
+        assert_eq!(wide.adjust_bounds(Rect::new(0.0, 0.0, 100.0, 100.0)), Rect::new(0.0, 25.0, 100.0, 75.0));
+        assert_eq!(wide.adjust_bounds(Rect::new(10.0, 0.0, 110.0, 20.0)), Rect::new(40.0, 0.0, 80.0, 20.0));
+        let limits = limits_of(wide);
+        assert_eq!(limits.min, Point::new(40.0, 20.0));
+        assert_eq!(limits.max, Point::new(FULL_EXTENT, FULL_EXTENT / 2.0));
+
+        // Clicks reach the subject only inside the centered rect
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let sink = clicks.clone();
+        let ok = button("OK").on_click(move || { sink.fetch_add(1, Ordering::SeqCst); });
+        let view = TestView::new(share(aspect_ratio(1.0, ok)), Extent::new(200.0, 100.0));
+        view.click_at(Point::new(100.0, 50.0));
+        view.click_at(Point::new(20.0, 50.0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+    }
+}
@@ -4,10 +4,11 @@ use std::any::Any;
 use std::sync::RwLock;
 use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
+use crate::support::canvas::Canvas;
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
+use crate::support::theme::{get_theme, ThemeColor};
 use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
 
 /// Slider state.
@@ -31,6 +32,113 @@ pub enum SliderOrientation {
 /// Callback type for value changes.
 pub type ValueChangeCallback = Box<dyn Fn(f64) + Send + Sync>;
 
+/// Callback type for range changes, called with the low and high values.
+pub type RangeChangeCallback = Box<dyn Fn(f64, f64) + Send + Sync>;
+
+/// Clamps `value` to `[min, max]` and snaps it to the nearest `step`
+/// above `min`, if there is one.
+fn snap_value(value: f64, min: f64, max: f64, step: Option<f64>) -> f64 {
+    let clamped = value.clamp(min, max);
+    let stepped = if let Some(step) = step.filter(|&step| step > 0.0) {
+        let steps = ((clamped - min) / step).round();
+        min + steps * step
+    } else {
+        clamped
+    };
+    stepped.clamp(min, max)
+}
+
+/// Returns the point on the track at `normalized` (0.0 to 1.0).
+///
+/// The track runs between thumb centers at either end of `bounds`, left to
+/// right or bottom to top.
+fn track_point(orientation: SliderOrientation, bounds: &Rect, thumb_size: f32, normalized: f32) -> Point {
+    match orientation {
+        SliderOrientation::Horizontal => {
+            let track_start = bounds.left + thumb_size / 2.0;
+            let track_end = bounds.right - thumb_size / 2.0;
+            let x = track_start + normalized * (track_end - track_start);
+            Point::new(x, bounds.center().y)
+        }
+        SliderOrientation::Vertical => {
+            let track_start = bounds.bottom - thumb_size / 2.0;
+            let track_end = bounds.top + thumb_size / 2.0;
+            let y = track_start - normalized * (track_start - track_end);
+            Point::new(bounds.center().x, y)
+        }
+    }
+}
+
+/// Converts a point to a normalized track position.
+fn track_normalized(orientation: SliderOrientation, bounds: &Rect, thumb_size: f32, p: Point) -> f64 {
+    match orientation {
+        SliderOrientation::Horizontal => {
+            let track_start = bounds.left + thumb_size / 2.0;
+            let track_end = bounds.right - thumb_size / 2.0;
+            ((p.x - track_start) / (track_end - track_start)).clamp(0.0, 1.0) as f64
+        }
+        SliderOrientation::Vertical => {
+            let track_start = bounds.bottom - thumb_size / 2.0;
+            let track_end = bounds.top + thumb_size / 2.0;
+            ((track_start - p.y) / (track_start - track_end)).clamp(0.0, 1.0) as f64
+        }
+    }
+}
+
+/// Returns the rect of the track section between normalized positions
+/// `from` and `to`, `thickness` wide.
+fn track_section(
+    orientation: SliderOrientation,
+    bounds: &Rect,
+    thumb_size: f32,
+    thickness: f32,
+    from: f32,
+    to: f32,
+) -> Rect {
+    let start = track_point(orientation, bounds, thumb_size, from);
+    let end = track_point(orientation, bounds, thumb_size, to);
+    match orientation {
+        SliderOrientation::Horizontal => {
+            let top = bounds.center().y - thickness / 2.0;
+            Rect::new(start.x, top, end.x, top + thickness)
+        }
+        SliderOrientation::Vertical => {
+            let left = bounds.center().x - thickness / 2.0;
+            Rect::new(left, end.y, left + thickness, start.y)
+        }
+    }
+}
+
+/// Draws the track slot and its filled section.
+fn draw_slot(canvas: &mut Canvas, track: Rect, active: Rect, track_color: Color, active_color: Color) {
+    let radius = get_theme().slider_slot_corner_radius;
+
+    // Draw background track
+    canvas.fill_style(track_color);
+    canvas.fill_round_rect(track, radius);
+
+    // Draw active portion
+    if active.width() > 0.0 && active.height() > 0.0 {
+        canvas.fill_style(active_color);
+        canvas.fill_round_rect(active, radius);
+    }
+}
+
+/// Draws a round thumb at `pos`, shaded for `state`.
+fn draw_thumb_at(canvas: &mut Canvas, pos: Point, size: f32, color: Color, state: SliderState) {
+    let color = match state {
+        SliderState::Normal => color,
+        SliderState::Hover => color.level(1.2),
+        SliderState::Dragging => color.level(0.8),
+        SliderState::Disabled => color.with_alpha(0.5),
+    };
+
+    canvas.fill_style(color);
+    canvas.begin_path();
+    canvas.add_circle(crate::support::circle::Circle::new(pos, size / 2.0));
+    canvas.fill();
+}
+
 /// A basic slider element for selecting a value within a range.
 pub struct Slider {
     value: RwLock<f64>,
@@ -39,12 +147,14 @@ pub struct Slider {
     step: Option<f64>,
     orientation: SliderOrientation,
     state: RwLock<SliderState>,
-    track_color: Color,
-    thumb_color: Color,
-    active_color: Color,
+    track_color: ThemeColor,
+    thumb_color: ThemeColor,
+    active_color: ThemeColor,
     thumb_size: f32,
     track_height: f32,
     length: f32,
+    /// Value restored on double-click (defaults to the middle of the range)
+    default_value: Option<f64>,
     enabled: bool,
     on_change: Option<ValueChangeCallback>,
     drag_start_value: RwLock<f64>,
@@ -53,7 +163,6 @@ pub struct Slider {
 impl Slider {
     /// Creates a new horizontal slider with default range [0.0, 1.0].
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             value: RwLock::new(0.0),
             min_value: 0.0,
@@ -61,12 +170,13 @@ impl Slider {
             step: None,
             orientation: SliderOrientation::Horizontal,
             state: RwLock::new(SliderState::Normal),
-            track_color: theme.slider_slot_color,
-            thumb_color: theme.slider_thumb_color,
-            active_color: theme.indicator_bright_color,
+            track_color: ThemeColor::Theme(|theme| theme.slider_slot_color),
+            thumb_color: ThemeColor::Theme(|theme| theme.slider_thumb_color),
+            active_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
             thumb_size: 16.0,
             track_height: 4.0,
             length: 150.0,
+            default_value: None,
             enabled: true,
             on_change: None,
             drag_start_value: RwLock::new(0.0),
@@ -97,24 +207,26 @@ impl Slider {
     /// Sets the step increment (for discrete values).
     pub fn step(mut self, step: f64) -> Self {
         self.step = Some(step);
+        // Snap a value set before the step
+        self.set_value(self.get_value());
         self
     }
 
     /// Sets the track color.
     pub fn track_color(mut self, color: Color) -> Self {
-        self.track_color = color;
+        self.track_color = color.into();
         self
     }
 
     /// Sets the thumb color.
     pub fn thumb_color(mut self, color: Color) -> Self {
-        self.thumb_color = color;
+        self.thumb_color = color.into();
         self
     }
 
     /// Sets the active (filled) track color.
     pub fn active_color(mut self, color: Color) -> Self {
-        self.active_color = color;
+        self.active_color = color.into();
         self
     }
 
@@ -130,6 +242,12 @@ impl Slider {
         self
     }
 
+    /// Sets the value restored by a double-click.
+    pub fn default_value(mut self, value: f64) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+
     /// Sets the value change callback.
     pub fn on_change<F: Fn(f64) + Send + Sync + 'static>(mut self, callback: F) -> Self {
         self.on_change = Some(Box::new(callback));
@@ -143,14 +261,7 @@ impl Slider {
 
     /// Sets the current value.
     pub fn set_value(&self, value: f64) {
-        let clamped = value.clamp(self.min_value, self.max_value);
-        let stepped = if let Some(step) = self.step {
-            let steps = ((clamped - self.min_value) / step).round();
-            self.min_value + steps * step
-        } else {
-            clamped
-        };
-        *self.value.write().unwrap() = stepped.clamp(self.min_value, self.max_value);
+        *self.value.write().unwrap() = snap_value(value, self.min_value, self.max_value, self.step);
     }
 
     /// Returns the normalized value (0.0 to 1.0).
@@ -169,116 +280,55 @@ impl Slider {
         self.set_value(value);
     }
 
-    /// Returns the thumb position based on bounds.
-    fn thumb_position(&self, bounds: &Rect) -> Point {
-        let norm = self.normalized_value() as f32;
-        match self.orientation {
-            SliderOrientation::Horizontal => {
-                let track_start = bounds.left + self.thumb_size / 2.0;
-                let track_end = bounds.right - self.thumb_size / 2.0;
-                let x = track_start + norm * (track_end - track_start);
-                Point::new(x, bounds.center().y)
-            }
-            SliderOrientation::Vertical => {
-                let track_start = bounds.bottom - self.thumb_size / 2.0;
-                let track_end = bounds.top + self.thumb_size / 2.0;
-                let y = track_start - norm * (track_start - track_end);
-                Point::new(bounds.center().x, y)
+    /// Restores the default value, firing the change callback.
+    fn reset_to_default(&self) {
+        let default = self.default_value.unwrap_or((self.min_value + self.max_value) / 2.0);
+        self.set_value(default);
+        if let Some(ref callback) = self.on_change {
+            callback(self.get_value());
+        }
+    }
+
+    /// Moves the value to the track position under `p`, firing the change
+    /// callback if the (snapped) value changed.
+    fn set_value_at(&self, bounds: &Rect, p: Point) {
+        let previous = self.get_value();
+        let normalized = self.point_to_normalized(bounds, p);
+        self.set_normalized_value(normalized);
+
+        let value = self.get_value();
+        if value != previous {
+            if let Some(ref callback) = self.on_change {
+                callback(value);
             }
         }
     }
 
+    /// Returns the thumb position based on bounds.
+    fn thumb_position(&self, bounds: &Rect) -> Point {
+        track_point(self.orientation, bounds, self.thumb_size, self.normalized_value() as f32)
+    }
+
     /// Converts a point to a normalized value.
     fn point_to_normalized(&self, bounds: &Rect, p: Point) -> f64 {
-        match self.orientation {
-            SliderOrientation::Horizontal => {
-                let track_start = bounds.left + self.thumb_size / 2.0;
-                let track_end = bounds.right - self.thumb_size / 2.0;
-                ((p.x - track_start) / (track_end - track_start)).clamp(0.0, 1.0) as f64
-            }
-            SliderOrientation::Vertical => {
-                let track_start = bounds.bottom - self.thumb_size / 2.0;
-                let track_end = bounds.top + self.thumb_size / 2.0;
-                ((track_start - p.y) / (track_start - track_end)).clamp(0.0, 1.0) as f64
-            }
-        }
+        track_normalized(self.orientation, bounds, self.thumb_size, p)
     }
 
     fn draw_track(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
         let bounds = ctx.bounds;
-        let theme = get_theme();
-
-        let (track_rect, active_rect) = match self.orientation {
-            SliderOrientation::Horizontal => {
-                let track_top = bounds.center().y - self.track_height / 2.0;
-                let track_bottom = track_top + self.track_height;
-                let thumb_x = self.thumb_position(&bounds).x;
-
-                let track = Rect::new(
-                    bounds.left + self.thumb_size / 2.0,
-                    track_top,
-                    bounds.right - self.thumb_size / 2.0,
-                    track_bottom,
-                );
-                let active = Rect::new(
-                    bounds.left + self.thumb_size / 2.0,
-                    track_top,
-                    thumb_x,
-                    track_bottom,
-                );
-                (track, active)
-            }
-            SliderOrientation::Vertical => {
-                let track_left = bounds.center().x - self.track_height / 2.0;
-                let track_right = track_left + self.track_height;
-                let thumb_y = self.thumb_position(&bounds).y;
-
-                let track = Rect::new(
-                    track_left,
-                    bounds.top + self.thumb_size / 2.0,
-                    track_right,
-                    bounds.bottom - self.thumb_size / 2.0,
-                );
-                let active = Rect::new(
-                    track_left,
-                    thumb_y,
-                    track_right,
-                    bounds.bottom - self.thumb_size / 2.0,
-                );
-                (track, active)
-            }
-        };
-
-        // Draw background track
-        canvas.fill_style(self.track_color);
-        canvas.fill_round_rect(track_rect, theme.slider_slot_corner_radius);
+        let norm = self.normalized_value() as f32;
 
-        // Draw active portion
-        if active_rect.width() > 0.0 && active_rect.height() > 0.0 {
-            canvas.fill_style(self.active_color);
-            canvas.fill_round_rect(active_rect, theme.slider_slot_corner_radius);
-        }
+        let track = track_section(self.orientation, &bounds, self.thumb_size, self.track_height, 0.0, 1.0);
+        let active = track_section(self.orientation, &bounds, self.thumb_size, self.track_height, 0.0, norm);
+        draw_slot(&mut canvas, track, active, self.track_color.get(), self.active_color.get());
     }
 
     fn draw_thumb(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
         let state = *self.state.read().unwrap();
-
-        let color = match state {
-            SliderState::Normal => self.thumb_color,
-            SliderState::Hover => self.thumb_color.level(1.2),
-            SliderState::Dragging => self.thumb_color.level(0.8),
-            SliderState::Disabled => self.thumb_color.with_alpha(0.5),
-        };
-
         let thumb_pos = self.thumb_position(&ctx.bounds);
-        let thumb_radius = self.thumb_size / 2.0;
-
-        canvas.fill_style(color);
-        canvas.begin_path();
-        canvas.add_circle(crate::support::circle::Circle::new(thumb_pos, thumb_radius));
-        canvas.fill();
+        draw_thumb_at(&mut canvas, thumb_pos, self.thumb_size, self.thumb_color.get(), state);
     }
 }
 
@@ -334,12 +384,12 @@ impl Element for Slider {
             *state = SliderState::Dragging;
             *self.drag_start_value.write().unwrap() = self.get_value();
 
-            // Jump to click position
-            let normalized = self.point_to_normalized(&ctx.bounds, btn.pos);
             drop(state);
-            self.set_normalized_value(normalized);
-            if let Some(ref callback) = self.on_change {
-                callback(self.get_value());
+            if btn.click_count == 2 {
+                self.reset_to_default();
+            } else {
+                // Jump to click position
+                self.set_value_at(&ctx.bounds, btn.pos);
             }
         } else {
             *state = if ctx.bounds.contains(btn.pos) {
@@ -361,11 +411,7 @@ impl Element for Slider {
             return;
         }
 
-        let normalized = self.point_to_normalized(&ctx.bounds, btn.pos);
-        self.set_normalized_value(normalized);
-        if let Some(ref callback) = self.on_change {
-            callback(self.get_value());
-        }
+        self.set_value_at(&ctx.bounds, btn.pos);
     }
 
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
@@ -432,3 +478,427 @@ pub fn vslider() -> Slider {
 pub fn vslider_with_range(min: f64, max: f64) -> Slider {
     Slider::with_range(min, max).orientation(SliderOrientation::Vertical)
 }
+
+/// One of the two thumbs of a range slider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeThumb {
+    Low,
+    High,
+}
+
+/// A slider with two thumbs selecting a range within its bounds.
+///
+/// The thumbs can't cross: dragging one past the other stops it there.
+pub struct RangeSlider {
+    low: RwLock<f64>,
+    high: RwLock<f64>,
+    min_value: f64,
+    max_value: f64,
+    step: Option<f64>,
+    orientation: SliderOrientation,
+    state: RwLock<SliderState>,
+    /// Thumb being dragged
+    active_thumb: RwLock<Option<RangeThumb>>,
+    track_color: ThemeColor,
+    thumb_color: ThemeColor,
+    active_color: ThemeColor,
+    thumb_size: f32,
+    track_height: f32,
+    length: f32,
+    enabled: bool,
+    on_change: Option<RangeChangeCallback>,
+}
+
+impl RangeSlider {
+    /// Creates a new horizontal range slider spanning [0.0, 1.0].
+    pub fn new() -> Self {
+        Self {
+            low: RwLock::new(0.0),
+            high: RwLock::new(1.0),
+            min_value: 0.0,
+            max_value: 1.0,
+            step: None,
+            orientation: SliderOrientation::Horizontal,
+            state: RwLock::new(SliderState::Normal),
+            active_thumb: RwLock::new(None),
+            track_color: ThemeColor::Theme(|theme| theme.slider_slot_color),
+            thumb_color: ThemeColor::Theme(|theme| theme.slider_thumb_color),
+            active_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            thumb_size: 16.0,
+            track_height: 4.0,
+            length: 150.0,
+            enabled: true,
+            on_change: None,
+        }
+    }
+
+    /// Creates a range slider with the specified range, selecting all of it.
+    pub fn with_range(min: f64, max: f64) -> Self {
+        let mut slider = Self::new();
+        slider.min_value = min;
+        slider.max_value = max;
+        slider.set_values(min, max);
+        slider
+    }
+
+    /// Sets the orientation.
+    pub fn orientation(mut self, orientation: SliderOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets the initial low and high values.
+    pub fn values(self, low: f64, high: f64) -> Self {
+        self.set_values(low, high);
+        self
+    }
+
+    /// Sets the step increment (for discrete values).
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        // Snap values set before the step
+        self.set_values(self.low(), self.high());
+        self
+    }
+
+    /// Sets the track color.
+    pub fn track_color(mut self, color: Color) -> Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Sets the thumb color.
+    pub fn thumb_color(mut self, color: Color) -> Self {
+        self.thumb_color = color.into();
+        self
+    }
+
+    /// Sets the color of the selected range.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = color.into();
+        self
+    }
+
+    /// Sets the thumb size.
+    pub fn thumb_size(mut self, size: f32) -> Self {
+        self.thumb_size = size;
+        self
+    }
+
+    /// Sets the slider length.
+    pub fn length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Sets the range change callback.
+    pub fn on_change<F: Fn(f64, f64) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the low value.
+    pub fn low(&self) -> f64 {
+        *self.low.read().unwrap()
+    }
+
+    /// Returns the high value.
+    pub fn high(&self) -> f64 {
+        *self.high.read().unwrap()
+    }
+
+    /// Sets both values. They are swapped if given out of order.
+    pub fn set_values(&self, low: f64, high: f64) {
+        let (low, high) = if low <= high { (low, high) } else { (high, low) };
+        *self.low.write().unwrap() = snap_value(low, self.min_value, self.max_value, self.step);
+        *self.high.write().unwrap() = snap_value(high, self.min_value, self.max_value, self.step);
+    }
+
+    /// Sets the low value, stopping at the high value.
+    pub fn set_low(&self, value: f64) {
+        let high = self.high();
+        let snapped = snap_value(value, self.min_value, self.max_value, self.step);
+        *self.low.write().unwrap() = snapped.min(high);
+    }
+
+    /// Sets the high value, stopping at the low value.
+    pub fn set_high(&self, value: f64) {
+        let low = self.low();
+        let snapped = snap_value(value, self.min_value, self.max_value, self.step);
+        *self.high.write().unwrap() = snapped.max(low);
+    }
+
+    fn normalize(&self, value: f64) -> f32 {
+        if (self.max_value - self.min_value).abs() < f64::EPSILON {
+            0.0
+        } else {
+            ((value - self.min_value) / (self.max_value - self.min_value)) as f32
+        }
+    }
+
+    /// Returns the thumb nearer to `p`. When the thumbs overlap, the one
+    /// on the side `p` is on is picked, so they can be pulled apart.
+    fn thumb_near(&self, bounds: &Rect, p: Point) -> RangeThumb {
+        let at = track_normalized(self.orientation, bounds, self.thumb_size, p) as f32;
+        let low = self.normalize(self.low());
+        let high = self.normalize(self.high());
+        let to_low = (at - low).abs();
+        let to_high = (at - high).abs();
+        if to_low < to_high || (to_low == to_high && at < low) {
+            RangeThumb::Low
+        } else {
+            RangeThumb::High
+        }
+    }
+
+    /// Moves `thumb` to the track position under `p`, firing the change
+    /// callback if either value changed.
+    fn move_thumb(&self, thumb: RangeThumb, bounds: &Rect, p: Point) {
+        let previous = (self.low(), self.high());
+        let normalized = track_normalized(self.orientation, bounds, self.thumb_size, p);
+        let value = self.min_value + normalized * (self.max_value - self.min_value);
+        match thumb {
+            RangeThumb::Low => self.set_low(value),
+            RangeThumb::High => self.set_high(value),
+        }
+
+        let current = (self.low(), self.high());
+        if current != previous {
+            if let Some(ref callback) = self.on_change {
+                callback(current.0, current.1);
+            }
+        }
+    }
+}
+
+impl Default for RangeSlider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Element for RangeSlider {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        match self.orientation {
+            SliderOrientation::Horizontal => ViewLimits::fixed(self.length, self.thumb_size),
+            SliderOrientation::Vertical => ViewLimits::fixed(self.thumb_size, self.length),
+        }
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        match self.orientation {
+            SliderOrientation::Horizontal => ViewStretch::new(1.0, 0.0),
+            SliderOrientation::Vertical => ViewStretch::new(0.0, 1.0),
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let bounds = ctx.bounds;
+        let low = self.normalize(self.low());
+        let high = self.normalize(self.high());
+
+        let track = track_section(self.orientation, &bounds, self.thumb_size, self.track_height, 0.0, 1.0);
+        let active = track_section(self.orientation, &bounds, self.thumb_size, self.track_height, low, high);
+        draw_slot(&mut canvas, track, active, self.track_color.get(), self.active_color.get());
+
+        // Only the dragged thumb is drawn pressed
+        let state = *self.state.read().unwrap();
+        let active_thumb = *self.active_thumb.read().unwrap();
+        for (thumb, normalized) in [(RangeThumb::Low, low), (RangeThumb::High, high)] {
+            let thumb_state = match state {
+                SliderState::Dragging if active_thumb != Some(thumb) => SliderState::Hover,
+                state => state,
+            };
+            let pos = track_point(self.orientation, &bounds, self.thumb_size, normalized);
+            draw_thumb_at(&mut canvas, pos, self.thumb_size, self.thumb_color.get(), thumb_state);
+        }
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) && self.enabled {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn wants_control(&self) -> bool {
+        self.enabled
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if !self.enabled || btn.button != MouseButtonKind::Left {
+            return false;
+        }
+
+        if btn.down {
+            // Grab the nearer thumb and jump it to the click position
+            let thumb = self.thumb_near(&ctx.bounds, btn.pos);
+            *self.state.write().unwrap() = SliderState::Dragging;
+            *self.active_thumb.write().unwrap() = Some(thumb);
+            self.move_thumb(thumb, &ctx.bounds, btn.pos);
+        } else {
+            *self.active_thumb.write().unwrap() = None;
+            *self.state.write().unwrap() = if ctx.bounds.contains(btn.pos) {
+                SliderState::Hover
+            } else {
+                SliderState::Normal
+            };
+        }
+
+        true
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if !self.enabled {
+            return;
+        }
+
+        let thumb = *self.active_thumb.read().unwrap();
+        if let Some(thumb) = thumb {
+            self.move_thumb(thumb, &ctx.bounds, btn.pos);
+        }
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let mut state = self.state.write().unwrap();
+        if *state == SliderState::Dragging {
+            return true;
+        }
+
+        match status {
+            CursorTracking::Entering | CursorTracking::Hovering => {
+                *state = SliderState::Hover;
+            }
+            CursorTracking::Leaving => {
+                *state = SliderState::Normal;
+            }
+        }
+
+        true
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.enabled = state;
+        let mut slider_state = self.state.write().unwrap();
+        if !state {
+            *slider_state = SliderState::Disabled;
+        } else if *slider_state == SliderState::Disabled {
+            *slider_state = SliderState::Normal;
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a horizontal range slider.
+pub fn range_slider() -> RangeSlider {
+    RangeSlider::new()
+}
+
+/// Creates a horizontal range slider with range.
+pub fn range_slider_with_range(min: f64, max: f64) -> RangeSlider {
+    RangeSlider::with_range(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_step_snaps_click_and_drag() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let s = Arc::new(slider().step(0.25).on_change(move |v| sink.lock().unwrap().push(v)));
+        let view = TestView::new(s, Extent::new(116.0, 16.0));
+
+        view.press(Point::new(0.0, 8.0));
+        for x in 0..=116 {
+            view.drag_to(Point::new(x as f32, 8.0));
+        }
+        view.release(Point::new(116.0, 8.0));
+        view.click_at(Point::new(40.0, 8.0));
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(*reported, vec![0.25, 0.5, 0.75, 1.0, 0.25]);
+    }
+
+    #[test]
+    fn test_double_click_resets_to_default() {
+        let size = Extent::new(116.0, 16.0);
+        let p = Point::new(100.0, 8.0);
+
+        let s = Arc::new(slider_with_range(0.0, 10.0).value(2.0));
+        TestView::new(s.clone(), size).double_click_at(p);
+        assert_eq!(s.get_value(), 5.0);
+
+        let s = Arc::new(slider_with_range(0.0, 10.0).value(2.0).default_value(1.5));
+        TestView::new(s.clone(), size).double_click_at(p);
+        assert_eq!(s.get_value(), 1.5);
+    }
+
+    #[test]
+    fn test_range_thumbs_drag_without_crossing() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let s = Arc::new(
+            range_slider_with_range(0.0, 100.0)
+                .values(20.0, 80.0)
+                .step(10.0)
+                .on_change(move |low, high| sink.lock().unwrap().push((low, high))),
+        );
+
+        // The track runs from x = 8 to x = 108
+        let view = TestView::new(s.clone(), Extent::new(116.0, 16.0));
+        let at = |x: f32| Point::new(x, 8.0);
+
+        // Clicking nearer the high thumb moves it
+        view.press(at(98.0));
+        assert_eq!((s.low(), s.high()), (20.0, 90.0));
+        view.release(at(98.0));
+
+        // Dragging the low thumb past the high one stops it there
+        view.drag(at(30.0), at(112.0));
+        assert_eq!((s.low(), s.high()), (90.0, 90.0));
+
+        // Overlapping thumbs are pulled apart toward the side clicked
+        view.press(at(58.0));
+        assert_eq!((s.low(), s.high()), (50.0, 90.0));
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(*reported, vec![(20.0, 90.0), (90.0, 90.0), (50.0, 90.0)]);
+    }
+
+    #[test]
+    fn test_vertical_click_puts_top_at_max() {
+        let s = Arc::new(vslider());
+        let view = TestView::new(s.clone(), Extent::new(16.0, 116.0));
+
+        view.click_at(Point::new(8.0, 8.0));
+        assert_eq!(s.get_value(), 1.0);
+        view.click_at(Point::new(8.0, 83.0));
+        assert_eq!(s.get_value(), 0.25);
+    }
+}
@@ -0,0 +1,443 @@
+//! Splitter element with two resizable panes.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ElementPtr, ViewLimits, ViewStretch, FocusRequest, share};
+use super::context::{BasicContext, Context};
+use crate::support::color::Color;
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::theme::ThemeColor;
+use crate::view::{set_cursor, MouseButton, MouseButtonKind, KeyInfo, TextInfo, DropInfo, CursorTracking, CursorType};
+
+/// Splitter orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitOrientation {
+    /// Panes side by side, divided by a vertical gutter.
+    #[default]
+    Horizontal,
+    /// Panes stacked, divided by a horizontal gutter.
+    Vertical,
+}
+
+/// Callback type for split changes.
+pub type SplitChangeCallback = Box<dyn Fn(f32) + Send + Sync>;
+
+/// Two panes with a draggable divider between them.
+///
+/// The split is kept as the fraction of the space (excluding the gutter)
+/// given to the first pane, so the panes keep their proportions as the
+/// splitter is resized.
+pub struct Splitter {
+    first: ElementPtr,
+    second: ElementPtr,
+    orientation: SplitOrientation,
+    ratio: RwLock<f32>,
+    min_first: f32,
+    min_second: f32,
+    gutter: f32,
+    dragging: RwLock<bool>,
+    hovered: RwLock<bool>,
+    gutter_color: ThemeColor,
+    hilite_color: ThemeColor,
+    on_change: Option<SplitChangeCallback>,
+}
+
+impl Splitter {
+    /// Creates a splitter with the panes sharing the space equally.
+    pub fn new<A: Element + 'static, B: Element + 'static>(
+        orientation: SplitOrientation,
+        first: A,
+        second: B,
+    ) -> Self {
+        Self {
+            first: share(first),
+            second: share(second),
+            orientation,
+            ratio: RwLock::new(0.5),
+            min_first: 0.0,
+            min_second: 0.0,
+            gutter: 6.0,
+            dragging: RwLock::new(false),
+            hovered: RwLock::new(false),
+            gutter_color: ThemeColor::Theme(|theme| theme.frame_color),
+            hilite_color: ThemeColor::Theme(|theme| theme.frame_hilite_color),
+            on_change: None,
+        }
+    }
+
+    /// Sets the initial split, as the fraction given to the first pane.
+    pub fn ratio(self, ratio: f32) -> Self {
+        *self.ratio.write().unwrap() = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the minimum sizes of the two panes along the split axis.
+    pub fn min_sizes(mut self, first: f32, second: f32) -> Self {
+        self.min_first = first;
+        self.min_second = second;
+        self
+    }
+
+    /// Sets the gutter thickness.
+    pub fn gutter(mut self, thickness: f32) -> Self {
+        self.gutter = thickness;
+        self
+    }
+
+    /// Sets the gutter color.
+    pub fn gutter_color(mut self, color: Color) -> Self {
+        self.gutter_color = color.into();
+        self
+    }
+
+    /// Sets the callback called with the new ratio when the user drags the divider.
+    pub fn on_change<F: Fn(f32) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the fraction of the space given to the first pane.
+    pub fn get_ratio(&self) -> f32 {
+        *self.ratio.read().unwrap()
+    }
+
+    /// Sets the fraction of the space given to the first pane.
+    pub fn set_ratio(&self, ratio: f32) {
+        *self.ratio.write().unwrap() = ratio.clamp(0.0, 1.0);
+    }
+
+    /// Returns the start of `r` along the split axis, and its extent.
+    fn main_axis(&self, r: &Rect) -> (f32, f32) {
+        match self.orientation {
+            SplitOrientation::Horizontal => (r.left, r.width()),
+            SplitOrientation::Vertical => (r.top, r.height()),
+        }
+    }
+
+    /// Returns the size of the first pane, honoring the minimum sizes.
+    fn first_size(&self, bounds: &Rect) -> f32 {
+        let (_, extent) = self.main_axis(bounds);
+        let available = (extent - self.gutter).max(0.0);
+        let max = (available - self.min_second).max(self.min_first);
+        (available * self.get_ratio()).clamp(self.min_first, max).min(available)
+    }
+
+    /// Returns the bounds of the first pane, the gutter and the second pane.
+    fn split(&self, bounds: &Rect) -> (Rect, Rect, Rect) {
+        let (start, _) = self.main_axis(bounds);
+        let divider = start + self.first_size(bounds);
+        let r = bounds;
+        match self.orientation {
+            SplitOrientation::Horizontal => (
+                Rect::new(r.left, r.top, divider, r.bottom),
+                Rect::new(divider, r.top, divider + self.gutter, r.bottom),
+                Rect::new(divider + self.gutter, r.top, r.right, r.bottom),
+            ),
+            SplitOrientation::Vertical => (
+                Rect::new(r.left, r.top, r.right, divider),
+                Rect::new(r.left, divider, r.right, divider + self.gutter),
+                Rect::new(r.left, divider + self.gutter, r.right, r.bottom),
+            ),
+        }
+    }
+
+    /// Returns the panes with their contexts.
+    fn panes<'a>(&self, ctx: &'a Context) -> [(&ElementPtr, Context<'a>); 2] {
+        let (first, _, second) = self.split(&ctx.bounds);
+        [
+            (&self.first, ctx.with_bounds(first)),
+            (&self.second, ctx.with_bounds(second)),
+        ]
+    }
+
+    /// Moves the divider so its center follows `p`.
+    fn drag_divider(&self, ctx: &Context, p: Point) {
+        let (start, extent) = self.main_axis(&ctx.bounds);
+        let available = extent - self.gutter;
+        if available <= 0.0 {
+            return;
+        }
+        let pos = match self.orientation {
+            SplitOrientation::Horizontal => p.x,
+            SplitOrientation::Vertical => p.y,
+        };
+        let max = (available - self.min_second).max(self.min_first);
+        let size = (pos - start - self.gutter / 2.0).clamp(self.min_first, max).min(available);
+        let ratio = size / available;
+        if ratio != self.get_ratio() {
+            self.set_ratio(ratio);
+            ctx.view.refresh();
+            if let Some(ref callback) = self.on_change {
+                callback(ratio);
+            }
+        }
+    }
+
+    fn resize_cursor(&self) -> CursorType {
+        match self.orientation {
+            SplitOrientation::Horizontal => CursorType::HResize,
+            SplitOrientation::Vertical => CursorType::VResize,
+        }
+    }
+}
+
+impl Element for Splitter {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        let a = self.first.limits(ctx);
+        let b = self.second.limits(ctx);
+        match self.orientation {
+            SplitOrientation::Horizontal => ViewLimits::min_size(
+                a.min.x.max(self.min_first) + b.min.x.max(self.min_second) + self.gutter,
+                a.min.y.max(b.min.y),
+            ),
+            SplitOrientation::Vertical => ViewLimits::min_size(
+                a.min.x.max(b.min.x),
+                a.min.y.max(self.min_first) + b.min.y.max(self.min_second) + self.gutter,
+            ),
+        }
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(1.0, 1.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        for (pane, pane_ctx) in self.panes(ctx) {
+            pane.draw(&pane_ctx);
+        }
+
+        let (_, gutter, _) = self.split(&ctx.bounds);
+        let active = *self.dragging.read().unwrap() || *self.hovered.read().unwrap();
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.fill_style(if active { self.hilite_color.get() } else { self.gutter_color.get() });
+        let c = gutter.center();
+        let line = match self.orientation {
+            SplitOrientation::Horizontal => Rect::new(c.x - 0.5, gutter.top, c.x + 0.5, gutter.bottom),
+            SplitOrientation::Vertical => Rect::new(gutter.left, c.y - 0.5, gutter.right, c.y + 0.5),
+        };
+        canvas.fill_rect(if active { gutter } else { line });
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        if !ctx.bounds.contains(p) {
+            return None;
+        }
+        let (_, gutter, _) = self.split(&ctx.bounds);
+        if gutter.contains(p) {
+            return Some(self);
+        }
+        for (pane, pane_ctx) in self.panes(ctx) {
+            if let Some(hit) = pane.hit_test(&pane_ctx, p, leaf, control) {
+                return Some(hit);
+            }
+        }
+        if leaf { None } else { Some(self) }
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.panes(ctx).into_iter().find_map(|(pane, pane_ctx)| pane.bounds_of_element(&pane_ctx, element))
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.panes(ctx).into_iter().find_map(|(pane, pane_ctx)| pane.focus_bounds(&pane_ctx))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.panes(ctx).into_iter().fold(false, |handled, (pane, pane_ctx)| pane.handle_cursor(&pane_ctx, p, status) || handled)
+    }
+
+    fn cursor_type(&self, ctx: &Context, p: Point) -> Option<CursorType> {
+        let (_, gutter, _) = self.split(&ctx.bounds);
+        (*self.dragging.read().unwrap() || gutter.contains(p)).then(|| self.resize_cursor())
+    }
+
+    fn wants_control(&self) -> bool {
+        true
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if btn.button == MouseButtonKind::Left {
+            let mut dragging = self.dragging.write().unwrap();
+            if !btn.down && *dragging {
+                *dragging = false;
+                drop(dragging);
+                self.refresh(ctx, 0);
+                return true;
+            }
+            let (_, gutter, _) = self.split(&ctx.bounds);
+            if btn.down && gutter.contains(btn.pos) {
+                *dragging = true;
+                set_cursor(self.resize_cursor());
+                return true;
+            }
+        }
+
+        for (pane, pane_ctx) in self.panes(ctx) {
+            if pane.hit_test(&pane_ctx, btn.pos, false, false).is_some() && pane.handle_click(&pane_ctx, btn) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if *self.dragging.read().unwrap() {
+            self.drag_divider(ctx, btn.pos);
+            return;
+        }
+        for (pane, pane_ctx) in self.panes(ctx) {
+            if pane.hit_test(&pane_ctx, btn.pos, false, false).is_some() {
+                pane.handle_drag(&pane_ctx, btn);
+                return;
+            }
+        }
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.panes(ctx).into_iter().any(|(pane, pane_ctx)| pane.handle_key(&pane_ctx, k))
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.panes(ctx).into_iter().any(|(pane, pane_ctx)| pane.handle_text(&pane_ctx, info))
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.panes(ctx).into_iter().any(|(pane, pane_ctx)| {
+            pane_ctx.bounds.contains(p) && pane.handle_scroll(&pane_ctx, dir, p)
+        })
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.panes(ctx).into_iter().any(|(pane, pane_ctx)| {
+            pane_ctx.bounds.contains(center) && pane.handle_magnify(&pane_ctx, scale_delta, center)
+        })
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        for (pane, pane_ctx) in self.panes(ctx) {
+            pane.handle_track_drop(&pane_ctx, info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.panes(ctx).into_iter().any(|(pane, pane_ctx)| {
+            pane_ctx.bounds.contains(info.where_) && pane.handle_drop(&pane_ctx, info)
+        })
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let (_, gutter, _) = self.split(&ctx.bounds);
+        let over = status != CursorTracking::Leaving && gutter.contains(p);
+        let mut hovered = self.hovered.write().unwrap();
+        if *hovered != over {
+            *hovered = over;
+            set_cursor(if over { self.resize_cursor() } else { CursorType::Arrow });
+            drop(hovered);
+            self.refresh(ctx, 0);
+        }
+        over
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.first.wants_focus() || self.second.wants_focus()
+    }
+
+    fn clear_focus(&self) {
+        self.first.clear_focus();
+        self.second.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.first.has_focus() || self.second.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        super::begin_child_focus(&[self.first.as_ref(), self.second.as_ref()], req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        super::child_focus_next(&[self.first.as_ref(), self.second.as_ref()], forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        // Both panes get the tick
+        let first = self.first.handle_poll();
+        self.second.handle_poll() || first
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a splitter with side by side panes.
+pub fn hsplitter<A: Element + 'static, B: Element + 'static>(left: A, right: B) -> Splitter {
+    Splitter::new(SplitOrientation::Horizontal, left, right)
+}
+
+/// Creates a splitter with stacked panes.
+pub fn vsplitter<A: Element + 'static, B: Element + 'static>(top: A, bottom: B) -> Splitter {
+    Splitter::new(SplitOrientation::Vertical, top, bottom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::element::label::label;
+    use crate::element::share;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_dragging_gutter_resizes_panes() {
+        let splitter = Arc::new(hsplitter(label("Left"), label("Right")).min_sizes(50.0, 80.0));
+        let view = TestView::new(splitter.clone(), Extent::new(306.0, 100.0));
+        let bounds = Rect::new(0.0, 0.0, 306.0, 100.0);
+
+        // The gutter sits in the middle and hit-tests as the splitter
+        let (first, gutter, second) = splitter.split(&bounds);
+        assert_eq!((first.right, gutter.right, second.left), (150.0, 156.0, 156.0));
+        assert!(view.with_context(|ctx| splitter.hit_test(ctx, gutter.center(), true, true).is_some()));
+
+        let at = |x| Point::new(x, 50.0);
+        view.press(at(153.0));
+        view.drag_to(at(103.0));
+        assert_eq!(splitter.split(&bounds).0.right, 100.0);
+
+        // Dragging stops at the minimum sizes
+        view.drag_to(at(10.0));
+        assert_eq!(splitter.split(&bounds).0.right, 50.0);
+        view.drag_to(at(300.0));
+        assert_eq!(splitter.split(&bounds).2.left, 226.0);
+        view.release(at(300.0));
+
+        // Once released, drags no longer move the divider
+        view.drag_to(at(103.0));
+        assert_eq!(splitter.split(&bounds).2.left, 226.0);
+        assert!(view.render().is_some());
+    }
+
+    #[test]
+    fn test_cursor_follows_element_under_mouse() {
+        let splitter = hsplitter(crate::element::text_box::text_box(), label("Right"));
+        let view = TestView::new(share(splitter), Extent::new(306.0, 100.0));
+
+        let at = |x| view.cursor_at(Point::new(x, 50.0));
+        assert_eq!(at(20.0), CursorType::IBeam);
+        assert_eq!(at(153.0), CursorType::HResize);
+        assert_eq!(at(250.0), CursorType::Arrow);
+    }
+}
@@ -1,18 +1,25 @@
 //! Status bar element.
 
 use std::any::Any;
-use std::sync::RwLock;
+use std::fmt;
+use std::sync::{Arc, RwLock};
 use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
+use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, CursorType};
+
+/// Callback run when a status bar segment is clicked.
+pub type StatusClickCallback = Arc<dyn Fn() + Send + Sync>;
 
 /// A status bar segment.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct StatusSegment {
     pub text: String,
     pub flex: f32, // Relative width (0.0 for fixed width based on text)
+    pub on_click: Option<StatusClickCallback>,
 }
 
 impl StatusSegment {
@@ -20,6 +27,7 @@ impl StatusSegment {
         Self {
             text: text.into(),
             flex: 0.0,
+            on_click: None,
         }
     }
 
@@ -27,31 +35,60 @@ impl StatusSegment {
         Self {
             text: text.into(),
             flex,
+            on_click: None,
         }
     }
+
+    /// Makes the segment clickable, running `callback` when it is clicked.
+    pub fn on_click<F: Fn() + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_click = Some(Arc::new(callback));
+        self
+    }
+
+    /// Returns whether the segment reacts to clicks.
+    pub fn is_clickable(&self) -> bool {
+        self.on_click.is_some()
+    }
+}
+
+impl fmt::Debug for StatusSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatusSegment")
+            .field("text", &self.text)
+            .field("flex", &self.flex)
+            .field("clickable", &self.is_clickable())
+            .finish()
+    }
 }
 
 /// A status bar element typically shown at the bottom of a window.
 pub struct StatusBar {
     segments: RwLock<Vec<StatusSegment>>,
-    background_color: Color,
-    text_color: Color,
-    separator_color: Color,
+    background_color: ThemeColor,
+    text_color: ThemeColor,
+    separator_color: ThemeColor,
+    hover_color: ThemeColor,
     height: f32,
     padding: f32,
+    /// Clickable segment under the mouse
+    hovered: RwLock<Option<usize>>,
+    /// Clickable segment the mouse was pressed on
+    pressed: RwLock<Option<usize>>,
 }
 
 impl StatusBar {
     /// Creates a new status bar.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             segments: RwLock::new(Vec::new()),
-            background_color: theme.panel_color,
-            text_color: theme.label_font_color.with_alpha(0.8),
-            separator_color: theme.frame_color,
+            background_color: ThemeColor::Theme(|theme| theme.panel_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color.with_alpha(0.8)),
+            separator_color: ThemeColor::Theme(|theme| theme.frame_color),
+            hover_color: ThemeColor::Theme(|theme| theme.frame_hilite_color.with_alpha(0.3)),
             height: 24.0,
             padding: 8.0,
+            hovered: RwLock::new(None),
+            pressed: RwLock::new(None),
         }
     }
 
@@ -69,13 +106,13 @@ impl StatusBar {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
@@ -132,6 +169,27 @@ impl StatusBar {
 
         widths
     }
+
+    /// Returns the index of the clickable segment at `p`.
+    fn clickable_segment_at(&self, bounds: &Rect, p: Point) -> Option<usize> {
+        if !bounds.contains(p) {
+            return None;
+        }
+        let widths = self.calculate_segment_widths(bounds.width());
+        let mut right = bounds.left;
+        let index = widths.iter().position(|width| {
+            right += width;
+            p.x < right
+        })?;
+        self.segments.read().unwrap()[index].is_clickable().then_some(index)
+    }
+
+    /// Returns the rect of the segment at `index`.
+    fn segment_rect(&self, bounds: &Rect, index: usize) -> Rect {
+        let widths = self.calculate_segment_widths(bounds.width());
+        let left = bounds.left + widths[..index].iter().sum::<f32>();
+        Rect::new(left, bounds.top, left + widths[index], bounds.bottom)
+    }
 }
 
 impl Default for StatusBar {
@@ -157,17 +215,24 @@ impl Element for StatusBar {
         let theme = get_theme();
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_rect(ctx.bounds);
 
         // Top border
-        canvas.stroke_style(self.separator_color);
+        canvas.stroke_style(self.separator_color.get());
         canvas.line_width(1.0);
         canvas.begin_path();
         canvas.move_to(Point::new(ctx.bounds.left, ctx.bounds.top));
         canvas.line_to(Point::new(ctx.bounds.right, ctx.bounds.top));
         canvas.stroke();
 
+        // Hilite the clickable segment under the mouse
+        let hovered = *self.hovered.read().unwrap();
+        if let Some(index) = hovered.filter(|&i| i < self.segments.read().unwrap().len()) {
+            canvas.fill_style(self.hover_color.get());
+            canvas.fill_rect(self.segment_rect(&ctx.bounds, index).inset(0.0, 1.0));
+        }
+
         // Draw segments
         let segments = self.segments.read().unwrap();
         let widths = self.calculate_segment_widths(ctx.bounds.width());
@@ -177,7 +242,7 @@ impl Element for StatusBar {
             let width = widths.get(i).copied().unwrap_or(0.0);
 
             // Text
-            canvas.fill_style(self.text_color);
+            canvas.fill_style(self.text_color.get());
             canvas.font_size(theme.label_font_size * 0.9);
 
             let text_x = x + self.padding;
@@ -197,7 +262,7 @@ impl Element for StatusBar {
 
             // Separator (except for last segment)
             if i < segments.len() - 1 {
-                canvas.stroke_style(self.separator_color);
+                canvas.stroke_style(self.separator_color.get());
                 canvas.line_width(1.0);
                 canvas.begin_path();
                 canvas.move_to(Point::new(x, ctx.bounds.top + 4.0));
@@ -207,6 +272,62 @@ impl Element for StatusBar {
         }
     }
 
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn wants_control(&self) -> bool {
+        self.segments.read().unwrap().iter().any(StatusSegment::is_clickable)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if btn.button != MouseButtonKind::Left {
+            return false;
+        }
+        let index = self.clickable_segment_at(&ctx.bounds, btn.pos);
+        if btn.down {
+            *self.pressed.write().unwrap() = index;
+            return index.is_some();
+        }
+
+        // Click only if released over the segment it was pressed on
+        let pressed = self.pressed.write().unwrap().take();
+        if pressed.is_none() || pressed != index {
+            return pressed.is_some();
+        }
+        let callback = index.and_then(|i| self.segments.read().unwrap()[i].on_click.clone());
+        if let Some(callback) = callback {
+            callback();
+        }
+        true
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let hovered = match status {
+            CursorTracking::Leaving => None,
+            _ => self.clickable_segment_at(&ctx.bounds, p),
+        };
+        let mut current = self.hovered.write().unwrap();
+        if *current != hovered {
+            *current = hovered;
+            drop(current);
+            self.refresh(ctx, 0);
+        }
+        hovered.is_some()
+    }
+
+    fn cursor_type(&self, ctx: &Context, p: Point) -> Option<CursorType> {
+        self.clickable_segment_at(&ctx.bounds, p).map(|_| CursorType::Hand)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -225,3 +346,54 @@ pub fn status_bar() -> StatusBar {
 pub fn status_bar_with_text(text: impl Into<String>) -> StatusBar {
     StatusBar::new().text(text)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::element::share;
+    use crate::support::point::Extent;
+    use crate::view::TestView;
+
+    #[test]
+    fn test_segment_click() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let sink = clicks.clone();
+        let bar = status_bar().segments(vec![
+            StatusSegment::flex("Ready", 1.0),
+            StatusSegment::flex("Ln 12, Col 4", 1.0).on_click(move || { sink.fetch_add(1, Ordering::SeqCst); }),
+        ]);
+        let view = TestView::new(share(bar), Extent::new(200.0, 24.0));
+
+        assert!(!view.click_at(Point::new(50.0, 12.0)));
+        assert!(view.click_at(Point::new(150.0, 12.0)));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+
+        // Dragging off the segment before releasing cancels the click
+        view.drag(Point::new(150.0, 12.0), Point::new(50.0, 12.0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+
+        assert_eq!(view.cursor_at(Point::new(150.0, 12.0)), CursorType::Hand);
+        assert_eq!(view.cursor_at(Point::new(50.0, 12.0)), CursorType::Arrow);
+    }
+
+    #[test]
+    fn test_hover_highlights_clickable_segment() {
+        let bar = Arc::new(status_bar().segments(vec![
+            StatusSegment::flex("Ready", 1.0),
+            StatusSegment::flex("Ln 12, Col 4", 1.0).on_click(|| {}),
+        ]));
+        let view = TestView::new(bar.clone(), Extent::new(200.0, 24.0));
+
+        assert!(view.hover(Point::new(150.0, 12.0)));
+        assert_eq!(*bar.hovered.read().unwrap(), Some(1));
+        assert!(view.take_dirty().is_some());
+
+        // Plain segments don't light up
+        assert!(!view.hover(Point::new(50.0, 12.0)));
+        assert_eq!(*bar.hovered.read().unwrap(), None);
+        view.hover(Point::new(150.0, 12.0));
+        view.leave(Point::new(150.0, 12.0));
+        assert_eq!(*bar.hovered.read().unwrap(), None);
+    }
+}
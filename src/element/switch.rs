@@ -6,7 +6,7 @@ use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
+use crate::support::theme::ThemeColor;
 use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
 
 /// Switch state.
@@ -26,8 +26,8 @@ pub type SwitchCallback = Box<dyn Fn(bool) + Send + Sync>;
 pub struct SlideSwitch {
     on: RwLock<bool>,
     state: RwLock<SwitchState>,
-    track_on_color: Color,
-    track_off_color: Color,
+    track_on_color: ThemeColor,
+    track_off_color: ThemeColor,
     thumb_color: Color,
     width: f32,
     height: f32,
@@ -40,12 +40,11 @@ pub struct SlideSwitch {
 impl SlideSwitch {
     /// Creates a new slide switch.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             on: RwLock::new(false),
             state: RwLock::new(SwitchState::Normal),
-            track_on_color: theme.indicator_bright_color,
-            track_off_color: theme.frame_color,
+            track_on_color: ThemeColor::Theme(|theme| theme.indicator_bright_color),
+            track_off_color: ThemeColor::Theme(|theme| theme.frame_color),
             thumb_color: Color::new(1.0, 1.0, 1.0, 1.0),
             width: 44.0,
             height: 24.0,
@@ -64,13 +63,13 @@ impl SlideSwitch {
 
     /// Sets the track color when on.
     pub fn track_on_color(mut self, color: Color) -> Self {
-        self.track_on_color = color;
+        self.track_on_color = color.into();
         self
     }
 
     /// Sets the track color when off.
     pub fn track_off_color(mut self, color: Color) -> Self {
-        self.track_off_color = color;
+        self.track_off_color = color.into();
         self
     }
 
@@ -118,10 +117,10 @@ impl SlideSwitch {
 
         // Interpolate between off and on colors
         let track_color = Color::new(
-            self.track_off_color.red + (self.track_on_color.red - self.track_off_color.red) * progress,
-            self.track_off_color.green + (self.track_on_color.green - self.track_off_color.green) * progress,
-            self.track_off_color.blue + (self.track_on_color.blue - self.track_off_color.blue) * progress,
-            self.track_off_color.alpha + (self.track_on_color.alpha - self.track_off_color.alpha) * progress,
+            self.track_off_color.get().red + (self.track_on_color.get().red - self.track_off_color.get().red) * progress,
+            self.track_off_color.get().green + (self.track_on_color.get().green - self.track_off_color.get().green) * progress,
+            self.track_off_color.get().blue + (self.track_on_color.get().blue - self.track_off_color.get().blue) * progress,
+            self.track_off_color.get().alpha + (self.track_on_color.get().alpha - self.track_off_color.get().alpha) * progress,
         );
 
         let color = match state {
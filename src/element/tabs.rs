@@ -2,13 +2,14 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, share};
+use super::{Element, ElementPtr, FocusRequest, ViewLimits, ViewStretch, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
+use crate::support::canvas::Canvas;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, DropInfo, KeyInfo, TextInfo};
 
 /// Tab position.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -23,10 +24,17 @@ pub enum TabPosition {
 /// Callback type for tab changes.
 pub type TabChangeCallback = Box<dyn Fn(usize) + Send + Sync>;
 
+/// Size of the close button drawn on closeable tabs.
+const CLOSE_BUTTON_SIZE: f32 = 14.0;
+
+/// Width of the scroll buttons shown when the tabs don't fit the bar.
+const CHEVRON_WIDTH: f32 = 20.0;
+
 /// A single tab.
 pub struct Tab {
     label: String,
     content: Option<ElementPtr>,
+    closeable: bool,
 }
 
 impl Tab {
@@ -35,6 +43,7 @@ impl Tab {
         Self {
             label: label.into(),
             content: None,
+            closeable: false,
         }
     }
 
@@ -43,48 +52,70 @@ impl Tab {
         self.content = Some(share(content));
         self
     }
+
+    /// Sets whether the tab shows a close button.
+    pub fn closeable(mut self, closeable: bool) -> Self {
+        self.closeable = closeable;
+        self
+    }
 }
 
 /// A tabbed container element.
+///
+/// Tab indices, including those passed to callbacks, always refer to the
+/// position in the list given to [`TabBar::tabs`], so they stay stable as
+/// other tabs are closed.
 pub struct TabBar {
     tabs: Vec<Tab>,
+    closed: RwLock<Vec<bool>>,
     active_index: RwLock<usize>,
     hovered_index: RwLock<Option<usize>>,
+    close_hovered: RwLock<bool>,
+    /// How far the tab strip is scrolled when the tabs overflow the bar
+    strip_offset: RwLock<f32>,
+    /// Set when the active tab changes, so the next draw scrolls it into view
+    reveal_active: RwLock<bool>,
     position: TabPosition,
-    active_color: Color,
-    inactive_color: Color,
-    hover_color: Color,
-    text_color: Color,
-    background_color: Color,
+    active_color: ThemeColor,
+    inactive_color: ThemeColor,
+    hover_color: ThemeColor,
+    text_color: ThemeColor,
+    background_color: ThemeColor,
     tab_height: f32,
     tab_padding: f32,
     corner_radius: f32,
     on_change: Option<TabChangeCallback>,
+    on_close: Option<TabChangeCallback>,
 }
 
 impl TabBar {
     /// Creates a new tab bar.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             tabs: Vec::new(),
+            closed: RwLock::new(Vec::new()),
             active_index: RwLock::new(0),
             hovered_index: RwLock::new(None),
+            close_hovered: RwLock::new(false),
+            strip_offset: RwLock::new(0.0),
+            reveal_active: RwLock::new(true),
             position: TabPosition::Top,
-            active_color: theme.active_tab_color,
-            inactive_color: theme.inactive_tab_color,
-            hover_color: theme.tab_hilite_color,
-            text_color: theme.label_font_color,
-            background_color: theme.panel_color,
+            active_color: ThemeColor::Theme(|theme| theme.active_tab_color),
+            inactive_color: ThemeColor::Theme(|theme| theme.inactive_tab_color),
+            hover_color: ThemeColor::Theme(|theme| theme.tab_hilite_color),
+            text_color: ThemeColor::Theme(|theme| theme.label_font_color),
+            background_color: ThemeColor::Theme(|theme| theme.panel_color),
             tab_height: 32.0,
             tab_padding: 16.0,
             corner_radius: 4.0,
             on_change: None,
+            on_close: None,
         }
     }
 
     /// Adds tabs.
     pub fn tabs(mut self, tabs: Vec<Tab>) -> Self {
+        *self.closed.write().unwrap() = vec![false; tabs.len()];
         self.tabs = tabs;
         self
     }
@@ -97,13 +128,13 @@ impl TabBar {
 
     /// Sets the active color.
     pub fn active_color(mut self, color: Color) -> Self {
-        self.active_color = color;
+        self.active_color = color.into();
         self
     }
 
     /// Sets the inactive color.
     pub fn inactive_color(mut self, color: Color) -> Self {
-        self.inactive_color = color;
+        self.inactive_color = color.into();
         self
     }
 
@@ -113,6 +144,47 @@ impl TabBar {
         self
     }
 
+    /// Sets the callback fired when a tab is closed.
+    pub fn on_close<F: Fn(usize) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_close = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns true if the tab at `index` has been closed.
+    pub fn is_closed(&self, index: usize) -> bool {
+        self.closed.read().unwrap().get(index).copied().unwrap_or(true)
+    }
+
+    /// Closes the tab at `index`.
+    ///
+    /// If it was the active tab, the next open tab (or, failing that, the
+    /// previous one) becomes active.
+    pub fn close_tab(&self, index: usize) {
+        if self.is_closed(index) {
+            return;
+        }
+        self.closed.write().unwrap()[index] = true;
+        *self.hovered_index.write().unwrap() = None;
+
+        if let Some(ref callback) = self.on_close {
+            callback(index);
+        }
+
+        if index != self.get_active() {
+            return;
+        }
+        let neighbor = (index + 1..self.tabs.len())
+            .chain((0..index).rev())
+            .find(|&i| !self.is_closed(i));
+        if let Some(neighbor) = neighbor {
+            *self.active_index.write().unwrap() = neighbor;
+            *self.reveal_active.write().unwrap() = true;
+            if let Some(ref callback) = self.on_change {
+                callback(neighbor);
+            }
+        }
+    }
+
     /// Returns the active tab index.
     pub fn get_active(&self) -> usize {
         *self.active_index.read().unwrap()
@@ -120,8 +192,9 @@ impl TabBar {
 
     /// Sets the active tab index.
     pub fn set_active(&self, index: usize) {
-        if index < self.tabs.len() {
+        if !self.is_closed(index) {
             *self.active_index.write().unwrap() = index;
+            *self.reveal_active.write().unwrap() = true;
         }
     }
 
@@ -183,15 +256,92 @@ impl TabBar {
         }
     }
 
+    fn tab_width(&self, tab: &Tab) -> f32 {
+        let theme = get_theme();
+        let mut width = tab.label.len() as f32 * theme.label_font_size * 0.6 + self.tab_padding * 2.0;
+        if tab.closeable {
+            width += CLOSE_BUTTON_SIZE;
+        }
+        width
+    }
+
+    /// Returns the total width of the open tabs.
+    fn strip_width(&self) -> f32 {
+        self.tabs.iter().enumerate()
+            .filter(|&(i, _)| !self.is_closed(i))
+            .map(|(_, tab)| self.tab_width(tab))
+            .sum()
+    }
+
+    /// Returns true if horizontal tabs are too wide for the bar, in which
+    /// case the tab strip scrolls between two chevron buttons.
+    fn overflows(&self, ctx: &Context) -> bool {
+        if !matches!(self.position, TabPosition::Top | TabPosition::Bottom) {
+            return false;
+        }
+        self.strip_width() > self.tab_bar_rect(ctx).width()
+    }
+
+    /// Returns the part of the bar the tabs are drawn in.
+    fn strip_rect(&self, ctx: &Context) -> Rect {
+        let bar = self.tab_bar_rect(ctx);
+        if self.overflows(ctx) {
+            Rect::new(bar.left + CHEVRON_WIDTH, bar.top, bar.right - CHEVRON_WIDTH, bar.bottom)
+        } else {
+            bar
+        }
+    }
+
+    /// Returns the left and right chevron buttons, or empty rects if the
+    /// tabs fit the bar.
+    fn chevron_rects(&self, ctx: &Context) -> (Rect, Rect) {
+        if !self.overflows(ctx) {
+            return (Rect::zero(), Rect::zero());
+        }
+        let bar = self.tab_bar_rect(ctx);
+        (
+            Rect::new(bar.left, bar.top, bar.left + CHEVRON_WIDTH, bar.bottom),
+            Rect::new(bar.right - CHEVRON_WIDTH, bar.top, bar.right, bar.bottom),
+        )
+    }
+
+    fn max_strip_offset(&self, ctx: &Context) -> f32 {
+        if !self.overflows(ctx) {
+            return 0.0;
+        }
+        (self.strip_width() - self.strip_rect(ctx).width()).max(0.0)
+    }
+
+    /// Scrolls the tab strip by `delta` pixels.
+    fn scroll_strip(&self, ctx: &Context, delta: f32) {
+        let max = self.max_strip_offset(ctx);
+        let mut offset = self.strip_offset.write().unwrap();
+        *offset = (offset.min(max) + delta).clamp(0.0, max);
+    }
+
+    /// Scrolls the tab strip just enough to show the tab at `index`.
+    fn reveal_tab(&self, ctx: &Context, index: usize) {
+        let rect = self.tab_rect(ctx, index);
+        let strip = self.strip_rect(ctx);
+        if rect.left < strip.left {
+            self.scroll_strip(ctx, rect.left - strip.left);
+        } else if rect.right > strip.right {
+            self.scroll_strip(ctx, rect.right - strip.right);
+        }
+    }
+
     fn tab_rect(&self, ctx: &Context, index: usize) -> Rect {
         let bar = self.tab_bar_rect(ctx);
-        let theme = get_theme();
 
         match self.position {
             TabPosition::Top | TabPosition::Bottom => {
-                let mut x = bar.left;
+                let offset = self.strip_offset.read().unwrap().min(self.max_strip_offset(ctx));
+                let mut x = self.strip_rect(ctx).left - offset;
                 for (i, tab) in self.tabs.iter().enumerate() {
-                    let width = tab.label.len() as f32 * theme.label_font_size * 0.6 + self.tab_padding * 2.0;
+                    if self.is_closed(i) {
+                        continue;
+                    }
+                    let width = self.tab_width(tab);
                     if i == index {
                         return Rect::new(x, bar.top, x + width, bar.bottom);
                     }
@@ -201,6 +351,9 @@ impl TabBar {
             TabPosition::Left | TabPosition::Right => {
                 let mut y = bar.top;
                 for i in 0..self.tabs.len() {
+                    if self.is_closed(i) {
+                        continue;
+                    }
                     if i == index {
                         return Rect::new(bar.left, y, bar.right, y + self.tab_height);
                     }
@@ -212,19 +365,56 @@ impl TabBar {
         Rect::zero()
     }
 
+    /// Returns the close button of the tab at `index`, or an empty rect if
+    /// the tab is not closeable.
+    fn close_button_rect(&self, ctx: &Context, index: usize) -> Rect {
+        if !self.tabs.get(index).is_some_and(|tab| tab.closeable) {
+            return Rect::zero();
+        }
+        let rect = self.tab_rect(ctx, index);
+        let right = rect.right - self.tab_padding / 2.0;
+        let top = rect.center().y - CLOSE_BUTTON_SIZE / 2.0;
+        Rect::new(right - CLOSE_BUTTON_SIZE, top, right, top + CLOSE_BUTTON_SIZE)
+    }
+
+    fn draw_chevron(&self, canvas: &mut Canvas, rect: Rect, left: bool, enabled: bool) {
+        canvas.fill_style(self.background_color.get());
+        canvas.fill_rect(rect);
+
+        let c = rect.center();
+        let dx = if left { 3.0 } else { -3.0 };
+        let color = if enabled { self.text_color.get() } else { self.text_color.get().with_alpha(0.3) };
+        canvas.stroke_style(color);
+        canvas.line_width(1.5);
+        canvas.begin_path();
+        canvas.move_to(Point::new(c.x + dx, c.y - 5.0));
+        canvas.line_to(Point::new(c.x - dx, c.y));
+        canvas.line_to(Point::new(c.x + dx, c.y + 5.0));
+        canvas.stroke();
+    }
+
     fn draw_tabs(&self, ctx: &Context) {
+        let active = *self.active_index.read().unwrap();
+        if std::mem::take(&mut *self.reveal_active.write().unwrap()) {
+            self.reveal_tab(ctx, active);
+        }
+
         let mut canvas = ctx.canvas.borrow_mut();
         let theme = get_theme();
         let bar = self.tab_bar_rect(ctx);
-        let active = *self.active_index.read().unwrap();
         let hovered = *self.hovered_index.read().unwrap();
+        let close_hovered = *self.close_hovered.read().unwrap();
 
         // Tab bar background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_rect(bar);
 
         // Draw each tab
+        canvas.push_clip(self.strip_rect(ctx));
         for (i, tab) in self.tabs.iter().enumerate() {
+            if self.is_closed(i) {
+                continue;
+            }
             let rect = self.tab_rect(ctx, i);
 
             let is_active = i == active;
@@ -232,11 +422,11 @@ impl TabBar {
 
             // Tab background
             let bg_color = if is_active {
-                self.active_color
+                self.active_color.get()
             } else if is_hovered {
-                self.hover_color
+                self.hover_color.get()
             } else {
-                self.inactive_color
+                self.inactive_color.get()
             };
 
             let tab_rect = match self.position {
@@ -260,9 +450,9 @@ impl TabBar {
 
             // Tab text
             let text_color = if is_active {
-                self.text_color
+                self.text_color.get()
             } else {
-                self.text_color.with_alpha(0.7)
+                self.text_color.get().with_alpha(0.7)
             };
 
             canvas.fill_style(text_color);
@@ -271,12 +461,53 @@ impl TabBar {
             let x = rect.left + self.tab_padding;
             let y = rect.center().y + theme.label_font_size * 0.35;
             canvas.fill_text(&tab.label, Point::new(x, y));
+
+            // Close button
+            if tab.closeable {
+                let close = self.close_button_rect(ctx, i);
+                if hovered == Some(i) && close_hovered {
+                    canvas.fill_style(self.text_color.get().with_alpha(0.15));
+                    canvas.fill_round_rect(close, CLOSE_BUTTON_SIZE / 2.0);
+                }
+
+                let cross = close.inset(4.0, 4.0);
+                canvas.stroke_style(text_color);
+                canvas.line_width(1.5);
+                canvas.begin_path();
+                canvas.move_to(Point::new(cross.left, cross.top));
+                canvas.line_to(Point::new(cross.right, cross.bottom));
+                canvas.move_to(Point::new(cross.right, cross.top));
+                canvas.line_to(Point::new(cross.left, cross.bottom));
+                canvas.stroke();
+            }
+        }
+        canvas.pop_clip();
+
+        // Scroll buttons
+        if self.overflows(ctx) {
+            let (left, right) = self.chevron_rects(ctx);
+            let offset = *self.strip_offset.read().unwrap();
+            self.draw_chevron(&mut canvas, left, true, offset > 0.0);
+            self.draw_chevron(&mut canvas, right, false, offset < self.max_strip_offset(ctx));
         }
     }
 
-    fn draw_content(&self, ctx: &Context) {
+    /// Returns the active tab, unless every tab has been closed.
+    fn active_tab(&self) -> Option<&Tab> {
         let active = *self.active_index.read().unwrap();
-        if let Some(tab) = self.tabs.get(active) {
+        if self.is_closed(active) {
+            return None;
+        }
+        self.tabs.get(active)
+    }
+
+    /// Returns the content of the active tab, if it has any.
+    fn active_content(&self) -> Option<&ElementPtr> {
+        self.active_tab().and_then(|tab| tab.content.as_ref())
+    }
+
+    fn draw_content(&self, ctx: &Context) {
+        if let Some(tab) = self.active_tab() {
             if let Some(ref content) = tab.content {
                 let content_rect = self.content_rect(ctx);
                 let content_ctx = ctx.with_bounds(content_rect);
@@ -321,8 +552,7 @@ impl Element for TabBar {
         }
 
         // Check content
-        let active = *self.active_index.read().unwrap();
-        if let Some(tab) = self.tabs.get(active) {
+        if let Some(tab) = self.active_tab() {
             if let Some(ref content) = tab.content {
                 let content_rect = self.content_rect(ctx);
                 let content_ctx = ctx.with_bounds(content_rect);
@@ -348,8 +578,28 @@ impl Element for TabBar {
             return true;
         }
 
+        // Check the scroll buttons
+        let (left, right) = self.chevron_rects(ctx);
+        let step = self.strip_rect(ctx).width() / 2.0;
+        if left.contains(btn.pos) {
+            self.scroll_strip(ctx, -step);
+            return true;
+        }
+        if right.contains(btn.pos) {
+            self.scroll_strip(ctx, step);
+            return true;
+        }
+
         // Check if clicking on a tab
+        let strip = self.strip_rect(ctx);
         for i in 0..self.tabs.len() {
+            if self.is_closed(i) || !strip.contains(btn.pos) {
+                continue;
+            }
+            if self.close_button_rect(ctx, i).contains(btn.pos) {
+                self.close_tab(i);
+                return true;
+            }
             let rect = self.tab_rect(ctx, i);
             if rect.contains(btn.pos) {
                 let old_active = *self.active_index.read().unwrap();
@@ -359,13 +609,13 @@ impl Element for TabBar {
                         callback(i);
                     }
                 }
+                self.reveal_tab(ctx, i);
                 return true;
             }
         }
 
         // Forward to content
-        let active = *self.active_index.read().unwrap();
-        if let Some(tab) = self.tabs.get(active) {
+        if let Some(tab) = self.active_tab() {
             if let Some(ref content) = tab.content {
                 let content_rect = self.content_rect(ctx);
                 let content_ctx = ctx.with_bounds(content_rect);
@@ -378,26 +628,138 @@ impl Element for TabBar {
         true
     }
 
-    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
-        match status {
-            CursorTracking::Leaving => {
-                *self.hovered_index.write().unwrap() = None;
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        // Scrolling over the bar moves the tab strip
+        if self.tab_bar_rect(ctx).contains(p) {
+            if !self.overflows(ctx) {
+                return false;
             }
-            _ => {
-                let mut hovered = self.hovered_index.write().unwrap();
-                *hovered = None;
+            self.scroll_strip(ctx, -(dir.x + dir.y) * 20.0);
+            return true;
+        }
 
-                for i in 0..self.tabs.len() {
-                    let rect = self.tab_rect(ctx, i);
-                    if rect.contains(p) {
-                        *hovered = Some(i);
-                        break;
-                    }
-                }
+        if let Some(tab) = self.active_tab() {
+            if let Some(ref content) = tab.content {
+                let content_ctx = ctx.with_bounds(self.content_rect(ctx));
+                return content.handle_scroll(&content_ctx, dir, p);
             }
         }
+        false
+    }
 
-        true
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        let content_rect = self.content_rect(ctx);
+        if !content_rect.contains(center) {
+            return false;
+        }
+        if let Some(tab) = self.active_tab() {
+            if let Some(ref content) = tab.content {
+                return content.handle_magnify(&ctx.with_bounds(content_rect), scale_delta, center);
+            }
+        }
+        false
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        if let Some(content) = self.active_tab().and_then(|tab| tab.content.as_ref()) {
+            content.handle_track_drop(&ctx.with_bounds(self.content_rect(ctx)), info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        let content_ctx = ctx.with_bounds(self.content_rect(ctx));
+        match self.active_tab().and_then(|tab| tab.content.as_ref()) {
+            Some(content) if content_ctx.bounds.contains(info.where_) => content.handle_drop(&content_ctx, info),
+            _ => false,
+        }
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.active_content().is_some_and(|content| content.handle_key(&ctx.with_bounds(self.content_rect(ctx)), k))
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.active_content().is_some_and(|content| content.handle_text(&ctx.with_bounds(self.content_rect(ctx)), info))
+    }
+
+    // Only the active tab's content takes part in tabbing
+    fn wants_focus(&self) -> bool {
+        self.active_content().is_some_and(|content| content.wants_focus())
+    }
+
+    fn clear_focus(&self) {
+        for content in self.tabs.iter().filter_map(|tab| tab.content.as_ref()) {
+            content.clear_focus();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.active_content().is_some_and(|content| content.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.active_content().is_some_and(|content| content.handle_begin_focus(req))
+    }
+
+    fn handle_end_focus(&self) {
+        if let Some(content) = self.active_content() {
+            content.handle_end_focus();
+        }
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.active_content().is_some_and(|content| content.handle_focus_next(forward))
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.active_content().is_some_and(|content| content.handle_poll())
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        let content = self.active_content()?;
+        content.bounds_of_element(&ctx.with_bounds(self.content_rect(ctx)), element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        let content = self.active_content()?;
+        content.focus_bounds(&ctx.with_bounds(self.content_rect(ctx)))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let strip = self.strip_rect(ctx);
+        let (hovered, close_hovered) = match status {
+            CursorTracking::Leaving => (None, false),
+            _ => {
+                let hovered = (0..self.tabs.len())
+                    .filter(|&i| !self.is_closed(i) && strip.contains(p))
+                    .find(|&i| self.tab_rect(ctx, i).contains(p));
+                (hovered, hovered.is_some_and(|i| self.close_button_rect(ctx, i).contains(p)))
+            }
+        };
+
+        let changed = {
+            let mut current = self.hovered_index.write().unwrap();
+            let mut close = self.close_hovered.write().unwrap();
+            let changed = *current != hovered || *close != close_hovered;
+            *current = hovered;
+            *close = close_hovered;
+            changed
+        };
+        if changed {
+            ctx.view.refresh_area(strip);
+        }
+
+        let in_content = self.active_content().is_some_and(|content| {
+            content.handle_cursor(&ctx.with_bounds(self.content_rect(ctx)), p, status)
+        });
+        in_content || hovered.is_some()
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -418,3 +780,109 @@ pub fn tab_bar() -> TabBar {
 pub fn tab(label: impl Into<String>) -> Tab {
     Tab::new(label)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_close_active_tab_selects_neighbor() {
+        let closed = Arc::new(Mutex::new(Vec::new()));
+        let changed = Arc::new(Mutex::new(Vec::new()));
+        let (c, ch) = (closed.clone(), changed.clone());
+        let bar = tab_bar()
+            .tabs(vec![tab("A").closeable(true), tab("B").closeable(true), tab("C").closeable(true)])
+            .on_close(move |i| c.lock().unwrap().push(i))
+            .on_change(move |i| ch.lock().unwrap().push(i));
+
+        // Closing an inactive tab keeps the selection
+        bar.set_active(1);
+        bar.close_tab(0);
+        assert_eq!(bar.get_active(), 1);
+
+        // Closing the active tab selects the next one, then the previous one
+        bar.close_tab(1);
+        assert_eq!(bar.get_active(), 2);
+        bar.close_tab(2);
+        assert!(bar.is_closed(2));
+        assert!(bar.active_tab().is_none());
+
+        assert_eq!(*closed.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(*changed.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_overflowing_tabs_scroll() {
+        use crate::support::point::Extent;
+        use crate::view::testing::TestView;
+
+        let bar = Arc::new(tab_bar().tabs((0..10).map(|i| tab(format!("Document {i}"))).collect()));
+        let view = TestView::new(bar.clone(), Extent::new(300.0, 200.0));
+        assert!(view.with_context(|ctx| bar.overflows(ctx)));
+
+        // Selecting the last tab scrolls it into view on the next draw
+        bar.set_active(9);
+        assert!(view.render().is_some());
+        let strip = view.with_context(|ctx| bar.strip_rect(ctx));
+        let tab_rect = |i| view.with_context(|ctx| bar.tab_rect(ctx, i));
+        assert_eq!(tab_rect(9).right, strip.right);
+
+        // The left chevron scrolls back towards the first tab
+        let (left, _) = view.with_context(|ctx| bar.chevron_rects(ctx));
+        let before = tab_rect(0).left;
+        view.click_at(left.center());
+        assert_eq!(tab_rect(0).left, before + strip.width() / 2.0);
+        assert_eq!(bar.get_active(), 9);
+    }
+
+    #[test]
+    fn test_hover_tracks_tabs_and_close_buttons() {
+        use crate::support::point::Extent;
+        use crate::view::testing::TestView;
+
+        let bar = Arc::new(tab_bar().tabs(vec![tab("A").closeable(true), tab("B").closeable(true)]));
+        let view = TestView::new(bar.clone(), Extent::new(300.0, 200.0));
+        let (tab_b, close_b) = view.with_context(|ctx| (bar.tab_rect(ctx, 1), bar.close_button_rect(ctx, 1)));
+
+        view.hover(Point::new(tab_b.left + 2.0, tab_b.center().y));
+        assert_eq!(*bar.hovered_index.read().unwrap(), Some(1));
+        assert!(!*bar.close_hovered.read().unwrap());
+
+        view.hover(close_b.center());
+        assert!(*bar.close_hovered.read().unwrap());
+        assert!(view.take_dirty().is_some());
+
+        // Off the strip nothing is hovered
+        view.hover(Point::new(150.0, 150.0));
+        assert_eq!(*bar.hovered_index.read().unwrap(), None);
+        view.hover(close_b.center());
+        view.leave(Point::new(400.0, 10.0));
+        assert_eq!(*bar.hovered_index.read().unwrap(), None);
+        assert!(!*bar.close_hovered.read().unwrap());
+    }
+
+    #[test]
+    fn test_tab_reaches_active_content_only() {
+        use crate::support::point::Extent;
+        use crate::view::testing::TestView;
+        use crate::view::{KeyCode, modifiers};
+        use crate::element::text_box::text_box;
+        use crate::element::tile::VTile;
+
+        let field = Arc::new(text_box());
+        let bar = Arc::new(tab_bar().tabs(vec![tab("A").content(text_box()), tab("B")]));
+        let view = TestView::new(Arc::new(VTile::from_vec(vec![field.clone(), bar.clone()])), Extent::new(300.0, 200.0));
+        assert!(view.focus_first());
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(bar.has_focus() && !field.has_focus());
+        assert!(view.type_text("x"));
+        assert!(view.key(KeyCode::Tab, modifiers::SHIFT));
+        assert!(field.has_focus() && !bar.has_focus());
+
+        // A tab with nothing to focus is skipped
+        bar.set_active(1);
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(field.has_focus() && !bar.has_focus());
+    }
+}
@@ -0,0 +1,896 @@
+//! Multi-line text input element.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
+use super::context::{BasicContext, Context};
+use super::text_box::{byte_index, char_index_at, TextBoxState, TextChangeCallback};
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::support::color::Color;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, TextInfo, CursorTracking, CursorType, KeyCode};
+
+/// A caret position in a text area: a line index and a character column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct TextPosition {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl TextPosition {
+    /// Creates a new text position.
+    pub const fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+/// A multi-line text input element.
+///
+/// Text is stored as lines and broken only at `\n`. Enter inserts a new
+/// line; the content scrolls vertically when it is taller than the area.
+pub struct TextArea {
+    lines: RwLock<Vec<String>>,
+    placeholder: String,
+    state: RwLock<TextBoxState>,
+    cursor_pos: RwLock<TextPosition>,
+    selection_start: RwLock<Option<TextPosition>>,
+    scroll_offset: RwLock<f32>,
+    background_color: ThemeColor,
+    text_color: ThemeColor,
+    placeholder_color: ThemeColor,
+    highlight_color: ThemeColor,
+    caret_color: ThemeColor,
+    font_size: f32,
+    line_height: f32,
+    width: f32,
+    height: f32,
+    padding: f32,
+    corner_radius: f32,
+    enabled: bool,
+    on_change: Option<TextChangeCallback>,
+}
+
+impl TextArea {
+    /// Creates a new text area.
+    pub fn new() -> Self {
+        let theme = get_theme();
+        Self {
+            lines: RwLock::new(vec![String::new()]),
+            placeholder: String::new(),
+            state: RwLock::new(TextBoxState::Idle),
+            cursor_pos: RwLock::new(TextPosition::default()),
+            selection_start: RwLock::new(None),
+            scroll_offset: RwLock::new(0.0),
+            background_color: ThemeColor::Theme(|theme| theme.input_box_color),
+            text_color: ThemeColor::Theme(|theme| theme.text_box_font_color),
+            placeholder_color: ThemeColor::Theme(|theme| theme.text_box_idle_color),
+            highlight_color: ThemeColor::Theme(|theme| theme.text_box_hilite_color),
+            caret_color: ThemeColor::Theme(|theme| theme.text_box_caret_color),
+            font_size: theme.text_box_font_size,
+            line_height: theme.text_box_font_size * 1.4,
+            width: 200.0,
+            height: 120.0,
+            padding: 8.0,
+            corner_radius: 4.0,
+            enabled: true,
+            on_change: None,
+        }
+    }
+
+    /// Sets the initial text.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// Sets the placeholder text.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the dimensions.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the background color.
+    pub fn background_color(mut self, color: Color) -> Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the text color.
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Sets the change callback.
+    pub fn on_change<F: Fn(&str) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the current text, with lines joined by `\n`.
+    pub fn get_text(&self) -> String {
+        self.lines.read().unwrap().join("\n")
+    }
+
+    /// Sets the text and moves the caret to the end.
+    pub fn set_text(&self, text: impl Into<String>) {
+        let text: String = text.into();
+        let lines: Vec<String> = text.split('\n').map(String::from).collect();
+        let end = TextPosition::new(lines.len() - 1, lines[lines.len() - 1].chars().count());
+        *self.lines.write().unwrap() = lines;
+        *self.cursor_pos.write().unwrap() = end;
+        *self.selection_start.write().unwrap() = None;
+    }
+
+    /// Returns the caret position.
+    pub fn cursor_position(&self) -> TextPosition {
+        *self.cursor_pos.read().unwrap()
+    }
+
+    /// Returns the selected text, or `None` if nothing is selected.
+    fn selected_text(&self) -> Option<String> {
+        let lines = self.lines.read().unwrap();
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        let sel_start = (*self.selection_start.read().unwrap())?;
+        if sel_start == cursor_pos {
+            return None;
+        }
+
+        let start = sel_start.min(cursor_pos);
+        let end = sel_start.max(cursor_pos);
+        let mut selected = String::new();
+        for line in start.line..=end.line {
+            let text = &lines[line];
+            let from = if line == start.line { byte_index(text, start.col) } else { 0 };
+            let to = if line == end.line { byte_index(text, end.col) } else { text.len() };
+            if line > start.line {
+                selected.push('\n');
+            }
+            selected.push_str(&text[from..to]);
+        }
+        Some(selected)
+    }
+
+    /// Removes the selected text, if any, and moves the caret to its start.
+    /// Returns true if something was selected.
+    fn delete_selection(&self, lines: &mut Vec<String>) -> bool {
+        let mut cursor_pos = self.cursor_pos.write().unwrap();
+        let Some(sel_start) = self.selection_start.write().unwrap().take() else {
+            return false;
+        };
+        if sel_start == *cursor_pos {
+            return false;
+        }
+
+        let start = sel_start.min(*cursor_pos);
+        let end = sel_start.max(*cursor_pos);
+
+        let tail = lines[end.line][byte_index(&lines[end.line], end.col)..].to_string();
+        let first = &mut lines[start.line];
+        first.truncate(byte_index(first, start.col));
+        first.push_str(&tail);
+        lines.drain(start.line + 1..=end.line);
+
+        *cursor_pos = start;
+        true
+    }
+
+    /// Inserts text at the caret, replacing the selection. `\n` starts a new line.
+    fn insert_text(&self, s: &str) {
+        let mut lines = self.lines.write().unwrap();
+        self.delete_selection(&mut lines);
+
+        let mut cursor_pos = self.cursor_pos.write().unwrap();
+        let line = &mut lines[cursor_pos.line];
+        let tail = line.split_off(byte_index(line, cursor_pos.col));
+
+        let mut pieces = s.split('\n');
+        if let Some(first) = pieces.next() {
+            line.push_str(first);
+            cursor_pos.col += first.chars().count();
+        }
+        for piece in pieces {
+            cursor_pos.line += 1;
+            cursor_pos.col = piece.chars().count();
+            lines.insert(cursor_pos.line, piece.to_string());
+        }
+        lines[cursor_pos.line].push_str(&tail);
+    }
+
+    /// Deletes the character before the caret, joining lines at a line start.
+    fn delete_backward(&self) {
+        let mut lines = self.lines.write().unwrap();
+        if self.delete_selection(&mut lines) {
+            return;
+        }
+
+        let mut cursor_pos = self.cursor_pos.write().unwrap();
+        if cursor_pos.col > 0 {
+            let line = &mut lines[cursor_pos.line];
+            let start = byte_index(line, cursor_pos.col - 1);
+            let end = byte_index(line, cursor_pos.col);
+            line.replace_range(start..end, "");
+            cursor_pos.col -= 1;
+        } else if cursor_pos.line > 0 {
+            let line = lines.remove(cursor_pos.line);
+            cursor_pos.line -= 1;
+            cursor_pos.col = lines[cursor_pos.line].chars().count();
+            lines[cursor_pos.line].push_str(&line);
+        }
+    }
+
+    /// Deletes the character after the caret, joining lines at a line end.
+    fn delete_forward(&self) {
+        let mut lines = self.lines.write().unwrap();
+        if self.delete_selection(&mut lines) {
+            return;
+        }
+
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        let line_len = lines[cursor_pos.line].chars().count();
+        if cursor_pos.col < line_len {
+            let line = &mut lines[cursor_pos.line];
+            let start = byte_index(line, cursor_pos.col);
+            let end = byte_index(line, cursor_pos.col + 1);
+            line.replace_range(start..end, "");
+        } else if cursor_pos.line + 1 < lines.len() {
+            let next = lines.remove(cursor_pos.line + 1);
+            lines[cursor_pos.line].push_str(&next);
+        }
+    }
+
+    /// Moves the caret to `pos`, extending the selection if `select` is set.
+    fn move_to(&self, pos: TextPosition, select: bool) {
+        let mut cursor_pos = self.cursor_pos.write().unwrap();
+        let mut selection_start = self.selection_start.write().unwrap();
+
+        // Back at the anchor nothing is selected
+        let anchor = selection_start.unwrap_or(*cursor_pos);
+        *selection_start = (select && anchor != pos).then_some(anchor);
+        *cursor_pos = pos;
+    }
+
+    /// Returns the caret position after a key movement.
+    fn moved_position(&self, key: KeyCode) -> TextPosition {
+        let lines = self.lines.read().unwrap();
+        let pos = *self.cursor_pos.read().unwrap();
+        let line_len = |line: usize| lines[line].chars().count();
+
+        match key {
+            KeyCode::Left if pos.col > 0 => TextPosition::new(pos.line, pos.col - 1),
+            KeyCode::Left if pos.line > 0 => TextPosition::new(pos.line - 1, line_len(pos.line - 1)),
+            KeyCode::Right if pos.col < line_len(pos.line) => TextPosition::new(pos.line, pos.col + 1),
+            KeyCode::Right if pos.line + 1 < lines.len() => TextPosition::new(pos.line + 1, 0),
+            KeyCode::Up if pos.line > 0 => {
+                TextPosition::new(pos.line - 1, pos.col.min(line_len(pos.line - 1)))
+            }
+            KeyCode::Up => TextPosition::new(0, 0),
+            KeyCode::Down if pos.line + 1 < lines.len() => {
+                TextPosition::new(pos.line + 1, pos.col.min(line_len(pos.line + 1)))
+            }
+            KeyCode::Down => TextPosition::new(pos.line, line_len(pos.line)),
+            KeyCode::Home => TextPosition::new(pos.line, 0),
+            KeyCode::End => TextPosition::new(pos.line, line_len(pos.line)),
+            _ => pos,
+        }
+    }
+
+    /// Selects all text.
+    fn select_all(&self) {
+        let lines = self.lines.read().unwrap();
+        let last = lines.len() - 1;
+        *self.selection_start.write().unwrap() = Some(TextPosition::new(0, 0));
+        *self.cursor_pos.write().unwrap() = TextPosition::new(last, lines[last].chars().count());
+    }
+
+    /// Returns the area inside the padding, where text is drawn.
+    fn text_area(&self, ctx: &Context) -> Rect {
+        ctx.bounds.inset(self.padding, self.padding)
+    }
+
+    /// Returns the largest scroll offset for the given bounds.
+    fn max_scroll(&self, ctx: &Context) -> f32 {
+        let content_height = self.lines.read().unwrap().len() as f32 * self.line_height;
+        (content_height - self.text_area(ctx).height()).max(0.0)
+    }
+
+    /// Scrolls so that the caret line is fully visible.
+    fn scroll_to_caret(&self, ctx: &Context) {
+        let visible_height = self.text_area(ctx).height();
+        let caret_top = self.cursor_pos.read().unwrap().line as f32 * self.line_height;
+        let mut scroll = self.scroll_offset.write().unwrap();
+
+        if caret_top < *scroll {
+            *scroll = caret_top;
+        } else if caret_top + self.line_height > *scroll + visible_height {
+            *scroll = caret_top + self.line_height - visible_height;
+        }
+        *scroll = scroll.clamp(0.0, self.max_scroll(ctx));
+    }
+
+    /// Returns the top of a line's box on screen.
+    fn line_top(&self, ctx: &Context, line: usize) -> f32 {
+        self.text_area(ctx).top + line as f32 * self.line_height - *self.scroll_offset.read().unwrap()
+    }
+
+    /// Maps a point to the nearest caret position.
+    fn position_at(&self, ctx: &Context, p: Point) -> TextPosition {
+        let lines = self.lines.read().unwrap();
+        let area = self.text_area(ctx);
+        let scroll = *self.scroll_offset.read().unwrap();
+
+        let rel_y = p.y - area.top + scroll;
+        let line = ((rel_y / self.line_height).max(0.0) as usize).min(lines.len() - 1);
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.font_size(self.font_size);
+        let col = char_index_at(&canvas, &lines[line], p.x - area.left);
+        canvas.restore();
+
+        TextPosition::new(line, col)
+    }
+
+    fn notify_change(&self) {
+        if let Some(ref callback) = self.on_change {
+            callback(&self.get_text());
+        }
+    }
+
+    fn draw_background(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let state = *self.state.read().unwrap();
+
+        let color = match state {
+            TextBoxState::Idle => self.background_color.get(),
+            TextBoxState::Hover => self.background_color.get().level(1.1),
+            TextBoxState::Focused => self.background_color.get().level(1.2),
+            TextBoxState::Disabled => self.background_color.get().with_alpha(0.5),
+        };
+
+        canvas.fill_style(color);
+        canvas.fill_round_rect(ctx.bounds, self.corner_radius);
+
+        // Draw focus border
+        if state == TextBoxState::Focused {
+            let theme = get_theme();
+            canvas.stroke_style(theme.frame_hilite_color);
+            canvas.line_width(1.0);
+            canvas.begin_path();
+            canvas.add_round_rect(ctx.bounds, self.corner_radius);
+            canvas.stroke();
+        }
+    }
+
+    fn draw_selection(&self, ctx: &Context) {
+        let Some(sel_start) = *self.selection_start.read().unwrap() else {
+            return;
+        };
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        if sel_start == cursor_pos {
+            return;
+        }
+
+        let start = sel_start.min(cursor_pos);
+        let end = sel_start.max(cursor_pos);
+        let lines = self.lines.read().unwrap();
+        let area = self.text_area(ctx);
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.font_size(self.font_size);
+        canvas.fill_style(self.highlight_color.get());
+
+        for line in start.line..=end.line {
+            let text = &lines[line];
+            let from = if line == start.line { start.col } else { 0 };
+            let x1 = area.left + canvas.text_width_to_position(text, from);
+            let x2 = if line == end.line {
+                area.left + canvas.text_width_to_position(text, end.col)
+            } else {
+                // Show the selected line break as a little extra width
+                area.left + canvas.text_width(text) + self.font_size * 0.3
+            };
+            let top = self.line_top(ctx, line);
+            canvas.fill_rect(Rect::new(x1, top, x2, top + self.line_height));
+        }
+    }
+
+    fn draw_text(&self, ctx: &Context) {
+        let lines = self.lines.read().unwrap();
+        let state = *self.state.read().unwrap();
+        let area = self.text_area(ctx);
+        let baseline = (self.line_height + self.font_size * 0.7) / 2.0;
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.font_size(self.font_size);
+
+        if lines.len() == 1 && lines[0].is_empty() && !self.placeholder.is_empty() {
+            let color = if state == TextBoxState::Disabled {
+                self.placeholder_color.get().with_alpha(0.3)
+            } else {
+                self.placeholder_color.get()
+            };
+            canvas.fill_style(color);
+            canvas.fill_text(&self.placeholder, Point::new(area.left, area.top + baseline));
+            return;
+        }
+
+        let color = if state == TextBoxState::Disabled {
+            self.text_color.get().with_alpha(0.5)
+        } else {
+            self.text_color.get()
+        };
+        canvas.fill_style(color);
+
+        for (i, line) in lines.iter().enumerate() {
+            let top = self.line_top(ctx, i);
+            // Skip lines scrolled out of view
+            if top + self.line_height < area.top || top > area.bottom {
+                continue;
+            }
+            canvas.fill_text(line, Point::new(area.left, top + baseline));
+        }
+    }
+
+    fn draw_caret(&self, ctx: &Context) {
+        let state = *self.state.read().unwrap();
+        if state != TextBoxState::Focused {
+            return;
+        }
+
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        let lines = self.lines.read().unwrap();
+        let area = self.text_area(ctx);
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.font_size(self.font_size);
+        let x = area.left + canvas.text_width_to_position(&lines[cursor_pos.line], cursor_pos.col);
+        let top = self.line_top(ctx, cursor_pos.line);
+
+        canvas.stroke_style(self.caret_color.get());
+        canvas.line_width(1.5);
+        canvas.begin_path();
+        canvas.move_to(Point::new(x, top + 2.0));
+        canvas.line_to(Point::new(x, top + self.line_height - 2.0));
+        canvas.stroke();
+    }
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Element for TextArea {
+    fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+        ViewLimits::fixed(self.width, self.height)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        ViewStretch::new(1.0, 1.0)
+    }
+
+    fn draw(&self, ctx: &Context) {
+        self.draw_background(ctx);
+
+        // Keep scrolled lines inside the padding
+        let area = self.text_area(ctx);
+        ctx.canvas.borrow_mut().push_clip(area);
+        self.draw_selection(ctx);
+        self.draw_text(ctx);
+        self.draw_caret(ctx);
+        ctx.canvas.borrow_mut().pop_clip();
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, _leaf: bool, _control: bool) -> Option<&dyn Element> {
+        if ctx.bounds.contains(p) && self.enabled {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn cursor_type(&self, _ctx: &Context, _p: Point) -> Option<CursorType> {
+        Some(CursorType::IBeam)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.enabled
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.enabled
+    }
+
+    fn begin_focus(&mut self, _req: FocusRequest) {
+        *self.state.write().unwrap() = TextBoxState::Focused;
+    }
+
+    fn end_focus(&mut self) -> bool {
+        *self.state.write().unwrap() = TextBoxState::Idle;
+        true
+    }
+
+    fn clear_focus(&self) {
+        let mut state = self.state.write().unwrap();
+        if *state == TextBoxState::Focused {
+            *state = TextBoxState::Idle;
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        *self.state.read().unwrap() == TextBoxState::Focused
+    }
+
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        *self.state.write().unwrap() = TextBoxState::Focused;
+        true
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        if !self.enabled || btn.button != MouseButtonKind::Left {
+            return false;
+        }
+
+        if btn.down {
+            *self.state.write().unwrap() = TextBoxState::Focused;
+            let pos = self.position_at(ctx, btn.pos);
+            self.move_to(pos, false);
+        }
+
+        true
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if !self.enabled || btn.button != MouseButtonKind::Left {
+            return;
+        }
+
+        let pos = self.position_at(ctx, btn.pos);
+        self.move_to(pos, true);
+        self.scroll_to_caret(ctx);
+    }
+
+    fn key(&mut self, ctx: &Context, k: KeyInfo) -> bool {
+        self.handle_key(ctx, k)
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let state = *self.state.read().unwrap();
+        if state != TextBoxState::Focused {
+            return false;
+        }
+
+        if k.action != crate::view::KeyAction::Press && k.action != crate::view::KeyAction::Repeat {
+            return true;
+        }
+
+        let shift = k.modifiers & crate::view::modifiers::SHIFT != 0;
+        let ctrl = k.modifiers & (crate::view::modifiers::CONTROL | crate::view::modifiers::SUPER) != 0;
+
+        match k.key {
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down
+            | KeyCode::Home | KeyCode::End => {
+                let pos = self.moved_position(k.key);
+                self.move_to(pos, shift);
+            }
+            KeyCode::Backspace => {
+                self.delete_backward();
+                self.notify_change();
+            }
+            KeyCode::Delete => {
+                self.delete_forward();
+                self.notify_change();
+            }
+            KeyCode::Enter => {
+                self.insert_text("\n");
+                self.notify_change();
+            }
+            KeyCode::A if ctrl => {
+                self.select_all();
+            }
+            KeyCode::C if ctrl => {
+                if let Some(selected) = self.selected_text() {
+                    crate::view::set_clipboard(&selected);
+                }
+            }
+            KeyCode::X if ctrl => {
+                if let Some(selected) = self.selected_text() {
+                    crate::view::set_clipboard(&selected);
+                    self.delete_backward();
+                    self.notify_change();
+                }
+            }
+            KeyCode::V if ctrl => {
+                let pasted = crate::view::clipboard().replace("\r\n", "\n").replace('\r', "\n");
+                if !pasted.is_empty() {
+                    self.insert_text(&pasted);
+                    self.notify_change();
+                }
+            }
+            _ => return false,
+        }
+
+        self.scroll_to_caret(ctx);
+        true
+    }
+
+    fn text(&mut self, ctx: &Context, info: TextInfo) -> bool {
+        self.handle_text(ctx, info)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let state = *self.state.read().unwrap();
+        if state != TextBoxState::Focused {
+            return false;
+        }
+
+        // Filter control characters; Enter arrives as a key event
+        let c = info.codepoint;
+        if !c.is_control() {
+            self.insert_text(&c.to_string());
+            self.notify_change();
+            self.scroll_to_caret(ctx);
+        }
+
+        true
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, _p: Point) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let max_scroll = self.max_scroll(ctx);
+        if max_scroll <= 0.0 {
+            return false;
+        }
+
+        let mut scroll = self.scroll_offset.write().unwrap();
+        *scroll = (*scroll - dir.y * 20.0).clamp(0.0, max_scroll);
+        true
+    }
+
+    fn cursor(&mut self, _ctx: &Context, _p: Point, status: CursorTracking) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let mut state = self.state.write().unwrap();
+        if *state == TextBoxState::Focused {
+            return true;
+        }
+
+        match status {
+            CursorTracking::Entering | CursorTracking::Hovering => {
+                *state = TextBoxState::Hover;
+            }
+            CursorTracking::Leaving => {
+                *state = TextBoxState::Idle;
+            }
+        }
+
+        true
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.enabled = state;
+        let mut area_state = self.state.write().unwrap();
+        if !state {
+            *area_state = TextBoxState::Disabled;
+        } else if *area_state == TextBoxState::Disabled {
+            *area_state = TextBoxState::Idle;
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Creates a text area.
+pub fn text_area() -> TextArea {
+    TextArea::new()
+}
+
+/// Creates a text area with initial text.
+pub fn text_area_with_text(text: impl Into<String>) -> TextArea {
+    TextArea::new().text(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::modifiers::{CONTROL, SHIFT};
+    use crate::view::testing::TestView;
+
+    fn focused(text: &str) -> (Arc<TextArea>, TestView) {
+        let area = Arc::new(text_area_with_text(text));
+        let view = TestView::new(area.clone(), Extent::new(200.0, 120.0));
+        assert!(view.focus_first());
+        (area, view)
+    }
+
+    fn move_caret(area: &TextArea, line: usize, col: usize) {
+        area.move_to(TextPosition::new(line, col), false);
+    }
+
+    #[test]
+    fn test_enter_splits_and_deletes_join_lines() {
+        let (area, view) = focused("hello world");
+
+        move_caret(&area, 0, 5);
+        assert!(view.key(KeyCode::Enter, 0));
+        assert_eq!(*area.lines.read().unwrap(), ["hello", " world"]);
+        assert_eq!(area.cursor_position(), TextPosition::new(1, 0));
+
+        // Backspace at the start of a line joins it to the one above
+        assert!(view.key(KeyCode::Backspace, 0));
+        assert_eq!(area.get_text(), "hello world");
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 5));
+
+        // Delete at the end of a line pulls up the next one
+        area.set_text("one\ntwo");
+        move_caret(&area, 0, 3);
+        assert!(view.key(KeyCode::Delete, 0));
+        assert_eq!(area.get_text(), "onetwo");
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 3));
+
+        // Neither joins past the ends of the text
+        move_caret(&area, 0, 0);
+        assert!(view.key(KeyCode::Backspace, 0));
+        move_caret(&area, 0, 6);
+        assert!(view.key(KeyCode::Delete, 0));
+        assert_eq!(area.get_text(), "onetwo");
+    }
+
+    #[test]
+    fn test_selection_across_lines() {
+        let (area, view) = focused("one\ntwo\nthree");
+
+        // Shift-selecting down takes in the line break
+        move_caret(&area, 0, 1);
+        assert!(view.key(KeyCode::Down, SHIFT));
+        assert!(view.key(KeyCode::Down, SHIFT));
+        assert!(view.key(KeyCode::Right, SHIFT));
+        assert_eq!(area.selected_text().as_deref(), Some("ne\ntwo\nth"));
+
+        assert!(area.delete_selection(&mut area.lines.write().unwrap()));
+        assert_eq!(area.get_text(), "oree");
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 1));
+        assert!(!area.delete_selection(&mut area.lines.write().unwrap()));
+
+        // Typing replaces a selection made backwards, too
+        area.set_text("one\ntwo\nthree");
+        move_caret(&area, 2, 2);
+        assert!(view.key(KeyCode::Up, SHIFT));
+        assert!(view.type_text("X"));
+        assert_eq!(area.get_text(), "one\ntwXree");
+    }
+
+    #[test]
+    fn test_drag_back_to_anchor_selects_nothing() {
+        let (area, view) = focused("hello");
+        let point = |col: usize| view.with_context(|ctx| {
+            let mut canvas = ctx.canvas.borrow_mut();
+            canvas.font_size(area.font_size);
+            let x = area.text_area(ctx).left + canvas.text_width_to_position("hello", col);
+            Point::new(x, area.line_top(ctx, 0) + area.line_height / 2.0)
+        });
+
+        view.press(point(3));
+        view.drag_to(point(1));
+        view.drag_to(point(3));
+        view.release(point(3));
+        assert_eq!(*area.selection_start.read().unwrap(), None);
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 3));
+
+        // So Backspace and Delete still edit around the caret
+        assert!(view.key(KeyCode::Backspace, 0));
+        assert!(view.key(KeyCode::Delete, 0));
+        assert_eq!(area.get_text(), "heo");
+
+        // An empty selection left behind deletes nothing by itself
+        *area.selection_start.write().unwrap() = Some(area.cursor_position());
+        assert!(!area.delete_selection(&mut area.lines.write().unwrap()));
+    }
+
+    #[test]
+    fn test_up_and_down_clamp_column() {
+        let (area, view) = focused("a long line\nab\nanother line");
+
+        move_caret(&area, 0, 7);
+        assert!(view.key(KeyCode::Down, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(1, 2));
+        assert!(view.key(KeyCode::Down, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(2, 2));
+
+        // Past the last line the caret goes to its end, and before the first to the start
+        assert!(view.key(KeyCode::Down, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(2, 12));
+        assert!(view.key(KeyCode::Up, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(1, 2));
+        assert!(view.key(KeyCode::Up, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 2));
+        assert!(view.key(KeyCode::Up, 0));
+        assert_eq!(area.cursor_position(), TextPosition::new(0, 0));
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "macos", ignore = "uses the system clipboard")]
+    fn test_paste_normalizes_line_breaks() {
+        let (area, view) = focused("[]");
+
+        crate::view::set_clipboard("a\r\nb\rc\nd");
+        move_caret(&area, 0, 1);
+        assert!(view.key(KeyCode::V, CONTROL));
+        assert_eq!(*area.lines.read().unwrap(), ["[a", "b", "c", "d]"]);
+        assert_eq!(area.cursor_position(), TextPosition::new(3, 1));
+    }
+
+    #[test]
+    fn test_caret_movement_scrolls_into_view() {
+        let text = (0..20).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n");
+        let (area, view) = focused(&text);
+        let scroll = || *area.scroll_offset.read().unwrap();
+
+        // Moving to the last line scrolls it to the bottom of the text area
+        move_caret(&area, 0, 0);
+        for _ in 0..19 {
+            assert!(view.key(KeyCode::Down, 0));
+        }
+        view.with_context(|ctx| {
+            assert_eq!(scroll(), area.max_scroll(ctx));
+            let bottom = area.line_top(ctx, 19) + area.line_height;
+            assert!((bottom - area.text_area(ctx).bottom).abs() < 0.01);
+        });
+
+        // Lines already in view don't scroll
+        assert!(view.key(KeyCode::Up, 0));
+        view.with_context(|ctx| assert_eq!(scroll(), area.max_scroll(ctx)));
+
+        // Back at the top it scrolls all the way up
+        move_caret(&area, 1, 0);
+        assert!(view.key(KeyCode::Up, 0));
+        assert_eq!(scroll(), 0.0);
+        view.with_context(|ctx| assert_eq!(area.line_top(ctx, 0), area.text_area(ctx).top));
+    }
+}
@@ -2,13 +2,15 @@
 
 use std::any::Any;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use super::{Element, ViewLimits, ViewStretch, FocusRequest};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, KeyInfo, TextInfo, CursorTracking, KeyCode};
+use crate::support::canvas::Canvas;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{MouseButton, MouseButtonKind, KeyInfo, TextInfo, CursorTracking, CursorType, KeyCode};
 
 /// Text box state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -20,10 +22,64 @@ pub enum TextBoxState {
     Disabled,
 }
 
+/// How long the caret stays on, and then off, while blinking.
+const CARET_BLINK_PERIOD: Duration = Duration::from_millis(530);
+
 /// Callback type for text changes.
 pub type TextChangeCallback = Box<dyn Fn(&str) + Send + Sync>;
 /// Callback type for enter key.
 pub type EnterCallback = Box<dyn Fn(&str) + Send + Sync>;
+/// Predicate deciding which typed or pasted characters a text box accepts.
+pub type CharFilter = Box<dyn Fn(char) -> bool + Send + Sync>;
+
+/// Returns the byte offset of the character at `char_pos`, or the text
+/// length if `char_pos` is past the end.
+pub(crate) fn byte_index(text: &str, char_pos: usize) -> usize {
+    text.char_indices().nth(char_pos).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/// Returns the caret position (in characters) nearest to `x`, measured from
+/// the start of `text` with the canvas's current font.
+pub(crate) fn char_index_at(canvas: &Canvas, text: &str, x: f32) -> usize {
+    let mut prev_width = 0.0;
+    for (index, (byte, c)) in text.char_indices().enumerate() {
+        let width = canvas.text_width(&text[..byte + c.len_utf8()]);
+        // Past the middle of a character, the caret goes after it
+        if x < (prev_width + width) / 2.0 {
+            return index;
+        }
+        prev_width = width;
+    }
+    text.chars().count()
+}
+
+/// Returns the character range of the word around `char_pos`. Words are
+/// broken at whitespace and ASCII punctuation; on a break character the
+/// range covers just that character.
+pub(crate) fn word_range(text: &str, char_pos: usize) -> (usize, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    let is_break = |c: char| c.is_whitespace() || c.is_ascii_punctuation();
+
+    // A caret at the end of the text or before a break belongs to the preceding word
+    let pos = if char_pos > 0
+        && chars.get(char_pos).is_none_or(|&c| is_break(c))
+        && !is_break(chars[char_pos - 1])
+    {
+        char_pos - 1
+    } else {
+        char_pos
+    };
+
+    match chars.get(pos) {
+        None => (chars.len(), chars.len()),
+        Some(&c) if is_break(c) => (pos, pos + 1),
+        Some(_) => {
+            let start = chars[..pos].iter().rposition(|&c| is_break(c)).map_or(0, |i| i + 1);
+            let end = chars[pos..].iter().position(|&c| is_break(c)).map_or(chars.len(), |i| pos + i);
+            (start, end)
+        }
+    }
+}
 
 /// A single-line text input element.
 pub struct TextBox {
@@ -32,11 +88,11 @@ pub struct TextBox {
     state: RwLock<TextBoxState>,
     cursor_pos: RwLock<usize>,
     selection_start: RwLock<Option<usize>>,
-    background_color: Color,
-    text_color: Color,
-    placeholder_color: Color,
-    highlight_color: Color,
-    caret_color: Color,
+    background_color: ThemeColor,
+    text_color: ThemeColor,
+    placeholder_color: ThemeColor,
+    highlight_color: ThemeColor,
+    caret_color: ThemeColor,
     font_size: f32,
     width: f32,
     height: f32,
@@ -44,9 +100,18 @@ pub struct TextBox {
     corner_radius: f32,
     enabled: bool,
     password_mode: bool,
+    mask_char: char,
+    /// Shows a password in plain text while set.
+    revealed: RwLock<bool>,
     on_change: Option<TextChangeCallback>,
     on_enter: Option<EnterCallback>,
+    filter: Option<CharFilter>,
+    max_length: Option<usize>,
+    /// How far the text is scrolled left to keep the caret in view.
     scroll_offset: RwLock<f32>,
+    /// When the caret last moved; the blink phase is measured from here.
+    caret_reset: RwLock<Instant>,
+    caret_visible: RwLock<bool>,
 }
 
 impl TextBox {
@@ -59,11 +124,11 @@ impl TextBox {
             state: RwLock::new(TextBoxState::Idle),
             cursor_pos: RwLock::new(0),
             selection_start: RwLock::new(None),
-            background_color: theme.input_box_color,
-            text_color: theme.text_box_font_color,
-            placeholder_color: theme.text_box_idle_color,
-            highlight_color: theme.text_box_hilite_color,
-            caret_color: theme.text_box_caret_color,
+            background_color: ThemeColor::Theme(|theme| theme.input_box_color),
+            text_color: ThemeColor::Theme(|theme| theme.text_box_font_color),
+            placeholder_color: ThemeColor::Theme(|theme| theme.text_box_idle_color),
+            highlight_color: ThemeColor::Theme(|theme| theme.text_box_hilite_color),
+            caret_color: ThemeColor::Theme(|theme| theme.text_box_caret_color),
             font_size: theme.text_box_font_size,
             width: 150.0,
             height: theme.text_box_font_size * 2.0,
@@ -71,16 +136,22 @@ impl TextBox {
             corner_radius: 4.0,
             enabled: true,
             password_mode: false,
+            mask_char: '\u{2022}',
+            revealed: RwLock::new(false),
             on_change: None,
             on_enter: None,
+            filter: None,
+            max_length: None,
             scroll_offset: RwLock::new(0.0),
+            caret_reset: RwLock::new(Instant::now()),
+            caret_visible: RwLock::new(true),
         }
     }
 
     /// Sets the initial text.
     pub fn text(self, text: impl Into<String>) -> Self {
         let s: String = text.into();
-        let len = s.len();
+        let len = s.chars().count();
         *self.text.write().unwrap() = s;
         *self.cursor_pos.write().unwrap() = len;
         self
@@ -104,15 +175,37 @@ impl TextBox {
         self
     }
 
+    /// Sets the character a password is masked with.
+    pub fn mask_char(mut self, mask_char: char) -> Self {
+        self.mask_char = mask_char;
+        self
+    }
+
+    /// Sets whether a password starts out shown in plain text.
+    pub fn reveal(self, reveal: bool) -> Self {
+        self.set_reveal(reveal);
+        self
+    }
+
+    /// Shows or masks a password, as for a show-password toggle.
+    pub fn set_reveal(&self, reveal: bool) {
+        *self.revealed.write().unwrap() = reveal;
+    }
+
+    /// Returns whether a password is shown in plain text.
+    pub fn is_revealed(&self) -> bool {
+        *self.revealed.read().unwrap()
+    }
+
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
@@ -128,6 +221,36 @@ impl TextBox {
         self
     }
 
+    /// Sets a filter deciding which typed or pasted characters are
+    /// accepted. Rejected characters are dropped.
+    pub fn filter<F: Fn(char) -> bool + Send + Sync + 'static>(mut self, filter: F) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Accepts only characters that can appear in a decimal number.
+    pub fn numeric(self) -> Self {
+        self.filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+    }
+
+    /// Accepts only digits and a sign.
+    pub fn integer(self) -> Self {
+        self.filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+'))
+    }
+
+    /// Sets the maximum number of characters that typing or pasting can
+    /// produce. Insertions past the limit are cut short; text set
+    /// programmatically is not truncated.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Returns whether the filter, if any, accepts `c`.
+    fn accepts(&self, c: char) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(c))
+    }
+
     /// Returns the current text.
     pub fn get_text(&self) -> String {
         self.text.read().unwrap().clone()
@@ -136,36 +259,60 @@ impl TextBox {
     /// Sets the text.
     pub fn set_text(&self, text: impl Into<String>) {
         let s: String = text.into();
-        let len = s.len();
+        let len = s.chars().count();
         *self.text.write().unwrap() = s;
         *self.cursor_pos.write().unwrap() = len;
         *self.selection_start.write().unwrap() = None;
     }
 
+    /// Returns the selected text, or `None` if nothing is selected.
+    fn selected_text(&self) -> Option<String> {
+        let text = self.text.read().unwrap();
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        let sel_start = (*self.selection_start.read().unwrap())?;
+        if sel_start == cursor_pos {
+            return None;
+        }
+
+        let start = byte_index(&text, sel_start.min(cursor_pos));
+        let end = byte_index(&text, sel_start.max(cursor_pos));
+        Some(text[start..end].to_string())
+    }
+
     /// Returns the display text (masked if password mode).
     fn display_text(&self) -> String {
         let text = self.text.read().unwrap();
-        if self.password_mode {
-            "•".repeat(text.chars().count())
+        if self.password_mode && !self.is_revealed() {
+            self.mask_char.to_string().repeat(text.chars().count())
         } else {
             text.clone()
         }
     }
 
-    /// Inserts text at cursor position.
-    fn insert_text(&self, s: &str) {
+    /// Inserts text at cursor position, replacing the selection. Text past
+    /// the maximum length is dropped. Returns whether anything changed.
+    fn insert_text(&self, s: &str) -> bool {
         let mut text = self.text.write().unwrap();
         let mut cursor_pos = self.cursor_pos.write().unwrap();
         let mut selection_start = self.selection_start.write().unwrap();
 
+        let selected = selection_start.map_or(0, |sel_start| sel_start.abs_diff(*cursor_pos));
+        let room = self.max_length.map_or(usize::MAX, |max| {
+            max.saturating_sub(text.chars().count() - selected)
+        });
+        let s: String = s.chars().take(room).collect();
+        if s.is_empty() && selected == 0 {
+            return false;
+        }
+
         // Delete selection if any
         if let Some(sel_start) = *selection_start {
             let start = sel_start.min(*cursor_pos);
             let end = sel_start.max(*cursor_pos);
 
             // Find byte indices
-            let start_byte = text.char_indices().nth(start).map(|(i, _)| i).unwrap_or(text.len());
-            let end_byte = text.char_indices().nth(end).map(|(i, _)| i).unwrap_or(text.len());
+            let start_byte = byte_index(&text, start);
+            let end_byte = byte_index(&text, end);
 
             text.replace_range(start_byte..end_byte, "");
             *cursor_pos = start;
@@ -173,9 +320,10 @@ impl TextBox {
         }
 
         // Insert new text
-        let byte_pos = text.char_indices().nth(*cursor_pos).map(|(i, _)| i).unwrap_or(text.len());
-        text.insert_str(byte_pos, s);
+        let byte_pos = byte_index(&text, *cursor_pos);
+        text.insert_str(byte_pos, &s);
         *cursor_pos += s.chars().count();
+        true
     }
 
     /// Deletes character before cursor.
@@ -189,16 +337,16 @@ impl TextBox {
             let start = sel_start.min(*cursor_pos);
             let end = sel_start.max(*cursor_pos);
 
-            let start_byte = text.char_indices().nth(start).map(|(i, _)| i).unwrap_or(text.len());
-            let end_byte = text.char_indices().nth(end).map(|(i, _)| i).unwrap_or(text.len());
+            let start_byte = byte_index(&text, start);
+            let end_byte = byte_index(&text, end);
 
             text.replace_range(start_byte..end_byte, "");
             *cursor_pos = start;
             *selection_start = None;
         } else if *cursor_pos > 0 {
             let prev_pos = *cursor_pos - 1;
-            let start_byte = text.char_indices().nth(prev_pos).map(|(i, _)| i).unwrap_or(0);
-            let end_byte = text.char_indices().nth(*cursor_pos).map(|(i, _)| i).unwrap_or(text.len());
+            let start_byte = byte_index(&text, prev_pos);
+            let end_byte = byte_index(&text, *cursor_pos);
 
             text.replace_range(start_byte..end_byte, "");
             *cursor_pos = prev_pos;
@@ -216,8 +364,8 @@ impl TextBox {
             let start = sel_start.min(*cursor_pos);
             let end = sel_start.max(*cursor_pos);
 
-            let start_byte = text.char_indices().nth(start).map(|(i, _)| i).unwrap_or(text.len());
-            let end_byte = text.char_indices().nth(end).map(|(i, _)| i).unwrap_or(text.len());
+            let start_byte = byte_index(&text, start);
+            let end_byte = byte_index(&text, end);
 
             text.replace_range(start_byte..end_byte, "");
             *cursor_pos = start;
@@ -225,8 +373,8 @@ impl TextBox {
         } else {
             let char_count = text.chars().count();
             if *cursor_pos < char_count {
-                let start_byte = text.char_indices().nth(*cursor_pos).map(|(i, _)| i).unwrap_or(text.len());
-                let end_byte = text.char_indices().nth(*cursor_pos + 1).map(|(i, _)| i).unwrap_or(text.len());
+                let start_byte = byte_index(&text, *cursor_pos);
+                let end_byte = byte_index(&text, *cursor_pos + 1);
 
                 text.replace_range(start_byte..end_byte, "");
             }
@@ -319,15 +467,90 @@ impl TextBox {
         *self.cursor_pos.write().unwrap() = char_count;
     }
 
+    /// Shows the caret solid and restarts its blink.
+    fn reset_caret(&self) {
+        *self.caret_reset.write().unwrap() = Instant::now();
+        *self.caret_visible.write().unwrap() = true;
+    }
+
+    /// Updates the caret's blink phase.
+    ///
+    /// Returns true if the caret was shown or hidden.
+    fn tick(&self, now: Instant) -> bool {
+        if *self.state.read().unwrap() != TextBoxState::Focused {
+            return false;
+        }
+
+        let elapsed = now.saturating_duration_since(*self.caret_reset.read().unwrap());
+        let visible = (elapsed.as_millis() / CARET_BLINK_PERIOD.as_millis()) % 2 == 0;
+        let mut caret_visible = self.caret_visible.write().unwrap();
+        if *caret_visible == visible {
+            return false;
+        }
+        *caret_visible = visible;
+        true
+    }
+
+    /// Maps an x coordinate to the nearest caret position.
+    fn position_at(&self, ctx: &Context, x: f32) -> usize {
+        let display = self.display_text();
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.save();
+        canvas.font_size(self.font_size);
+        let scroll = *self.scroll_offset.read().unwrap();
+        let pos = char_index_at(&canvas, &display, x - ctx.bounds.left - self.padding + scroll);
+        canvas.restore();
+        pos
+    }
+
+    /// Returns the x coordinate of the caret before character `pos`, from
+    /// the shaped width of the text up to it and the scroll offset.
+    fn caret_x(&self, canvas: &mut Canvas, bounds: &Rect, display: &str, pos: usize) -> f32 {
+        canvas.font_size(self.font_size);
+        let scroll = *self.scroll_offset.read().unwrap();
+        bounds.left + self.padding + canvas.text_width_to_position(display, pos) - scroll
+    }
+
+    /// Returns the area the text is drawn in, inside the padding.
+    fn text_area(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.left + self.padding,
+            bounds.top,
+            bounds.right - self.padding,
+            bounds.bottom,
+        )
+    }
+
+    /// Adjusts the scroll offset so the caret stays inside the text area,
+    /// without scrolling past the end of the text.
+    fn scroll_to_caret(&self, ctx: &Context) {
+        let mut canvas = ctx.canvas.borrow_mut();
+        let display = self.display_text();
+        let cursor_pos = *self.cursor_pos.read().unwrap();
+        let visible = self.text_area(&ctx.bounds).width().max(0.0);
+
+        canvas.font_size(self.font_size);
+        let caret = canvas.text_width_to_position(&display, cursor_pos);
+        let text_width = canvas.text_width(&display);
+
+        let mut scroll = self.scroll_offset.write().unwrap();
+        if caret - *scroll > visible {
+            *scroll = caret - visible;
+        } else if caret < *scroll {
+            *scroll = caret;
+        }
+        *scroll = scroll.min((text_width - visible).max(0.0)).max(0.0);
+    }
+
     fn draw_background(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
         let state = *self.state.read().unwrap();
 
         let color = match state {
-            TextBoxState::Idle => self.background_color,
-            TextBoxState::Hover => self.background_color.level(1.1),
-            TextBoxState::Focused => self.background_color.level(1.2),
-            TextBoxState::Disabled => self.background_color.with_alpha(0.5),
+            TextBoxState::Idle => self.background_color.get(),
+            TextBoxState::Hover => self.background_color.get().level(1.1),
+            TextBoxState::Focused => self.background_color.get().level(1.2),
+            TextBoxState::Disabled => self.background_color.get().with_alpha(0.5),
         };
 
         canvas.fill_style(color);
@@ -348,22 +571,16 @@ impl TextBox {
         let mut canvas = ctx.canvas.borrow_mut();
         let state = *self.state.read().unwrap();
         let display = self.display_text();
-
-        let text_area = Rect::new(
-            ctx.bounds.left + self.padding,
-            ctx.bounds.top,
-            ctx.bounds.right - self.padding,
-            ctx.bounds.bottom,
-        );
+        let text_area = self.text_area(&ctx.bounds);
 
         canvas.font_size(self.font_size);
 
         if display.is_empty() && !self.placeholder.is_empty() {
             // Draw placeholder
             let color = if state == TextBoxState::Disabled {
-                self.placeholder_color.with_alpha(0.3)
+                self.placeholder_color.get().with_alpha(0.3)
             } else {
-                self.placeholder_color
+                self.placeholder_color.get()
             };
             canvas.fill_style(color);
             let y = text_area.center().y + self.font_size * 0.35;
@@ -371,13 +588,16 @@ impl TextBox {
         } else {
             // Draw text
             let color = if state == TextBoxState::Disabled {
-                self.text_color.with_alpha(0.5)
+                self.text_color.get().with_alpha(0.5)
             } else {
-                self.text_color
+                self.text_color.get()
             };
             canvas.fill_style(color);
             let y = text_area.center().y + self.font_size * 0.35;
-            canvas.fill_text(&display, Point::new(text_area.left, y));
+            let x = text_area.left - *self.scroll_offset.read().unwrap();
+            canvas.push_clip(text_area);
+            canvas.fill_text(&display, Point::new(x, y));
+            canvas.pop_clip();
         }
     }
 
@@ -400,10 +620,8 @@ impl TextBox {
         let start = sel_start.min(cursor_pos);
         let end = sel_start.max(cursor_pos);
 
-        // Measure text width up to start and end positions
-        canvas.font_size(self.font_size);
-        let x1 = ctx.bounds.left + self.padding + canvas.text_width_to_position(&display, start);
-        let x2 = ctx.bounds.left + self.padding + canvas.text_width_to_position(&display, end);
+        let x1 = self.caret_x(&mut canvas, &ctx.bounds, &display, start);
+        let x2 = self.caret_x(&mut canvas, &ctx.bounds, &display, end);
 
         let sel_rect = Rect::new(
             x1,
@@ -412,13 +630,15 @@ impl TextBox {
             ctx.bounds.bottom - 4.0,
         );
 
-        canvas.fill_style(self.highlight_color);
+        canvas.push_clip(self.text_area(&ctx.bounds));
+        canvas.fill_style(self.highlight_color.get());
         canvas.fill_rect(sel_rect);
+        canvas.pop_clip();
     }
 
     fn draw_caret(&self, ctx: &Context) {
         let state = *self.state.read().unwrap();
-        if state != TextBoxState::Focused {
+        if state != TextBoxState::Focused || !*self.caret_visible.read().unwrap() {
             return;
         }
 
@@ -426,13 +646,11 @@ impl TextBox {
         let cursor_pos = *self.cursor_pos.read().unwrap();
         let display = self.display_text();
 
-        // Measure text width up to cursor position
-        canvas.font_size(self.font_size);
-        let x = ctx.bounds.left + self.padding + canvas.text_width_to_position(&display, cursor_pos);
+        let x = self.caret_x(&mut canvas, &ctx.bounds, &display, cursor_pos);
         let y1 = ctx.bounds.top + 4.0;
         let y2 = ctx.bounds.bottom - 4.0;
 
-        canvas.stroke_style(self.caret_color);
+        canvas.stroke_style(self.caret_color.get());
         canvas.line_width(1.5);
         canvas.begin_path();
         canvas.move_to(Point::new(x, y1));
@@ -457,6 +675,7 @@ impl Element for TextBox {
     }
 
     fn draw(&self, ctx: &Context) {
+        self.scroll_to_caret(ctx);
         self.draw_background(ctx);
         self.draw_selection(ctx);
         self.draw_text(ctx);
@@ -471,6 +690,10 @@ impl Element for TextBox {
         }
     }
 
+    fn cursor_type(&self, _ctx: &Context, _p: Point) -> Option<CursorType> {
+        Some(CursorType::IBeam)
+    }
+
     fn wants_control(&self) -> bool {
         self.enabled
     }
@@ -481,6 +704,7 @@ impl Element for TextBox {
 
     fn begin_focus(&mut self, _req: FocusRequest) {
         *self.state.write().unwrap() = TextBoxState::Focused;
+        self.reset_caret();
     }
 
     fn end_focus(&mut self) -> bool {
@@ -495,6 +719,19 @@ impl Element for TextBox {
         }
     }
 
+    fn has_focus(&self) -> bool {
+        *self.state.read().unwrap() == TextBoxState::Focused
+    }
+
+    fn handle_begin_focus(&self, _req: FocusRequest) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        *self.state.write().unwrap() = TextBoxState::Focused;
+        self.reset_caret();
+        true
+    }
+
     fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
         if !self.enabled || btn.button != MouseButtonKind::Left {
             return false;
@@ -502,23 +739,47 @@ impl Element for TextBox {
 
         if btn.down {
             *self.state.write().unwrap() = TextBoxState::Focused;
+            self.reset_caret();
 
             // Set cursor position based on click location
-            let char_width = self.font_size * 0.6;
-            let text = self.text.read().unwrap();
-            let char_count = text.chars().count();
-            drop(text);
-
-            let rel_x = btn.pos.x - ctx.bounds.left - self.padding;
-            let pos = ((rel_x / char_width).round() as usize).min(char_count);
+            let pos = self.position_at(ctx, btn.pos.x);
+            let char_count = self.text.read().unwrap().chars().count();
+
+            let (start, end) = match btn.click_count {
+                ..=1 => (pos, pos),
+                // Password text hides its words, so a double-click selects everything
+                2 if !self.password_mode => word_range(&self.text.read().unwrap(), pos),
+                _ => (0, char_count),
+            };
 
-            *self.cursor_pos.write().unwrap() = pos;
-            *self.selection_start.write().unwrap() = None;
+            *self.cursor_pos.write().unwrap() = end;
+            *self.selection_start.write().unwrap() = (start != end).then_some(start);
         }
 
         true
     }
 
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if !self.enabled || btn.button != MouseButtonKind::Left {
+            return;
+        }
+
+        let pos = self.position_at(ctx, btn.pos.x);
+        self.reset_caret();
+        let mut cursor_pos = self.cursor_pos.write().unwrap();
+        let mut selection_start = self.selection_start.write().unwrap();
+
+        // The caret placed by the mouse-down is the selection anchor. Back
+        // at the anchor nothing is selected, as after a plain click
+        let anchor = selection_start.unwrap_or(*cursor_pos);
+        *selection_start = (anchor != pos).then_some(anchor);
+        *cursor_pos = pos;
+    }
+
     fn key(&mut self, _ctx: &Context, k: KeyInfo) -> bool {
         self.handle_key(_ctx, k)
     }
@@ -536,6 +797,7 @@ impl Element for TextBox {
         if k.action != crate::view::KeyAction::Press && k.action != crate::view::KeyAction::Repeat {
             return true;
         }
+        self.reset_caret();
 
         let shift = k.modifiers & crate::view::modifiers::SHIFT != 0;
         let ctrl = k.modifiers & (crate::view::modifiers::CONTROL | crate::view::modifiers::SUPER) != 0;
@@ -581,6 +843,40 @@ impl Element for TextBox {
                 self.select_all();
                 return true;
             }
+            KeyCode::C if ctrl => {
+                // Never copy a password out of the box
+                if !self.password_mode {
+                    if let Some(selected) = self.selected_text() {
+                        crate::view::set_clipboard(&selected);
+                    }
+                }
+                return true;
+            }
+            KeyCode::X if ctrl => {
+                if !self.password_mode {
+                    if let Some(selected) = self.selected_text() {
+                        crate::view::set_clipboard(&selected);
+                        self.delete_backward();
+                        if let Some(ref callback) = self.on_change {
+                            callback(&self.get_text());
+                        }
+                    }
+                }
+                return true;
+            }
+            KeyCode::V if ctrl => {
+                // A single-line box drops line breaks from pasted text
+                let pasted: String = crate::view::clipboard()
+                    .chars()
+                    .filter(|&c| c != '\n' && c != '\r' && self.accepts(c))
+                    .collect();
+                if !pasted.is_empty() && self.insert_text(&pasted) {
+                    if let Some(ref callback) = self.on_change {
+                        callback(&self.get_text());
+                    }
+                }
+                return true;
+            }
             _ => {}
         }
 
@@ -603,9 +899,8 @@ impl Element for TextBox {
 
         // Filter control characters
         let c = info.codepoint;
-        if !c.is_control() {
-            let s = c.to_string();
-            self.insert_text(&s);
+        if !c.is_control() && self.accepts(c) && self.insert_text(&c.to_string()) {
+            self.reset_caret();
             if let Some(ref callback) = self.on_change {
                 callback(&self.get_text());
             }
@@ -614,26 +909,25 @@ impl Element for TextBox {
         true
     }
 
-    fn cursor(&mut self, _ctx: &Context, _p: Point, status: CursorTracking) -> bool {
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
         if !self.enabled {
             return false;
         }
 
+        let inside = status != CursorTracking::Leaving && ctx.bounds.contains(p);
         let mut state = self.state.write().unwrap();
-        if *state == TextBoxState::Focused {
-            return true;
-        }
-
-        match status {
-            CursorTracking::Entering | CursorTracking::Hovering => {
-                *state = TextBoxState::Hover;
-            }
-            CursorTracking::Leaving => {
-                *state = TextBoxState::Idle;
-            }
+        if *state != TextBoxState::Focused {
+            *state = if inside { TextBoxState::Hover } else { TextBoxState::Idle };
         }
+        inside
+    }
 
-        true
+    fn handle_poll(&self) -> bool {
+        self.tick(Instant::now())
     }
 
     fn enable(&mut self, state: bool) {
@@ -673,3 +967,243 @@ pub fn text_box_with_text(text: impl Into<String>) -> TextBox {
 pub fn password_box() -> TextBox {
     TextBox::new().password(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_caret_blinks_and_resets() {
+        let text_box = Arc::new(text_box());
+        let view = TestView::new(text_box.clone(), Extent::new(150.0, 30.0));
+        assert!(view.focus_first());
+        let start = *text_box.caret_reset.read().unwrap();
+
+        assert!(!text_box.tick(start + CARET_BLINK_PERIOD / 2));
+        assert!(text_box.tick(start + CARET_BLINK_PERIOD * 3 / 2));
+        assert!(!*text_box.caret_visible.read().unwrap());
+        assert!(text_box.tick(start + CARET_BLINK_PERIOD * 5 / 2));
+        assert!(*text_box.caret_visible.read().unwrap());
+
+        // Typing brings the caret back solid right away
+        assert!(text_box.tick(start + CARET_BLINK_PERIOD * 7 / 2));
+        assert!(view.type_text("a"));
+        assert!(*text_box.caret_visible.read().unwrap());
+
+        // An unfocused box does not blink
+        text_box.clear_focus();
+        assert!(!text_box.tick(Instant::now() + CARET_BLINK_PERIOD));
+    }
+
+    #[test]
+    fn test_hover_state_follows_cursor() {
+        let text_box = Arc::new(text_box());
+        let view = TestView::new(text_box.clone(), Extent::new(150.0, 30.0));
+        view.hover(Point::new(20.0, 15.0));
+        assert_eq!(*text_box.state.read().unwrap(), TextBoxState::Hover);
+        view.leave(Point::new(200.0, 15.0));
+        assert_eq!(*text_box.state.read().unwrap(), TextBoxState::Idle);
+
+        // Focus wins over hover
+        assert!(view.focus_first());
+        view.hover(Point::new(20.0, 15.0));
+        view.leave(Point::new(200.0, 15.0));
+        assert_eq!(*text_box.state.read().unwrap(), TextBoxState::Focused);
+    }
+
+    #[test]
+    fn test_caret_lands_on_glyph_boundaries() {
+        let text_box = Arc::new(text_box_with_text("iiWW"));
+        let view = TestView::new(text_box.clone(), Extent::new(200.0, 30.0));
+        let bounds = Rect::new(10.0, 0.0, 190.0, 30.0);
+
+        view.with_context(|ctx| {
+            let ctx = ctx.with_bounds(bounds);
+
+            // Caret positions come from the measured widths of each prefix
+            let boundaries: Vec<f32> = (0..=4)
+                .map(|pos| text_box.caret_x(&mut ctx.canvas.borrow_mut(), &bounds, "iiWW", pos))
+                .collect();
+            assert_eq!(boundaries[0], 18.0);
+
+            // Clicks snap to the nearer side of the glyph under them
+            for (pos, pair) in boundaries.windows(2).enumerate() {
+                let middle = (pair[0] + pair[1]) / 2.0;
+                assert_eq!(text_box.position_at(&ctx, middle - 0.5), pos);
+                assert_eq!(text_box.position_at(&ctx, middle + 0.5), pos + 1);
+            }
+        });
+    }
+
+    #[test]
+    fn test_long_text_scrolls_to_keep_caret_visible() {
+        let text_box = Arc::new(text_box());
+        let view = TestView::new(text_box.clone(), Extent::new(100.0, 30.0));
+        let text = "a rather long value that overflows the field";
+        let bounds = Rect::new(0.0, 0.0, 100.0, 30.0);
+        let area = text_box.text_area(&bounds);
+
+        // The caret ends up at the end of the text, against the right edge
+        text_box.set_text(text);
+        assert!(view.render().is_some());
+        assert!(*text_box.scroll_offset.read().unwrap() > 0.0);
+        view.with_context(|ctx| {
+            let end = text_box.caret_x(&mut ctx.canvas.borrow_mut(), &bounds, text, text.len());
+            assert!((end - area.right).abs() < 0.01);
+            assert!(text_box.position_at(ctx, area.left) > 0);
+        });
+
+        // Moving back to the start scrolls back
+        *text_box.cursor_pos.write().unwrap() = 0;
+        assert!(view.render().is_some());
+        assert_eq!(*text_box.scroll_offset.read().unwrap(), 0.0);
+        assert_eq!(view.with_context(|ctx| text_box.position_at(ctx, area.left)), 0);
+    }
+
+    /// Returns the point of the caret before character `pos`, halfway down
+    /// a box filling the view.
+    fn caret_point(view: &TestView, text_box: &TextBox, pos: usize) -> Point {
+        view.with_context(|ctx| {
+            let x = text_box.caret_x(&mut ctx.canvas.borrow_mut(), &ctx.bounds, &text_box.display_text(), pos);
+            Point::new(x, ctx.bounds.center().y)
+        })
+    }
+
+    #[test]
+    fn test_drag_selects_text() {
+        let text_box = Arc::new(text_box_with_text("hello world"));
+        let view = TestView::new(text_box.clone(), Extent::new(200.0, 30.0));
+
+        // The press anchors the selection and the drag extends it
+        view.press(caret_point(&view, &text_box, 1));
+        view.drag_to(caret_point(&view, &text_box, 4));
+        assert_eq!(text_box.selected_text().as_deref(), Some("ell"));
+
+        // It stays selected after the button is released
+        view.release(caret_point(&view, &text_box, 4));
+        assert_eq!(text_box.selected_text().as_deref(), Some("ell"));
+        assert_eq!(*text_box.cursor_pos.read().unwrap(), 4);
+
+        // Dragging backwards leaves the caret at the start
+        view.drag(caret_point(&view, &text_box, 11), caret_point(&view, &text_box, 6));
+        assert_eq!(text_box.selected_text().as_deref(), Some("world"));
+        assert_eq!(*text_box.cursor_pos.read().unwrap(), 6);
+
+        // A plain click drops the selection
+        view.click_at(caret_point(&view, &text_box, 2));
+        assert_eq!(text_box.selected_text(), None);
+        assert_eq!(*text_box.cursor_pos.read().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_drag_back_to_anchor_selects_nothing() {
+        let text_box = Arc::new(text_box_with_text("hello"));
+        let view = TestView::new(text_box.clone(), Extent::new(200.0, 30.0));
+
+        view.press(caret_point(&view, &text_box, 3));
+        view.drag_to(caret_point(&view, &text_box, 1));
+        view.drag_to(caret_point(&view, &text_box, 3));
+        view.release(caret_point(&view, &text_box, 3));
+        assert_eq!(*text_box.selection_start.read().unwrap(), None);
+
+        // So Backspace deletes before the caret
+        assert!(view.key(KeyCode::Backspace, 0));
+        assert_eq!(text_box.get_text(), "helo");
+    }
+
+    #[test]
+    fn test_multi_click_selects_word_and_all() {
+        let text_box = Arc::new(text_box_with_text("hello, big world"));
+        let view = TestView::new(text_box.clone(), Extent::new(200.0, 30.0));
+
+        // A double-click selects the word under it, stopping at punctuation
+        view.double_click_at(caret_point(&view, &text_box, 2));
+        assert_eq!(text_box.selected_text().as_deref(), Some("hello"));
+        view.double_click_at(caret_point(&view, &text_box, 14));
+        assert_eq!(text_box.selected_text().as_deref(), Some("world"));
+        assert_eq!(*text_box.cursor_pos.read().unwrap(), 16);
+
+        // A third click selects everything
+        let mut btn = MouseButton::new(true, MouseButtonKind::Left, caret_point(&view, &text_box, 8));
+        btn.click_count = 3;
+        view.mouse(btn);
+        assert_eq!(text_box.selected_text().as_deref(), Some("hello, big world"));
+
+        // A password's words are hidden, so a double-click takes it all
+        let password = Arc::new(password_box().text("secret words"));
+        let view = TestView::new(password.clone(), Extent::new(200.0, 30.0));
+        view.double_click_at(caret_point(&view, &password, 2));
+        assert_eq!(password.selected_text().as_deref(), Some("secret words"));
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "macos", ignore = "uses the system clipboard")]
+    fn test_cut_copy_and_paste() {
+        use crate::view::{clipboard, set_clipboard, modifiers::CONTROL};
+
+        let text_box = Arc::new(text_box_with_text("hello world"));
+        let view = TestView::new(text_box.clone(), Extent::new(200.0, 30.0));
+        assert!(view.focus_first());
+
+        // Copy leaves the text alone
+        *text_box.selection_start.write().unwrap() = Some(0);
+        *text_box.cursor_pos.write().unwrap() = 5;
+        assert!(view.key(KeyCode::C, CONTROL));
+        assert_eq!(clipboard(), "hello");
+        assert_eq!(text_box.get_text(), "hello world");
+
+        // Cut removes the selection
+        *text_box.selection_start.write().unwrap() = Some(5);
+        *text_box.cursor_pos.write().unwrap() = 11;
+        assert!(view.key(KeyCode::X, CONTROL));
+        assert_eq!(clipboard(), " world");
+        assert_eq!(text_box.get_text(), "hello");
+
+        // Paste replaces the selection, dropping line breaks
+        set_clipboard("big\r\nbad\n");
+        *text_box.selection_start.write().unwrap() = Some(0);
+        *text_box.cursor_pos.write().unwrap() = 1;
+        assert!(view.key(KeyCode::V, CONTROL));
+        assert_eq!(text_box.get_text(), "bigbadello");
+        assert_eq!(*text_box.cursor_pos.read().unwrap(), 6);
+
+        // Passwords are never copied out
+        let password = Arc::new(password_box().text("secret"));
+        let view = TestView::new(password.clone(), Extent::new(200.0, 30.0));
+        assert!(view.focus_first());
+        password.select_all();
+        assert!(view.key(KeyCode::X, CONTROL));
+        assert_eq!(clipboard(), "big\r\nbad\n");
+        assert_eq!(password.get_text(), "secret");
+    }
+
+    #[test]
+    fn test_filter_and_max_length() {
+        let text_box = Arc::new(text_box().integer().max_length(3));
+        let view = TestView::new(text_box.clone(), Extent::new(150.0, 30.0));
+        assert!(view.focus_first());
+
+        view.type_text("1a2-34");
+        assert_eq!(text_box.get_text(), "12-");
+
+        // Replacing a selection frees its room
+        *text_box.selection_start.write().unwrap() = Some(0);
+        assert!(text_box.insert_text("9876"));
+        assert_eq!(text_box.get_text(), "987");
+        assert!(!text_box.insert_text("5"));
+    }
+
+    #[test]
+    fn test_password_mask_and_reveal() {
+        let password = password_box().text("abc");
+        assert_eq!(password.display_text(), "\u{2022}\u{2022}\u{2022}");
+        password.set_reveal(true);
+        assert_eq!(password.display_text(), "abc");
+
+        let password = password_box().text("abc").mask_char('*');
+        assert_eq!(password.display_text(), "***");
+    }
+}
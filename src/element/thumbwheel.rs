@@ -2,12 +2,17 @@
 
 use std::any::Any;
 use std::sync::RwLock;
+use std::time::Instant;
 use super::{Element, ViewLimits, ViewStretch};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::{MouseButton, MouseButtonKind, CursorTracking};
+use crate::support::theme::ThemeColor;
+use crate::view::{MouseButton, MouseButtonKind, CursorTracking, modifiers};
+
+/// Drag speed, in pixels per second, at which acceleration adds its full
+/// factor to the drag.
+const ACCELERATION_SPEED: f32 = 500.0;
 
 /// Thumbwheel orientation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -38,21 +43,25 @@ pub struct Thumbwheel {
     step: f64,
     orientation: ThumbwheelOrientation,
     state: RwLock<ThumbwheelState>,
-    background_color: Color,
-    groove_color: Color,
-    tick_color: Color,
+    background_color: ThemeColor,
+    groove_color: ThemeColor,
+    tick_color: ThemeColor,
     width: f32,
     height: f32,
     enabled: bool,
     on_change: Option<ThumbwheelCallback>,
-    drag_start: RwLock<f32>,
-    drag_start_value: RwLock<f64>,
+    acceleration: f32,
+    fine_ratio: f64,
+    /// Mouse position along the wheel at the last drag event
+    drag_pos: RwLock<f32>,
+    drag_time: RwLock<Option<Instant>>,
+    /// Unstepped value the drag has reached, so slow drags still add up
+    drag_value: RwLock<f64>,
 }
 
 impl Thumbwheel {
     /// Creates a new thumbwheel.
     pub fn new() -> Self {
-        let theme = get_theme();
         Self {
             value: RwLock::new(0.0),
             min_value: 0.0,
@@ -60,15 +69,18 @@ impl Thumbwheel {
             step: 1.0,
             orientation: ThumbwheelOrientation::Horizontal,
             state: RwLock::new(ThumbwheelState::Normal),
-            background_color: theme.frame_color,
-            groove_color: theme.frame_color.level(0.7),
-            tick_color: theme.label_font_color.with_alpha(0.5),
+            background_color: ThemeColor::Theme(|theme| theme.frame_color),
+            groove_color: ThemeColor::Theme(|theme| theme.frame_color.level(0.7)),
+            tick_color: ThemeColor::Theme(|theme| theme.label_font_color.with_alpha(0.5)),
             width: 80.0,
             height: 24.0,
             enabled: true,
             on_change: None,
-            drag_start: RwLock::new(0.0),
-            drag_start_value: RwLock::new(0.0),
+            acceleration: 0.0,
+            fine_ratio: 0.1,
+            drag_pos: RwLock::new(0.0),
+            drag_time: RwLock::new(None),
+            drag_value: RwLock::new(0.0),
         }
     }
 
@@ -109,7 +121,24 @@ impl Thumbwheel {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets how much faster drags change the value, like a jog wheel.
+    ///
+    /// A drag moving at 500 pixels per second changes the value
+    /// `1 + acceleration` times as much as a slow one. 0 (the default)
+    /// keeps drags linear.
+    pub fn acceleration(mut self, acceleration: f32) -> Self {
+        self.acceleration = acceleration.max(0.0);
+        self
+    }
+
+    /// Sets how much of the usual change drags make while Shift is held,
+    /// for fine adjustment. Defaults to 0.1.
+    pub fn fine_ratio(mut self, ratio: f64) -> Self {
+        self.fine_ratio = ratio;
         self
     }
 
@@ -131,15 +160,43 @@ impl Thumbwheel {
         *self.value.write().unwrap() = stepped.clamp(self.min_value, self.max_value);
     }
 
+    /// Returns the position of `p` along the wheel, growing in the
+    /// direction that raises the value.
+    fn position_along(&self, p: Point) -> f32 {
+        match self.orientation {
+            ThumbwheelOrientation::Horizontal => p.x,
+            ThumbwheelOrientation::Vertical => -p.y,
+        }
+    }
+
+    /// Moves the value for a drag of `delta` pixels over `dt` seconds.
+    fn drag_by(&self, delta: f32, dt: f32, mods: i32) {
+        let speed = delta.abs() / dt.max(1e-3);
+        let mut gain = 1.0 + (self.acceleration * speed / ACCELERATION_SPEED) as f64;
+        if mods & modifiers::SHIFT != 0 {
+            gain *= self.fine_ratio;
+        }
+        let sensitivity = (self.max_value - self.min_value) / 200.0;
+
+        let mut drag_value = self.drag_value.write().unwrap();
+        *drag_value = (*drag_value + delta as f64 * sensitivity * gain).clamp(self.min_value, self.max_value);
+        self.set_value(*drag_value);
+        drop(drag_value);
+
+        if let Some(ref callback) = self.on_change {
+            callback(self.get_value());
+        }
+    }
+
     fn draw_background(&self, ctx: &Context) {
         let mut canvas = ctx.canvas.borrow_mut();
         let state = *self.state.read().unwrap();
 
         let color = match state {
-            ThumbwheelState::Normal => self.background_color,
-            ThumbwheelState::Hover => self.background_color.level(1.1),
-            ThumbwheelState::Dragging => self.background_color.level(1.2),
-            ThumbwheelState::Disabled => self.background_color.with_alpha(0.5),
+            ThumbwheelState::Normal => self.background_color.get(),
+            ThumbwheelState::Hover => self.background_color.get().level(1.1),
+            ThumbwheelState::Dragging => self.background_color.get().level(1.2),
+            ThumbwheelState::Disabled => self.background_color.get().with_alpha(0.5),
         };
 
         canvas.fill_style(color);
@@ -148,7 +205,7 @@ impl Thumbwheel {
         // Inner groove
         let groove_inset = 4.0;
         let groove_rect = ctx.bounds.inset(groove_inset, groove_inset);
-        canvas.fill_style(self.groove_color);
+        canvas.fill_style(self.groove_color.get());
         canvas.fill_round_rect(groove_rect, 2.0);
     }
 
@@ -157,9 +214,9 @@ impl Thumbwheel {
         let state = *self.state.read().unwrap();
 
         let tick_color = if state == ThumbwheelState::Disabled {
-            self.tick_color.with_alpha(0.3)
+            self.tick_color.get().with_alpha(0.3)
         } else {
-            self.tick_color
+            self.tick_color.get()
         };
 
         canvas.stroke_style(tick_color);
@@ -249,15 +306,9 @@ impl Element for Thumbwheel {
         let mut state = self.state.write().unwrap();
         if btn.down {
             *state = ThumbwheelState::Dragging;
-            match self.orientation {
-                ThumbwheelOrientation::Horizontal => {
-                    *self.drag_start.write().unwrap() = btn.pos.x;
-                }
-                ThumbwheelOrientation::Vertical => {
-                    *self.drag_start.write().unwrap() = btn.pos.y;
-                }
-            }
-            *self.drag_start_value.write().unwrap() = self.get_value();
+            *self.drag_pos.write().unwrap() = self.position_along(btn.pos);
+            *self.drag_time.write().unwrap() = Some(Instant::now());
+            *self.drag_value.write().unwrap() = self.get_value();
         } else {
             *state = if ctx.bounds.contains(btn.pos) {
                 ThumbwheelState::Hover
@@ -269,29 +320,30 @@ impl Element for Thumbwheel {
         true
     }
 
-    fn drag(&mut self, _ctx: &Context, btn: MouseButton) {
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, _ctx: &Context, btn: MouseButton) {
         if !self.enabled {
             return;
         }
 
-        let drag_start = *self.drag_start.read().unwrap();
-        let start_value = *self.drag_start_value.read().unwrap();
-
-        let delta = match self.orientation {
-            ThumbwheelOrientation::Horizontal => btn.pos.x - drag_start,
-            ThumbwheelOrientation::Vertical => drag_start - btn.pos.y,
+        // The speed since the last event drives the acceleration
+        let now = Instant::now();
+        let Some(last_time) = self.drag_time.write().unwrap().replace(now) else {
+            return;
         };
+        let pos = self.position_along(btn.pos);
+        let delta = pos - std::mem::replace(&mut *self.drag_pos.write().unwrap(), pos);
+        self.drag_by(delta, now.duration_since(last_time).as_secs_f32(), btn.modifiers);
+    }
 
-        let sensitivity = (self.max_value - self.min_value) / 200.0;
-        let new_value = start_value + delta as f64 * sensitivity;
-        self.set_value(new_value);
-
-        if let Some(ref callback) = self.on_change {
-            callback(self.get_value());
-        }
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.handle_scroll(ctx, dir, p)
     }
 
-    fn scroll(&mut self, _ctx: &Context, dir: Point, _p: Point) -> bool {
+    fn handle_scroll(&self, _ctx: &Context, dir: Point, _p: Point) -> bool {
         if !self.enabled {
             return false;
         }
@@ -365,3 +417,37 @@ pub fn thumbwheel() -> Thumbwheel {
 pub fn vthumbwheel() -> Thumbwheel {
     Thumbwheel::new().orientation(ThumbwheelOrientation::Vertical)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_acceleration_and_fine_mode() {
+        let wheel = Arc::new(thumbwheel().range(0.0, 200.0).step(0.1).value(100.0).acceleration(1.0));
+        TestView::new(wheel.clone(), Extent::new(80.0, 24.0)).press(Point::new(40.0, 12.0));
+
+        // Slow drags are linear: a pixel per unit over this range
+        wheel.drag_by(10.0, 10.0, 0);
+        assert!((wheel.get_value() - 110.0).abs() < 0.1);
+
+        // 10 pixels in 20ms is 500 pixels per second, which doubles the change
+        wheel.drag_by(10.0, 0.02, 0);
+        assert!((wheel.get_value() - 130.0).abs() < 0.1);
+
+        // Shift scales it down
+        wheel.drag_by(-10.0, 10.0, modifiers::SHIFT);
+        assert!((wheel.get_value() - 129.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_scroll_wheel() {
+        let wheel = Arc::new(vthumbwheel().value(50.0));
+        let view = TestView::new(wheel.clone(), Extent::new(24.0, 80.0));
+        assert!(view.scroll(Point::new(0.0, 3.0), Point::zero()));
+        assert_eq!(wheel.get_value(), 53.0);
+    }
+}
@@ -9,11 +9,63 @@ use super::context::{BasicContext, Context};
 use super::composite::{Storage, CompositeBase, Composite};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
+use crate::view::CursorTracking;
+
+/// Child offsets along a tile's axis, cached between layout passes.
+#[derive(Default)]
+struct TileLayout {
+    /// The extent the offsets were computed for, if any
+    extent: Option<f32>,
+    offsets: Vec<f32>,
+}
+
+/// Allocates `extent` among children given as `(min, max, stretch)` along
+/// the tile's axis, returning the offsets of their edges.
+///
+/// Space beyond the minimums is shared in proportion to stretch. A child
+/// that reaches its maximum stops there, and what it leaves is shared again
+/// among the children still growing.
+fn allocate(children: &[(f32, f32, f32)], extent: f32) -> Vec<f32> {
+    let mut sizes: Vec<f32> = children.iter().map(|&(min, _, _)| min).collect();
+    let mut extra = (extent - sizes.iter().sum::<f32>()).max(0.0);
+    let mut growing: Vec<usize> = (0..children.len())
+        .filter(|&i| children[i].2 > 0.0 && children[i].1 > children[i].0)
+        .collect();
+
+    while extra > 0.0 && !growing.is_empty() {
+        let total_stretch: f32 = growing.iter().map(|&i| children[i].2).sum();
+        let share = |i: usize| extra * children[i].2 / total_stretch;
+
+        // Children that would pass their maximum take only what they can
+        let (clamped, rest): (Vec<usize>, Vec<usize>) =
+            growing.iter().partition(|&&i| sizes[i] + share(i) >= children[i].1);
+        if clamped.is_empty() {
+            for &i in &growing {
+                sizes[i] += share(i);
+            }
+            break;
+        }
+        for i in clamped {
+            extra -= children[i].1 - sizes[i];
+            sizes[i] = children[i].1;
+        }
+        growing = rest;
+    }
+
+    let mut offsets = Vec::with_capacity(sizes.len() + 1);
+    let mut offset = 0.0;
+    offsets.push(offset);
+    for size in sizes {
+        offset += size;
+        offsets.push(offset);
+    }
+    offsets
+}
 
 /// Vertical tile element - stacks children vertically.
 pub struct VTile {
     inner: Composite,
-    tiles: RwLock<Vec<f32>>,
+    tiles: RwLock<TileLayout>,
 }
 
 impl VTile {
@@ -21,66 +73,45 @@ impl VTile {
     pub fn new() -> Self {
         Self {
             inner: Composite::new(),
-            tiles: RwLock::new(Vec::new()),
+            tiles: RwLock::new(TileLayout::default()),
         }
     }
 
     /// Creates a vertical tile from a vector of elements.
     pub fn from_vec(children: Vec<ElementPtr>) -> Self {
-        let len = children.len();
         Self {
             inner: Composite::from_vec(children),
-            tiles: RwLock::new(vec![0.0; len + 1]),
+            tiles: RwLock::new(TileLayout::default()),
         }
     }
 
     /// Adds an element.
     pub fn push(&mut self, element: ElementPtr) {
         self.inner.push(element);
-        self.tiles.write().unwrap().push(0.0);
+        self.tiles.write().unwrap().extent = None;
     }
 
     fn compute_layout(&self, ctx: &BasicContext, height: f32) -> Vec<f32> {
-        let count = self.inner.len();
-        if count == 0 {
-            return vec![0.0];
-        }
-
-        let mut tiles = vec![0.0; count + 1];
-        let mut total_min = 0.0f32;
-        let mut total_stretch = 0.0f32;
-
-        // Calculate minimum heights and stretch factors
-        for i in 0..count {
-            if let Some(child) = self.inner.at(i) {
-                let limits = child.limits(ctx);
-                let stretch = child.stretch();
-                total_min += limits.min.y;
-                total_stretch += stretch.y;
-            }
-        }
-
-        // Distribute extra space
-        let extra = (height - total_min).max(0.0);
-        let mut y = 0.0f32;
-
-        for i in 0..count {
-            tiles[i] = y;
-            if let Some(child) = self.inner.at(i) {
+        let children: Vec<_> = (0..self.inner.len())
+            .filter_map(|i| self.inner.at(i))
+            .map(|child| {
                 let limits = child.limits(ctx);
-                let stretch = child.stretch();
+                (limits.min.y, limits.max.y, child.stretch().y)
+            })
+            .collect();
+        allocate(&children, height)
+    }
 
-                let mut elem_height = limits.min.y;
-                if total_stretch > 0.0 {
-                    let alloc = extra * (stretch.y / total_stretch);
-                    elem_height = (elem_height + alloc).min(limits.max.y);
-                }
-                y += elem_height;
-            }
+    /// Recomputes the child offsets if the height changed since the last
+    /// layout, or always if `force` is set.
+    fn update_layout(&self, ctx: &Context, force: bool) {
+        let height = ctx.bounds.height();
+        let mut tiles = self.tiles.write().unwrap();
+        if force || tiles.extent != Some(height) || tiles.offsets.len() != self.inner.len() + 1 {
+            let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
+            tiles.offsets = self.compute_layout(&basic_ctx, height);
+            tiles.extent = Some(height);
         }
-        tiles[count] = y;
-
-        tiles
     }
 }
 
@@ -106,22 +137,10 @@ impl Storage for VTile {
 
 impl CompositeBase for VTile {
     fn bounds_of(&self, ctx: &Context, index: usize) -> Rect {
-        // Compute layout if needed
-        // tiles should have count+1 elements, and the last element should be non-zero if properly computed
-        let count = self.inner.len();
-        {
-            let mut tiles = self.tiles.write().unwrap();
-            // Recompute if wrong size or not yet computed (last element is 0)
-            let needs_compute = tiles.len() != count + 1 ||
-                (count > 0 && tiles.get(count).map_or(true, |&v| v == 0.0));
-            if needs_compute && count > 0 {
-                let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
-                let height = ctx.bounds.height();
-                *tiles = self.compute_layout(&basic_ctx, height);
-            }
-        }
+        self.update_layout(ctx, false);
 
         let tiles = self.tiles.read().unwrap();
+        let tiles = &tiles.offsets;
         if index >= tiles.len().saturating_sub(1) {
             return Rect::zero();
         }
@@ -159,6 +178,8 @@ impl Element for VTile {
     }
 
     fn draw(&self, ctx: &Context) {
+        // Children may have changed size since the last frame
+        self.update_layout(ctx, true);
         for i in 0..self.inner.len() {
             if let Some(child) = self.inner.at(i) {
                 let bounds = self.bounds_of(ctx, i);
@@ -201,10 +222,8 @@ impl Element for VTile {
             if let Some(child) = self.inner.at(i) {
                 let child_ctx = ctx.with_bounds(bounds);
                 // Check if this child wants the click via hit_test
-                if child.hit_test(&child_ctx, btn.pos, false, false).is_some() {
-                    if child.handle_click(&child_ctx, btn) {
-                        return true;
-                    }
+                if child.hit_test(&child_ctx, btn.pos, false, false).is_some() && child.handle_click(&child_ctx, btn) {
+                    return true;
                 }
             }
         }
@@ -229,10 +248,43 @@ impl Element for VTile {
             let bounds = self.bounds_of(ctx, i);
             if let Some(child) = self.inner.at(i) {
                 let child_ctx = ctx.with_bounds(bounds);
-                if child.hit_test(&child_ctx, p, false, false).is_some() {
-                    if child.handle_scroll(&child_ctx, dir, p) {
-                        return true;
-                    }
+                if child.hit_test(&child_ctx, p, false, false).is_some() && child.handle_scroll(&child_ctx, dir, p) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: crate::support::point::Point) -> bool {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                let child_ctx = ctx.with_bounds(bounds);
+                if child.hit_test(&child_ctx, center, false, false).is_some() && child.handle_magnify(&child_ctx, scale_delta, center) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &crate::view::DropInfo, status: crate::view::CursorTracking) {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                child.handle_track_drop(&ctx.with_bounds(bounds), info, status);
+            }
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &crate::view::DropInfo) -> bool {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                let child_ctx = ctx.with_bounds(bounds);
+                if child.hit_test(&child_ctx, info.where_, false, false).is_some() && child.handle_drop(&child_ctx, info) {
+                    return true;
                 }
             }
         }
@@ -301,6 +353,47 @@ impl Element for VTile {
         }
     }
 
+    fn has_focus(&self) -> bool {
+        self.inner.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.inner.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.inner.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.inner.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.bounds_of_element(&child_ctx, element)
+        })
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.focus_bounds(&child_ctx)
+        })
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Every child sees the move, so the one the mouse left can tell
+        (0..self.inner.len()).fold(false, |handled, i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| child.handle_cursor(&child_ctx, p, status)) || handled
+        })
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -313,7 +406,7 @@ impl Element for VTile {
 /// Horizontal tile element - arranges children horizontally.
 pub struct HTile {
     inner: Composite,
-    tiles: RwLock<Vec<f32>>,
+    tiles: RwLock<TileLayout>,
 }
 
 impl HTile {
@@ -321,64 +414,45 @@ impl HTile {
     pub fn new() -> Self {
         Self {
             inner: Composite::new(),
-            tiles: RwLock::new(Vec::new()),
+            tiles: RwLock::new(TileLayout::default()),
         }
     }
 
     /// Creates a horizontal tile from a vector of elements.
     pub fn from_vec(children: Vec<ElementPtr>) -> Self {
-        let len = children.len();
         Self {
             inner: Composite::from_vec(children),
-            tiles: RwLock::new(vec![0.0; len + 1]),
+            tiles: RwLock::new(TileLayout::default()),
         }
     }
 
     /// Adds an element.
     pub fn push(&mut self, element: ElementPtr) {
         self.inner.push(element);
-        self.tiles.write().unwrap().push(0.0);
+        self.tiles.write().unwrap().extent = None;
     }
 
     fn compute_layout(&self, ctx: &BasicContext, width: f32) -> Vec<f32> {
-        let count = self.inner.len();
-        if count == 0 {
-            return vec![0.0];
-        }
-
-        let mut tiles = vec![0.0; count + 1];
-        let mut total_min = 0.0f32;
-        let mut total_stretch = 0.0f32;
-
-        for i in 0..count {
-            if let Some(child) = self.inner.at(i) {
+        let children: Vec<_> = (0..self.inner.len())
+            .filter_map(|i| self.inner.at(i))
+            .map(|child| {
                 let limits = child.limits(ctx);
-                let stretch = child.stretch();
-                total_min += limits.min.x;
-                total_stretch += stretch.x;
-            }
-        }
-
-        let extra = (width - total_min).max(0.0);
-        let mut x = 0.0f32;
-
-        for i in 0..count {
-            tiles[i] = x;
-            if let Some(child) = self.inner.at(i) {
-                let limits = child.limits(ctx);
-                let stretch = child.stretch();
+                (limits.min.x, limits.max.x, child.stretch().x)
+            })
+            .collect();
+        allocate(&children, width)
+    }
 
-                let mut elem_width = limits.min.x;
-                if total_stretch > 0.0 {
-                    let alloc = extra * (stretch.x / total_stretch);
-                    elem_width = (elem_width + alloc).min(limits.max.x);
-                }
-                x += elem_width;
-            }
+    /// Recomputes the child offsets if the width changed since the last
+    /// layout, or always if `force` is set.
+    fn update_layout(&self, ctx: &Context, force: bool) {
+        let width = ctx.bounds.width();
+        let mut tiles = self.tiles.write().unwrap();
+        if force || tiles.extent != Some(width) || tiles.offsets.len() != self.inner.len() + 1 {
+            let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
+            tiles.offsets = self.compute_layout(&basic_ctx, width);
+            tiles.extent = Some(width);
         }
-        tiles[count] = x;
-
-        tiles
     }
 }
 
@@ -404,21 +478,10 @@ impl Storage for HTile {
 
 impl CompositeBase for HTile {
     fn bounds_of(&self, ctx: &Context, index: usize) -> Rect {
-        // Compute layout if needed
-        let count = self.inner.len();
-        {
-            let mut tiles = self.tiles.write().unwrap();
-            // Recompute if wrong size or not yet computed (last element is 0)
-            let needs_compute = tiles.len() != count + 1 ||
-                (count > 0 && tiles.get(count).map_or(true, |&v| v == 0.0));
-            if needs_compute && count > 0 {
-                let basic_ctx = BasicContext::new(ctx.view, ctx.canvas);
-                let width = ctx.bounds.width();
-                *tiles = self.compute_layout(&basic_ctx, width);
-            }
-        }
+        self.update_layout(ctx, false);
 
         let tiles = self.tiles.read().unwrap();
+        let tiles = &tiles.offsets;
         if index >= tiles.len().saturating_sub(1) {
             return Rect::zero();
         }
@@ -456,6 +519,8 @@ impl Element for HTile {
     }
 
     fn draw(&self, ctx: &Context) {
+        // Children may have changed size since the last frame
+        self.update_layout(ctx, true);
         for i in 0..self.inner.len() {
             if let Some(child) = self.inner.at(i) {
                 let bounds = self.bounds_of(ctx, i);
@@ -494,10 +559,8 @@ impl Element for HTile {
             if let Some(child) = self.inner.at(i) {
                 let child_ctx = ctx.with_bounds(bounds);
                 // Check if this child wants the click via hit_test
-                if child.hit_test(&child_ctx, btn.pos, false, false).is_some() {
-                    if child.handle_click(&child_ctx, btn) {
-                        return true;
-                    }
+                if child.hit_test(&child_ctx, btn.pos, false, false).is_some() && child.handle_click(&child_ctx, btn) {
+                    return true;
                 }
             }
         }
@@ -522,10 +585,43 @@ impl Element for HTile {
             let bounds = self.bounds_of(ctx, i);
             if let Some(child) = self.inner.at(i) {
                 let child_ctx = ctx.with_bounds(bounds);
-                if child.hit_test(&child_ctx, p, false, false).is_some() {
-                    if child.handle_scroll(&child_ctx, dir, p) {
-                        return true;
-                    }
+                if child.hit_test(&child_ctx, p, false, false).is_some() && child.handle_scroll(&child_ctx, dir, p) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: crate::support::point::Point) -> bool {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                let child_ctx = ctx.with_bounds(bounds);
+                if child.hit_test(&child_ctx, center, false, false).is_some() && child.handle_magnify(&child_ctx, scale_delta, center) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &crate::view::DropInfo, status: crate::view::CursorTracking) {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                child.handle_track_drop(&ctx.with_bounds(bounds), info, status);
+            }
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &crate::view::DropInfo) -> bool {
+        for i in 0..self.inner.len() {
+            let bounds = self.bounds_of(ctx, i);
+            if let Some(child) = self.inner.at(i) {
+                let child_ctx = ctx.with_bounds(bounds);
+                if child.hit_test(&child_ctx, info.where_, false, false).is_some() && child.handle_drop(&child_ctx, info) {
+                    return true;
                 }
             }
         }
@@ -594,6 +690,47 @@ impl Element for HTile {
         }
     }
 
+    fn has_focus(&self) -> bool {
+        self.inner.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.inner.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.inner.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.inner.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.bounds_of_element(&child_ctx, element)
+        })
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        (0..self.inner.len()).find_map(|i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i)?.focus_bounds(&child_ctx)
+        })
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        // Every child sees the move, so the one the mouse left can tell
+        (0..self.inner.len()).fold(false, |handled, i| {
+            let child_ctx = ctx.with_bounds(self.bounds_of(ctx, i));
+            self.inner.at(i).is_some_and(|child| child.handle_cursor(&child_ctx, p, status)) || handled
+        })
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -638,3 +775,60 @@ macro_rules! htile {
         tile
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    /// Element with a minimum height and an optional maximum.
+    struct Block {
+        min: f32,
+        max: f32,
+    }
+
+    impl Element for Block {
+        fn limits(&self, _ctx: &BasicContext) -> ViewLimits {
+            ViewLimits::new(Point::new(0.0, self.min), Point::new(FULL_EXTENT, self.max))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_layout_follows_resize() {
+        let tile = Arc::new(crate::vtile![Block { min: 10.0, max: FULL_EXTENT }, Block { min: 10.0, max: FULL_EXTENT }]);
+        let bounds_at = |height, i| {
+            TestView::new(tile.clone(), Extent::new(100.0, height)).with_context(|ctx| tile.bounds_of(ctx, i))
+        };
+
+        assert_eq!(bounds_at(100.0, 1).bottom, 100.0);
+        assert_eq!(bounds_at(200.0, 0).bottom, 100.0);
+        assert_eq!(bounds_at(200.0, 1).bottom, 200.0);
+    }
+
+    #[test]
+    fn test_space_left_by_capped_child_goes_to_others() {
+        let tile = Arc::new(crate::vtile![Block { min: 10.0, max: 30.0 }, Block { min: 10.0, max: FULL_EXTENT }]);
+        let view = TestView::new(tile.clone(), Extent::new(100.0, 200.0));
+
+        view.with_context(|ctx| {
+            assert_eq!(tile.bounds_of(ctx, 0), Rect::new(0.0, 0.0, 100.0, 30.0));
+            assert_eq!(tile.bounds_of(ctx, 1), Rect::new(0.0, 30.0, 100.0, 200.0));
+        });
+
+        // Stretch still sets the proportions among the others
+        assert_eq!(
+            allocate(&[(0.0, 10.0, 1.0), (0.0, FULL_EXTENT, 1.0), (0.0, FULL_EXTENT, 3.0)], 90.0),
+            vec![0.0, 10.0, 30.0, 90.0],
+        );
+    }
+}
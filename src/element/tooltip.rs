@@ -2,13 +2,47 @@
 
 use std::any::Any;
 use std::sync::RwLock;
-use super::{Element, ElementPtr, ViewLimits, ViewStretch, share};
+use std::time::{Duration, Instant};
+use super::{Element, ElementPtr, FocusRequest, ViewLimits, ViewStretch, share};
 use super::context::{BasicContext, Context};
 use crate::support::point::Point;
 use crate::support::rect::Rect;
 use crate::support::color::Color;
-use crate::support::theme::get_theme;
-use crate::view::CursorTracking;
+use crate::support::theme::{get_theme, ThemeColor};
+use crate::view::{CursorTracking, DropInfo, KeyInfo, MouseButton, TextInfo};
+
+/// Places a tooltip box of `size` next to the cursor at `anchor`, keeping
+/// it inside `view`.
+///
+/// The box goes below and to the right of the cursor where it fits, flips
+/// to the left or above where that fits instead, and is otherwise shifted
+/// inside the view.
+fn place_tooltip(anchor: Point, size: Point, view: Rect) -> Rect {
+    let mut left = anchor.x + 10.0;
+    let flipped_left = anchor.x - 10.0 - size.x;
+    if left + size.x > view.right && flipped_left >= view.left {
+        left = flipped_left;
+    }
+    let mut top = anchor.y + 20.0;
+    let flipped_top = anchor.y - 10.0 - size.y;
+    if top + size.y > view.bottom && flipped_top >= view.top {
+        top = flipped_top;
+    }
+
+    left = left.min(view.right - size.x).max(view.left);
+    top = top.min(view.bottom - size.y).max(view.top);
+    Rect::new(left, top, left + size.x, top + size.y)
+}
+
+/// Measures the box needed to show `text` at `font_size` with `padding`.
+fn tooltip_size(ctx: &Context, text: &str, font_size: f32, padding: f32) -> Point {
+    let mut canvas = ctx.canvas.borrow_mut();
+    canvas.save();
+    canvas.font_size(font_size);
+    let width = canvas.text_width(text);
+    canvas.restore();
+    Point::new(width + padding * 2.0, font_size + padding * 2.0)
+}
 
 /// A tooltip wrapper element.
 pub struct Tooltip {
@@ -16,12 +50,17 @@ pub struct Tooltip {
     tooltip_text: String,
     visible: RwLock<bool>,
     position: RwLock<Point>,
-    background_color: Color,
-    text_color: Color,
+    background_color: ThemeColor,
+    text_color: ThemeColor,
     font_size: f32,
     padding: f32,
     corner_radius: f32,
-    delay_ms: u32,
+    delay: Duration,
+    fade: Duration,
+    /// When the cursor came to rest over the content, while waiting to show
+    hover_start: RwLock<Option<Instant>>,
+    opacity: RwLock<f32>,
+    last_tick: RwLock<Option<Instant>>,
 }
 
 impl Tooltip {
@@ -33,12 +72,16 @@ impl Tooltip {
             tooltip_text: text.into(),
             visible: RwLock::new(false),
             position: RwLock::new(Point::zero()),
-            background_color: theme.tooltip_color,
-            text_color: theme.tooltip_text_color,
+            background_color: ThemeColor::Theme(|theme| theme.tooltip_color),
+            text_color: ThemeColor::Theme(|theme| theme.tooltip_text_color),
             font_size: theme.tooltip_font_size,
             padding: 6.0,
             corner_radius: 4.0,
-            delay_ms: 500,
+            delay: Duration::from_millis(500),
+            fade: Duration::from_millis(150),
+            hover_start: RwLock::new(None),
+            opacity: RwLock::new(0.0),
+            last_tick: RwLock::new(None),
         }
     }
 
@@ -56,19 +99,26 @@ impl Tooltip {
 
     /// Sets the background color.
     pub fn background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = color.into();
         self
     }
 
     /// Sets the text color.
     pub fn text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = color.into();
         self
     }
 
-    /// Sets the delay in milliseconds.
-    pub fn delay(mut self, ms: u32) -> Self {
-        self.delay_ms = ms;
+    /// Sets how long the cursor must rest over the content before the
+    /// tooltip appears.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets how long the tooltip takes to fade in and out.
+    pub fn fade(mut self, fade: Duration) -> Self {
+        self.fade = fade;
         self
     }
 
@@ -76,11 +126,18 @@ impl Tooltip {
     pub fn show(&self, position: Point) {
         *self.position.write().unwrap() = position;
         *self.visible.write().unwrap() = true;
+        if self.fade.is_zero() {
+            *self.opacity.write().unwrap() = 1.0;
+        }
     }
 
     /// Hides the tooltip.
     pub fn hide(&self) {
         *self.visible.write().unwrap() = false;
+        *self.hover_start.write().unwrap() = None;
+        if self.fade.is_zero() {
+            *self.opacity.write().unwrap() = 0.0;
+        }
     }
 
     /// Returns whether the tooltip is visible.
@@ -88,28 +145,44 @@ impl Tooltip {
         *self.visible.read().unwrap()
     }
 
-    fn tooltip_bounds(&self) -> Rect {
-        let pos = *self.position.read().unwrap();
-        let width = self.tooltip_text.len() as f32 * self.font_size * 0.55 + self.padding * 2.0;
-        let height = self.font_size + self.padding * 2.0;
-
-        // Position tooltip below and slightly to the right of cursor
-        Rect::new(
-            pos.x + 10.0,
-            pos.y + 20.0,
-            pos.x + 10.0 + width,
-            pos.y + 20.0 + height,
-        )
+    fn tooltip_bounds(&self, ctx: &Context) -> Rect {
+        let size = tooltip_size(ctx, &self.tooltip_text, self.font_size, self.padding);
+        place_tooltip(*self.position.read().unwrap(), size, ctx.view_bounds())
     }
 
-    fn draw_tooltip(&self, _ctx: &Context) {
-        if !self.is_visible() || self.tooltip_text.is_empty() {
-            return;
+    /// Shows the tooltip once the hover delay has passed and steps the fade.
+    ///
+    /// Returns true if the tooltip needs to be redrawn.
+    fn tick(&self, now: Instant) -> bool {
+        let last_tick = self.last_tick.write().unwrap().replace(now);
+        let dt = last_tick.map_or(0.0, |t| now.duration_since(t).as_secs_f32());
+
+        let hover_start = *self.hover_start.read().unwrap();
+        if let Some(start) = hover_start {
+            if !self.is_visible() && now.duration_since(start) >= self.delay {
+                let position = *self.position.read().unwrap();
+                self.show(position);
+                // The fade starts from this tick
+                return true;
+            }
         }
 
-        // Note: In a real implementation, tooltips would typically be drawn
-        // in a separate overlay layer after all other content, to ensure
-        // they appear on top. This simplified version draws inline.
+        let target = if self.is_visible() { 1.0 } else { 0.0 };
+        let mut opacity = self.opacity.write().unwrap();
+        if *opacity == target {
+            if hover_start.is_none() {
+                *self.last_tick.write().unwrap() = None;
+            }
+            return false;
+        }
+
+        let step = dt / self.fade.as_secs_f32();
+        *opacity = if target > *opacity {
+            (*opacity + step).min(target)
+        } else {
+            (*opacity - step).max(target)
+        };
+        true
     }
 }
 
@@ -136,22 +209,24 @@ impl Element for Tooltip {
             content.draw(ctx);
         }
 
-        // Draw tooltip if visible
-        if self.is_visible() && !self.tooltip_text.is_empty() {
-            let bounds = self.tooltip_bounds();
+        // Draw tooltip while visible or fading out
+        let opacity = *self.opacity.read().unwrap();
+        if opacity > 0.0 && !self.tooltip_text.is_empty() {
+            let bounds = self.tooltip_bounds(ctx);
             let mut canvas = ctx.canvas.borrow_mut();
 
             // Shadow
             let shadow_rect = bounds.translate(2.0, 2.0);
-            canvas.fill_style(Color::new(0.0, 0.0, 0.0, 0.3));
+            canvas.fill_style(Color::new(0.0, 0.0, 0.0, 0.3 * opacity));
             canvas.fill_round_rect(shadow_rect, self.corner_radius);
 
             // Background
-            canvas.fill_style(self.background_color);
+            let background = self.background_color.get();
+            canvas.fill_style(background.with_alpha(background.alpha * opacity));
             canvas.fill_round_rect(bounds, self.corner_radius);
 
             // Text
-            canvas.fill_style(self.text_color);
+            canvas.fill_style(self.text_color.get().with_alpha(self.text_color.get().alpha * opacity));
             canvas.font_size(self.font_size);
 
             let x = bounds.left + self.padding;
@@ -180,7 +255,7 @@ impl Element for Tooltip {
         }
     }
 
-    fn handle_click(&self, ctx: &Context, btn: crate::view::MouseButton) -> bool {
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
         // Hide tooltip on click
         self.hide();
 
@@ -192,19 +267,102 @@ impl Element for Tooltip {
     }
 
     fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
-        match status {
-            CursorTracking::Entering | CursorTracking::Hovering => {
-                if ctx.bounds.contains(p) {
-                    // Show tooltip after delay (simplified - immediate show)
-                    self.show(p);
-                }
+        self.handle_cursor(ctx, p, status)
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        if let Some(ref content) = self.content {
+            content.handle_drag(ctx, btn);
+        }
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        // Typing hides the tooltip, as clicking does
+        self.hide();
+        self.content.as_ref().is_some_and(|content| content.handle_key(ctx, k))
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_text(ctx, info))
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_scroll(ctx, dir, p))
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_magnify(ctx, scale_delta, center))
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        if let Some(ref content) = self.content {
+            content.handle_track_drop(ctx, info, status);
+        }
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_drop(ctx, info))
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.wants_focus())
+    }
+
+    fn clear_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.clear_focus();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.content.as_ref().is_some_and(|content| content.has_focus())
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_begin_focus(req))
+    }
+
+    fn handle_end_focus(&self) {
+        if let Some(ref content) = self.content {
+            content.handle_end_focus();
+        }
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.content.as_ref().is_some_and(|content| content.handle_focus_next(forward))
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        self.content.as_ref()?.bounds_of_element(ctx, element)
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        self.content.as_ref()?.focus_bounds(ctx)
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let content_handled = self.content.as_ref().is_some_and(|content| content.handle_cursor(ctx, p, status));
+        if status != CursorTracking::Leaving && ctx.bounds.contains(p) {
+            // Until the tooltip shows, every movement restarts the delay
+            if !self.is_visible() {
+                *self.position.write().unwrap() = p;
+                *self.hover_start.write().unwrap() = Some(Instant::now());
             }
-            CursorTracking::Leaving => {
+            true
+        } else {
+            if self.is_visible() || self.hover_start.read().unwrap().is_some() {
                 self.hide();
             }
+            content_handled
         }
+    }
 
-        true
+    fn handle_poll(&self) -> bool {
+        let content_redraw = self.content.as_ref().is_some_and(|c| c.handle_poll());
+        self.tick(Instant::now()) || content_redraw
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -221,8 +379,8 @@ pub struct TooltipOverlay {
     text: RwLock<String>,
     visible: RwLock<bool>,
     position: RwLock<Point>,
-    background_color: Color,
-    text_color: Color,
+    background_color: ThemeColor,
+    text_color: ThemeColor,
     font_size: f32,
     padding: f32,
     corner_radius: f32,
@@ -236,8 +394,8 @@ impl TooltipOverlay {
             text: RwLock::new(String::new()),
             visible: RwLock::new(false),
             position: RwLock::new(Point::zero()),
-            background_color: theme.tooltip_color,
-            text_color: theme.tooltip_text_color,
+            background_color: ThemeColor::Theme(|theme| theme.tooltip_color),
+            text_color: ThemeColor::Theme(|theme| theme.tooltip_text_color),
             font_size: theme.tooltip_font_size,
             padding: 6.0,
             corner_radius: 4.0,
@@ -261,18 +419,9 @@ impl TooltipOverlay {
         *self.visible.read().unwrap()
     }
 
-    fn tooltip_bounds(&self) -> Rect {
-        let pos = *self.position.read().unwrap();
-        let text = self.text.read().unwrap();
-        let width = text.len() as f32 * self.font_size * 0.55 + self.padding * 2.0;
-        let height = self.font_size + self.padding * 2.0;
-
-        Rect::new(
-            pos.x + 10.0,
-            pos.y + 20.0,
-            pos.x + 10.0 + width,
-            pos.y + 20.0 + height,
-        )
+    fn tooltip_bounds(&self, ctx: &Context) -> Rect {
+        let size = tooltip_size(ctx, &self.text.read().unwrap(), self.font_size, self.padding);
+        place_tooltip(*self.position.read().unwrap(), size, ctx.view_bounds())
     }
 }
 
@@ -301,7 +450,7 @@ impl Element for TooltipOverlay {
             return;
         }
 
-        let bounds = self.tooltip_bounds();
+        let bounds = self.tooltip_bounds(ctx);
         let mut canvas = ctx.canvas.borrow_mut();
 
         // Shadow
@@ -310,11 +459,11 @@ impl Element for TooltipOverlay {
         canvas.fill_round_rect(shadow_rect, self.corner_radius);
 
         // Background
-        canvas.fill_style(self.background_color);
+        canvas.fill_style(self.background_color.get());
         canvas.fill_round_rect(bounds, self.corner_radius);
 
         // Text
-        canvas.fill_style(self.text_color);
+        canvas.fill_style(self.text_color.get());
         canvas.font_size(self.font_size);
 
         let x = bounds.left + self.padding;
@@ -340,3 +489,89 @@ pub fn tooltip(text: impl Into<String>) -> Tooltip {
 pub fn tooltip_overlay() -> TooltipOverlay {
     TooltipOverlay::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::support::point::Extent;
+    use crate::view::testing::TestView;
+
+    #[test]
+    fn test_delay_and_fade() {
+        let tip = Arc::new(
+            tooltip("Help")
+                .delay(Duration::from_millis(500))
+                .fade(Duration::from_millis(100)),
+        );
+        let view = TestView::new(tip.clone(), Extent::new(100.0, 30.0));
+        assert!(view.hover(Point::new(50.0, 15.0)));
+        let start = Instant::now();
+
+        // Nothing shows before the delay
+        tip.tick(start + Duration::from_millis(400));
+        assert!(!tip.is_visible());
+
+        // Then it fades in over the fade duration
+        assert!(tip.tick(start + Duration::from_millis(500)));
+        assert!(tip.is_visible());
+        assert_eq!(*tip.opacity.read().unwrap(), 0.0);
+        tip.tick(start + Duration::from_millis(550));
+        assert!((*tip.opacity.read().unwrap() - 0.5).abs() < 1e-3);
+        tip.tick(start + Duration::from_millis(700));
+        assert_eq!(*tip.opacity.read().unwrap(), 1.0);
+        assert!(!tip.tick(start + Duration::from_millis(750)));
+
+        // And fades back out once hidden
+        tip.hide();
+        assert!(tip.tick(start + Duration::from_millis(800)));
+        assert!(tip.tick(start + Duration::from_millis(900)));
+        assert_eq!(*tip.opacity.read().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_hover_inside_container() {
+        use crate::element::empty;
+        use crate::element::size::fixed_size;
+        use crate::element::tile::VTile;
+
+        let tip = Arc::new(tooltip("Help").delay(Duration::ZERO).fade(Duration::ZERO).content(fixed_size(100.0, 30.0, empty())));
+        let tile = VTile::from_vec(vec![tip.clone(), Arc::new(fixed_size(100.0, 30.0, empty()))]);
+        let view = TestView::new(Arc::new(tile), Extent::new(100.0, 60.0));
+
+        // Resting over the content shows the tooltip on the next tick
+        view.hover(Point::new(50.0, 15.0));
+        assert!(!tip.is_visible());
+        assert!(view.poll());
+        assert!(tip.is_visible());
+
+        // Moving onto a sibling hides it
+        assert!(!view.hover(Point::new(50.0, 45.0)));
+        assert!(!tip.is_visible());
+        assert!(!view.poll());
+
+        // As does leaving the view
+        view.hover(Point::new(50.0, 15.0));
+        view.poll();
+        view.leave(Point::new(50.0, 15.0));
+        assert!(!tip.is_visible());
+    }
+
+    #[test]
+    fn test_placement_stays_in_view() {
+        let view = Rect::new(0.0, 0.0, 400.0, 300.0);
+        let size = Point::new(100.0, 24.0);
+
+        // Below and to the right when there is room
+        let bounds = place_tooltip(Point::new(50.0, 50.0), size, view);
+        assert_eq!((bounds.left, bounds.top), (60.0, 70.0));
+
+        // Flipped to the left and above near the bottom-right corner
+        let bounds = place_tooltip(Point::new(380.0, 290.0), size, view);
+        assert_eq!((bounds.right, bounds.bottom), (370.0, 280.0));
+
+        // Shifted inside a view too narrow to flip into
+        let bounds = place_tooltip(Point::new(60.0, 50.0), size, Rect::new(0.0, 0.0, 120.0, 300.0));
+        assert_eq!((bounds.left, bounds.right), (20.0, 120.0));
+    }
+}
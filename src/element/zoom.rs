@@ -0,0 +1,363 @@
+//! Zoomable element that scales its subject with trackpad pinches.
+
+use std::any::Any;
+use std::sync::RwLock;
+use super::{Element, ViewLimits, ViewStretch, FocusRequest};
+use super::context::{BasicContext, Context};
+use crate::support::point::Point;
+use crate::support::rect::Rect;
+use crate::view::{MouseButton, KeyInfo, TextInfo, DropInfo, CursorTracking, CursorType};
+
+/// Distance scrolled per scroll step while panning zoomed content.
+const PAN_STEP: f32 = 20.0;
+
+/// Draws its subject magnified, zooming in and out with trackpad pinches
+/// around the point being pinched.
+///
+/// The subject is laid out in the zoomable's bounds and then scaled, so
+/// it draws the same content at any zoom. While zoomed in, scroll events
+/// the subject ignores pan the view. Events reach the subject in its own,
+/// unscaled coordinates.
+pub struct Zoomable<S: Element> {
+    subject: S,
+    scale: RwLock<f32>,
+    /// Offset of the scaled subject's top left from the bounds' top left
+    offset: RwLock<Point>,
+    min_scale: f32,
+    max_scale: f32,
+}
+
+impl<S: Element> Zoomable<S> {
+    /// Creates a zoomable element at a scale of 1.
+    pub fn new(subject: S) -> Self {
+        Self {
+            subject,
+            scale: RwLock::new(1.0),
+            offset: RwLock::new(Point::zero()),
+            min_scale: 1.0,
+            max_scale: 8.0,
+        }
+    }
+
+    /// Sets the smallest and largest scale. Defaults to 1 and 8.
+    pub fn scale_range(mut self, min: f32, max: f32) -> Self {
+        self.min_scale = min.max(f32::EPSILON);
+        self.max_scale = max.max(self.min_scale);
+        self
+    }
+
+    /// Returns a reference to the subject.
+    pub fn subject(&self) -> &S {
+        &self.subject
+    }
+
+    /// Returns the current scale.
+    pub fn get_scale(&self) -> f32 {
+        *self.scale.read().unwrap()
+    }
+
+    /// Returns the subject to a scale of 1, filling the bounds.
+    pub fn reset(&self) {
+        *self.scale.write().unwrap() = 1.0;
+        *self.offset.write().unwrap() = Point::zero();
+    }
+
+    /// Maps a point in the bounds to the subject's unscaled coordinates.
+    fn to_subject(&self, bounds: Rect, p: Point) -> Point {
+        let origin = bounds.top_left();
+        origin + (p - origin - *self.offset.read().unwrap()) / self.get_scale()
+    }
+
+    /// Maps a rect in the subject's coordinates to where it is drawn.
+    fn to_view_rect(&self, bounds: Rect, r: Rect) -> Rect {
+        let origin = bounds.top_left();
+        let offset = *self.offset.read().unwrap();
+        let scale = self.get_scale();
+        let map = |p: Point| origin + offset + (p - origin) * scale;
+        let (top_left, bottom_right) = (map(r.top_left()), map(Point::new(r.right, r.bottom)));
+        Rect::new(top_left.x, top_left.y, bottom_right.x, bottom_right.y)
+    }
+
+    /// Maps a rect in the bounds to the subject's coordinates.
+    fn to_subject_rect(&self, bounds: Rect, r: Rect) -> Rect {
+        let top_left = self.to_subject(bounds, r.top_left());
+        let bottom_right = self.to_subject(bounds, Point::new(r.right, r.bottom));
+        Rect::new(top_left.x, top_left.y, bottom_right.x, bottom_right.y)
+    }
+
+    /// Sets the offset, keeping a zoomed-in subject covering the bounds and
+    /// a zoomed-out one inside them.
+    fn set_offset(&self, bounds: Rect, offset: Point) {
+        let scale = self.get_scale();
+        let clamp = |offset: f32, extent: f32| {
+            let slack = extent * (1.0 - scale);
+            offset.clamp(slack.min(0.0), slack.max(0.0))
+        };
+        *self.offset.write().unwrap() = Point::new(clamp(offset.x, bounds.width()), clamp(offset.y, bounds.height()));
+    }
+
+    /// Multiplies the scale by `factor`, keeping the subject under `center`
+    /// in place.
+    fn zoom_about(&self, bounds: Rect, factor: f32, center: Point) {
+        let old_scale = self.get_scale();
+        let new_scale = (old_scale * factor).clamp(self.min_scale, self.max_scale);
+        *self.scale.write().unwrap() = new_scale;
+
+        let anchor = center - bounds.top_left();
+        let offset = *self.offset.read().unwrap();
+        self.set_offset(bounds, anchor - (anchor - offset) * (new_scale / old_scale));
+    }
+
+    /// Returns a copy of `btn` with its position in subject coordinates.
+    fn subject_button(&self, ctx: &Context, mut btn: MouseButton) -> MouseButton {
+        btn.pos = self.to_subject(ctx.bounds, btn.pos);
+        btn
+    }
+}
+
+impl<S: Element + 'static> Element for Zoomable<S> {
+    fn limits(&self, ctx: &BasicContext) -> ViewLimits {
+        self.subject.limits(ctx)
+    }
+
+    fn stretch(&self) -> ViewStretch {
+        self.subject.stretch()
+    }
+
+    fn span(&self) -> u32 {
+        self.subject.span()
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let origin = ctx.bounds.top_left();
+        let scale = self.get_scale();
+        {
+            let mut canvas = ctx.canvas.borrow_mut();
+            canvas.push_clip(ctx.bounds);
+            canvas.save();
+            canvas.translate(origin + *self.offset.read().unwrap());
+            canvas.scale(scale, scale);
+            canvas.translate(-origin);
+        }
+
+        // Only the part of the subject that shows in the bounds is visible
+        let visible = ctx.visible_bounds().unwrap_or(Rect::zero());
+        let mut subject_ctx = ctx.with_bounds(ctx.bounds);
+        subject_ctx.clip = Some(self.to_subject_rect(ctx.bounds, visible));
+        self.subject.draw(&subject_ctx);
+
+        let mut canvas = ctx.canvas.borrow_mut();
+        canvas.restore();
+        canvas.pop_clip();
+    }
+
+    fn layout(&mut self, ctx: &Context) {
+        self.subject.layout(ctx);
+    }
+
+    fn hit_test(&self, ctx: &Context, p: Point, leaf: bool, control: bool) -> Option<&dyn Element> {
+        if !ctx.bounds.contains(p) {
+            return None;
+        }
+        self.subject.hit_test(ctx, self.to_subject(ctx.bounds, p), leaf, control)
+    }
+
+    fn wants_control(&self) -> bool {
+        self.subject.wants_control()
+    }
+
+    fn click(&mut self, ctx: &Context, btn: MouseButton) -> bool {
+        self.handle_click(ctx, btn)
+    }
+
+    fn handle_click(&self, ctx: &Context, btn: MouseButton) -> bool {
+        self.subject.handle_click(ctx, self.subject_button(ctx, btn))
+    }
+
+    fn drag(&mut self, ctx: &Context, btn: MouseButton) {
+        self.handle_drag(ctx, btn);
+    }
+
+    fn handle_drag(&self, ctx: &Context, btn: MouseButton) {
+        self.subject.handle_drag(ctx, self.subject_button(ctx, btn));
+    }
+
+    fn handle_key(&self, ctx: &Context, k: KeyInfo) -> bool {
+        self.subject.handle_key(ctx, k)
+    }
+
+    fn handle_text(&self, ctx: &Context, info: TextInfo) -> bool {
+        self.subject.handle_text(ctx, info)
+    }
+
+    fn cursor(&mut self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        let p = self.to_subject(ctx.bounds, p);
+        self.subject.cursor(ctx, p, status)
+    }
+
+    fn cursor_type(&self, ctx: &Context, p: Point) -> Option<CursorType> {
+        self.subject.cursor_type(ctx, self.to_subject(ctx.bounds, p))
+    }
+
+    fn scroll(&mut self, ctx: &Context, dir: Point, p: Point) -> bool {
+        self.handle_scroll(ctx, dir, p)
+    }
+
+    fn handle_scroll(&self, ctx: &Context, dir: Point, p: Point) -> bool {
+        if self.subject.handle_scroll(ctx, dir, self.to_subject(ctx.bounds, p)) {
+            return true;
+        }
+        if self.get_scale() <= 1.0 {
+            return false;
+        }
+        let offset = *self.offset.read().unwrap();
+        self.set_offset(ctx.bounds, offset + dir * PAN_STEP);
+        true
+    }
+
+    fn handle_magnify(&self, ctx: &Context, scale_delta: f32, center: Point) -> bool {
+        if !ctx.bounds.contains(center) {
+            return false;
+        }
+        self.zoom_about(ctx.bounds, 1.0 + scale_delta, center);
+        true
+    }
+
+    fn handle_track_drop(&self, ctx: &Context, info: &DropInfo, status: CursorTracking) {
+        let mut info = info.clone();
+        info.where_ = self.to_subject(ctx.bounds, info.where_);
+        self.subject.handle_track_drop(ctx, &info, status);
+    }
+
+    fn handle_drop(&self, ctx: &Context, info: &DropInfo) -> bool {
+        let mut info = info.clone();
+        info.where_ = self.to_subject(ctx.bounds, info.where_);
+        self.subject.handle_drop(ctx, &info)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.subject.is_enabled()
+    }
+
+    fn enable(&mut self, state: bool) {
+        self.subject.enable(state);
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.subject.wants_focus()
+    }
+
+    fn begin_focus(&mut self, req: FocusRequest) {
+        self.subject.begin_focus(req);
+    }
+
+    fn end_focus(&mut self) -> bool {
+        self.subject.end_focus()
+    }
+
+    fn focus(&self) -> Option<&dyn Element> {
+        self.subject.focus()
+    }
+
+    fn focus_mut(&mut self) -> Option<&mut dyn Element> {
+        self.subject.focus_mut()
+    }
+
+    fn clear_focus(&self) {
+        self.subject.clear_focus();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.subject.has_focus()
+    }
+
+    fn handle_begin_focus(&self, req: FocusRequest) -> bool {
+        self.subject.handle_begin_focus(req)
+    }
+
+    fn handle_focus_next(&self, forward: bool) -> bool {
+        self.subject.handle_focus_next(forward)
+    }
+
+    fn handle_poll(&self) -> bool {
+        self.subject.handle_poll()
+    }
+
+    fn bounds_of_element(&self, ctx: &Context, element: &dyn Element) -> Option<Rect> {
+        if std::ptr::addr_eq(self, element) {
+            return Some(ctx.bounds);
+        }
+        let bounds = self.subject.bounds_of_element(ctx, element)?;
+        Some(self.to_view_rect(ctx.bounds, bounds))
+    }
+
+    fn focus_bounds(&self, ctx: &Context) -> Option<Rect> {
+        let bounds = self.subject.focus_bounds(ctx)?;
+        Some(self.to_view_rect(ctx.bounds, bounds))
+    }
+
+    fn handle_cursor(&self, ctx: &Context, p: Point, status: CursorTracking) -> bool {
+        self.subject.handle_cursor(ctx, self.to_subject(ctx.bounds, p), status)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Wraps `subject` so trackpad pinches zoom it.
+pub fn zoomable<S: Element>(subject: S) -> Zoomable<S> {
+    Zoomable::new(subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::element::share;
+    use crate::element::button::button;
+    use crate::element::size::vsize;
+    use crate::element::tile::VTile;
+    use crate::support::point::Extent;
+    use crate::view::TestView;
+
+    #[test]
+    fn test_pinch_zooms_about_center() {
+        let clicks = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+        let counter = |i: usize| {
+            let clicks = clicks.clone();
+            move || { clicks[i].fetch_add(1, Ordering::SeqCst); }
+        };
+        let tile = VTile::from_vec(vec![
+            share(vsize(50.0, button("Top").on_click(counter(0)))),
+            share(vsize(50.0, button("Bottom").on_click(counter(1)))),
+        ]);
+        let zoom = Arc::new(zoomable(tile));
+        let view = TestView::new(zoom.clone(), Extent::new(100.0, 100.0));
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        // Pinching out at the top doubles the scale and keeps the top in place
+        assert!(view.magnify(1.0, Point::new(50.0, 0.0)));
+        assert_eq!(zoom.get_scale(), 2.0);
+        assert_eq!(zoom.to_subject(bounds, Point::new(50.0, 0.0)), Point::new(50.0, 0.0));
+        assert_eq!(zoom.to_subject(bounds, Point::new(0.0, 100.0)), Point::new(25.0, 50.0));
+
+        // The top button now fills the view, and clicks land on it
+        view.click_at(Point::new(50.0, 90.0));
+        assert_eq!(clicks[0].load(Ordering::SeqCst), 1);
+        assert_eq!(clicks[1].load(Ordering::SeqCst), 0);
+
+        // The scale stays in range, and zooming out refills the bounds
+        view.magnify(100.0, Point::new(50.0, 50.0));
+        assert_eq!(zoom.get_scale(), 8.0);
+        view.magnify(-0.99, Point::new(10.0, 90.0));
+        assert_eq!(zoom.get_scale(), 1.0);
+        assert_eq!(*zoom.offset.read().unwrap(), Point::zero());
+        assert!(view.render().is_some());
+    }
+}
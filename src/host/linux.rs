@@ -5,120 +5,104 @@
 
 #![cfg(target_os = "linux")]
 
-use std::sync::Arc;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-use x11rb::connection::Connection;
+use x11rb::connection::{Connection, RequestConnection};
 use x11rb::protocol::xproto::*;
 use x11rb::protocol::Event;
 use x11rb::rust_connection::RustConnection;
 use x11rb::protocol::xproto::ConnectionExt;
+use x11rb::wrapper::ConnectionExt as _;
 use x11rb::COPY_DEPTH_FROM_PARENT;
 
 use crate::support::point::{Point, Extent};
+use crate::support::canvas::Canvas;
+use crate::support::rect::{union, Rect};
+use crate::element::context::Context;
+use crate::element::ElementPtr;
+use super::{close_allowed, notify, CloseRequestCallback, ClosedCallback, ResizeCallback, FullscreenCallback, WINDOW_BACKGROUND};
 use crate::view::{
-    View, BaseView, MouseButton, MouseButtonKind, KeyCode, KeyAction, KeyInfo,
-    TextInfo, CursorTracking, CursorType, DropInfo,
+    View, BaseView, MouseButton, MouseButtonKind, CursorTracking, KeyCode, KeyAction, KeyInfo, TextInfo, CursorType,
+    modifiers, ClickCounter, cursor_type_at, dispatch_click, dispatch_key,
 };
 
-/// Translates an X11 keycode to our KeyCode enum.
-pub fn translate_key(keycode: u8) -> KeyCode {
-    // X11 keycodes are hardware-dependent, this is a simplified mapping
-    // In practice, you'd use XKB for proper key translation
-    match keycode {
-        9 => KeyCode::Escape,
-        10..=19 => {
-            // 1-0 keys
-            let idx = keycode - 10;
-            match idx {
-                0 => KeyCode::Key1,
-                1 => KeyCode::Key2,
-                2 => KeyCode::Key3,
-                3 => KeyCode::Key4,
-                4 => KeyCode::Key5,
-                5 => KeyCode::Key6,
-                6 => KeyCode::Key7,
-                7 => KeyCode::Key8,
-                8 => KeyCode::Key9,
-                9 => KeyCode::Key0,
-                _ => KeyCode::Unknown,
-            }
-        }
-        22 => KeyCode::Backspace,
-        23 => KeyCode::Tab,
-        24 => KeyCode::Q,
-        25 => KeyCode::W,
-        26 => KeyCode::E,
-        27 => KeyCode::R,
-        28 => KeyCode::T,
-        29 => KeyCode::Y,
-        30 => KeyCode::U,
-        31 => KeyCode::I,
-        32 => KeyCode::O,
-        33 => KeyCode::P,
-        36 => KeyCode::Enter,
-        37 => KeyCode::LeftControl,
-        38 => KeyCode::A,
-        39 => KeyCode::S,
-        40 => KeyCode::D,
-        41 => KeyCode::F,
-        42 => KeyCode::G,
-        43 => KeyCode::H,
-        44 => KeyCode::J,
-        45 => KeyCode::K,
-        46 => KeyCode::L,
-        50 => KeyCode::LeftShift,
-        52 => KeyCode::Z,
-        53 => KeyCode::X,
-        54 => KeyCode::C,
-        55 => KeyCode::V,
-        56 => KeyCode::B,
-        57 => KeyCode::N,
-        58 => KeyCode::M,
-        62 => KeyCode::RightShift,
-        64 => KeyCode::LeftAlt,
-        65 => KeyCode::Space,
-        66 => KeyCode::CapsLock,
-        67..=76 => {
-            // F1-F10
-            let idx = keycode - 67;
-            match idx {
-                0 => KeyCode::F1,
-                1 => KeyCode::F2,
-                2 => KeyCode::F3,
-                3 => KeyCode::F4,
-                4 => KeyCode::F5,
-                5 => KeyCode::F6,
-                6 => KeyCode::F7,
-                7 => KeyCode::F8,
-                8 => KeyCode::F9,
-                9 => KeyCode::F10,
-                _ => KeyCode::Unknown,
-            }
-        }
-        95 => KeyCode::F11,
-        96 => KeyCode::F12,
-        105 => KeyCode::RightControl,
-        108 => KeyCode::RightAlt,
-        110 => KeyCode::Home,
-        111 => KeyCode::Up,
-        112 => KeyCode::PageUp,
-        113 => KeyCode::Left,
-        114 => KeyCode::Right,
-        115 => KeyCode::End,
-        116 => KeyCode::Down,
-        117 => KeyCode::PageDown,
-        118 => KeyCode::Insert,
-        119 => KeyCode::Delete,
-        133 => KeyCode::LeftSuper,
-        134 => KeyCode::RightSuper,
+/// Interval between idle ticks while no events are pending.
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+];
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+];
+
+const FUNCTION_KEYS: [KeyCode; 12] = [
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+];
+
+/// Translates an X11 keysym to our KeyCode enum.
+pub fn translate_keysym(keysym: Keysym) -> KeyCode {
+    match keysym {
+        0x61..=0x7a => LETTERS[(keysym - 0x61) as usize],
+        0x41..=0x5a => LETTERS[(keysym - 0x41) as usize],
+        0x30..=0x39 => DIGITS[(keysym - 0x30) as usize],
+        0xffbe..=0xffc9 => FUNCTION_KEYS[(keysym - 0xffbe) as usize],
+        0x20 => KeyCode::Space,
+        0xff08 => KeyCode::Backspace,
+        0xff09 | 0xfe20 => KeyCode::Tab,   // Tab, ISO_Left_Tab
+        0xff0d | 0xff8d => KeyCode::Enter, // Return, KP_Enter
+        0xff1b => KeyCode::Escape,
+        0xffff => KeyCode::Delete,
+        0xff63 => KeyCode::Insert,
+        0xff50 => KeyCode::Home,
+        0xff51 => KeyCode::Left,
+        0xff52 => KeyCode::Up,
+        0xff53 => KeyCode::Right,
+        0xff54 => KeyCode::Down,
+        0xff55 => KeyCode::PageUp,
+        0xff56 => KeyCode::PageDown,
+        0xff57 => KeyCode::End,
+        0xffe1 => KeyCode::LeftShift,
+        0xffe2 => KeyCode::RightShift,
+        0xffe3 => KeyCode::LeftControl,
+        0xffe4 => KeyCode::RightControl,
+        0xffe5 => KeyCode::CapsLock,
+        0xffe9 => KeyCode::LeftAlt,
+        0xffea => KeyCode::RightAlt,
+        0xffeb => KeyCode::LeftSuper,
+        0xffec => KeyCode::RightSuper,
+        0xff7f => KeyCode::NumLock,
+        0xff14 => KeyCode::ScrollLock,
+        0xff61 => KeyCode::PrintScreen,
+        0xff13 => KeyCode::Pause,
+        0xff67 => KeyCode::Menu,
         _ => KeyCode::Unknown,
     }
 }
 
+/// Returns the character typed by an X11 keysym, if any.
+pub fn keysym_to_char(keysym: Keysym) -> Option<char> {
+    match keysym {
+        // Printable Latin-1 keysyms are their own code points
+        0x20..=0x7e | 0xa0..=0xff => char::from_u32(keysym),
+        // Keysyms outside Latin-1 encode the code point directly
+        0x0100_0100..=0x0110_ffff => char::from_u32(keysym - 0x0100_0000),
+        _ => None,
+    }
+}
+
 /// Translates X11 modifier state to our modifier bitmask.
 pub fn translate_modifiers(state: u16) -> i32 {
-    use crate::view::modifiers;
-
     let mut mods = 0i32;
 
     if state & 0x01 != 0 {
@@ -141,25 +125,160 @@ pub fn translate_modifiers(state: u16) -> i32 {
         // Lock (Caps Lock)
         mods |= modifiers::CAPS_LOCK;
     }
+    if state & 0x10 != 0 {
+        // Mod2 (Num Lock)
+        mods |= modifiers::NUM_LOCK;
+    }
 
     mods
 }
 
+/// The server's keycode to keysym table.
+struct Keymap {
+    min_keycode: u8,
+    keysyms_per_keycode: usize,
+    keysyms: Vec<Keysym>,
+}
+
+impl Keymap {
+    /// Fetches the keyboard mapping from the server.
+    fn load(conn: &RustConnection) -> Option<Self> {
+        let setup = conn.setup();
+        let count = setup.max_keycode - setup.min_keycode + 1;
+        let reply = conn.get_keyboard_mapping(setup.min_keycode, count).ok()?.reply().ok()?;
+        Some(Self {
+            min_keycode: setup.min_keycode,
+            keysyms_per_keycode: reply.keysyms_per_keycode as usize,
+            keysyms: reply.keysyms,
+        })
+    }
+
+    /// Returns the keysym in `column` for `keycode` (0 is unshifted, 1 is shifted).
+    fn keysym(&self, keycode: u8, column: usize) -> Keysym {
+        if keycode < self.min_keycode || column >= self.keysyms_per_keycode {
+            return 0;
+        }
+        let index = (keycode - self.min_keycode) as usize * self.keysyms_per_keycode + column;
+        self.keysyms.get(index).copied().unwrap_or(0)
+    }
+
+    /// Looks up the key for `keycode` and the character it types under the
+    /// modifier `state`.
+    ///
+    /// The key is always taken from the unshifted keysym so that shortcuts
+    /// such as Shift-1 still report `Key1`.
+    fn lookup(&self, keycode: u8, state: u16) -> (KeyCode, Option<char>) {
+        let base = self.keysym(keycode, 0);
+        let shift = state & 0x01 != 0;
+        let caps = state & 0x02 != 0;
+
+        let typed = if (0x61..=0x7a).contains(&base) {
+            // Letters: Caps Lock inverts Shift
+            if shift != caps { base - 0x20 } else { base }
+        } else if shift {
+            match self.keysym(keycode, 1) {
+                0 => base,
+                shifted => shifted,
+            }
+        } else {
+            base
+        };
+
+        (translate_keysym(base), keysym_to_char(typed))
+    }
+}
+
+/// Returns the connection shared by the application and its windows,
+/// opening it on first use.
+fn display() -> Option<(Arc<RustConnection>, usize)> {
+    static DISPLAY: OnceLock<Option<(Arc<RustConnection>, usize)>> = OnceLock::new();
+    DISPLAY
+        .get_or_init(|| {
+            let (conn, screen_num) = RustConnection::connect(None).ok()?;
+            Some((Arc::new(conn), screen_num))
+        })
+        .clone()
+}
+
+/// Interns an atom by name.
+fn intern_atom(conn: &RustConnection, name: &[u8]) -> Option<Atom> {
+    Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+}
+
+/// Builds the EWMH request asking the window manager to add or remove a
+/// `_NET_WM_STATE` atom, such as `_NET_WM_STATE_FULLSCREEN`, on `window`.
+fn wm_state_message(window: Window, net_wm_state: Atom, state: Atom, on: bool) -> ClientMessageEvent {
+    // Actions are 0 to remove and 1 to add; the 1 last marks a normal application
+    let action = u32::from(on);
+    ClientMessageEvent::new(32, window, net_wm_state, [action, state, 0, 1, 0])
+}
+
+thread_local! {
+    /// Open windows by X11 id, so the event loop can route events to them.
+    static WINDOWS: RefCell<HashMap<Window, Rc<WindowState>>> = RefCell::new(HashMap::new());
+
+    /// Window the pointer last moved over, whose cursor `set_cursor` changes.
+    static POINTER_WINDOW: Cell<Option<Window>> = const { Cell::new(None) };
+}
+
+/// Returns the open window with the given id.
+fn find_window(id: Window) -> Option<Rc<WindowState>> {
+    WINDOWS.with(|windows| windows.borrow().get(&id).cloned())
+}
+
+/// Returns all open windows.
+fn open_windows() -> Vec<Rc<WindowState>> {
+    WINDOWS.with(|windows| windows.borrow().values().cloned().collect())
+}
+
+/// Sets the cursor shown over the window under the pointer.
+pub fn set_cursor(cursor: CursorType) {
+    if let Some(window) = POINTER_WINDOW.with(Cell::get).and_then(find_window) {
+        window.set_cursor(cursor);
+    }
+}
+
+/// Returns the window an event is addressed to.
+fn event_window(event: &Event) -> Option<Window> {
+    match event {
+        Event::Expose(e) => Some(e.window),
+        Event::ConfigureNotify(e) => Some(e.window),
+        Event::ButtonPress(e) => Some(e.event),
+        Event::ButtonRelease(e) => Some(e.event),
+        Event::MotionNotify(e) => Some(e.event),
+        Event::EnterNotify(e) => Some(e.event),
+        Event::LeaveNotify(e) => Some(e.event),
+        Event::KeyPress(e) => Some(e.event),
+        Event::KeyRelease(e) => Some(e.event),
+        Event::ClientMessage(e) => Some(e.window),
+        Event::DestroyNotify(e) => Some(e.window),
+        Event::FocusIn(e) => Some(e.event),
+        Event::FocusOut(e) => Some(e.event),
+        _ => None,
+    }
+}
+
 /// Linux/X11 application wrapper.
 pub struct LinuxApp {
     conn: Arc<RustConnection>,
     screen_num: usize,
-    running: bool,
+    keymap: RefCell<Keymap>,
+    /// Event read ahead while checking a key release for autorepeat.
+    peeked: RefCell<Option<Event>>,
+    running: Cell<bool>,
 }
 
 impl LinuxApp {
     /// Creates a new Linux application.
     pub fn new() -> Option<Self> {
-        let (conn, screen_num) = RustConnection::connect(None).ok()?;
+        let (conn, screen_num) = display()?;
+        let keymap = Keymap::load(&conn)?;
         Some(Self {
-            conn: Arc::new(conn),
+            conn,
             screen_num,
-            running: false,
+            keymap: RefCell::new(keymap),
+            peeked: RefCell::new(None),
+            running: Cell::new(false),
         })
     }
 
@@ -173,70 +292,581 @@ impl LinuxApp {
         self.screen_num
     }
 
-    /// Runs the application event loop.
-    pub fn run(&mut self) {
-        self.running = true;
+    /// Runs the application event loop until it is stopped or the last
+    /// window is closed.
+    pub fn run(&self) {
+        self.running.set(true);
+
+        while self.running.get() {
+            loop {
+                let peeked = self.peeked.borrow_mut().take();
+                match peeked.map_or_else(|| self.conn.poll_for_event(), |event| Ok(Some(event))) {
+                    Ok(Some(event)) => self.handle_event(event),
+                    Ok(None) => break,
+                    Err(_) => {
+                        self.running.set(false);
+                        return;
+                    }
+                }
+            }
+
+            let windows = open_windows();
+            if windows.is_empty() {
+                break;
+            }
 
-        while self.running {
-            match self.conn.wait_for_event() {
-                Ok(event) => {
-                    self.handle_event(event);
+            // Timer callbacks may change anything, so repaint everything
+            let fired = super::timer::fire_due(Instant::now());
+
+            // Idle tick: let animations advance, then repaint what changed
+            for window in &windows {
+                if fired {
+                    window.dirty.set(true);
+                } else if window.with_context(|content, _| content.handle_poll()) == Some(true) {
+                    window.invalidate();
                 }
-                Err(_) => {
-                    self.running = false;
+                let damaged = window.collect_damage(&window.view.borrow()) || window.damage.get().is_some();
+                if window.dirty.get() || damaged {
+                    window.redraw();
                 }
             }
+
+            // Sleep until the next tick, or sooner if a timer is due
+            let now = Instant::now();
+            let wait = super::timer::next_due()
+                .map_or(POLL_INTERVAL, |due| due.saturating_duration_since(now).min(POLL_INTERVAL));
+            std::thread::sleep(wait);
         }
+
+        self.running.set(false);
     }
 
     /// Stops the application.
-    pub fn stop(&mut self) {
-        self.running = false;
+    pub fn stop(&self) {
+        self.running.set(false);
     }
 
-    fn handle_event(&mut self, event: Event) {
+    fn handle_event(&self, event: Event) {
+        if let Event::MappingNotify(_) = event {
+            if let Some(keymap) = Keymap::load(&self.conn) {
+                *self.keymap.borrow_mut() = keymap;
+            }
+            return;
+        }
+
+        let Some(window) = event_window(&event).and_then(find_window) else {
+            return;
+        };
+
+        // A held key repeats as a release and a press sent together; deliver
+        // the pair as one repeat
+        if let Event::KeyRelease(release) = event {
+            match self.conn.poll_for_event() {
+                Ok(Some(Event::KeyPress(press))) if is_key_repeat(&release, &press) => {
+                    window.handle_key(&self.keymap.borrow(), press.detail, u16::from(press.state), KeyAction::Repeat);
+                    return;
+                }
+                Ok(Some(next)) => *self.peeked.borrow_mut() = Some(next),
+                _ => {}
+            }
+        }
+        window.handle_event(&self.keymap.borrow(), event);
+    }
+}
+
+/// Names the `_NET_WM_STATE` atoms a window is mapped with.
+fn initial_wm_states(fullscreen: bool, always_on_top: bool) -> Vec<&'static [u8]> {
+    [
+        (fullscreen, &b"_NET_WM_STATE_FULLSCREEN"[..]),
+        (always_on_top, &b"_NET_WM_STATE_ABOVE"[..]),
+    ]
+    .into_iter()
+    .filter(|&(on, _)| on)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+/// Returns whether `press` directly follows `release` as an autorepeat of
+/// the same key.
+fn is_key_repeat(release: &KeyReleaseEvent, press: &KeyPressEvent) -> bool {
+    press.detail == release.detail && press.time == release.time && press.event == release.event
+}
+
+/// Per-window state shared between a `LinuxWindow` and the event loop.
+struct WindowState {
+    conn: Arc<RustConnection>,
+    window: Window,
+    gc: Gcontext,
+    depth: u8,
+    /// Root window, which window manager requests are sent to.
+    root: Window,
+    wm_protocols: Atom,
+    wm_delete_window: Atom,
+    /// The view every event is dispatched through, which holds the
+    /// content, size, keyboard modifiers and window focus.
+    view: RefCell<View>,
+    canvas: RefCell<Option<Canvas>>,
+    /// Whether the whole window needs repainting.
+    dirty: Cell<bool>,
+    /// Area elements refreshed since the last repaint.
+    damage: Cell<Option<Rect>>,
+    /// Whether elements refreshed anything during the last dispatch.
+    refreshed: Cell<bool>,
+    close_requested: RefCell<Option<CloseRequestCallback>>,
+    closed: RefCell<Option<ClosedCallback>>,
+    resized: RefCell<Option<ResizeCallback>>,
+    /// Whether the window was last made fullscreen.
+    fullscreen: Cell<bool>,
+    /// Whether the window stays above others.
+    always_on_top: Cell<bool>,
+    fullscreen_changed: RefCell<Option<FullscreenCallback>>,
+    clicks: RefCell<ClickCounter>,
+    /// Cursor shown over the window.
+    cursor: Cell<CursorType>,
+    /// Cursors created so far, by shape.
+    cursors: RefCell<HashMap<CursorType, Cursor>>,
+}
+
+impl WindowState {
+    /// Calls `f` with the content and a context spanning the window.
+    ///
+    /// Areas the content refreshes are collected for the next repaint.
+    fn with_context<R>(&self, f: impl FnOnce(&ElementPtr, &Context) -> R) -> Option<R> {
+        let view = self.view.borrow();
+        let content = view.content()?.clone();
+        let canvas = RefCell::new(Canvas::new(1, 1)?);
+        let ctx = Context::new(&view, &canvas, view.bounds());
+
+        // Keep refreshes made outside of event handling apart from the
+        // ones this event makes
+        self.collect_damage(&view);
+        let result = f(&content, &ctx);
+        self.refreshed.set(self.collect_damage(&view));
+        Some(result)
+    }
+
+    /// Moves the areas refreshed through `view` into the damage for the
+    /// next repaint. Returns false if there were none.
+    fn collect_damage(&self, view: &View) -> bool {
+        let Some(area) = view.take_dirty() else {
+            return false;
+        };
+        self.damage.set(Some(match self.damage.get() {
+            Some(damage) => union(&damage, &area),
+            None => area,
+        }));
+        true
+    }
+
+    /// Returns the window size.
+    fn size(&self) -> Extent {
+        self.view.borrow().size()
+    }
+
+    /// Schedules a repaint after the content handled an event.
+    ///
+    /// Only the areas the content refreshed are repainted. Elements that
+    /// don't report refreshes still change on events, so if nothing was
+    /// refreshed the whole window is repainted.
+    fn invalidate(&self) {
+        if !self.refreshed.get() {
+            self.dirty.set(true);
+        }
+    }
+
+    fn handle_event(&self, keymap: &Keymap, event: Event) {
         match event {
-            Event::Expose(_) => {
-                // Handle expose (redraw)
+            Event::Expose(e) if e.count == 0 => {
+                self.dirty.set(true);
             }
-            Event::ConfigureNotify(_) => {
-                // Handle resize
+            Event::ConfigureNotify(e) => {
+                let size = Extent::new(e.width as f32, e.height as f32);
+                if size != self.size() {
+                    self.view.borrow_mut().set_size(size);
+                    self.dirty.set(true);
+
+                    // Content lays itself out from the bounds it is drawn
+                    // with, so the redraw that follows relays it out
+                    notify(&self.resized, size);
+                }
             }
             Event::ButtonPress(e) => {
-                // Handle mouse press
+                let pos = Point::new(e.event_x as f32, e.event_y as f32);
+                self.handle_button(e.detail, true, e.time, u16::from(e.state), pos);
             }
             Event::ButtonRelease(e) => {
-                // Handle mouse release
+                let pos = Point::new(e.event_x as f32, e.event_y as f32);
+                self.handle_button(e.detail, false, e.time, u16::from(e.state), pos);
             }
             Event::MotionNotify(e) => {
-                // Handle mouse motion
+                let pos = Point::new(e.event_x as f32, e.event_y as f32);
+                self.handle_motion(u16::from(e.state), pos);
+            }
+            Event::EnterNotify(e) => {
+                let pos = Point::new(e.event_x as f32, e.event_y as f32);
+                self.with_context(|content, ctx| content.handle_cursor(ctx, pos, CursorTracking::Entering));
+            }
+            Event::LeaveNotify(e) => {
+                let pos = Point::new(e.event_x as f32, e.event_y as f32);
+                self.with_context(|content, ctx| content.handle_cursor(ctx, pos, CursorTracking::Leaving));
             }
             Event::KeyPress(e) => {
-                // Handle key press
+                self.handle_key(keymap, e.detail, u16::from(e.state), KeyAction::Press);
             }
             Event::KeyRelease(e) => {
-                // Handle key release
+                self.handle_key(keymap, e.detail, u16::from(e.state), KeyAction::Release);
+            }
+            Event::ClientMessage(e)
+                if e.type_ == self.wm_protocols
+                    && e.data.as_data32()[0] == self.wm_delete_window
+                    && close_allowed(&self.close_requested) =>
+            {
+                self.close();
+            }
+            Event::FocusIn(_) => {
+                self.view.borrow_mut().begin_focus();
+            }
+            Event::FocusOut(_) => {
+                self.view.borrow_mut().end_focus();
             }
             Event::DestroyNotify(_) => {
-                self.running = false;
+                self.forget();
             }
             _ => {}
         }
     }
+
+    fn handle_button(&self, detail: u8, down: bool, time: u32, state: u16, pos: Point) {
+        // Buttons 4-7 are the scroll wheel, reported as press/release pairs
+        let scroll = match detail {
+            4 => Some(Point::new(0.0, 1.0)),
+            5 => Some(Point::new(0.0, -1.0)),
+            6 => Some(Point::new(1.0, 0.0)),
+            7 => Some(Point::new(-1.0, 0.0)),
+            _ => None,
+        };
+        if let Some(dir) = scroll {
+            if !down {
+                return;
+            }
+            self.view.borrow().set_modifiers(translate_modifiers(state));
+            let scrolled = self.with_context(|content, ctx| content.handle_scroll(ctx, dir, pos));
+            if scrolled == Some(true) {
+                self.invalidate();
+            }
+            return;
+        }
+
+        let button = match detail {
+            1 => MouseButtonKind::Left,
+            2 => MouseButtonKind::Middle,
+            3 => MouseButtonKind::Right,
+            _ => return,
+        };
+
+        // Server time is in milliseconds
+        let mut clicks = self.clicks.borrow_mut();
+        let click_count = if down {
+            clicks.press(button, Duration::from_millis(u64::from(time)), pos)
+        } else {
+            clicks.count()
+        };
+        drop(clicks);
+
+        self.view.borrow().set_modifiers(translate_modifiers(state));
+        let btn = MouseButton {
+            down,
+            click_count,
+            button,
+            modifiers: translate_modifiers(state),
+            pos,
+        };
+
+        self.with_context(|content, ctx| dispatch_click(content.as_ref(), ctx, btn));
+        self.invalidate();
+    }
+
+    fn handle_motion(&self, state: u16, pos: Point) {
+        POINTER_WINDOW.with(|window| window.set(Some(self.window)));
+        self.view.borrow().set_modifiers(translate_modifiers(state));
+        let button = if state & u16::from(KeyButMask::BUTTON1) != 0 {
+            MouseButtonKind::Left
+        } else if state & u16::from(KeyButMask::BUTTON3) != 0 {
+            MouseButtonKind::Right
+        } else if state & u16::from(KeyButMask::BUTTON2) != 0 {
+            MouseButtonKind::Middle
+        } else {
+            // Hovering: show the cursor the element under the pointer asks
+            // for, and let the content respond. Areas it refreshes are
+            // repainted on the next tick.
+            let shape = self.with_context(|content, ctx| {
+                content.handle_cursor(ctx, pos, CursorTracking::Hovering);
+                cursor_type_at(content.as_ref(), ctx, pos)
+            });
+            self.set_cursor(shape.unwrap_or_default());
+            return;
+        };
+
+        let btn = MouseButton {
+            down: true,
+            click_count: 1,
+            button,
+            modifiers: translate_modifiers(state),
+            pos,
+        };
+
+        self.with_context(|content, ctx| content.handle_drag(ctx, btn));
+        self.invalidate();
+    }
+
+    /// Shows `shape` while the pointer is over the window.
+    fn set_cursor(&self, shape: CursorType) {
+        if self.cursor.get() == shape {
+            return;
+        }
+        let Some(cursor) = self.load_cursor(shape) else {
+            return;
+        };
+        self.cursor.set(shape);
+        let _ = self.conn.change_window_attributes(self.window, &ChangeWindowAttributesAux::new().cursor(cursor));
+        let _ = self.conn.flush();
+    }
+
+    /// Returns the X cursor for `shape`, creating it from the standard
+    /// cursor font on first use.
+    fn load_cursor(&self, shape: CursorType) -> Option<Cursor> {
+        if let Some(&cursor) = self.cursors.borrow().get(&shape) {
+            return Some(cursor);
+        }
+
+        // Glyph indices in the cursor font; each glyph's mask follows it
+        let glyph = match shape {
+            CursorType::Arrow => 68,     // left_ptr
+            CursorType::IBeam => 152,    // xterm
+            CursorType::CrossHair => 34, // crosshair
+            CursorType::Hand => 60,      // hand2
+            CursorType::HResize => 108,  // sb_h_double_arrow
+            CursorType::VResize => 116,  // sb_v_double_arrow
+        };
+        let font = self.conn.generate_id().ok()?;
+        self.conn.open_font(font, b"cursor").ok()?;
+        let cursor = self.conn.generate_id().ok()?;
+        let created = self.conn.create_glyph_cursor(cursor, font, font, glyph, glyph + 1, 0, 0, 0, 0xffff, 0xffff, 0xffff);
+        let _ = self.conn.close_font(font);
+        created.ok()?;
+        self.cursors.borrow_mut().insert(shape, cursor);
+        Some(cursor)
+    }
+
+    fn handle_key(&self, keymap: &Keymap, keycode: u8, state: u16, action: KeyAction) {
+        let (key, typed) = keymap.lookup(keycode, state);
+        let mods = translate_modifiers(state);
+        self.view.borrow().set_modifiers(mods);
+        let info = KeyInfo { key, action, modifiers: mods };
+
+        let handled = self.with_context(|content, ctx| dispatch_key(content.as_ref(), ctx, info));
+        if handled == Some(true) {
+            self.invalidate();
+        }
+
+        // Control and Alt chords are shortcuts, not text
+        if action == KeyAction::Release || mods & (modifiers::CONTROL | modifiers::ALT) != 0 {
+            return;
+        }
+        if let Some(codepoint) = typed {
+            let info = TextInfo { codepoint, modifiers: mods };
+            if self.with_context(|content, ctx| content.handle_text(ctx, info)) == Some(true) {
+                self.invalidate();
+            }
+        }
+    }
+
+    /// Draws the content and presents it, limited to the damaged area
+    /// unless the whole window is dirty.
+    fn redraw(&self) {
+        let mut full = self.dirty.replace(false);
+        self.collect_damage(&self.view.borrow());
+        let damage = self.damage.take();
+
+        let size = self.size();
+        let width = size.x as u32;
+        let height = size.y as u32;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Create or resize canvas
+        let mut canvas_opt = self.canvas.borrow_mut();
+        let needs_new = match &*canvas_opt {
+            Some(c) => c.width() != width || c.height() != height,
+            None => true,
+        };
+        if needs_new {
+            *canvas_opt = Canvas::new(width, height);
+            full = true;
+        }
+        let Some(mut canvas) = canvas_opt.take() else {
+            return;
+        };
+
+        // Repaint whole pixels so antialiased edges are redrawn entirely
+        let bounds = Rect::from_origin_size(Point::zero(), size);
+        let area = match damage {
+            _ if full => bounds,
+            Some(damage) => Rect::new(
+                damage.left.floor(),
+                damage.top.floor(),
+                damage.right.ceil(),
+                damage.bottom.ceil(),
+            ),
+            None => {
+                *canvas_opt = Some(canvas);
+                return;
+            }
+        };
+        let Some(area) = area.intersection(bounds) else {
+            *canvas_opt = Some(canvas);
+            return;
+        };
+        log::trace!("repainting {area:?}");
+
+        // Clear with dark background
+        canvas.push_clip(area);
+        canvas.fill_style(WINDOW_BACKGROUND);
+        canvas.fill_rect(area);
+
+        let canvas_cell = RefCell::new(canvas);
+        let view = self.view.borrow();
+        if let Some(content) = view.content().cloned() {
+            let ctx = Context::new(&view, &canvas_cell, bounds).with_clip(area);
+            content.draw(&ctx);
+        }
+        drop(view);
+        let mut canvas = canvas_cell.into_inner();
+        canvas.pop_clip();
+
+        self.present(&canvas, area);
+        *canvas_opt = Some(canvas);
+    }
+
+    /// Copies `area` of the canvas into the window.
+    ///
+    /// The window has the root visual, which on any current server is 24 or
+    /// 32-bit TrueColor stored as 32 bits per pixel. The canvas is opaque,
+    /// so its premultiplied RGBA converts to that layout by reordering.
+    fn present(&self, canvas: &Canvas, area: Rect) {
+        let (x, y) = (area.left as usize, area.top as usize);
+        let width = area.width() as usize;
+        let height = area.height() as usize;
+        let src_stride = canvas.width() as usize * 4;
+        let msb_first = self.conn.setup().image_byte_order == ImageOrder::MSB_FIRST;
+
+        let mut data = Vec::with_capacity(width * height * 4);
+        for row in canvas.pixmap().data().chunks_exact(src_stride).skip(y).take(height) {
+            for px in row[x * 4..(x + width) * 4].chunks_exact(4) {
+                if msb_first {
+                    data.extend_from_slice(&[0, px[0], px[1], px[2]]);
+                } else {
+                    data.extend_from_slice(&[px[2], px[1], px[0], 0]);
+                }
+            }
+        }
+
+        // Send the image in bands that fit within the maximum request size
+        let stride = width * 4;
+        let rows_per_band = ((self.conn.maximum_request_bytes() - 32) / stride).max(1);
+        for (i, band) in data.chunks(rows_per_band * stride).enumerate() {
+            let _ = self.conn.put_image(
+                ImageFormat::Z_PIXMAP,
+                self.window,
+                self.gc,
+                width as u16,
+                (band.len() / stride) as u16,
+                x as i16,
+                (y + i * rows_per_band) as i16,
+                0,
+                self.depth,
+                band,
+            );
+        }
+        let _ = self.conn.flush();
+    }
+
+    /// Asks the window manager to add or remove a `_NET_WM_STATE` atom.
+    ///
+    /// Returns false if the request couldn't be sent.
+    fn request_wm_state(&self, name: &[u8], on: bool) -> bool {
+        let (Some(net_wm_state), Some(state)) =
+            (intern_atom(&self.conn, b"_NET_WM_STATE"), intern_atom(&self.conn, name))
+        else {
+            return false;
+        };
+        let message = wm_state_message(self.window, net_wm_state, state, on);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        if self.conn.send_event(false, self.root, mask, message).is_err() {
+            return false;
+        }
+        let _ = self.conn.flush();
+        true
+    }
+
+    /// Writes the `_NET_WM_STATE` the window should be mapped with.
+    ///
+    /// Window managers only read the property when mapping a window and
+    /// ignore requests for windows that aren't mapped yet.
+    fn set_initial_wm_state(&self) {
+        let Some(net_wm_state) = intern_atom(&self.conn, b"_NET_WM_STATE") else {
+            return;
+        };
+        let states: Vec<Atom> = initial_wm_states(self.fullscreen.get(), self.always_on_top.get())
+            .into_iter()
+            .filter_map(|name| intern_atom(&self.conn, name))
+            .collect();
+        let _ = self.conn.change_property32(
+            PropMode::REPLACE,
+            self.window,
+            net_wm_state,
+            AtomEnum::ATOM,
+            &states,
+        );
+    }
+
+    fn close(&self) {
+        if self.forget() {
+            let _ = self.conn.destroy_window(self.window);
+            let _ = self.conn.flush();
+        }
+    }
+
+    /// Removes the window from the event loop and runs its closed callback.
+    ///
+    /// Returns false if the window was already closed.
+    fn forget(&self) -> bool {
+        let removed = WINDOWS.with(|windows| windows.borrow_mut().remove(&self.window));
+        if removed.is_none() {
+            return false;
+        }
+        for cursor in self.cursors.borrow_mut().drain().map(|(_, cursor)| cursor) {
+            let _ = self.conn.free_cursor(cursor);
+        }
+        let closed = self.closed.borrow().clone();
+        if let Some(callback) = closed {
+            callback();
+        }
+        true
+    }
 }
 
 /// Linux/X11 window wrapper.
 pub struct LinuxWindow {
-    conn: Arc<RustConnection>,
-    window: Window,
-    view: Option<View>,
+    state: Rc<WindowState>,
 }
 
 impl LinuxWindow {
     /// Creates a new Linux window.
-    pub fn new(app: &LinuxApp, title: &str, size: Extent) -> Option<Self> {
-        let conn = app.connection().clone();
-        let screen = &conn.setup().roots[app.screen_num()];
+    pub fn new(title: &str, size: Extent) -> Option<Self> {
+        let (conn, screen_num) = display()?;
+        let screen = &conn.setup().roots[screen_num];
 
         let window = conn.generate_id().ok()?;
 
@@ -270,55 +900,276 @@ impl LinuxWindow {
         )
         .ok()?;
 
-        // Set window title
-        conn.change_property8(
+        // Ask the window manager for a close message instead of a disconnect
+        let wm_protocols = intern_atom(&conn, b"WM_PROTOCOLS")?;
+        let wm_delete_window = intern_atom(&conn, b"WM_DELETE_WINDOW")?;
+        conn.change_property32(
             PropMode::REPLACE,
             window,
-            AtomEnum::WM_NAME,
-            AtomEnum::STRING,
-            title.as_bytes(),
+            wm_protocols,
+            AtomEnum::ATOM,
+            &[wm_delete_window],
         )
         .ok()?;
 
-        conn.flush().ok()?;
+        let gc = conn.generate_id().ok()?;
+        conn.create_gc(gc, window, &CreateGCAux::new()).ok()?;
 
-        Some(Self {
-            conn,
+        let state = Rc::new(WindowState {
+            conn: conn.clone(),
             window,
-            view: Some(View::new(size)),
-        })
+            gc,
+            depth: screen.root_depth,
+            root: screen.root,
+            wm_protocols,
+            wm_delete_window,
+            view: RefCell::new(View::new(size)),
+            canvas: RefCell::new(None),
+            dirty: Cell::new(true),
+            damage: Cell::new(None),
+            refreshed: Cell::new(false),
+            close_requested: RefCell::new(None),
+            closed: RefCell::new(None),
+            resized: RefCell::new(None),
+            fullscreen: Cell::new(false),
+            always_on_top: Cell::new(false),
+            fullscreen_changed: RefCell::new(None),
+            clicks: RefCell::new(ClickCounter::default()),
+            cursor: Cell::new(CursorType::Arrow),
+            cursors: RefCell::new(HashMap::new()),
+        });
+        WINDOWS.with(|windows| windows.borrow_mut().insert(window, state.clone()));
+
+        let this = Self { state };
+        this.set_title(title);
+        conn.flush().ok()?;
+
+        Some(this)
     }
 
     /// Shows the window.
     pub fn show(&self) {
-        let _ = self.conn.map_window(self.window);
-        let _ = self.conn.flush();
+        self.state.set_initial_wm_state();
+        let _ = self.state.conn.map_window(self.state.window);
+        let _ = self.state.conn.flush();
     }
 
     /// Hides the window.
     pub fn hide(&self) {
-        let _ = self.conn.unmap_window(self.window);
-        let _ = self.conn.flush();
+        let _ = self.state.conn.unmap_window(self.state.window);
+        let _ = self.state.conn.flush();
     }
 
     /// Closes the window.
     pub fn close(&self) {
-        let _ = self.conn.destroy_window(self.window);
-        let _ = self.conn.flush();
+        self.state.close();
+    }
+
+    /// Sets the window title.
+    pub fn set_title(&self, title: &str) {
+        let conn = &self.state.conn;
+        let _ = conn.change_property8(
+            PropMode::REPLACE,
+            self.state.window,
+            AtomEnum::WM_NAME,
+            AtomEnum::STRING,
+            title.as_bytes(),
+        );
+
+        // Modern window managers read the UTF-8 title instead
+        if let (Some(net_wm_name), Some(utf8_string)) =
+            (intern_atom(conn, b"_NET_WM_NAME"), intern_atom(conn, b"UTF8_STRING"))
+        {
+            let _ = conn.change_property8(
+                PropMode::REPLACE,
+                self.state.window,
+                net_wm_name,
+                utf8_string,
+                title.as_bytes(),
+            );
+        }
+        let _ = conn.flush();
+    }
+
+    /// Returns the window size.
+    pub fn size(&self) -> Extent {
+        self.state.size()
+    }
+
+    /// Sets the window size.
+    pub fn set_size(&self, size: Extent) {
+        let values = ConfigureWindowAux::new()
+            .width(size.x as u32)
+            .height(size.y as u32);
+        let _ = self.state.conn.configure_window(self.state.window, &values);
+        let _ = self.state.conn.flush();
+    }
+
+    /// Sets the window content.
+    pub fn set_content(&self, content: ElementPtr) {
+        self.state.view.borrow_mut().set_content(content);
+        self.state.dirty.set(true);
     }
 
     /// Returns the window ID.
     pub fn window_id(&self) -> Window {
-        self.window
+        self.state.window
+    }
+
+    /// Returns the view events are dispatched through.
+    ///
+    /// The view is borrowed while an event is handled, so this must not be
+    /// held across the event loop.
+    pub fn view(&self) -> Ref<'_, View> {
+        self.state.view.borrow()
+    }
+
+    /// Returns the view events are dispatched through, for changing it.
+    pub fn view_mut(&mut self) -> RefMut<'_, View> {
+        self.state.view.borrow_mut()
+    }
+
+    /// Sets the callback asked before the user closes the window; returning
+    /// false keeps it open.
+    pub fn set_on_close_requested(&self, callback: CloseRequestCallback) {
+        *self.state.close_requested.borrow_mut() = Some(callback);
+    }
+
+    /// Sets the callback run once the window has closed.
+    pub fn set_on_closed(&self, callback: ClosedCallback) {
+        *self.state.closed.borrow_mut() = Some(callback);
+    }
+
+    /// Sets the callback run with the new content size when the window is
+    /// resized.
+    pub fn set_on_resize(&self, callback: ResizeCallback) {
+        *self.state.resized.borrow_mut() = Some(callback);
+    }
+
+    /// Asks the window manager to make the window fullscreen, or to restore
+    /// it to its previous frame.
+    pub fn set_fullscreen(&self, on: bool) {
+        if on == self.state.fullscreen.get()
+            || !self.state.request_wm_state(b"_NET_WM_STATE_FULLSCREEN", on)
+        {
+            return;
+        }
+        self.state.fullscreen.set(on);
+        notify(&self.state.fullscreen_changed, on);
+    }
+
+    /// Returns whether the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.state.fullscreen.get()
+    }
+
+    /// Sets the callback run when the window enters or leaves fullscreen.
+    pub fn set_on_fullscreen_change(&self, callback: FullscreenCallback) {
+        *self.state.fullscreen_changed.borrow_mut() = Some(callback);
+    }
+
+    /// Keeps the window above other windows, or lets it be covered again.
+    pub fn set_always_on_top(&self, on: bool) {
+        // Before the window is shown, this is written as its initial state
+        self.state.always_on_top.set(on);
+        self.state.request_wm_state(b"_NET_WM_STATE_ABOVE", on);
+    }
+
+    /// Returns whether this window has the keyboard focus.
+    pub fn is_key(&self) -> bool {
+        self.state.view.borrow().has_focus()
+    }
+
+    /// Triggers a redraw.
+    pub fn refresh(&self) {
+        self.state.dirty.set(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_keysym() {
+        assert_eq!(translate_keysym(0x61), KeyCode::A);
+        assert_eq!(translate_keysym(0x5a), KeyCode::Z);
+        assert_eq!(translate_keysym(0x37), KeyCode::Key7);
+        assert_eq!(translate_keysym(0xffc9), KeyCode::F12);
+        assert_eq!(translate_keysym(0xfe20), KeyCode::Tab);
+        assert_eq!(translate_keysym(0xff0d), KeyCode::Enter);
+        assert_eq!(translate_keysym(0x1234), KeyCode::Unknown);
+
+        assert_eq!(keysym_to_char(0x41), Some('A'));
+        assert_eq!(keysym_to_char(0xe9), Some('é'));
+        assert_eq!(keysym_to_char(0x0100_20ac), Some('€'));
+        assert_eq!(keysym_to_char(0xff0d), None);
+
+        let mods = translate_modifiers(0x01 | 0x04 | 0x10);
+        assert_eq!(mods, modifiers::SHIFT | modifiers::CONTROL | modifiers::NUM_LOCK);
+    }
+
+    #[test]
+    fn test_keymap_lookup() {
+        // Keycode 10 is 'a'/'A', keycode 11 is '1'/'!'
+        let keymap = Keymap {
+            min_keycode: 10,
+            keysyms_per_keycode: 2,
+            keysyms: vec![0x61, 0x41, 0x31, 0x21],
+        };
+
+        assert_eq!(keymap.lookup(10, 0), (KeyCode::A, Some('a')));
+        assert_eq!(keymap.lookup(10, 0x01), (KeyCode::A, Some('A')));
+        assert_eq!(keymap.lookup(10, 0x02), (KeyCode::A, Some('A')));
+        assert_eq!(keymap.lookup(10, 0x03), (KeyCode::A, Some('a')));
+        assert_eq!(keymap.lookup(11, 0x01), (KeyCode::Key1, Some('!')));
+        assert_eq!(keymap.lookup(11, 0x02), (KeyCode::Key1, Some('1')));
+        assert_eq!(keymap.lookup(9, 0), (KeyCode::Unknown, None));
+    }
+
+    #[test]
+    fn test_key_repeat_detection() {
+        let release = KeyReleaseEvent { detail: 38, time: 1000, event: 7, ..Default::default() };
+        let repeat = KeyPressEvent { detail: 38, time: 1000, event: 7, ..Default::default() };
+        assert!(is_key_repeat(&release, &repeat));
+
+        // A later press, or another key, is a new press
+        assert!(!is_key_repeat(&release, &KeyPressEvent { time: 1040, ..repeat }));
+        assert!(!is_key_repeat(&release, &KeyPressEvent { detail: 39, ..repeat }));
+    }
+
+    #[test]
+    fn test_events_routed_to_their_window() {
+        let press = Event::ButtonPress(ButtonPressEvent { event: 7, child: 9, ..Default::default() });
+        assert_eq!(event_window(&press), Some(7));
+        let key = Event::KeyPress(KeyPressEvent { event: 8, root: 1, ..Default::default() });
+        assert_eq!(event_window(&key), Some(8));
+        let focus = Event::FocusIn(FocusInEvent { event: 9, ..Default::default() });
+        assert_eq!(event_window(&focus), Some(9));
+        let expose = Event::Expose(ExposeEvent { window: 10, ..Default::default() });
+        assert_eq!(event_window(&expose), Some(10));
+
+        // Events that aren't addressed to a window aren't routed
+        assert_eq!(event_window(&Event::MappingNotify(Default::default())), None);
     }
 
-    /// Returns a reference to the view.
-    pub fn view(&self) -> Option<&View> {
-        self.view.as_ref()
+    #[test]
+    fn test_initial_wm_states() {
+        assert!(initial_wm_states(false, false).is_empty());
+        assert_eq!(initial_wm_states(false, true), [&b"_NET_WM_STATE_ABOVE"[..]]);
+        assert_eq!(
+            initial_wm_states(true, true),
+            [&b"_NET_WM_STATE_FULLSCREEN"[..], &b"_NET_WM_STATE_ABOVE"[..]]
+        );
     }
 
-    /// Returns a mutable reference to the view.
-    pub fn view_mut(&mut self) -> Option<&mut View> {
-        self.view.as_mut()
+    #[test]
+    fn test_wm_state_message() {
+        let enter = wm_state_message(7, 100, 101, true);
+        assert_eq!(enter.window, 7);
+        assert_eq!(enter.type_, 100);
+        assert_eq!(enter.data.as_data32(), [1, 101, 0, 1, 0]);
+        let leave = wm_state_message(7, 100, 101, false);
+        assert_eq!(leave.data.as_data32()[0], 0);
     }
 }
@@ -5,19 +5,26 @@
 
 #![cfg(target_os = "macos")]
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject, NSObjectProtocol, ProtocolObject};
 use objc2::{declare_class, msg_send_id, mutability, ClassType, DeclaredClass};
 use objc2_foundation::{
-    NSString, MainThreadMarker, NSPoint, NSRect, NSSize,
+    NSArray, NSString, NSURL, MainThreadMarker, NSPoint, NSRect, NSSize, NSTimer, NSNotification,
 };
 use objc2_app_kit::{
     NSApplication, NSApplicationActivationPolicy, NSBackingStoreType,
-    NSWindow, NSWindowStyleMask, NSCursor, NSPasteboard, NSView,
-    NSGraphicsContext, NSEvent, NSMenu, NSMenuItem,
+    NSWindow, NSWindowStyleMask, NSCursor, NSPasteboard, NSPasteboardTypeString, NSView,
+    NSGraphicsContext, NSEvent, NSMenu, NSMenuItem, NSApplicationDelegate, NSWindowDelegate,
+    NSDraggingInfo, NSDragOperation, NSPasteboardTypeFileURL, NSTrackingArea, NSTrackingAreaOptions,
+    NSWindowCollectionBehavior, NSFloatingWindowLevel, NSNormalWindowLevel,
 };
-use core_graphics::color_space::CGColorSpace;
+use core_graphics::base::{kCGBitmapByteOrder32Big, kCGImageAlphaPremultipliedLast, kCGRenderingIntentDefault};
+use core_graphics::color_space::{kCGColorSpaceSRGB, CGColorSpace};
 use core_graphics::context::CGContext;
 use core_graphics::data_provider::CGDataProvider;
 use core_graphics::image::CGImage;
@@ -28,7 +35,8 @@ use crate::support::color::Color;
 use crate::support::rect::Rect;
 use crate::element::context::Context;
 use crate::element::ElementPtr;
-use crate::view::{View, KeyCode, CursorType, modifiers, MouseButton, MouseButtonKind};
+use crate::view::{View, KeyCode, CursorType, CursorTracking, DropInfo, modifiers, MouseButton, MouseButtonKind, ClickCounter, cursor_type_at, dispatch_click, dispatch_key};
+use super::{close_allowed, notify, WindowStyle, CloseRequestCallback, ClosedCallback, ResizeCallback, FullscreenCallback, WINDOW_BACKGROUND};
 
 /// Converts NSPoint to our Point type.
 fn ns_point_to_point(p: NSPoint) -> Point {
@@ -50,6 +58,16 @@ fn extent_to_ns_size(e: Extent) -> NSSize {
     NSSize::new(e.x as f64, e.y as f64)
 }
 
+/// Converts NSRect to our Rect type.
+fn ns_rect_to_rect(r: NSRect) -> Rect {
+    Rect::from_origin_size(ns_point_to_point(r.origin), ns_size_to_extent(r.size))
+}
+
+/// Converts our Rect type to NSRect.
+fn rect_to_ns_rect(r: Rect) -> NSRect {
+    NSRect::new(point_to_ns_point(r.top_left()), extent_to_ns_size(r.size()))
+}
+
 /// Translates a macOS key code to our KeyCode enum.
 pub fn translate_key(keycode: u16) -> KeyCode {
     match keycode {
@@ -128,6 +146,28 @@ pub fn translate_key(keycode: u16) -> KeyCode {
     }
 }
 
+/// Translates a window style to an AppKit style mask.
+///
+/// Borderless windows have no title bar, so the close and miniaturize
+/// buttons only apply to titled windows.
+pub fn translate_style(style: &WindowStyle) -> NSWindowStyleMask {
+    let mut mask = if style.borderless {
+        NSWindowStyleMask::Borderless
+    } else {
+        NSWindowStyleMask::Titled
+    };
+    if !style.borderless && style.closable {
+        mask |= NSWindowStyleMask::Closable;
+    }
+    if !style.borderless && style.miniaturizable {
+        mask |= NSWindowStyleMask::Miniaturizable;
+    }
+    if style.resizable {
+        mask |= NSWindowStyleMask::Resizable;
+    }
+    mask
+}
+
 /// Translates macOS modifier flags to our modifier bitmask.
 pub fn translate_flags(flags: usize) -> i32 {
     let mut mods = 0i32;
@@ -194,17 +234,175 @@ pub fn set_cursor(cursor: CursorType) {
 /// Gets the clipboard contents.
 pub fn get_clipboard() -> String {
     unsafe {
-        let _pasteboard = NSPasteboard::generalPasteboard();
-        // Would need to read string from pasteboard
-        String::new()
+        let pasteboard = NSPasteboard::generalPasteboard();
+        pasteboard
+            .stringForType(NSPasteboardTypeString)
+            .map(|string| string.to_string())
+            .unwrap_or_default()
     }
 }
 
 /// Sets the clipboard contents.
-pub fn set_clipboard(_text: &str) {
+pub fn set_clipboard(text: &str) {
+    unsafe {
+        let pasteboard = NSPasteboard::generalPasteboard();
+        pasteboard.clearContents();
+        pasteboard.setString_forType(&NSString::from_str(text), NSPasteboardTypeString);
+    }
+}
+
+/// Resolves a `file://` URL from the pasteboard to a path.
+///
+/// Finder drags carry file reference URLs (`file:///.file/id=...`), which
+/// only Foundation can turn back into a path, so the URL is left to NSURL
+/// to parse and resolve.
+fn file_url_to_path(url: &NSString) -> Option<PathBuf> {
     unsafe {
-        let _pasteboard = NSPasteboard::generalPasteboard();
-        // Would need to write string to pasteboard
+        let url = NSURL::URLWithString(url)?;
+        let path = url.filePathURL()?.path()?;
+        Some(PathBuf::from(path.to_string()))
+    }
+}
+
+/// Action callback of a native menu item.
+type MenuAction = Arc<dyn Fn() + Send + Sync>;
+
+/// Actions of native menu items, registered under the tag given to each
+/// item.
+#[derive(Default)]
+struct MenuActions(RefCell<Vec<MenuAction>>);
+
+impl MenuActions {
+    /// Registers an action and returns the tag to give its menu item.
+    fn add(&self, action: MenuAction) -> isize {
+        let mut actions = self.0.borrow_mut();
+        actions.push(action);
+        (actions.len() - 1) as isize
+    }
+
+    /// Returns the action registered under `tag`.
+    fn get(&self, tag: isize) -> Option<MenuAction> {
+        usize::try_from(tag).ok().and_then(|index| self.0.borrow().get(index).cloned())
+    }
+}
+
+/// State for the menu item target.
+#[derive(Default)]
+struct MKMenuTargetIvars {
+    actions: MenuActions,
+}
+
+declare_class!(
+    /// Target of custom menu items; runs the action registered under the
+    /// sending item's tag.
+    struct MKMenuTarget;
+
+    unsafe impl ClassType for MKMenuTarget {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "MKMenuTarget";
+    }
+
+    impl DeclaredClass for MKMenuTarget {
+        type Ivars = MKMenuTargetIvars;
+    }
+
+    unsafe impl MKMenuTarget {
+        #[method(performMenuAction:)]
+        fn perform_menu_action(&self, sender: &NSMenuItem) {
+            // Clone the action out so it may safely rebuild the menu
+            let action = self.ivars().actions.get(unsafe { sender.tag() });
+            if let Some(action) = action {
+                action();
+            }
+        }
+    }
+);
+
+impl MKMenuTarget {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = mtm.alloc::<MKMenuTarget>().set_ivars(MKMenuTargetIvars::default());
+        unsafe { msg_send_id![super(this), init] }
+    }
+
+    /// Registers an action and returns the tag to give its menu item.
+    fn add_action(&self, action: MenuAction) -> isize {
+        self.ivars().actions.add(action)
+    }
+}
+
+/// Interval of the run loop tick that drives timers, in seconds.
+const TICK_INTERVAL: f64 = 1.0 / 60.0;
+
+declare_class!(
+    /// Target of the run loop tick; fires due timers, lets animations
+    /// advance, and repaints the windows that changed.
+    struct MKTickTarget;
+
+    unsafe impl ClassType for MKTickTarget {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "MKTickTarget";
+    }
+
+    impl DeclaredClass for MKTickTarget {}
+
+    unsafe impl MKTickTarget {
+        #[method(tick:)]
+        fn tick(&self, _timer: &NSTimer) {
+            // Timer callbacks may change anything, so repaint everything
+            let fired = super::timer::fire_due(Instant::now());
+
+            let app = NSApplication::sharedApplication(MainThreadMarker::from(self));
+            for window in app.windows().iter() {
+                let Some(view) = window.contentView() else {
+                    continue;
+                };
+                if fired {
+                    unsafe { view.setNeedsDisplay(true); }
+                } else if view.isKindOfClass(MKView::class()) {
+                    // Checked above, so the content view is one of ours
+                    let view = unsafe { Retained::cast::<MKView>(view) };
+                    view.poll();
+                }
+            }
+        }
+    }
+);
+
+impl MKTickTarget {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = mtm.alloc::<MKTickTarget>().set_ivars(());
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
+declare_class!(
+    /// Application delegate; quits once the last window has closed.
+    struct MKAppDelegate;
+
+    unsafe impl ClassType for MKAppDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "MKAppDelegate";
+    }
+
+    impl DeclaredClass for MKAppDelegate {}
+
+    unsafe impl NSObjectProtocol for MKAppDelegate {}
+
+    unsafe impl NSApplicationDelegate for MKAppDelegate {
+        #[method(applicationShouldTerminateAfterLastWindowClosed:)]
+        fn should_terminate_after_last_window_closed(&self, _sender: &NSApplication) -> bool {
+            true
+        }
+    }
+);
+
+impl MKAppDelegate {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = mtm.alloc::<MKAppDelegate>().set_ivars(());
+        unsafe { msg_send_id![super(this), init] }
     }
 }
 
@@ -212,6 +410,10 @@ pub fn set_clipboard(_text: &str) {
 pub struct MacOSApp {
     app: Retained<NSApplication>,
     mtm: MainThreadMarker,
+    /// Kept alive here because menu items hold their target weakly.
+    menu_target: Retained<MKMenuTarget>,
+    /// Kept alive here because the application holds its delegate weakly.
+    _delegate: Retained<MKAppDelegate>,
 }
 
 impl MacOSApp {
@@ -222,7 +424,25 @@ impl MacOSApp {
         let app = NSApplication::sharedApplication(mtm);
         app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
 
-        let macos_app = Self { app, mtm };
+        let menu_target = MKMenuTarget::new(mtm);
+
+        // The timer retains its target and the run loop retains the timer
+        let tick_target = MKTickTarget::new(mtm);
+        let target: &AnyObject = &tick_target;
+        unsafe {
+            NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+                TICK_INTERVAL,
+                target,
+                objc2::sel!(tick:),
+                None,
+                true,
+            );
+        }
+
+        let delegate = MKAppDelegate::new(mtm);
+        app.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+
+        let macos_app = Self { app, mtm, menu_target, _delegate: delegate };
         macos_app.setup_menu();
 
         Some(macos_app)
@@ -488,14 +708,20 @@ impl MacOSApp {
             .unwrap_or_default();
         let key_str = NSString::from_str(&key_equiv);
 
-        // For items with callbacks, we can't easily map to Objective-C selectors
-        // So for now, items without callbacks will have no action
+        // Callbacks are dispatched through the shared target by item tag.
+        // Disabled items get no action so the menu keeps them greyed out.
+        let action = item.action.as_ref().filter(|_| item.enabled);
         let ns_item = NSMenuItem::initWithTitle_action_keyEquivalent(
             self.mtm.alloc(),
             &title,
-            None, // Custom callbacks require more complex setup
+            action.map(|_| objc2::sel!(performMenuAction:)),
             &key_str,
         );
+        if let Some(action) = action {
+            let target: &AnyObject = &self.menu_target;
+            ns_item.setTag(self.menu_target.add_action(action.clone()));
+            ns_item.setTarget(Some(target));
+        }
 
         // Set modifier mask if there's a shortcut
         if let Some(ref shortcut) = item.shortcut {
@@ -552,6 +778,7 @@ struct MKViewIvars {
     canvas: RefCell<Option<Canvas>>,
     content: RefCell<Option<ElementPtr>>,
     size: RefCell<Extent>,
+    clicks: RefCell<ClickCounter>,
 }
 
 declare_class!(
@@ -608,11 +835,31 @@ declare_class!(
             self.handle_mouse_drag(event);
         }
 
+        #[method(mouseMoved:)]
+        fn mouse_moved(&self, event: &NSEvent) {
+            self.handle_mouse_moved(event, CursorTracking::Hovering);
+        }
+
+        #[method(mouseEntered:)]
+        fn mouse_entered(&self, event: &NSEvent) {
+            self.handle_mouse_moved(event, CursorTracking::Entering);
+        }
+
+        #[method(mouseExited:)]
+        fn mouse_exited(&self, event: &NSEvent) {
+            self.handle_mouse_moved(event, CursorTracking::Leaving);
+        }
+
         #[method(scrollWheel:)]
         fn scroll_wheel(&self, event: &NSEvent) {
             self.handle_scroll(event);
         }
 
+        #[method(magnifyWithEvent:)]
+        fn magnify_with_event(&self, event: &NSEvent) {
+            self.handle_magnify(event);
+        }
+
         #[method(keyDown:)]
         fn key_down(&self, event: &NSEvent) {
             self.handle_key_event(event, true);
@@ -623,8 +870,39 @@ declare_class!(
             self.handle_key_event(event, false);
         }
 
+        #[method(draggingEntered:)]
+        fn dragging_entered(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> NSDragOperation {
+            self.handle_dragging(sender, Some(CursorTracking::Entering));
+            NSDragOperation::Copy
+        }
+
+        #[method(draggingUpdated:)]
+        fn dragging_updated(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> NSDragOperation {
+            self.handle_dragging(sender, Some(CursorTracking::Hovering));
+            NSDragOperation::Copy
+        }
+
+        #[method(draggingExited:)]
+        fn dragging_exited(&self, sender: Option<&ProtocolObject<dyn NSDraggingInfo>>) {
+            if let Some(sender) = sender {
+                self.handle_dragging(sender, Some(CursorTracking::Leaving));
+            }
+        }
+
+        #[method(performDragOperation:)]
+        fn perform_drag_operation(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> bool {
+            self.handle_dragging(sender, None)
+        }
+
+        #[method(viewDidChangeBackingProperties)]
+        fn view_did_change_backing_properties(&self) {
+            // Moved to a display with a different scale: the next draw
+            // reallocates the canvas at the new pixel size
+            unsafe { self.setNeedsDisplay(true); }
+        }
+
         #[method(drawRect:)]
-        fn draw_rect(&self, _dirty_rect: NSRect) {
+        fn draw_rect(&self, dirty_rect: NSRect) {
             let ivars = self.ivars();
 
             // Get actual view frame size
@@ -632,15 +910,20 @@ declare_class!(
             let size = Extent::new(frame.size.width as f32, frame.size.height as f32);
             *ivars.size.borrow_mut() = size;
 
-            let width = size.x as u32;
-            let height = size.y as u32;
+            // The canvas has a pixel per device pixel, so text and shapes
+            // stay crisp on Retina displays. Elements still lay out and
+            // receive events in points, which the canvas transform maps
+            // to pixels.
+            let scale = self.window().map_or(1.0, |window| window.backingScaleFactor()) as f32;
+            let width = (size.x * scale).ceil() as u32;
+            let height = (size.y * scale).ceil() as u32;
 
             if width == 0 || height == 0 {
                 return;
             }
 
             // Create or resize canvas
-            {
+            let needs_new = {
                 let mut canvas_opt = ivars.canvas.borrow_mut();
                 let needs_new = match &*canvas_opt {
                     Some(c) => c.width() != width || c.height() != height,
@@ -649,33 +932,53 @@ declare_class!(
                 if needs_new {
                     *canvas_opt = Canvas::new(width, height);
                 }
-            }
+                needs_new
+            };
+
+            // Only the dirty rect is repainted; the rest of the canvas still
+            // holds the last frame, unless it was just created
+            let bounds = Rect {
+                left: 0.0,
+                top: 0.0,
+                right: size.x,
+                bottom: size.y,
+            };
+            let dirty = ns_rect_to_rect(dirty_rect);
+            let area = if needs_new {
+                bounds
+            } else {
+                Rect::new(dirty.left.floor(), dirty.top.floor(), dirty.right.ceil(), dirty.bottom.ceil())
+            };
+            let Some(area) = area.intersection(bounds) else {
+                return;
+            };
+            log::trace!("repainting {area:?}");
 
             // Draw content and blit to screen
             let mut canvas_opt = ivars.canvas.borrow_mut();
             if let Some(ref mut canvas) = *canvas_opt {
+                canvas.save();
+                canvas.scale(scale, scale);
+
                 // Clear with dark background
-                canvas.clear(Color::new(0.2, 0.2, 0.2, 1.0));
+                canvas.push_clip(area);
+                canvas.fill_style(WINDOW_BACKGROUND);
+                canvas.fill_rect(area);
 
                 // Draw elements if we have content
                 let content_ref = ivars.content.borrow();
                 if let Some(ref content) = *content_ref {
-                    let bounds = Rect {
-                        left: 0.0,
-                        top: 0.0,
-                        right: size.x,
-                        bottom: size.y,
-                    };
 
                     // Create a temporary view for the context
-                    let temp_view = View::new(size);
+                    let mut temp_view = View::new(size);
+                    temp_view.set_scale(scale);
 
                     // We need to temporarily move the canvas into a RefCell for the Context
                     // Take canvas out, wrap in RefCell, draw, then put back
                     let temp_canvas = std::mem::replace(canvas, Canvas::new(1, 1).unwrap());
                     let canvas_cell = RefCell::new(temp_canvas);
 
-                    let ctx = Context::new(&temp_view, &canvas_cell, bounds);
+                    let ctx = Context::new(&temp_view, &canvas_cell, bounds).with_clip(area);
 
                     // Draw the content element
                     content.draw(&ctx);
@@ -683,9 +986,11 @@ declare_class!(
                     // Get the canvas back
                     *canvas = canvas_cell.into_inner();
                 }
+                canvas.pop_clip();
+                canvas.restore();
 
                 // Blit to screen
-                Self::blit_to_screen(canvas, width, height);
+                Self::blit_to_screen(canvas, size);
             }
         }
     }
@@ -702,9 +1007,35 @@ impl MKView {
             canvas: RefCell::new(None),
             content: RefCell::new(None),
             size: RefCell::new(size),
+            clicks: RefCell::new(ClickCounter::default()),
         });
 
-        unsafe { msg_send_id![super(this), initWithFrame: frame] }
+        let this: Retained<Self> = unsafe { msg_send_id![super(this), initWithFrame: frame] };
+
+        // Accept files and text dragged in from other applications
+        unsafe {
+            let types = NSArray::from_slice(&[NSPasteboardTypeFileURL, NSPasteboardTypeString]);
+            this.registerForDraggedTypes(&types);
+        }
+
+        // Track the mouse while it is over the view, to update the cursor
+        // and let elements respond to hovering
+        unsafe {
+            let options = NSTrackingAreaOptions::NSTrackingMouseMoved
+                | NSTrackingAreaOptions::NSTrackingMouseEnteredAndExited
+                | NSTrackingAreaOptions::NSTrackingActiveInKeyWindow
+                | NSTrackingAreaOptions::NSTrackingInVisibleRect;
+            let owner: &AnyObject = &this;
+            let area = NSTrackingArea::initWithRect_options_owner_userInfo(
+                mtm.alloc(),
+                NSRect::ZERO,
+                options,
+                Some(owner),
+                None,
+            );
+            this.addTrackingArea(&area);
+        }
+        this
     }
 
     fn set_content(&self, content: ElementPtr) {
@@ -716,6 +1047,27 @@ impl MKView {
         *self.ivars().size.borrow_mut() = size;
     }
 
+    /// Runs an idle tick: lets the content advance its animations, and
+    /// repaints if any of them moved.
+    fn poll(&self) {
+        let needs_redraw = self.ivars().content.borrow().as_ref().is_some_and(|content| content.handle_poll());
+        if needs_redraw {
+            unsafe { self.setNeedsDisplay(true); }
+        }
+    }
+
+    /// Schedules a repaint after the content handled an event.
+    ///
+    /// Only the areas the content refreshed are repainted. Elements that
+    /// don't report refreshes still change on events, so if nothing was
+    /// refreshed the whole view is repainted.
+    fn repaint(&self, view: &View) {
+        match view.take_dirty() {
+            Some(area) => unsafe { self.setNeedsDisplayInRect(rect_to_ns_rect(area)) },
+            None => unsafe { self.setNeedsDisplay(true) },
+        }
+    }
+
     fn handle_mouse_event(&self, event: &NSEvent, down: bool) {
         unsafe {
             // Get the mouse location in view coordinates
@@ -732,10 +1084,20 @@ impl MKView {
                 _ => MouseButtonKind::Left,
             };
 
+            // Count multi-clicks against our own threshold rather than the
+            // system's, so every host agrees on what a double-click is
+            let mut clicks = self.ivars().clicks.borrow_mut();
+            let click_count = if down {
+                clicks.press(button_kind, Duration::from_secs_f64(event.timestamp().max(0.0)), pos)
+            } else {
+                clicks.count()
+            };
+            drop(clicks);
+
             // Create MouseButton event
             let mouse_btn = MouseButton {
                 down,
-                click_count: event.clickCount() as i32,
+                click_count,
                 button: button_kind,
                 modifiers: translate_flags(event.modifierFlags().bits() as usize),
                 pos,
@@ -760,20 +1122,10 @@ impl MKView {
                     let temp_view = View::new(size);
                     let ctx = Context::new(&temp_view, &canvas_cell, bounds);
 
-                    // Handle the click first - this allows menus and other controls
-                    // to process the click before focus is cleared
-                    let handled = content.handle_click(&ctx, mouse_btn);
-
-                    // Clear focus from all elements on mouse down
-                    // This ensures text boxes lose focus when clicking elsewhere.
-                    // Note: Controls like TextBox will re-establish focus in handle_click
-                    // if they were the target of the click.
-                    if down {
-                        content.clear_focus();
-                    }
-
-                    // Trigger redraw
-                    self.setNeedsDisplay(true);
+                    // A press takes the focus from wherever it was, so
+                    // the control clicked can claim it
+                    dispatch_click(content.as_ref(), &ctx, mouse_btn);
+                    self.repaint(&temp_view);
                 }
             }
         }
@@ -822,12 +1174,40 @@ impl MKView {
 
                     // Call handle_drag on the content (immutable version)
                     content.handle_drag(&ctx, mouse_btn);
-                    self.setNeedsDisplay(true);
+                    self.repaint(&temp_view);
                 }
             }
         }
     }
 
+    /// Shows the cursor the element under the mouse asks for, and lets
+    /// the content respond to the mouse moving over it.
+    fn handle_mouse_moved(&self, event: &NSEvent, status: CursorTracking) {
+        let pos = unsafe { ns_point_to_point(self.convertPoint_fromView(event.locationInWindow(), None)) };
+        let ivars = self.ivars();
+        let size = *ivars.size.borrow();
+        let content_ref = ivars.content.borrow();
+
+        let Some(ref content) = *content_ref else {
+            return;
+        };
+        let Some(dummy_canvas) = Canvas::new(1, 1) else {
+            return;
+        };
+        let canvas_cell = RefCell::new(dummy_canvas);
+        let temp_view = View::new(size);
+        let ctx = Context::new(&temp_view, &canvas_cell, Rect::new(0.0, 0.0, size.x, size.y));
+        if status != CursorTracking::Leaving {
+            set_cursor(cursor_type_at(content.as_ref(), &ctx, pos));
+        }
+
+        // Hovering only repaints what the content refreshed
+        content.handle_cursor(&ctx, pos, status);
+        if let Some(area) = temp_view.take_dirty() {
+            unsafe { self.setNeedsDisplayInRect(rect_to_ns_rect(area)) };
+        }
+    }
+
     fn handle_scroll(&self, event: &NSEvent) {
         unsafe {
             let location_in_window = event.locationInWindow();
@@ -853,16 +1233,108 @@ impl MKView {
                 if let Some(dummy_canvas) = Canvas::new(1, 1) {
                     let canvas_cell = RefCell::new(dummy_canvas);
                     let temp_view = View::new(size);
+                    temp_view.set_modifiers(translate_flags(event.modifierFlags().bits() as usize));
                     let ctx = Context::new(&temp_view, &canvas_cell, bounds);
 
                     if content.handle_scroll(&ctx, dir, pos) {
-                        self.setNeedsDisplay(true);
+                        self.repaint(&temp_view);
                     }
                 }
             }
         }
     }
 
+    fn handle_magnify(&self, event: &NSEvent) {
+        unsafe {
+            let location = self.convertPoint_fromView(event.locationInWindow(), None);
+            let center = ns_point_to_point(location);
+            let scale_delta = event.magnification() as f32;
+
+            let ivars = self.ivars();
+            let size = *ivars.size.borrow();
+            let content_ref = ivars.content.borrow();
+            let Some(ref content) = *content_ref else {
+                return;
+            };
+            let Some(dummy_canvas) = Canvas::new(1, 1) else {
+                return;
+            };
+            let canvas_cell = RefCell::new(dummy_canvas);
+            let temp_view = View::new(size);
+            let ctx = Context::new(&temp_view, &canvas_cell, Rect::new(0.0, 0.0, size.x, size.y));
+
+            if content.handle_magnify(&ctx, scale_delta, center) {
+                self.repaint(&temp_view);
+            }
+        }
+    }
+
+    /// Reads the location and data of a drag from another application.
+    fn drop_info(&self, sender: &ProtocolObject<dyn NSDraggingInfo>) -> DropInfo {
+        unsafe {
+            let location = self.convertPoint_fromView(sender.draggingLocation(), None);
+            let info = DropInfo::new(ns_point_to_point(location));
+            let pasteboard = sender.draggingPasteboard();
+
+            let paths: Vec<PathBuf> = pasteboard
+                .pasteboardItems()
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.stringForType(NSPasteboardTypeFileURL))
+                        .filter_map(|url| file_url_to_path(&url))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if !paths.is_empty() {
+                return info.with_paths(paths);
+            }
+            match pasteboard.stringForType(NSPasteboardTypeString) {
+                Some(text) => info.with_text(text.to_string()),
+                None => info,
+            }
+        }
+    }
+
+    /// Passes a drag from another application to the content.
+    ///
+    /// With a tracking status the content follows the drag; without one
+    /// the data is dropped. Returns whether the drop was accepted.
+    fn handle_dragging(&self, sender: &ProtocolObject<dyn NSDraggingInfo>, status: Option<CursorTracking>) -> bool {
+        let info = self.drop_info(sender);
+        let ivars = self.ivars();
+        let size = *ivars.size.borrow();
+        let content_ref = ivars.content.borrow();
+
+        let Some(ref content) = *content_ref else {
+            return false;
+        };
+        let Some(dummy_canvas) = Canvas::new(1, 1) else {
+            return false;
+        };
+        let bounds = Rect::new(0.0, 0.0, size.x, size.y);
+        let canvas_cell = RefCell::new(dummy_canvas);
+        let temp_view = View::new(size);
+        let ctx = Context::new(&temp_view, &canvas_cell, bounds);
+
+        match status {
+            Some(status) => {
+                // Hovering only repaints what the content refreshed
+                content.handle_track_drop(&ctx, &info, status);
+                if let Some(area) = temp_view.take_dirty() {
+                    unsafe { self.setNeedsDisplayInRect(rect_to_ns_rect(area)) };
+                }
+                false
+            }
+            None => {
+                let accepted = content.handle_drop(&ctx, &info);
+                content.handle_track_drop(&ctx, &info, CursorTracking::Leaving);
+                self.repaint(&temp_view);
+                accepted
+            }
+        }
+    }
+
     fn handle_key_event(&self, event: &NSEvent, down: bool) {
         unsafe {
             use crate::view::{KeyInfo, KeyAction};
@@ -896,8 +1368,9 @@ impl MKView {
                     let temp_view = View::new(size);
                     let ctx = Context::new(&temp_view, &canvas_cell, bounds);
 
-                    if content.handle_key(&ctx, key_info) {
-                        self.setNeedsDisplay(true);
+                    // An unhandled Tab moves the focus; Shift-Tab moves it backward
+                    if dispatch_key(content.as_ref(), &ctx, key_info) {
+                        self.repaint(&temp_view);
                     }
                 }
             }
@@ -933,7 +1406,7 @@ impl MKView {
                                     let ctx = Context::new(&temp_view, &canvas_cell, bounds);
 
                                     if content.handle_text(&ctx, text_info) {
-                                        self.setNeedsDisplay(true);
+                                        self.repaint(&temp_view);
                                     }
                                 }
                             }
@@ -944,7 +1417,9 @@ impl MKView {
         }
     }
 
-    fn blit_to_screen(canvas: &Canvas, width: u32, height: u32) {
+    /// Draws the canvas into the current graphics context, filling `size`
+    /// in points.
+    fn blit_to_screen(canvas: &Canvas, size: Extent) {
         unsafe {
             // Get the current graphics context
             let Some(ns_ctx) = NSGraphicsContext::currentContext() else {
@@ -958,35 +1433,11 @@ impl MKView {
                 return;
             }
 
-            // Get pixmap data - tiny-skia stores premultiplied RGBA
-            let pixmap = canvas.pixmap();
-            let data = pixmap.data();
-
-            // Create CGImage from our pixmap
-            let color_space = CGColorSpace::create_device_rgb();
-            let provider = CGDataProvider::from_slice(data);
-
-            // tiny-skia uses premultiplied RGBA in native byte order
-            // On macOS (little-endian), we need:
-            // kCGImageAlphaPremultipliedLast (1) = RGBA with premultiplied alpha
-            // kCGBitmapByteOrderDefault (0) = native byte order
-            // Combined: just 1 for standard RGBA premultiplied
-            let cg_image = CGImage::new(
-                width as usize,
-                height as usize,
-                8,
-                32,
-                width as usize * 4,
-                &color_space,
-                1, // kCGImageAlphaPremultipliedLast (RGBA order)
-                &provider,
-                false,
-                0, // kCGRenderingIntentDefault
-            );
+            let cg_image = canvas_image(canvas);
 
             let rect = core_graphics::geometry::CGRect::new(
                 &core_graphics::geometry::CGPoint::new(0.0, 0.0),
-                &core_graphics::geometry::CGSize::new(width as f64, height as f64),
+                &core_graphics::geometry::CGSize::new(size.x as f64, size.y as f64),
             );
 
             let cg_ctx = CGContext::from_existing_context_ptr(cg_ctx_ptr as *mut _);
@@ -994,7 +1445,7 @@ impl MKView {
             // Flip the context to match our top-left origin coordinate system
             // Core Graphics has origin at bottom-left, we need top-left
             cg_ctx.save();
-            cg_ctx.translate(0.0, height as f64);
+            cg_ctx.translate(0.0, size.y as f64);
             cg_ctx.scale(1.0, -1.0);
             cg_ctx.draw_image(rect, &cg_image);
             cg_ctx.restore();
@@ -1002,25 +1453,145 @@ impl MKView {
     }
 }
 
+/// Bitmap layout of a tiny-skia pixmap: premultiplied R, G, B, A bytes in
+/// memory order, whatever the host byte order.
+///
+/// The default byte order leaves the 32-bit pixel interpretation up to
+/// Core Graphics; spelling out the big-endian order pins the alpha to the
+/// last byte, where tiny-skia keeps it.
+const CANVAS_BITMAP_INFO: u32 = kCGImageAlphaPremultipliedLast | kCGBitmapByteOrder32Big;
+
+/// Wraps the canvas pixels in a `CGImage`.
+///
+/// # Safety
+/// The image borrows the canvas pixels without copying them, so it must be
+/// drawn before the canvas changes.
+unsafe fn canvas_image(canvas: &Canvas) -> CGImage {
+    let pixmap = canvas.pixmap();
+    // tiny-skia renders in sRGB; tagging the image as such keeps colors
+    // from being shifted when Core Graphics matches them to the display
+    let color_space = CGColorSpace::create_with_name(kCGColorSpaceSRGB)
+        .unwrap_or_else(CGColorSpace::create_device_rgb);
+    let provider = CGDataProvider::from_slice(pixmap.data());
+    CGImage::new(
+        pixmap.width() as usize,
+        pixmap.height() as usize,
+        8,
+        32,
+        pixmap.width() as usize * 4,
+        &color_space,
+        CANVAS_BITMAP_INFO,
+        &provider,
+        false,
+        kCGRenderingIntentDefault,
+    )
+}
+
+/// State for the window delegate.
+#[derive(Default)]
+struct MKWindowDelegateIvars {
+    close_requested: RefCell<Option<CloseRequestCallback>>,
+    closed: RefCell<Option<ClosedCallback>>,
+    resized: RefCell<Option<ResizeCallback>>,
+    fullscreen_changed: RefCell<Option<FullscreenCallback>>,
+    /// Frame the window had before entering fullscreen.
+    saved_frame: Cell<Option<NSRect>>,
+}
+
+declare_class!(
+    /// Window delegate; runs the window's close callbacks.
+    struct MKWindowDelegate;
+
+    unsafe impl ClassType for MKWindowDelegate {
+        type Super = NSObject;
+        type Mutability = mutability::MainThreadOnly;
+        const NAME: &'static str = "MKWindowDelegate";
+    }
+
+    impl DeclaredClass for MKWindowDelegate {
+        type Ivars = MKWindowDelegateIvars;
+    }
+
+    unsafe impl NSObjectProtocol for MKWindowDelegate {}
+
+    unsafe impl NSWindowDelegate for MKWindowDelegate {
+        #[method(windowShouldClose:)]
+        fn window_should_close(&self, _sender: &NSWindow) -> bool {
+            close_allowed(&self.ivars().close_requested)
+        }
+
+        #[method(windowDidResize:)]
+        fn window_did_resize(&self, notification: &NSNotification) {
+            let Some(object) = (unsafe { notification.object() }) else {
+                return;
+            };
+            // The sender of windowDidResize: is always the window
+            let window = unsafe { Retained::cast::<NSWindow>(object) };
+            if let Some(view) = window.contentView() {
+                // Content lays itself out from the bounds it is drawn with,
+                // so the display pass that follows relays it out
+                notify(&self.ivars().resized, ns_size_to_extent(view.frame().size));
+            }
+        }
+
+        #[method(windowWillEnterFullScreen:)]
+        fn window_will_enter_full_screen(&self, notification: &NSNotification) {
+            if let Some(object) = unsafe { notification.object() } {
+                let window = unsafe { Retained::cast::<NSWindow>(object) };
+                self.ivars().saved_frame.set(Some(window.frame()));
+            }
+        }
+
+        #[method(windowDidEnterFullScreen:)]
+        fn window_did_enter_full_screen(&self, _notification: &NSNotification) {
+            notify(&self.ivars().fullscreen_changed, true);
+        }
+
+        #[method(windowDidExitFullScreen:)]
+        fn window_did_exit_full_screen(&self, notification: &NSNotification) {
+            let saved_frame = self.ivars().saved_frame.take();
+            if let (Some(frame), Some(object)) = (saved_frame, unsafe { notification.object() }) {
+                let window = unsafe { Retained::cast::<NSWindow>(object) };
+                window.setFrame_display(frame, true);
+            }
+            notify(&self.ivars().fullscreen_changed, false);
+        }
+
+        #[method(windowWillClose:)]
+        fn window_will_close(&self, _notification: &NSNotification) {
+            let closed = self.ivars().closed.borrow().clone();
+            if let Some(callback) = closed {
+                callback();
+            }
+        }
+    }
+);
+
+impl MKWindowDelegate {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = mtm.alloc::<MKWindowDelegate>().set_ivars(MKWindowDelegateIvars::default());
+        unsafe { msg_send_id![super(this), init] }
+    }
+}
+
 /// macOS window wrapper.
 pub struct MacOSWindow {
     window: Retained<NSWindow>,
     mk_view: Retained<MKView>,
+    /// Kept alive here because the window holds its delegate weakly.
+    delegate: Retained<MKWindowDelegate>,
     view: Option<View>,
 }
 
 impl MacOSWindow {
     /// Creates a new macOS window.
-    pub fn new(title: &str, size: Extent, mtm: MainThreadMarker) -> Self {
+    pub fn new(title: &str, size: Extent, window_style: &WindowStyle, mtm: MainThreadMarker) -> Self {
         let frame = NSRect::new(
             NSPoint::new(0.0, 0.0),
             extent_to_ns_size(size),
         );
 
-        let style = NSWindowStyleMask::Titled
-            | NSWindowStyleMask::Closable
-            | NSWindowStyleMask::Miniaturizable
-            | NSWindowStyleMask::Resizable;
+        let style = translate_style(window_style);
 
         let window = unsafe {
             NSWindow::initWithContentRect_styleMask_backing_defer(
@@ -1032,6 +1603,9 @@ impl MacOSWindow {
             )
         };
 
+        // The window is owned here, so AppKit must not release it on close
+        unsafe { window.setReleasedWhenClosed(false); }
+
         let title_str = NSString::from_str(title);
         window.setTitle(&title_str);
         window.center();
@@ -1040,11 +1614,19 @@ impl MacOSWindow {
         let mk_view = MKView::new(mtm, size);
         window.setContentView(Some(&mk_view));
 
-        Self {
+        let delegate = MKWindowDelegate::new(mtm);
+        window.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+
+        let this = Self {
             window,
             mk_view,
+            delegate,
             view: Some(View::new(size)),
+        };
+        if window_style.always_on_top {
+            this.set_always_on_top(true);
         }
+        this
     }
 
     /// Shows the window.
@@ -1074,6 +1656,11 @@ impl MacOSWindow {
         ns_size_to_extent(frame.size)
     }
 
+    /// Returns the size of the content area, excluding the title bar.
+    pub fn content_size(&self) -> Extent {
+        ns_size_to_extent(self.mk_view.frame().size)
+    }
+
     /// Sets the window size.
     pub fn set_size(&self, size: Extent) {
         let mut frame = self.window.frame();
@@ -1082,6 +1669,16 @@ impl MacOSWindow {
         self.mk_view.set_size(size);
     }
 
+    /// Sets the minimum content size the user can resize the window to.
+    pub fn set_min_size(&self, size: Extent) {
+        unsafe { self.window.setContentMinSize(extent_to_ns_size(size)); }
+    }
+
+    /// Sets the maximum content size the user can resize the window to.
+    pub fn set_max_size(&self, size: Extent) {
+        unsafe { self.window.setContentMaxSize(extent_to_ns_size(size)); }
+    }
+
     /// Sets the window content.
     pub fn set_content(&self, content: ElementPtr) {
         self.mk_view.set_content(content);
@@ -1097,8 +1694,135 @@ impl MacOSWindow {
         self.view.as_mut()
     }
 
+    /// Enters or leaves fullscreen; leaving restores the frame the window
+    /// had before.
+    pub fn set_fullscreen(&self, on: bool) {
+        if on == self.is_fullscreen() {
+            return;
+        }
+        // Windows that aren't resizable don't allow fullscreen by default
+        unsafe {
+            let behavior = self.window.collectionBehavior();
+            self.window.setCollectionBehavior(behavior | NSWindowCollectionBehavior::FullScreenPrimary);
+            self.window.toggleFullScreen(None);
+        }
+    }
+
+    /// Returns whether the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.styleMask().contains(NSWindowStyleMask::FullScreen)
+    }
+
+    /// Sets the callback run when the window enters or leaves fullscreen.
+    pub fn set_on_fullscreen_change(&self, callback: FullscreenCallback) {
+        *self.delegate.ivars().fullscreen_changed.borrow_mut() = Some(callback);
+    }
+
+    /// Floats the window above normal windows, or returns it to their level.
+    pub fn set_always_on_top(&self, on: bool) {
+        unsafe {
+            let level = if on { NSFloatingWindowLevel } else { NSNormalWindowLevel };
+            self.window.setLevel(level);
+        }
+    }
+
+    /// Returns whether this is the key window, which receives keyboard events.
+    pub fn is_key(&self) -> bool {
+        self.window.isKeyWindow()
+    }
+
+    /// Sets the callback asked before the user closes the window; returning
+    /// false keeps it open.
+    pub fn set_on_close_requested(&self, callback: CloseRequestCallback) {
+        *self.delegate.ivars().close_requested.borrow_mut() = Some(callback);
+    }
+
+    /// Sets the callback run once the window has closed.
+    pub fn set_on_closed(&self, callback: ClosedCallback) {
+        *self.delegate.ivars().closed.borrow_mut() = Some(callback);
+    }
+
+    /// Sets the callback run with the new content size when the window is
+    /// resized.
+    pub fn set_on_resize(&self, callback: ResizeCallback) {
+        *self.delegate.ivars().resized.borrow_mut() = Some(callback);
+    }
+
     /// Triggers a redraw.
     pub fn refresh(&self) {
         unsafe { self.mk_view.setNeedsDisplay(true); }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translucent_canvas_composites_over_black() {
+        // 50% white over an opaque black bitmap should come out mid gray
+        let mut canvas = Canvas::new(4, 4).unwrap();
+        canvas.fill_style(Color::new(1.0, 1.0, 1.0, 0.5));
+        canvas.fill_rect(Rect::new(0.0, 0.0, 4.0, 4.0));
+
+        let color_space = CGColorSpace::create_with_name(unsafe { kCGColorSpaceSRGB }).unwrap();
+        let mut target = CGContext::create_bitmap_context(None, 4, 4, 8, 16, &color_space, CANVAS_BITMAP_INFO);
+        let rect = core_graphics::geometry::CGRect::new(
+            &core_graphics::geometry::CGPoint::new(0.0, 0.0),
+            &core_graphics::geometry::CGSize::new(4.0, 4.0),
+        );
+        target.set_rgb_fill_color(0.0, 0.0, 0.0, 1.0);
+        target.fill_rect(rect);
+        unsafe { target.draw_image(rect, &canvas_image(&canvas)); }
+
+        for pixel in target.data().chunks_exact(4) {
+            for &channel in &pixel[..3] {
+                assert!(channel.abs_diff(128) <= 2, "got {pixel:?}");
+            }
+            assert_eq!(pixel[3], 255);
+        }
+    }
+
+    #[test]
+    fn test_translate_style() {
+        let mask = translate_style(&WindowStyle::default());
+        assert_eq!(
+            mask,
+            NSWindowStyleMask::Titled
+                | NSWindowStyleMask::Closable
+                | NSWindowStyleMask::Miniaturizable
+                | NSWindowStyleMask::Resizable
+        );
+
+        let fixed = WindowStyle { closable: false, resizable: false, ..WindowStyle::default() };
+        assert_eq!(translate_style(&fixed), NSWindowStyleMask::Titled | NSWindowStyleMask::Miniaturizable);
+
+        // Borderless windows have no title bar buttons, but may still resize
+        assert_eq!(translate_style(&WindowStyle::borderless()), NSWindowStyleMask::Borderless);
+        let resizable = WindowStyle { closable: true, resizable: true, ..WindowStyle::borderless() };
+        assert_eq!(translate_style(&resizable), NSWindowStyleMask::Borderless | NSWindowStyleMask::Resizable);
+    }
+
+    #[test]
+    fn test_menu_actions_by_tag() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let actions = MenuActions::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let tags: Vec<isize> = (1..=3)
+            .map(|amount| {
+                let runs = runs.clone();
+                actions.add(Arc::new(move || {
+                    runs.fetch_add(amount, Ordering::SeqCst);
+                }))
+            })
+            .collect();
+        assert_eq!(tags, vec![0, 1, 2]);
+
+        actions.get(tags[2]).unwrap()();
+        actions.get(tags[0]).unwrap()();
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+        assert!(actions.get(3).is_none());
+        assert!(actions.get(-1).is_none());
+    }
+}
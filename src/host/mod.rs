@@ -12,16 +12,50 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
+mod timer;
+
 #[cfg(target_os = "macos")]
 pub use macos::{MacOSApp, MacOSWindow};
 
-use crate::support::point::Extent;
+#[cfg(target_os = "macos")]
+pub(crate) use macos::{get_clipboard, set_clipboard, set_cursor};
+
+#[cfg(target_os = "linux")]
+pub use linux::{LinuxApp, LinuxWindow};
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux::set_cursor;
+
+pub use timer::TimerHandle;
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::support::canvas::Canvas;
+use crate::support::color::Color;
+use crate::support::point::{Extent, Point};
+use crate::support::rect::Rect;
+use crate::element::context::Context;
 use crate::view::View;
 use crate::element::ElementPtr;
 
 #[cfg(target_os = "macos")]
 use objc2_foundation::MainThreadMarker;
 
+/// Color windows clear to before drawing their content.
+pub(crate) const WINDOW_BACKGROUND: Color = Color::new(0.2, 0.2, 0.2, 1.0);
+
+/// Callback asked whether a window may close.
+pub type CloseRequestCallback = Rc<dyn Fn() -> bool>;
+/// Callback run after a window has closed.
+pub type ClosedCallback = Rc<dyn Fn()>;
+/// Callback run with a window's new content size.
+pub type ResizeCallback = Rc<dyn Fn(Extent)>;
+/// Callback run with whether a window is now fullscreen.
+pub type FullscreenCallback = Rc<dyn Fn(bool)>;
+
 /// Window position.
 #[derive(Debug, Clone, Copy)]
 pub struct WindowPosition {
@@ -54,6 +88,9 @@ pub struct WindowStyle {
     pub miniaturizable: bool,
     pub resizable: bool,
     pub borderless: bool,
+    /// Keeps the window above normal windows, as for tool palettes and
+    /// overlays.
+    pub always_on_top: bool,
 }
 
 impl Default for WindowStyle {
@@ -63,6 +100,7 @@ impl Default for WindowStyle {
             miniaturizable: true,
             resizable: true,
             borderless: false,
+            always_on_top: false,
         }
     }
 }
@@ -75,6 +113,7 @@ impl WindowStyle {
             miniaturizable: false,
             resizable: false,
             borderless: true,
+            always_on_top: false,
         }
     }
 }
@@ -144,10 +183,14 @@ pub struct Window {
     size: Extent,
     position: WindowPosition,
     style: WindowStyle,
+    min_size: Option<Extent>,
+    max_size: Option<Extent>,
     view: View,
     handle: Option<WindowHandle>,
     #[cfg(target_os = "macos")]
     macos_window: Option<MacOSWindow>,
+    #[cfg(target_os = "linux")]
+    linux_window: Option<LinuxWindow>,
 }
 
 impl Window {
@@ -157,37 +200,68 @@ impl Window {
 
         #[cfg(target_os = "macos")]
         let macos_window = {
-            MainThreadMarker::new().map(|mtm| MacOSWindow::new(&title_str, size, mtm))
+            MainThreadMarker::new().map(|mtm| {
+                MacOSWindow::new(&title_str, size, &WindowStyle::default(), mtm)
+            })
         };
 
+        #[cfg(target_os = "linux")]
+        let linux_window = LinuxWindow::new(&title_str, size);
+
         Self {
             title: title_str,
             size,
             position: WindowPosition::default(),
             style: WindowStyle::default(),
+            min_size: None,
+            max_size: None,
             view: View::new(size),
             handle: None,
             #[cfg(target_os = "macos")]
             macos_window,
+            #[cfg(target_os = "linux")]
+            linux_window,
         }
     }
 
     /// Creates a new window with the given options.
     fn new_with_options(builder: WindowBuilder) -> Self {
+        let size = constrain_size(builder.size, builder.min_size, builder.max_size);
+
         #[cfg(target_os = "macos")]
         let macos_window = {
-            MainThreadMarker::new().map(|mtm| MacOSWindow::new(&builder.title, builder.size, mtm))
+            MainThreadMarker::new().map(|mtm| {
+                let win = MacOSWindow::new(&builder.title, size, &builder.style, mtm);
+                if let Some(min_size) = builder.min_size {
+                    win.set_min_size(min_size);
+                }
+                if let Some(max_size) = builder.max_size {
+                    win.set_max_size(max_size);
+                }
+                win
+            })
         };
 
+        #[cfg(target_os = "linux")]
+        let linux_window = LinuxWindow::new(&builder.title, size).inspect(|win| {
+            if builder.style.always_on_top {
+                win.set_always_on_top(true);
+            }
+        });
+
         Self {
             title: builder.title,
-            size: builder.size,
+            size,
             position: builder.position,
             style: builder.style,
-            view: View::new(builder.size),
+            min_size: builder.min_size,
+            max_size: builder.max_size,
+            view: View::new(size),
             handle: None,
             #[cfg(target_os = "macos")]
             macos_window,
+            #[cfg(target_os = "linux")]
+            linux_window,
         }
     }
 
@@ -203,6 +277,10 @@ impl Window {
         if let Some(ref win) = self.macos_window {
             win.set_title(&self.title);
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_title(&self.title);
+        }
     }
 
     /// Returns the window size.
@@ -210,14 +288,29 @@ impl Window {
         self.size
     }
 
-    /// Sets the window size.
+    /// Sets the window size, constrained to the minimum and maximum sizes.
     pub fn set_size(&mut self, size: Extent) {
+        let size = constrain_size(size, self.min_size, self.max_size);
         self.size = size;
         self.view.set_size(size);
         #[cfg(target_os = "macos")]
         if let Some(ref win) = self.macos_window {
             win.set_size(size);
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_size(size);
+        }
+    }
+
+    /// Returns the minimum window size.
+    pub fn min_size(&self) -> Option<Extent> {
+        self.min_size
+    }
+
+    /// Returns the maximum window size.
+    pub fn max_size(&self) -> Option<Extent> {
+        self.max_size
     }
 
     /// Returns the window position.
@@ -247,6 +340,10 @@ impl Window {
         if let Some(ref win) = self.macos_window {
             win.set_content(content);
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_content(content);
+        }
     }
 
     /// Shows the window.
@@ -255,6 +352,10 @@ impl Window {
         if let Some(ref win) = self.macos_window {
             win.show();
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.show();
+        }
     }
 
     /// Hides the window.
@@ -263,6 +364,10 @@ impl Window {
         if let Some(ref win) = self.macos_window {
             win.hide();
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.hide();
+        }
     }
 
     /// Closes the window.
@@ -271,6 +376,120 @@ impl Window {
         if let Some(ref win) = self.macos_window {
             win.close();
         }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.close();
+        }
+    }
+
+    /// Sets a callback asked before the user closes the window, e.g. with its
+    /// close button. Returning false cancels the close.
+    pub fn on_close_requested<F: Fn() -> bool + 'static>(&mut self, callback: F) {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_on_close_requested(Rc::new(callback));
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_on_close_requested(Rc::new(callback));
+        }
+    }
+
+    /// Sets a callback run once the window has closed, however it was closed.
+    pub fn on_closed<F: Fn() + 'static>(&mut self, callback: F) {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_on_closed(Rc::new(callback));
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_on_closed(Rc::new(callback));
+        }
+    }
+
+    /// Sets a callback run with the new content size whenever the window is
+    /// resized, before the content is redrawn at that size.
+    pub fn on_resize<F: Fn(Extent) + 'static>(&mut self, callback: F) {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_on_resize(Rc::new(callback));
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_on_resize(Rc::new(callback));
+        }
+    }
+
+    /// Makes the window fullscreen, or restores it to the frame it had
+    /// before.
+    pub fn set_fullscreen(&mut self, on: bool) {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_fullscreen(on);
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_fullscreen(on);
+        }
+    }
+
+    /// Returns whether the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            return win.is_fullscreen();
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            return win.is_fullscreen();
+        }
+        false
+    }
+
+    /// Sets a callback run with the new state whenever the window enters or
+    /// leaves fullscreen, so layouts can adapt.
+    pub fn on_fullscreen_change<F: Fn(bool) + 'static>(&mut self, callback: F) {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_on_fullscreen_change(Rc::new(callback));
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_on_fullscreen_change(Rc::new(callback));
+        }
+    }
+
+    /// Keeps the window above normal windows, e.g. for a tool palette, or
+    /// lets other windows cover it again. May be called before or after the
+    /// window is shown.
+    pub fn set_always_on_top(&mut self, on: bool) {
+        self.style.always_on_top = on;
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.set_always_on_top(on);
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.set_always_on_top(on);
+        }
+    }
+
+    /// Returns whether the window stays above normal windows.
+    pub fn is_always_on_top(&self) -> bool {
+        self.style.always_on_top
+    }
+
+    /// Returns whether this is the key window, which receives keyboard events.
+    pub fn is_key(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            return win.is_key();
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            return win.is_key();
+        }
+        false
     }
 
     /// Returns whether the window is visible.
@@ -281,34 +500,114 @@ impl Window {
     /// Triggers a refresh of the window.
     pub fn refresh(&self) {
         self.view.refresh();
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            win.refresh();
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            win.refresh();
+        }
     }
 
     /// Returns the platform window handle.
     pub fn handle(&self) -> Option<WindowHandle> {
         self.handle
     }
+
+    /// Returns the size of the area the content is drawn in.
+    fn content_size(&self) -> Extent {
+        #[cfg(target_os = "macos")]
+        if let Some(ref win) = self.macos_window {
+            return win.content_size();
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(ref win) = self.linux_window {
+            return win.size();
+        }
+        self.size
+    }
+
+    /// Draws the current content offscreen, as the window shows it, and
+    /// saves it as a PNG file.
+    pub fn capture_png(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let size = self.content_size();
+        let canvas = Canvas::new(size.x.ceil() as u32, size.y.ceil() as u32)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "window has no area to capture"))?;
+        let canvas = RefCell::new(canvas);
+        let bounds = Rect::from_origin_size(Point::zero(), size);
+        {
+            let mut canvas = canvas.borrow_mut();
+            canvas.fill_style(WINDOW_BACKGROUND);
+            canvas.fill_rect(bounds);
+        }
+        if let Some(content) = self.view.content() {
+            let view = View::new(size);
+            content.draw(&Context::new(&view, &canvas, bounds));
+        }
+        canvas.into_inner().save_png(path)
+    }
+}
+
+/// Clamps `size` between the optional minimum and maximum sizes.
+fn constrain_size(size: Extent, min_size: Option<Extent>, max_size: Option<Extent>) -> Extent {
+    let mut size = size;
+    if let Some(max_size) = max_size {
+        size.x = size.x.min(max_size.x);
+        size.y = size.y.min(max_size.y);
+    }
+    if let Some(min_size) = min_size {
+        size.x = size.x.max(min_size.x);
+        size.y = size.y.max(min_size.y);
+    }
+    size
+}
+
+/// Asks a window's close-request callback whether the window may close.
+///
+/// Windows without one always may. The callback is cloned out before it
+/// runs, so it may replace itself.
+pub(crate) fn close_allowed(callback: &RefCell<Option<CloseRequestCallback>>) -> bool {
+    let callback = callback.borrow().clone();
+    callback.is_none_or(|callback| callback())
+}
+
+/// A window's slot for a callback taking a `T`.
+type CallbackSlot<T> = RefCell<Option<Rc<dyn Fn(T)>>>;
+
+/// Runs a window callback with `value`, if one is set.
+///
+/// The callback is cloned out before it runs, so it may replace itself.
+pub(crate) fn notify<T>(callback: &CallbackSlot<T>, value: T) {
+    let callback = callback.borrow().clone();
+    if let Some(callback) = callback {
+        callback(value);
+    }
 }
 
 /// The application.
+///
+/// Any number of windows can be open at once; they share the application's
+/// run loop, which ends when the last of them is closed.
 pub struct App {
     running: bool,
+    windows: Vec<Window>,
     #[cfg(target_os = "macos")]
     macos_app: Option<MacOSApp>,
+    #[cfg(target_os = "linux")]
+    linux_app: Option<LinuxApp>,
 }
 
 impl App {
     /// Creates a new application.
     pub fn new() -> Self {
-        #[cfg(target_os = "macos")]
-        {
-            Self {
-                running: false,
-                macos_app: MacOSApp::new(),
-            }
-        }
-        #[cfg(not(target_os = "macos"))]
-        {
-            Self { running: false }
+        Self {
+            running: false,
+            windows: Vec::new(),
+            #[cfg(target_os = "macos")]
+            macos_app: MacOSApp::new(),
+            #[cfg(target_os = "linux")]
+            linux_app: LinuxApp::new(),
         }
     }
 
@@ -321,6 +620,13 @@ impl App {
                 app.run();
             }
         }
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ref app) = self.linux_app {
+                app.run();
+            }
+            self.running = false;
+        }
     }
 
     /// Stops the application.
@@ -332,6 +638,12 @@ impl App {
                 app.stop();
             }
         }
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ref app) = self.linux_app {
+                app.stop();
+            }
+        }
     }
 
     /// Returns whether the application is running.
@@ -339,6 +651,46 @@ impl App {
         self.running
     }
 
+    /// Adds a window to the application and returns its index.
+    ///
+    /// The application keeps the window alive while it runs.
+    pub fn add_window(&mut self, window: Window) -> usize {
+        self.windows.push(window);
+        self.windows.len() - 1
+    }
+
+    /// Returns the application's windows, in the order they were added.
+    pub fn windows(&self) -> &[Window] {
+        &self.windows
+    }
+
+    /// Returns the window at `index`.
+    pub fn window(&self, index: usize) -> Option<&Window> {
+        self.windows.get(index)
+    }
+
+    /// Returns the window at `index` mutably.
+    pub fn window_mut(&mut self, index: usize) -> Option<&mut Window> {
+        self.windows.get_mut(index)
+    }
+
+    /// Returns the window that receives keyboard events, if it is one of
+    /// the application's windows.
+    pub fn key_window(&self) -> Option<&Window> {
+        self.windows.iter().find(|window| window.is_key())
+    }
+
+    /// Schedules `callback` to run on the event loop after `delay`, and then
+    /// every `delay` if `repeating`, until the returned handle is cancelled.
+    pub fn set_timer<F: Fn() + 'static>(&self, delay: Duration, repeating: bool, callback: F) -> TimerHandle {
+        timer::schedule(delay, repeating, callback)
+    }
+
+    /// Schedules `callback` to run once on the event loop after `delay`.
+    pub fn after<F: Fn() + 'static>(&self, delay: Duration, callback: F) -> TimerHandle {
+        timer::schedule(delay, false, callback)
+    }
+
     /// Returns the main thread marker (macOS only).
     #[cfg(target_os = "macos")]
     pub fn main_thread_marker(&self) -> Option<MainThreadMarker> {
@@ -367,3 +719,91 @@ pub enum PlatformError {
 
 /// Result type for platform operations.
 pub type PlatformResult<T> = Result<T, PlatformError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constrain_size() {
+        let min = Some(Extent::new(200.0, 100.0));
+        let max = Some(Extent::new(800.0, 600.0));
+        assert_eq!(constrain_size(Extent::new(400.0, 300.0), min, max), Extent::new(400.0, 300.0));
+        assert_eq!(constrain_size(Extent::new(100.0, 700.0), min, max), Extent::new(200.0, 600.0));
+        assert_eq!(constrain_size(Extent::new(900.0, 50.0), min, None), Extent::new(900.0, 100.0));
+        assert_eq!(constrain_size(Extent::new(900.0, 50.0), None, max), Extent::new(800.0, 50.0));
+
+        // A minimum larger than the maximum wins
+        let big_min = Some(Extent::new(1000.0, 100.0));
+        assert_eq!(constrain_size(Extent::new(400.0, 300.0), big_min, max), Extent::new(1000.0, 300.0));
+    }
+
+    #[test]
+    fn test_close_allowed() {
+        let callback = Rc::new(RefCell::new(None));
+        assert!(close_allowed(&callback));
+
+        *callback.borrow_mut() = Some(Rc::new(|| false) as CloseRequestCallback);
+        assert!(!close_allowed(&callback));
+
+        // Refuses once, then lets the next request through
+        let slot = Rc::downgrade(&callback);
+        *callback.borrow_mut() = Some(Rc::new(move || {
+            if let Some(slot) = slot.upgrade() {
+                *slot.borrow_mut() = Some(Rc::new(|| true) as CloseRequestCallback);
+            }
+            false
+        }));
+        assert!(!close_allowed(&callback));
+        assert!(close_allowed(&callback));
+    }
+
+    #[test]
+    fn test_notify_resized() {
+        let sizes = Rc::new(RefCell::new(Vec::new()));
+        let callback: Rc<RefCell<Option<ResizeCallback>>> = Rc::new(RefCell::new(None));
+        notify(&callback, Extent::new(100.0, 100.0));
+
+        // Records one size, then replaces itself with a callback that ignores
+        // the rest
+        let slot = Rc::downgrade(&callback);
+        let recorded = sizes.clone();
+        *callback.borrow_mut() = Some(Rc::new(move |size| {
+            recorded.borrow_mut().push(size);
+            if let Some(slot) = slot.upgrade() {
+                *slot.borrow_mut() = Some(Rc::new(|_| {}) as ResizeCallback);
+            }
+        }));
+        notify(&callback, Extent::new(400.0, 300.0));
+        notify(&callback, Extent::new(500.0, 300.0));
+        assert_eq!(*sizes.borrow(), [Extent::new(400.0, 300.0)]);
+    }
+
+    #[test]
+    fn test_always_on_top() {
+        let style = WindowStyle { always_on_top: true, ..WindowStyle::default() };
+        let mut window = WindowBuilder::new("Palette", Extent::new(200.0, 300.0)).style(style).build();
+        assert!(window.is_always_on_top());
+
+        window.set_always_on_top(false);
+        assert!(!window.is_always_on_top());
+        assert!(!Window::new("Main", Extent::new(400.0, 300.0)).is_always_on_top());
+    }
+
+    #[test]
+    fn test_app_keeps_windows_in_order() {
+        let mut app = App::new();
+        assert_eq!(app.add_window(Window::new("Main", Extent::new(400.0, 300.0))), 0);
+        assert_eq!(app.add_window(Window::new("Palette", Extent::new(200.0, 300.0))), 1);
+
+        let titles: Vec<_> = app.windows().iter().map(|window| window.title()).collect();
+        assert_eq!(titles, ["Main", "Palette"]);
+        assert!(app.window(2).is_none());
+
+        app.window_mut(1).unwrap().set_title("Tools");
+        assert_eq!(app.window(1).unwrap().title(), "Tools");
+
+        // Neither window has been shown, so none has the keyboard focus
+        assert!(app.key_window().is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! Timers driven by the application event loop.
+//!
+//! Timers live on the main thread. The platform run loop calls
+//! [`fire_due`] on every tick, which runs the callbacks whose time has
+//! come and reschedules the repeating ones.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Handle to a scheduled timer.
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl TimerHandle {
+    /// Cancels the timer. Its callback will not be called again.
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    /// Returns whether the timer has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+struct Timer {
+    due: Instant,
+    interval: Option<Duration>,
+    callback: Rc<dyn Fn()>,
+    cancelled: Rc<Cell<bool>>,
+}
+
+thread_local! {
+    static TIMERS: RefCell<Vec<Timer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Schedules `callback` to run after `delay`, and then every `delay` if
+/// `repeating`.
+pub(crate) fn schedule<F: Fn() + 'static>(delay: Duration, repeating: bool, callback: F) -> TimerHandle {
+    let cancelled = Rc::new(Cell::new(false));
+    TIMERS.with(|timers| {
+        timers.borrow_mut().push(Timer {
+            due: Instant::now() + delay,
+            interval: repeating.then_some(delay),
+            callback: Rc::new(callback),
+            cancelled: cancelled.clone(),
+        });
+    });
+    TimerHandle { cancelled }
+}
+
+/// Runs the callbacks of all timers due at `now`.
+///
+/// Returns true if any callback ran.
+pub(crate) fn fire_due(now: Instant) -> bool {
+    // Collect first so callbacks are free to schedule or cancel timers
+    let due: Vec<_> = TIMERS.with(|timers| {
+        let mut due = Vec::new();
+        timers.borrow_mut().retain_mut(|timer| {
+            if timer.cancelled.get() {
+                return false;
+            }
+            if timer.due > now {
+                return true;
+            }
+            due.push((timer.callback.clone(), timer.cancelled.clone()));
+            match timer.interval {
+                Some(interval) => {
+                    // Skip missed ticks rather than firing them in a burst
+                    timer.due = (timer.due + interval).max(now);
+                    true
+                }
+                None => false,
+            }
+        });
+        due
+    });
+
+    let mut fired = false;
+    for (callback, cancelled) in due {
+        // An earlier callback may have cancelled this one
+        if !cancelled.get() {
+            callback();
+            fired = true;
+        }
+    }
+    fired
+}
+
+/// Returns when the next timer is due, if any are scheduled.
+pub(crate) fn next_due() -> Option<Instant> {
+    TIMERS.with(|timers| {
+        timers
+            .borrow()
+            .iter()
+            .filter(|timer| !timer.cancelled.get())
+            .map(|timer| timer.due)
+            .min()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_shot_and_repeating() {
+        let count = Rc::new(Cell::new(0));
+
+        let c = count.clone();
+        schedule(Duration::from_millis(10), false, move || c.set(c.get() + 1));
+        let c = count.clone();
+        let repeating = schedule(Duration::from_millis(20), true, move || c.set(c.get() + 10));
+        let start = Instant::now();
+
+        assert!(!fire_due(start));
+        assert!(fire_due(start + Duration::from_millis(15)));
+        assert_eq!(count.get(), 1);
+
+        // The one-shot is gone; the repeating timer fires on each interval
+        assert!(fire_due(start + Duration::from_millis(25)));
+        assert!(fire_due(start + Duration::from_millis(45)));
+        assert_eq!(count.get(), 21);
+
+        repeating.cancel();
+        assert!(!fire_due(start + Duration::from_millis(100)));
+        assert_eq!(count.get(), 21);
+        assert!(next_due().is_none());
+    }
+}
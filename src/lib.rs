@@ -54,41 +54,52 @@ pub mod prelude {
         rect::Rect,
         color::{Color, colors},
         canvas::Canvas,
+        animation::{Animated, Easing},
     };
     pub use crate::element::{
         Element, ElementPtr, WeakElementPtr,
         ViewLimits, ViewStretch,
         share,
         context::{BasicContext, Context},
-        proxy::Proxy,
+        proxy::{Proxy, DoubleClick, on_double_click, DropTarget, on_drop},
         composite::{Composite, CompositeBase},
         tile::{vtile, htile, VTile, HTile},
         align::*,
         margin::*,
         size::*,
         layer::*,
-        label::{label, Label},
-        button::{button, BasicButton},
-        slider::{slider, vslider, Slider, SliderOrientation},
+        decoration::{background, border, drop_shadow, opacity, Background, Border, DropShadow, Opacity},
+        icon::{icon, Icon, IconPath, PathCommand},
+        image::{image_file, Image, ScaleMode},
+        label::{label, rich_label, Label, RichLabel, TextSpan},
+        button::{button, BasicButton, ButtonIcon, IconPosition},
+        slider::{slider, vslider, range_slider, Slider, RangeSlider, SliderOrientation},
         checkbox::{checkbox, Checkbox, radio_button, RadioButton},
         switch::{slide_switch, SlideSwitch},
         dial::{dial, dial_with_range, Dial},
+        color_picker::{color_picker, ColorPicker},
         text_box::{text_box, TextBox},
+        text_area::{text_area, TextArea},
+        number_input::{number_input, NumberInput},
         menu::{
-            menu, menu_item, menu_separator, popup, Menu, MenuItem, Popup,
+            menu, menu_item, menu_separator, popup, context_menu, Menu, MenuItem, Popup, ContextMenu,
             native_menu_item, native_separator, native_menu, native_menu_bar,
             set_native_menu_bar, get_native_menu_bar,
             NativeMenuItem, NativeMenu, NativeMenuBar, MenuShortcut, MenuModifiers, StandardAction,
         },
         list::{list, dropdown, List, Dropdown, ListItem},
-        grid::{grid, Grid},
+        grid::{grid, grid_with_rows, grid_span, Grid, GridSpan},
         floating::{floating, Floating},
         status_bar::{status_bar, StatusBar, StatusSegment},
         thumbwheel::{thumbwheel, Thumbwheel},
         scroll::{scroll_view, ScrollView},
         tabs::{tab_bar, TabBar, Tab},
+        segmented::{segmented_control, SegmentedControl},
+        collapsible::{collapsible, Collapsible},
+        splitter::{hsplitter, vsplitter, Splitter, SplitOrientation},
         tooltip::{tooltip, Tooltip},
         progress::{progress_bar, circular_progress, indeterminate_progress, ProgressBar, ProgressStyle},
+        zoom::{zoomable, Zoomable},
     };
     pub use crate::view::{
         View, BaseView,
@@ -96,6 +107,7 @@ pub mod prelude {
         KeyCode, KeyAction, KeyInfo,
         CursorTracking, CursorType,
         TextInfo, DropInfo,
+        render_to_pixmap, render_to_pixmap_scaled, TestView,
     };
     pub use crate::host::{App, Window};
     pub use crate::{vtile, htile};
@@ -0,0 +1,231 @@
+//! Animated value transitions.
+//!
+//! An [`Animated`] value moves from where it is to a new target over a
+//! set duration, following an [`Easing`] curve. Elements advance it from
+//! their idle tick and redraw while it is moving.
+
+use std::time::{Duration, Instant};
+use super::color::Color;
+use super::point::Point;
+
+/// A value that can be blended towards another.
+pub trait Interpolate: Copy + PartialEq {
+    /// Returns the value `t` of the way from `self` to `other`.
+    ///
+    /// `t` may fall outside 0 to 1 for easings that overshoot.
+    fn interpolate(self, other: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+/// How an animation's progress maps to the distance covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly and speeds up.
+    EaseIn,
+    /// Starts quickly and slows down towards the end.
+    EaseOut,
+    /// Speeds up and then slows down.
+    #[default]
+    EaseInOut,
+    /// Overshoots the target and settles back, like a spring.
+    Spring,
+}
+
+impl Easing {
+    /// Returns the distance covered at `t`, both running from 0 to 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Spring if t == 1.0 => 1.0,
+            Easing::Spring => 1.0 - (-6.0 * t).exp() * (3.0 * std::f32::consts::PI * t).cos(),
+        }
+    }
+}
+
+/// A value that transitions smoothly to new targets.
+///
+/// Setting a target starts a transition from the current value, so
+/// retargeting in the middle of one carries on from where it got to.
+/// Call [`poll`](Self::poll) from the element's idle tick and redraw while
+/// it returns true.
+#[derive(Debug, Clone, Copy)]
+pub struct Animated<T: Interpolate> {
+    from: T,
+    to: T,
+    value: T,
+    duration: Duration,
+    easing: Easing,
+    /// Seconds since the transition started, while one is running
+    elapsed: Option<f32>,
+    last_tick: Option<Instant>,
+}
+
+impl<T: Interpolate> Animated<T> {
+    /// Creates a value at rest, transitioning over 250ms with ease-in-out.
+    pub fn new(value: T) -> Self {
+        Self {
+            from: value,
+            to: value,
+            value,
+            duration: Duration::from_millis(250),
+            easing: Easing::EaseInOut,
+            elapsed: None,
+            last_tick: None,
+        }
+    }
+
+    /// Sets how long transitions take.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the easing curve.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> T {
+        self.value
+    }
+
+    /// Returns the value being moved towards.
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    /// Returns true while a transition is running.
+    pub fn is_animating(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    /// Starts a transition from the current value to `target`.
+    pub fn set_target(&mut self, target: T) {
+        if target == self.to {
+            return;
+        }
+        self.from = self.value;
+        self.to = target;
+        self.elapsed = Some(0.0);
+    }
+
+    /// Jumps straight to `value`, stopping any transition.
+    pub fn set(&mut self, value: T) {
+        self.from = value;
+        self.to = value;
+        self.value = value;
+        self.elapsed = None;
+    }
+
+    /// Advances the transition by `dt` seconds.
+    ///
+    /// Returns true if the value moved.
+    pub fn advance(&mut self, dt: f32) -> bool {
+        let Some(elapsed) = self.elapsed else {
+            return false;
+        };
+        let elapsed = elapsed + dt;
+        let duration = self.duration.as_secs_f32();
+        if elapsed >= duration {
+            self.value = self.to;
+            self.elapsed = None;
+        } else {
+            self.value = self.from.interpolate(self.to, self.easing.apply(elapsed / duration));
+            self.elapsed = Some(elapsed);
+        }
+        true
+    }
+
+    /// Advances the transition by the time since the last poll.
+    ///
+    /// Returns true if the value moved and needs to be redrawn.
+    pub fn poll(&mut self) -> bool {
+        if !self.is_animating() {
+            // Restart the clock with the next transition
+            self.last_tick = None;
+            return false;
+        }
+        let now = Instant::now();
+        let dt = self.last_tick.replace(now).map_or(0.0, |t| now.duration_since(t).as_secs_f32());
+        self.advance(dt)
+    }
+}
+
+impl<T: Interpolate + Default> Default for Animated<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut, Easing::Spring] {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+        // The spring overshoots on the way
+        assert!(Easing::Spring.apply(0.35) > 1.0);
+    }
+
+    #[test]
+    fn test_animated_transitions() {
+        let mut value = Animated::new(0.0f32).duration(Duration::from_millis(100)).easing(Easing::Linear);
+        assert!(!value.advance(0.1));
+
+        value.set_target(1.0);
+        assert!(value.advance(0.05));
+        assert!((value.get() - 0.5).abs() < 1e-5);
+
+        // Retargeting carries on from the current value
+        value.set_target(0.0);
+        assert!(value.advance(0.05));
+        assert!((value.get() - 0.25).abs() < 1e-5);
+        assert!(value.advance(0.05));
+        assert_eq!(value.get(), 0.0);
+        assert!(!value.is_animating());
+
+        let mut color = Animated::new(Color::new(0.0, 0.0, 0.0, 1.0)).easing(Easing::Linear);
+        color.set_target(Color::new(1.0, 1.0, 1.0, 1.0));
+        color.advance(0.125);
+        assert!((color.get().red - 0.5).abs() < 1e-5);
+        color.set(Color::new(1.0, 0.0, 0.0, 1.0));
+        assert!(!color.is_animating());
+    }
+}
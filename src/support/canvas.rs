@@ -3,13 +3,13 @@
 //! This module provides a high-level drawing API that wraps the underlying
 //! graphics backend (tiny-skia).
 
-use std::sync::OnceLock;
+use std::path::Path;
 
 use super::color::Color;
 use super::point::Point;
 use super::rect::Rect;
 use super::circle::Circle;
-use super::font::{Font, FontDatabase};
+use super::font::{self, CachedFace, FaceKey, Font, FontId, FontStyle};
 
 /// Text alignment options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -63,6 +63,14 @@ pub enum LineJoin {
     Bevel,
 }
 
+/// Sampling used when drawing scaled images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageSampling {
+    Nearest,
+    #[default]
+    Linear,
+}
+
 /// A color stop for gradients.
 #[derive(Debug, Clone, Copy)]
 pub struct ColorStop {
@@ -118,6 +126,67 @@ impl RadialGradient {
     }
 }
 
+/// An angular (conic) gradient, sweeping around a center.
+///
+/// Stop offsets run clockwise through one full turn, starting at
+/// `start_angle` (radians from the positive x axis, y pointing down).
+#[derive(Debug, Clone)]
+pub struct ConicGradient {
+    pub center: Point,
+    pub start_angle: f32,
+    pub stops: Vec<ColorStop>,
+}
+
+impl ConicGradient {
+    pub fn new(center: Point, start_angle: f32) -> Self {
+        Self {
+            center,
+            start_angle,
+            stops: Vec::new(),
+        }
+    }
+
+    pub fn add_stop(&mut self, offset: f32, color: Color) {
+        self.stops.push(ColorStop { offset, color });
+    }
+
+    /// Returns the gradient's color in the direction of `angle`.
+    pub fn color_at(&self, angle: f32) -> Color {
+        sample_stops(&sorted_stops(&self.stops), self.offset_at(angle))
+    }
+
+    /// Returns the stop offset in the direction of `angle`.
+    fn offset_at(&self, angle: f32) -> f32 {
+        let turn = std::f32::consts::TAU;
+        (angle - self.start_angle).rem_euclid(turn) / turn
+    }
+}
+
+/// Returns the stops with offsets clamped to `0.0..=1.0`, in order.
+fn sorted_stops(stops: &[ColorStop]) -> Vec<ColorStop> {
+    let mut sorted: Vec<ColorStop> = stops
+        .iter()
+        .map(|s| ColorStop { offset: s.offset.clamp(0.0, 1.0), color: s.color })
+        .collect();
+    sorted.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    sorted
+}
+
+/// Interpolates sorted stops at `offset`, padding with the end colors.
+fn sample_stops(stops: &[ColorStop], offset: f32) -> Color {
+    let Some(first) = stops.first() else {
+        return Color::new(0.0, 0.0, 0.0, 0.0);
+    };
+    match stops.iter().position(|s| s.offset > offset) {
+        Some(0) => first.color,
+        Some(i) => {
+            let (a, b) = (stops[i - 1], stops[i]);
+            a.color.lerp(b.color, (offset - a.offset) / (b.offset - a.offset))
+        }
+        None => stops[stops.len() - 1].color,
+    }
+}
+
 /// Text metrics returned from measuring text.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TextMetrics {
@@ -176,20 +245,42 @@ pub struct Canvas {
     fill_color: Color,
     stroke_color: Color,
     line_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f32,
+    /// Dash pattern and offset for strokes; solid when the pattern is empty
+    line_dash: (Vec<f32>, f32),
+    fill_rule: FillRule,
+    image_sampling: ImageSampling,
     text_align: TextAlign,
     transform: tiny_skia::Transform,
     save_stack: Vec<CanvasState>,
-    current_font: Option<Font>,
+    font: Font,
+    font_weight: u16,
+    font_style: FontStyle,
     font_size: f32,
     clip_rect: Option<Rect>,
+    clip_mask: Option<tiny_skia::Mask>,
+    clip_stack: Vec<Option<Rect>>,
+    /// Pixmaps set aside by `push_group_opacity`, with their group's opacity.
+    group_stack: Vec<(tiny_skia::Pixmap, f32)>,
 }
 
 struct CanvasState {
     fill_color: Color,
     stroke_color: Color,
     line_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f32,
+    line_dash: (Vec<f32>, f32),
+    fill_rule: FillRule,
+    image_sampling: ImageSampling,
     text_align: TextAlign,
     transform: tiny_skia::Transform,
+    font: Font,
+    font_weight: u16,
+    font_style: FontStyle,
     font_size: f32,
     clip_rect: Option<Rect>,
 }
@@ -204,12 +295,23 @@ impl Canvas {
             fill_color: Color::new(0.0, 0.0, 0.0, 1.0),
             stroke_color: Color::new(0.0, 0.0, 0.0, 1.0),
             line_width: 1.0,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
+            miter_limit: 4.0,
+            line_dash: (Vec::new(), 0.0),
+            fill_rule: FillRule::default(),
+            image_sampling: ImageSampling::default(),
             text_align: TextAlign::default(),
             transform: tiny_skia::Transform::identity(),
             save_stack: Vec::new(),
-            current_font: None,
+            font: Font::default(),
+            font_weight: 400,
+            font_style: FontStyle::Normal,
             font_size: 12.0,
             clip_rect: None,
+            clip_mask: None,
+            clip_stack: Vec::new(),
+            group_stack: Vec::new(),
         })
     }
 
@@ -221,12 +323,23 @@ impl Canvas {
             fill_color: Color::new(0.0, 0.0, 0.0, 1.0),
             stroke_color: Color::new(0.0, 0.0, 0.0, 1.0),
             line_width: 1.0,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
+            miter_limit: 4.0,
+            line_dash: (Vec::new(), 0.0),
+            fill_rule: FillRule::default(),
+            image_sampling: ImageSampling::default(),
             text_align: TextAlign::default(),
             transform: tiny_skia::Transform::identity(),
             save_stack: Vec::new(),
-            current_font: None,
+            font: Font::default(),
+            font_weight: 400,
+            font_style: FontStyle::Normal,
             font_size: 12.0,
             clip_rect: None,
+            clip_mask: None,
+            clip_stack: Vec::new(),
+            group_stack: Vec::new(),
         }
     }
 
@@ -250,6 +363,53 @@ impl Canvas {
         &mut self.pixmap
     }
 
+    /// Saves the canvas contents as a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.pixmap.save_png(path).map_err(std::io::Error::other)
+    }
+
+    /// Consumes the canvas, returning the underlying pixmap.
+    pub fn into_pixmap(self) -> tiny_skia::Pixmap {
+        self.pixmap
+    }
+
+    /// Returns the color of the pixel at (x, y), in device pixels, or
+    /// `None` outside the canvas.
+    pub fn pixel_at(&self, x: u32, y: u32) -> Option<Color> {
+        // The pixmap only checks the pixel index, so x past the right
+        // edge would wrap to the next row
+        if x >= self.pixmap.width() {
+            return None;
+        }
+        self.pixmap.pixel(x, y).map(Self::pixel_color)
+    }
+
+    /// Returns the colors of the pixels under `r`, in device pixels, row
+    /// by row.
+    ///
+    /// The rect is rounded out to whole pixels and clipped to the canvas;
+    /// a rect outside it reads nothing.
+    pub fn read_region(&self, r: Rect) -> Vec<Color> {
+        let clamp = |v: f32, max: u32| v.clamp(0.0, max as f32) as u32;
+        let (width, height) = (self.pixmap.width(), self.pixmap.height());
+        let (left, right) = (clamp(r.left.floor(), width), clamp(r.right.ceil(), width));
+        let (top, bottom) = (clamp(r.top.floor(), height), clamp(r.bottom.ceil(), height));
+        let pixels = self.pixmap.pixels();
+        (top..bottom)
+            .flat_map(|y| {
+                let row = (y * width) as usize;
+                pixels[row + left as usize..row + right.max(left) as usize].iter()
+            })
+            .map(|&pixel| Self::pixel_color(pixel))
+            .collect()
+    }
+
+    /// Converts a premultiplied pixel to a straight color.
+    fn pixel_color(pixel: tiny_skia::PremultipliedColorU8) -> Color {
+        let c = pixel.demultiply();
+        Color::from_rgba_u8(c.red(), c.green(), c.blue(), c.alpha())
+    }
+
     // --- Transforms ---
 
     /// Translates the canvas.
@@ -295,29 +455,106 @@ impl Canvas {
         }
     }
 
-    /// Draws an arc.
-    pub fn arc(&mut self, center: Point, radius: f32, start_angle: f32, end_angle: f32, ccw: bool) {
+    /// Draws a quadratic bezier curve to `p`, with control point `cp`.
+    pub fn quadratic_curve_to(&mut self, cp: Point, p: Point) {
+        if let Some(ref mut pb) = self.path_builder {
+            pb.quad_to(cp.x, cp.y, p.x, p.y);
+        }
+    }
+
+    /// Draws a cubic bezier curve to `p`, with control points `cp1` and `cp2`.
+    pub fn bezier_curve_to(&mut self, cp1: Point, cp2: Point, p: Point) {
         if let Some(ref mut pb) = self.path_builder {
-            // Convert angles to degrees and use arc_to approximation
-            let sweep = if ccw {
-                start_angle - end_angle
+            pb.cubic_to(cp1.x, cp1.y, cp2.x, cp2.y, p.x, p.y);
+        }
+    }
+
+    /// Adds a circular arc to the path.
+    ///
+    /// Angles are in radians, measured clockwise from the positive x axis
+    /// (y points down). The arc runs clockwise from `start_angle` to
+    /// `end_angle`, or counterclockwise if `ccw` is set. A sweep of 2π or
+    /// more draws a full circle. If the path has a current point, a line is
+    /// added from it to the start of the arc.
+    pub fn arc(&mut self, center: Point, radius: f32, start_angle: f32, end_angle: f32, ccw: bool) {
+        let sweep = Self::arc_sweep(start_angle, end_angle, ccw);
+        self.append_arc(center, radius, radius, 0.0, start_angle, sweep);
+    }
+
+    /// Adds an elliptical arc to the path.
+    ///
+    /// The ellipse has radii `rx` and `ry` and is rotated by `rotation`
+    /// radians around `center`. The arc runs clockwise from `start_angle` to
+    /// `end_angle`, both measured in the ellipse's own (unrotated) frame.
+    pub fn ellipse(&mut self, center: Point, rx: f32, ry: f32, rotation: f32, start_angle: f32, end_angle: f32) {
+        let sweep = Self::arc_sweep(start_angle, end_angle, false);
+        self.append_arc(center, rx, ry, rotation, start_angle, sweep);
+    }
+
+    /// Normalizes the sweep from `start` to `end` in the given direction.
+    fn arc_sweep(start: f32, end: f32, ccw: bool) -> f32 {
+        use std::f32::consts::TAU;
+        let delta = end - start;
+        if ccw {
+            if delta <= -TAU {
+                -TAU
             } else {
-                end_angle - start_angle
-            };
+                let sweep = delta % TAU;
+                if sweep > 0.0 { sweep - TAU } else { sweep }
+            }
+        } else if delta >= TAU {
+            TAU
+        } else {
+            let sweep = delta % TAU;
+            if sweep < 0.0 { sweep + TAU } else { sweep }
+        }
+    }
+
+    /// Appends an elliptical arc as cubic beziers of at most 90° each.
+    fn append_arc(&mut self, center: Point, rx: f32, ry: f32, rotation: f32, start: f32, sweep: f32) {
+        let Some(ref mut pb) = self.path_builder else {
+            return;
+        };
 
-            // For simplicity, approximate with bezier curves
-            // This is a simplified implementation
-            let start_x = center.x + radius * start_angle.cos();
-            let start_y = center.y + radius * start_angle.sin();
-            let end_x = center.x + radius * end_angle.cos();
-            let end_y = center.y + radius * end_angle.sin();
+        let (sin_r, cos_r) = rotation.sin_cos();
+        let point_at = |angle: f32, scale: f32| {
+            let (sin_a, cos_a) = angle.sin_cos();
+            let x = rx * cos_a * scale;
+            let y = ry * sin_a * scale;
+            (center.x + x * cos_r - y * sin_r, center.y + x * sin_r + y * cos_r)
+        };
+        let tangent_at = |angle: f32| {
+            let (sin_a, cos_a) = angle.sin_cos();
+            let x = -rx * sin_a;
+            let y = ry * cos_a;
+            (x * cos_r - y * sin_r, x * sin_r + y * cos_r)
+        };
 
-            pb.line_to(start_x, start_y);
-            // Use quadratic bezier approximation for the arc
-            let mid_angle = (start_angle + end_angle) / 2.0;
-            let ctrl_x = center.x + radius * 1.3 * mid_angle.cos();
-            let ctrl_y = center.y + radius * 1.3 * mid_angle.sin();
-            pb.quad_to(ctrl_x, ctrl_y, end_x, end_y);
+        let (x0, y0) = point_at(start, 1.0);
+        if pb.is_empty() {
+            pb.move_to(x0, y0);
+        } else {
+            pb.line_to(x0, y0);
+        }
+
+        let segments = (sweep.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / segments as f32;
+        // Control point distance along the tangent for a cubic arc segment
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+        let mut a0 = start;
+        for _ in 0..segments {
+            let a1 = a0 + step;
+            let (p0x, p0y) = point_at(a0, 1.0);
+            let (p1x, p1y) = point_at(a1, 1.0);
+            let (t0x, t0y) = tangent_at(a0);
+            let (t1x, t1y) = tangent_at(a1);
+            pb.cubic_to(
+                p0x + k * t0x, p0y + k * t0y,
+                p1x - k * t1x, p1y - k * t1y,
+                p1x, p1y,
+            );
+            a0 = a1;
         }
     }
 
@@ -394,29 +631,122 @@ impl Canvas {
         self.line_width = width;
     }
 
+    /// Sets the line cap style.
+    pub fn line_cap(&mut self, cap: LineCap) {
+        self.line_cap = cap;
+    }
+
+    /// Sets the line join style.
+    pub fn line_join(&mut self, join: LineJoin) {
+        self.line_join = join;
+    }
+
+    /// Sets the miter limit used by `LineJoin::Miter`.
+    pub fn miter_limit(&mut self, limit: f32) {
+        self.miter_limit = limit;
+    }
+
+    /// Sets the dash pattern for strokes: alternating dash and gap lengths,
+    /// starting `offset` into the pattern.
+    ///
+    /// A pattern with an odd number of lengths is repeated to make it even.
+    /// An empty pattern makes strokes solid again.
+    pub fn line_dash(&mut self, pattern: &[f32], offset: f32) {
+        let mut pattern = pattern.to_vec();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        self.line_dash = (pattern, offset);
+    }
+
+    /// Sets the rule deciding which parts of a self-overlapping path are
+    /// inside when it is filled.
+    pub fn fill_rule(&mut self, rule: FillRule) {
+        self.fill_rule = rule;
+    }
+
+    /// Sets the sampling used when drawing scaled images.
+    pub fn image_sampling(&mut self, sampling: ImageSampling) {
+        self.image_sampling = sampling;
+    }
+
     // --- Drawing ---
 
-    fn color_to_paint(color: Color) -> tiny_skia::Paint<'static> {
-        let mut paint = tiny_skia::Paint::default();
-        paint.set_color(tiny_skia::Color::from_rgba(
+    fn to_skia_color(color: Color) -> tiny_skia::Color {
+        tiny_skia::Color::from_rgba(
             color.red,
             color.green,
             color.blue,
             color.alpha,
-        ).unwrap_or(tiny_skia::Color::BLACK));
+        ).unwrap_or(tiny_skia::Color::BLACK)
+    }
+
+    fn color_to_paint(color: Color) -> tiny_skia::Paint<'static> {
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color(Self::to_skia_color(color));
         paint.anti_alias = true;
         paint
     }
 
-    /// Creates a clip mask for the current clip_rect.
-    fn create_clip_mask(&self) -> Option<tiny_skia::Mask> {
-        self.clip_rect.and_then(|clip| {
+    fn skia_fill_rule(&self) -> tiny_skia::FillRule {
+        match self.fill_rule {
+            FillRule::NonZero => tiny_skia::FillRule::Winding,
+            FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+        }
+    }
+
+    /// Builds a tiny-skia stroke from the current line settings.
+    fn make_stroke(&self) -> tiny_skia::Stroke {
+        tiny_skia::Stroke {
+            width: self.line_width,
+            miter_limit: self.miter_limit,
+            line_cap: match self.line_cap {
+                LineCap::Butt => tiny_skia::LineCap::Butt,
+                LineCap::Round => tiny_skia::LineCap::Round,
+                LineCap::Square => tiny_skia::LineCap::Square,
+            },
+            line_join: match self.line_join {
+                LineJoin::Miter => tiny_skia::LineJoin::Miter,
+                LineJoin::Round => tiny_skia::LineJoin::Round,
+                LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+            },
+            // Patterns tiny-skia rejects, e.g. all zeros, stroke solid
+            dash: match &self.line_dash {
+                (pattern, _) if pattern.is_empty() => None,
+                (pattern, offset) => tiny_skia::StrokeDash::new(pattern.clone(), *offset),
+            },
+        }
+    }
+
+    fn shader_to_paint(shader: tiny_skia::Shader<'static>) -> tiny_skia::Paint<'static> {
+        tiny_skia::Paint {
+            shader,
+            anti_alias: true,
+            ..Default::default()
+        }
+    }
+
+    /// Converts color stops to tiny-skia gradient stops.
+    ///
+    /// Offsets are clamped to `0.0..=1.0` and sorted, as tiny-skia expects.
+    fn gradient_stops(stops: &[ColorStop]) -> Vec<tiny_skia::GradientStop> {
+        sorted_stops(stops)
+            .into_iter()
+            .map(|s| tiny_skia::GradientStop::new(s.offset, Self::to_skia_color(s.color)))
+            .collect()
+    }
+
+    /// Rebuilds the cached clip mask from the current clip_rect.
+    fn update_clip_mask(&mut self) {
+        self.clip_mask = self.clip_rect.and_then(|clip| {
             let mut mask = tiny_skia::Mask::new(self.pixmap.width(), self.pixmap.height())?;
             let clip_path = {
                 let mut pb = tiny_skia::PathBuilder::new();
-                pb.push_rect(
-                    tiny_skia::Rect::from_ltrb(clip.left, clip.top, clip.right, clip.bottom)?
-                );
+                match tiny_skia::Rect::from_ltrb(clip.left, clip.top, clip.right, clip.bottom) {
+                    Some(r) => pb.push_rect(r),
+                    // Empty clip: the mask stays fully transparent
+                    None => return Some(mask),
+                }
                 pb.finish()?
             };
             mask.fill_path(
@@ -426,7 +756,18 @@ impl Canvas {
                 tiny_skia::Transform::identity(),
             );
             Some(mask)
-        })
+        });
+    }
+
+    /// Maps a rect in user space to its device-space bounding box.
+    fn to_device_rect(&self, r: Rect) -> Rect {
+        if self.transform.is_identity() {
+            return r;
+        }
+        tiny_skia::Rect::from_ltrb(r.left, r.top, r.right, r.bottom)
+            .and_then(|r| r.transform(self.transform))
+            .map(|r| Rect::new(r.left(), r.top(), r.right(), r.bottom()))
+            .unwrap_or(Rect::new(0.0, 0.0, 0.0, 0.0))
     }
 
     /// Fills the current path.
@@ -434,30 +775,188 @@ impl Canvas {
         if let Some(pb) = self.path_builder.take() {
             if let Some(path) = pb.finish() {
                 let paint = Self::color_to_paint(self.fill_color);
-                let clip_mask = self.create_clip_mask();
                 self.pixmap.fill_path(
                     &path,
                     &paint,
-                    tiny_skia::FillRule::Winding,
+                    self.skia_fill_rule(),
+                    self.transform,
+                    self.clip_mask.as_ref(),
+                );
+            }
+        }
+    }
+
+    /// Fills the current path with the given paint.
+    fn fill_with_paint(&mut self, paint: &tiny_skia::Paint) {
+        if let Some(pb) = self.path_builder.take() {
+            if let Some(path) = pb.finish() {
+                self.pixmap.fill_path(
+                    &path,
+                    paint,
+                    self.skia_fill_rule(),
                     self.transform,
-                    clip_mask.as_ref(),
+                    self.clip_mask.as_ref(),
                 );
             }
         }
     }
 
+    /// Fills the current path with a linear gradient.
+    ///
+    /// A gradient without stops paints nothing; a single stop fills solid.
+    pub fn fill_linear_gradient(&mut self, grad: &LinearGradient) {
+        let paint = match grad.stops.as_slice() {
+            [] => {
+                self.path_builder = None;
+                return;
+            }
+            [stop] => Self::color_to_paint(stop.color),
+            stops => {
+                let shader = tiny_skia::LinearGradient::new(
+                    tiny_skia::Point::from_xy(grad.start.x, grad.start.y),
+                    tiny_skia::Point::from_xy(grad.end.x, grad.end.y),
+                    Self::gradient_stops(stops),
+                    tiny_skia::SpreadMode::Pad,
+                    tiny_skia::Transform::identity(),
+                );
+                match shader {
+                    Some(shader) => Self::shader_to_paint(shader),
+                    // Degenerate geometry (start == end): use the last color
+                    None => Self::color_to_paint(stops[stops.len() - 1].color),
+                }
+            }
+        };
+        self.fill_with_paint(&paint);
+    }
+
+    /// Fills the current path with a radial gradient.
+    ///
+    /// Stop offsets are relative to the ring between the inner and outer
+    /// radius. A gradient without stops paints nothing; a single stop fills solid.
+    pub fn fill_radial_gradient(&mut self, grad: &RadialGradient) {
+        let paint = match grad.stops.as_slice() {
+            [] => {
+                self.path_builder = None;
+                return;
+            }
+            [stop] => Self::color_to_paint(stop.color),
+            stops => {
+                // tiny-skia gradients start at radius zero, so remap the
+                // stops from [radius1, radius2] into [0, radius2].
+                let inner = if grad.radius2 > 0.0 {
+                    (grad.radius1 / grad.radius2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let remapped: Vec<ColorStop> = stops
+                    .iter()
+                    .map(|s| ColorStop {
+                        offset: inner + s.offset.clamp(0.0, 1.0) * (1.0 - inner),
+                        color: s.color,
+                    })
+                    .collect();
+                let shader = tiny_skia::RadialGradient::new(
+                    tiny_skia::Point::from_xy(grad.center1.x, grad.center1.y),
+                    tiny_skia::Point::from_xy(grad.center2.x, grad.center2.y),
+                    grad.radius2,
+                    Self::gradient_stops(&remapped),
+                    tiny_skia::SpreadMode::Pad,
+                    tiny_skia::Transform::identity(),
+                );
+                match shader {
+                    Some(shader) => Self::shader_to_paint(shader),
+                    // Zero radius: use the last color
+                    None => Self::color_to_paint(stops[stops.len() - 1].color),
+                }
+            }
+        };
+        self.fill_with_paint(&paint);
+    }
+
+    /// Fills the current path with a conic gradient.
+    ///
+    /// tiny-skia has no conic shader, so the sweep is rasterized into an
+    /// image covering the path, which then fills it. A gradient without
+    /// stops paints nothing; a single stop fills solid.
+    pub fn fill_conic_gradient(&mut self, grad: &ConicGradient) {
+        let stops = match grad.stops.as_slice() {
+            [] => {
+                self.path_builder = None;
+                return;
+            }
+            [stop] => {
+                let paint = Self::color_to_paint(stop.color);
+                self.fill_with_paint(&paint);
+                return;
+            }
+            stops => sorted_stops(stops),
+        };
+        let Some(path) = self.path_builder.take().and_then(|pb| pb.finish()) else {
+            return;
+        };
+        let Some(inverse) = self.transform.invert() else {
+            return;
+        };
+
+        // The device pixels the path covers, within the canvas
+        let Some(bounds) = path.bounds().transform(self.transform) else {
+            return;
+        };
+        let left = bounds.left().floor().max(0.0);
+        let top = bounds.top().floor().max(0.0);
+        let right = bounds.right().ceil().min(self.pixmap.width() as f32);
+        let bottom = bounds.bottom().ceil().min(self.pixmap.height() as f32);
+        if right <= left || bottom <= top {
+            return;
+        }
+        let width = (right - left) as u32;
+        let Some(mut sweep) = tiny_skia::Pixmap::new(width, (bottom - top) as u32) else {
+            return;
+        };
+
+        // Color each pixel by its angle around the center, in user space
+        for (i, pixel) in sweep.pixels_mut().iter_mut().enumerate() {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let mut p = tiny_skia::Point::from_xy(left + x as f32 + 0.5, top + y as f32 + 0.5);
+            inverse.map_point(&mut p);
+            let angle = (p.y - grad.center.y).atan2(p.x - grad.center.x);
+            let (r, g, b, a) = sample_stops(&stops, grad.offset_at(angle)).to_rgba_u8();
+            *pixel = tiny_skia::ColorU8::from_rgba(r, g, b, a).premultiply();
+        }
+
+        // The pattern is in device space, so undo the canvas transform
+        let shader = tiny_skia::Pattern::new(
+            sweep.as_ref(),
+            tiny_skia::SpreadMode::Pad,
+            tiny_skia::FilterQuality::Nearest,
+            1.0,
+            inverse.pre_translate(left, top),
+        );
+        let paint = tiny_skia::Paint {
+            shader,
+            anti_alias: true,
+            ..Default::default()
+        };
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            self.skia_fill_rule(),
+            self.transform,
+            self.clip_mask.as_ref(),
+        );
+    }
+
     /// Fills the current path and preserves it.
     pub fn fill_preserve(&mut self) {
         if let Some(ref pb) = self.path_builder {
             if let Some(path) = pb.clone().finish() {
                 let paint = Self::color_to_paint(self.fill_color);
-                let clip_mask = self.create_clip_mask();
                 self.pixmap.fill_path(
                     &path,
                     &paint,
-                    tiny_skia::FillRule::Winding,
+                    self.skia_fill_rule(),
                     self.transform,
-                    clip_mask.as_ref(),
+                    self.clip_mask.as_ref(),
                 );
             }
         }
@@ -468,12 +967,8 @@ impl Canvas {
         if let Some(pb) = self.path_builder.take() {
             if let Some(path) = pb.finish() {
                 let paint = Self::color_to_paint(self.stroke_color);
-                let stroke = tiny_skia::Stroke {
-                    width: self.line_width,
-                    ..Default::default()
-                };
-                let clip_mask = self.create_clip_mask();
-                self.pixmap.stroke_path(&path, &paint, &stroke, self.transform, clip_mask.as_ref());
+                let stroke = self.make_stroke();
+                self.pixmap.stroke_path(&path, &paint, &stroke, self.transform, self.clip_mask.as_ref());
             }
         }
     }
@@ -483,12 +978,8 @@ impl Canvas {
         if let Some(ref pb) = self.path_builder {
             if let Some(path) = pb.clone().finish() {
                 let paint = Self::color_to_paint(self.stroke_color);
-                let stroke = tiny_skia::Stroke {
-                    width: self.line_width,
-                    ..Default::default()
-                };
-                let clip_mask = self.create_clip_mask();
-                self.pixmap.stroke_path(&path, &paint, &stroke, self.transform, clip_mask.as_ref());
+                let stroke = self.make_stroke();
+                self.pixmap.stroke_path(&path, &paint, &stroke, self.transform, self.clip_mask.as_ref());
             }
         }
     }
@@ -523,6 +1014,61 @@ impl Canvas {
         self.stroke();
     }
 
+    /// Fills a rounded rectangle with its edges blurred by `blur`, using
+    /// the fill color. This draws soft drop shadows.
+    ///
+    /// The shape is rendered into an offscreen mask, blurred with three box
+    /// passes (which approximate a gaussian) and composited in one go.
+    pub fn fill_shadow(&mut self, r: Rect, radius: f32, blur: f32) {
+        self.begin_path();
+        self.add_round_rect(r, radius);
+        let Some(path) = self.path_builder.take().and_then(|pb| pb.finish()) else {
+            return;
+        };
+
+        // Each of the three passes spreads the shape by its radius
+        let device = self.to_device_rect(r);
+        let scale = if r.width() > 0.0 { device.width() / r.width() } else { 1.0 };
+        let pass_radius = (blur.max(0.0) * scale / 3.0).round() as usize;
+        let spread = (pass_radius * 3) as f32;
+
+        let left = (device.left - spread).floor();
+        let top = (device.top - spread).floor();
+        let width = ((device.right + spread).ceil() - left) as u32;
+        let height = ((device.bottom + spread).ceil() - top) as u32;
+        let Some(mut mask) = tiny_skia::Mask::new(width, height) else {
+            return;
+        };
+        mask.fill_path(
+            &path,
+            tiny_skia::FillRule::Winding,
+            true,
+            self.transform.post_translate(-left, -top),
+        );
+
+        let mut coverage: Vec<f32> = mask.data().iter().map(|&a| a as f32 / 255.0).collect();
+        for _ in 0..3 {
+            box_blur(&mut coverage, width as usize, height as usize, pass_radius);
+        }
+
+        let Some(mut shadow) = tiny_skia::Pixmap::new(width, height) else {
+            return;
+        };
+        let (red, green, blue, alpha) = self.fill_color.to_rgba_u8();
+        for (px, a) in shadow.pixels_mut().iter_mut().zip(&coverage) {
+            let alpha = (alpha as f32 * a.clamp(0.0, 1.0)).round() as u8;
+            *px = tiny_skia::ColorU8::from_rgba(red, green, blue, alpha).premultiply();
+        }
+        self.pixmap.draw_pixmap(
+            left as i32,
+            top as i32,
+            shadow.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            self.clip_mask.as_ref(),
+        );
+    }
+
     // --- State management ---
 
     /// Saves the current canvas state.
@@ -531,8 +1077,17 @@ impl Canvas {
             fill_color: self.fill_color,
             stroke_color: self.stroke_color,
             line_width: self.line_width,
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            miter_limit: self.miter_limit,
+            line_dash: self.line_dash.clone(),
+            fill_rule: self.fill_rule,
+            image_sampling: self.image_sampling,
             text_align: self.text_align,
             transform: self.transform,
+            font: self.font.clone(),
+            font_weight: self.font_weight,
+            font_style: self.font_style,
             font_size: self.font_size,
             clip_rect: self.clip_rect,
         });
@@ -544,36 +1099,139 @@ impl Canvas {
             self.fill_color = state.fill_color;
             self.stroke_color = state.stroke_color;
             self.line_width = state.line_width;
+            self.line_cap = state.line_cap;
+            self.line_join = state.line_join;
+            self.miter_limit = state.miter_limit;
+            self.line_dash = state.line_dash;
+            self.fill_rule = state.fill_rule;
+            self.image_sampling = state.image_sampling;
             self.text_align = state.text_align;
             self.transform = state.transform;
+            self.font = state.font;
+            self.font_weight = state.font_weight;
+            self.font_style = state.font_style;
             self.font_size = state.font_size;
-            self.clip_rect = state.clip_rect;
+            if self.clip_rect != state.clip_rect {
+                self.clip_rect = state.clip_rect;
+                self.update_clip_mask();
+            }
         }
     }
 
-    /// Sets the clipping rectangle.
+    /// Sets the clipping rectangle, replacing the current one.
+    ///
+    /// The rect is given in user space and mapped through the current
+    /// transform. `None` removes clipping.
     pub fn set_clip_rect(&mut self, rect: Option<Rect>) {
-        self.clip_rect = rect;
+        self.clip_rect = rect.map(|r| self.to_device_rect(r));
+        self.update_clip_mask();
     }
 
-    /// Gets the current clipping rectangle.
+    /// Gets the current clipping rectangle in device space.
     pub fn clip_rect(&self) -> Option<Rect> {
         self.clip_rect
     }
 
     /// Intersects the current clip rect with the given rect.
     pub fn clip(&mut self, rect: Rect) {
+        let rect = self.to_device_rect(rect);
         self.clip_rect = Some(match self.clip_rect {
             Some(existing) => existing.intersection(rect).unwrap_or(Rect::new(0.0, 0.0, 0.0, 0.0)),
             None => rect,
         });
+        self.update_clip_mask();
+    }
+
+    /// Pushes a clip rect, intersecting it with the current clip.
+    ///
+    /// Every `push_clip` must be balanced by a `pop_clip`.
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.clip_stack.push(self.clip_rect);
+        self.clip(rect);
+    }
+
+    /// Pops the clip rect pushed by the last `push_clip`.
+    pub fn pop_clip(&mut self) {
+        if let Some(clip) = self.clip_stack.pop() {
+            if self.clip_rect != clip {
+                self.clip_rect = clip;
+                self.update_clip_mask();
+            }
+        }
+    }
+
+    // --- Groups ---
+
+    /// Starts a group drawn at `opacity` (0.0 to 1.0).
+    ///
+    /// Drawing until the matching `pop_group` goes to an offscreen pixmap,
+    /// which is then composited as a whole. Overlapping shapes in the group
+    /// therefore don't show through each other.
+    pub fn push_group_opacity(&mut self, opacity: f32) {
+        let width = self.pixmap.width();
+        let height = self.pixmap.height();
+        let Some(group) = tiny_skia::Pixmap::new(width, height) else {
+            return;
+        };
+        let below = std::mem::replace(&mut self.pixmap, group);
+        self.group_stack.push((below, opacity.clamp(0.0, 1.0)));
+    }
+
+    /// Ends the group started by the last `push_group_opacity` and
+    /// composites it onto what was drawn before.
+    pub fn pop_group(&mut self) {
+        let Some((below, opacity)) = self.group_stack.pop() else {
+            return;
+        };
+        let group = std::mem::replace(&mut self.pixmap, below);
+        let paint = tiny_skia::PixmapPaint {
+            opacity,
+            ..Default::default()
+        };
+        self.pixmap.draw_pixmap(
+            0,
+            0,
+            group.as_ref(),
+            &paint,
+            tiny_skia::Transform::identity(),
+            self.clip_mask.as_ref(),
+        );
     }
 
     // --- Font and text ---
 
     /// Sets the current font.
+    ///
+    /// This also sets the font weight and style to the font's own.
     pub fn font(&mut self, font: Font) {
-        self.current_font = Some(font);
+        self.font_weight = font.weight().value();
+        self.font_style = font.style();
+        self.font = font;
+    }
+
+    /// Sets the font weight (100-900, 400 is regular and 700 bold).
+    ///
+    /// If the family lacks this weight, the nearest available one is used.
+    pub fn font_weight(&mut self, weight: u16) {
+        self.font_weight = weight.clamp(1, 1000);
+    }
+
+    /// Sets the font style.
+    pub fn font_style(&mut self, style: FontStyle) {
+        self.font_style = style;
+    }
+
+    /// Returns the face cache key for the current font settings.
+    fn face_key(&self) -> FaceKey {
+        FaceKey::new(self.font.family(), self.font_weight, self.font_style)
+    }
+
+    /// Loads font data into the shared font database so it can be selected
+    /// by family name with [`Canvas::font`].
+    ///
+    /// Returns `None` if the data contains no usable face.
+    pub fn register_font(data: &[u8]) -> Option<FontId> {
+        font::register_font(data)
     }
 
     /// Sets the font size.
@@ -586,16 +1244,55 @@ impl Canvas {
         self.text_align = align;
     }
 
-    /// Measures text width using the font system.
+    /// Shapes text with the face matching `key` and passes the result to `f`.
+    ///
+    /// `f` receives the cached face, the shaped glyph buffer and the scale
+    /// factor from font units to pixels. Returns `None` if no usable font is found.
+    fn shape_text<R>(
+        text: &str,
+        key: &FaceKey,
+        font_size: f32,
+        f: impl FnOnce(&CachedFace, &rustybuzz::GlyphBuffer, f32) -> R,
+    ) -> Option<R> {
+        let cached = font::cached_face(key)?;
+        let face = cached.face();
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        let output = rustybuzz::shape(face, &[], buffer);
+
+        let scale = font_size / face.units_per_em() as f32;
+        Some(f(&cached, &output, scale))
+    }
+
+    /// Measures text using the shaped glyph advances and the face metrics.
     pub fn measure_text(&self, text: &str) -> TextMetrics {
-        let width = self.text_width(text);
-        TextMetrics {
+        let measured = Self::shape_text(text, &self.face_key(), self.font_size, |cached, output, scale| {
+            let face = cached.face();
+            let width: f32 = output
+                .glyph_positions()
+                .iter()
+                .map(|pos| pos.x_advance as f32 * scale)
+                .sum();
+            let ascent = face.ascender() as f32 * scale;
+            let descent = -(face.descender() as f32) * scale;
+            TextMetrics {
+                ascent,
+                descent,
+                leading: face.line_gap() as f32 * scale,
+                width,
+                height: ascent + descent,
+            }
+        });
+
+        measured.unwrap_or_else(|| TextMetrics {
+            // Fallback: estimate from the font size
             ascent: self.font_size * 0.8,
             descent: self.font_size * 0.2,
             leading: self.font_size * 0.1,
-            width,
+            width: text.chars().count() as f32 * self.font_size * 0.6,
             height: self.font_size,
-        }
+        })
     }
 
     /// Returns the width of the given text in pixels.
@@ -603,50 +1300,7 @@ impl Canvas {
         if text.is_empty() {
             return 0.0;
         }
-
-        static FONT_DB: OnceLock<FontDatabase> = OnceLock::new();
-        let font_db = FONT_DB.get_or_init(FontDatabase::with_system_fonts);
-
-        let query = fontdb::Query {
-            families: &[fontdb::Family::SansSerif],
-            weight: fontdb::Weight(400),
-            stretch: fontdb::Stretch::Normal,
-            style: fontdb::Style::Normal,
-        };
-
-        let Some(font_id) = font_db.inner().query(&query) else {
-            // Fallback: estimate width
-            return text.chars().count() as f32 * self.font_size * 0.6;
-        };
-
-        let mut total_width = 0.0f32;
-        font_db.inner().with_face_data(font_id, |font_data_ref, face_index| {
-            let Ok(face) = ttf_parser::Face::parse(font_data_ref, face_index) else {
-                return;
-            };
-
-            let Some(buzz_face) = rustybuzz::Face::from_slice(font_data_ref, face_index) else {
-                return;
-            };
-
-            let mut buffer = rustybuzz::UnicodeBuffer::new();
-            buffer.push_str(text);
-            let output = rustybuzz::shape(&buzz_face, &[], buffer);
-
-            let units_per_em = face.units_per_em() as f32;
-            let scale = self.font_size / units_per_em;
-
-            for pos in output.glyph_positions() {
-                total_width += (pos.x_advance as f32) * scale;
-            }
-        });
-
-        if total_width == 0.0 {
-            // Fallback if measurement failed
-            text.chars().count() as f32 * self.font_size * 0.6
-        } else {
-            total_width
-        }
+        self.measure_text(text).width
     }
 
     /// Returns the width of a substring (for cursor positioning).
@@ -659,230 +1313,127 @@ impl Canvas {
         self.text_width(&prefix)
     }
 
-    /// Fills text at the given position.
-    pub fn fill_text(&mut self, text: &str, p: Point) {
-        // Get or initialize the global font database
-        static FONT_DB: OnceLock<FontDatabase> = OnceLock::new();
-        let font_db = FONT_DB.get_or_init(FontDatabase::with_system_fonts);
-
-        // Find a suitable font
-        let query = fontdb::Query {
-            families: &[fontdb::Family::SansSerif],
-            weight: fontdb::Weight(400),
-            stretch: fontdb::Stretch::Normal,
-            style: fontdb::Style::Normal,
+    /// Offsets `p` so that text with the given metrics is placed according
+    /// to the current text alignment. Returns the pen origin on the baseline.
+    fn aligned_origin(&self, p: Point, width: f32, ascent: f32, descent: f32) -> Point {
+        let x = match self.text_align.horizontal {
+            HorizontalAlign::Left => p.x,
+            HorizontalAlign::Center => p.x - width / 2.0,
+            HorizontalAlign::Right => p.x - width,
         };
-
-        let Some(font_id) = font_db.inner().query(&query) else {
-            return;
+        let y = match self.text_align.vertical {
+            VerticalAlign::Top => p.y + ascent,
+            VerticalAlign::Baseline => p.y,
+            VerticalAlign::Middle => p.y + (ascent - descent) / 2.0,
+            VerticalAlign::Bottom => p.y - descent,
         };
+        Point::new(x, y)
+    }
 
-        // Use with_face_data to access the font bytes directly
-        let mut rendered = false;
-        font_db.inner().with_face_data(font_id, |font_data_ref, face_index| {
-            // Parse the font
-            let Ok(face) = ttf_parser::Face::parse(font_data_ref, face_index) else {
-                return;
-            };
-
-            // Create rustybuzz face
-            let Some(buzz_face) = rustybuzz::Face::from_slice(font_data_ref, face_index) else {
-                return;
-            };
-
-            // Shape the text
-            let mut buffer = rustybuzz::UnicodeBuffer::new();
-            buffer.push_str(text);
-            let output = rustybuzz::shape(&buzz_face, &[], buffer);
-
-            // Calculate scale factor
-            let units_per_em = face.units_per_em() as f32;
-            let scale = self.font_size / units_per_em;
-
-            // Render each glyph
-            let mut x_pos = p.x;
-            let y_pos = p.y;
-
-            let glyph_infos = output.glyph_infos();
-            let glyph_positions = output.glyph_positions();
+    /// Fills text at the given position, honoring the current text alignment.
+    pub fn fill_text(&mut self, text: &str, p: Point) {
+        self.draw_glyphs(text, p, Self::fill_glyph_path);
+    }
 
-            for (info, pos) in glyph_infos.iter().zip(glyph_positions.iter()) {
-                let glyph_id = ttf_parser::GlyphId(info.glyph_id as u16);
+    /// Strokes the outlines of text at the given position with the current
+    /// stroke color and line settings, honoring the current text alignment.
+    pub fn stroke_text(&mut self, text: &str, p: Point) {
+        self.draw_glyphs(text, p, Self::stroke_glyph_path);
+    }
 
+    /// Lays out text at the given position and calls `draw` with each glyph
+    /// outline and its baseline origin.
+    fn draw_glyphs(&mut self, text: &str, p: Point, mut draw: impl FnMut(&mut Self, &tiny_skia::Path, f32, f32)) {
+        let font_size = self.font_size;
+        let key = self.face_key();
+        Self::shape_text(text, &key, font_size, |cached, output, scale| {
+            let face = cached.face();
+            let width: f32 = output
+                .glyph_positions()
+                .iter()
+                .map(|pos| pos.x_advance as f32 * scale)
+                .sum();
+            let ascent = face.ascender() as f32 * scale;
+            let descent = -(face.descender() as f32) * scale;
+            let origin = self.aligned_origin(p, width, ascent, descent);
+
+            let mut x_pos = origin.x;
+            let y_pos = origin.y;
+            for (info, pos) in output.glyph_infos().iter().zip(output.glyph_positions()) {
                 let glyph_x = x_pos + (pos.x_offset as f32) * scale;
                 let glyph_y = y_pos + (pos.y_offset as f32) * scale;
 
-                // Render the glyph using outline
-                let clip_mask = self.create_clip_mask();
-                Self::render_glyph_static(
-                    &mut self.pixmap,
-                    &face,
-                    glyph_id,
-                    glyph_x,
-                    glyph_y,
-                    scale,
-                    self.fill_color,
-                    self.transform,
-                    clip_mask.as_ref(),
-                );
+                if let Some(path) = cached.glyph_path(info.glyph_id as u16, font_size) {
+                    draw(self, &path, glyph_x, glyph_y);
+                }
 
-                // Advance position
                 x_pos += (pos.x_advance as f32) * scale;
             }
-            rendered = true;
         });
+    }
 
-        // If rendering failed inside the closure, nothing more to do
-        let _ = rendered;
-    }
-
-    /// Renders a single glyph at the given position.
-    fn render_glyph(
-        &mut self,
-        face: &ttf_parser::Face,
-        glyph_id: ttf_parser::GlyphId,
-        x: f32,
-        y: f32,
-        scale: f32,
-    ) {
-        struct GlyphOutlineBuilder {
-            path: tiny_skia::PathBuilder,
-            x: f32,
-            y: f32,
-            scale: f32,
-        }
-
-        impl ttf_parser::OutlineBuilder for GlyphOutlineBuilder {
-            fn move_to(&mut self, px: f32, py: f32) {
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale; // Flip Y axis
-                self.path.move_to(tx, ty);
-            }
-
-            fn line_to(&mut self, px: f32, py: f32) {
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.line_to(tx, ty);
-            }
-
-            fn quad_to(&mut self, x1: f32, y1: f32, px: f32, py: f32) {
-                let tx1 = self.x + x1 * self.scale;
-                let ty1 = self.y - y1 * self.scale;
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.quad_to(tx1, ty1, tx, ty);
-            }
+    /// Fills a cached glyph outline with its baseline origin at (x, y).
+    fn fill_glyph_path(&mut self, path: &tiny_skia::Path, x: f32, y: f32) {
+        let paint = Self::color_to_paint(self.fill_color);
+        self.pixmap.fill_path(
+            path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            self.transform.pre_translate(x, y),
+            self.clip_mask.as_ref(),
+        );
+    }
 
-            fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, px: f32, py: f32) {
-                let tx1 = self.x + x1 * self.scale;
-                let ty1 = self.y - y1 * self.scale;
-                let tx2 = self.x + x2 * self.scale;
-                let ty2 = self.y - y2 * self.scale;
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.cubic_to(tx1, ty1, tx2, ty2, tx, ty);
-            }
+    /// Strokes a cached glyph outline with its baseline origin at (x, y).
+    fn stroke_glyph_path(&mut self, path: &tiny_skia::Path, x: f32, y: f32) {
+        let paint = Self::color_to_paint(self.stroke_color);
+        let stroke = self.make_stroke();
+        self.pixmap.stroke_path(
+            path,
+            &paint,
+            &stroke,
+            self.transform.pre_translate(x, y),
+            self.clip_mask.as_ref(),
+        );
+    }
 
-            fn close(&mut self) {
-                self.path.close();
-            }
-        }
+    // --- Images ---
 
-        let mut builder = GlyphOutlineBuilder {
-            path: tiny_skia::PathBuilder::new(),
-            x,
-            y,
-            scale,
+    /// Draws a straight-alpha RGBA8 image scaled into `dest`.
+    ///
+    /// `data` holds `width * height` pixels, row by row. Does nothing if the
+    /// buffer is too short or the image is empty.
+    pub fn draw_image(&mut self, data: &[u8], width: u32, height: u32, dest: Rect) {
+        let Some(mut pixmap) = tiny_skia::Pixmap::new(width, height) else {
+            return;
         };
-
-        if face.outline_glyph(glyph_id, &mut builder).is_some() {
-            if let Some(path) = builder.path.finish() {
-                let paint = Self::color_to_paint(self.fill_color);
-                self.pixmap.fill_path(
-                    &path,
-                    &paint,
-                    tiny_skia::FillRule::Winding,
-                    self.transform,
-                    None,
-                );
-            }
+        if data.len() < pixmap.data().len() {
+            return;
         }
+        for (dst, src) in pixmap.pixels_mut().iter_mut().zip(data.chunks_exact(4)) {
+            *dst = tiny_skia::ColorU8::from_rgba(src[0], src[1], src[2], src[3]).premultiply();
+        }
+        self.draw_pixmap(pixmap.as_ref(), dest);
     }
 
-    /// Renders a single glyph at the given position (static version for use in closures).
-    fn render_glyph_static(
-        pixmap: &mut tiny_skia::Pixmap,
-        face: &ttf_parser::Face,
-        glyph_id: ttf_parser::GlyphId,
-        x: f32,
-        y: f32,
-        scale: f32,
-        fill_color: Color,
-        transform: tiny_skia::Transform,
-        clip_mask: Option<&tiny_skia::Mask>,
-    ) {
-        struct GlyphOutlineBuilder {
-            path: tiny_skia::PathBuilder,
-            x: f32,
-            y: f32,
-            scale: f32,
-        }
-
-        impl ttf_parser::OutlineBuilder for GlyphOutlineBuilder {
-            fn move_to(&mut self, px: f32, py: f32) {
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale; // Flip Y axis
-                self.path.move_to(tx, ty);
-            }
-
-            fn line_to(&mut self, px: f32, py: f32) {
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.line_to(tx, ty);
-            }
-
-            fn quad_to(&mut self, x1: f32, y1: f32, px: f32, py: f32) {
-                let tx1 = self.x + x1 * self.scale;
-                let ty1 = self.y - y1 * self.scale;
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.quad_to(tx1, ty1, tx, ty);
-            }
-
-            fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, px: f32, py: f32) {
-                let tx1 = self.x + x1 * self.scale;
-                let ty1 = self.y - y1 * self.scale;
-                let tx2 = self.x + x2 * self.scale;
-                let ty2 = self.y - y2 * self.scale;
-                let tx = self.x + px * self.scale;
-                let ty = self.y - py * self.scale;
-                self.path.cubic_to(tx1, ty1, tx2, ty2, tx, ty);
-            }
-
-            fn close(&mut self) {
-                self.path.close();
-            }
+    /// Draws a premultiplied pixmap scaled into `dest`.
+    pub fn draw_pixmap(&mut self, pixmap: tiny_skia::PixmapRef, dest: Rect) {
+        if dest.is_empty() {
+            return;
         }
-
-        let mut builder = GlyphOutlineBuilder {
-            path: tiny_skia::PathBuilder::new(),
-            x,
-            y,
-            scale,
+        let sx = dest.width() / pixmap.width() as f32;
+        let sy = dest.height() / pixmap.height() as f32;
+        let paint = tiny_skia::PixmapPaint {
+            quality: match self.image_sampling {
+                ImageSampling::Nearest => tiny_skia::FilterQuality::Nearest,
+                ImageSampling::Linear => tiny_skia::FilterQuality::Bilinear,
+            },
+            ..Default::default()
         };
-
-        if face.outline_glyph(glyph_id, &mut builder).is_some() {
-            if let Some(path) = builder.path.finish() {
-                let paint = Self::color_to_paint(fill_color);
-                pixmap.fill_path(
-                    &path,
-                    &paint,
-                    tiny_skia::FillRule::Winding,
-                    transform,
-                    clip_mask,
-                );
-            }
-        }
+        let transform = self.transform
+            .pre_translate(dest.left, dest.top)
+            .pre_scale(sx, sy);
+        self.pixmap.draw_pixmap(0, 0, pixmap, &paint, transform, self.clip_mask.as_ref());
     }
 
     /// Clears the canvas with the given color.
@@ -896,6 +1447,41 @@ impl Canvas {
     }
 }
 
+/// Blurs a `width` by `height` coverage buffer in place with a box of
+/// `radius` pixels on each side, first along rows and then along columns.
+fn box_blur(values: &mut [f32], width: usize, height: usize, radius: usize) {
+    if radius == 0 {
+        return;
+    }
+    let mut scratch = Vec::new();
+    for y in 0..height {
+        box_blur_line(values, y * width, 1, width, radius, &mut scratch);
+    }
+    for x in 0..width {
+        box_blur_line(values, x, width, height, radius, &mut scratch);
+    }
+}
+
+/// Box-blurs the `len` values starting at `start`, `stride` apart.
+/// Values beyond either end count as zero.
+fn box_blur_line(values: &mut [f32], start: usize, stride: usize, len: usize, radius: usize, scratch: &mut Vec<f32>) {
+    scratch.clear();
+    scratch.extend((0..len).map(|i| values[start + i * stride]));
+
+    // Running sum over the window [i - radius, i + radius]
+    let size = (2 * radius + 1) as f32;
+    let mut sum: f32 = scratch.iter().take(radius).sum();
+    for i in 0..len {
+        if i + radius < len {
+            sum += scratch[i + radius];
+        }
+        values[start + i * stride] = sum / size;
+        if i >= radius {
+            sum -= scratch[i - radius];
+        }
+    }
+}
+
 /// A RAII guard that saves canvas state on creation and restores it on drop.
 pub struct CanvasStateGuard<'a> {
     canvas: &'a mut Canvas,
@@ -927,3 +1513,464 @@ impl<'a> std::ops::DerefMut for CanvasStateGuard<'a> {
         self.canvas
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn pixel(canvas: &Canvas, x: u32, y: u32) -> (u8, u8, u8, u8) {
+        let p = canvas.pixmap().pixel(x, y).unwrap().demultiply();
+        (p.red(), p.green(), p.blue(), p.alpha())
+    }
+
+    #[test]
+    fn test_stroke_uses_cap_and_join() {
+        // A 10 wide stroke, and the alpha of pixels around its ends or corner
+        let stroke = |setup: &dyn Fn(&mut Canvas), points: &[Point], probes: &[(u32, u32)]| -> Vec<u8> {
+            let mut canvas = Canvas::new(40, 40).unwrap();
+            canvas.stroke_style(Color::rgb(1.0, 0.0, 0.0));
+            canvas.line_width(10.0);
+            setup(&mut canvas);
+            canvas.begin_path();
+            canvas.move_to(points[0]);
+            for &p in &points[1..] {
+                canvas.line_to(p);
+            }
+            canvas.stroke();
+            probes.iter().map(|&(x, y)| pixel(&canvas, x, y).3).collect()
+        };
+
+        // Past the end of a line, beside it and off its corner
+        let line = [Point::new(10.0, 20.0), Point::new(30.0, 20.0)];
+        let ends = [(7, 20), (5, 15)];
+        assert_eq!(stroke(&|_| {}, &line, &ends), vec![0, 0]);
+        assert_eq!(stroke(&|c| c.line_cap(LineCap::Square), &line, &ends), vec![255, 255]);
+        assert_eq!(stroke(&|c| c.line_cap(LineCap::Round), &line, &ends), vec![255, 0]);
+
+        // The outer corner of a right angle
+        let corner = [Point::new(10.0, 30.0), Point::new(10.0, 10.0), Point::new(30.0, 10.0)];
+        let tip = [(5, 5)];
+        assert_eq!(stroke(&|_| {}, &corner, &tip), vec![255]);
+        assert_eq!(stroke(&|c| c.line_join(LineJoin::Bevel), &corner, &tip), vec![0]);
+        assert_eq!(stroke(&|c| c.line_join(LineJoin::Round), &corner, &tip), vec![0]);
+
+        // A miter longer than the limit is beveled
+        assert_eq!(stroke(&|c| c.miter_limit(1.0), &corner, &tip), vec![0]);
+
+        // Restoring the state brings the previous settings back
+        let restored = |c: &mut Canvas| {
+            c.save();
+            c.line_cap(LineCap::Square);
+            c.line_join(LineJoin::Bevel);
+            c.restore();
+        };
+        assert_eq!(stroke(&restored, &line, &ends), vec![0, 0]);
+        assert_eq!(stroke(&restored, &corner, &tip), vec![255]);
+    }
+
+    #[test]
+    fn test_measure_text_uses_shaped_advances() {
+        let mut canvas = Canvas::new(1, 1).unwrap();
+        canvas.font(font::fixture_font());
+        canvas.font_size(20.0);
+
+        let narrow = canvas.measure_text("iiii");
+        let wide = canvas.measure_text("WWWW");
+        assert!(narrow.width > 0.0);
+        assert!(wide.width > narrow.width);
+        assert!(narrow.ascent > 0.0 && narrow.descent > 0.0);
+
+        // A combining accent shapes into the same cluster as its base letter
+        let composed = canvas.measure_text("\u{e9}");
+        let decomposed = canvas.measure_text("e\u{301}");
+        assert!((composed.width - decomposed.width).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_fill_text_alignment() {
+        // Returns the horizontal and vertical extent of the inked pixels
+        fn ink_bounds(canvas: &Canvas) -> (u32, u32, u32, u32) {
+            let (mut l, mut t, mut r, mut b) = (u32::MAX, u32::MAX, 0, 0);
+            for y in 0..canvas.height() {
+                for x in 0..canvas.width() {
+                    if pixel(canvas, x, y).3 > 0 {
+                        l = l.min(x);
+                        t = t.min(y);
+                        r = r.max(x);
+                        b = b.max(y);
+                    }
+                }
+            }
+            (l, t, r, b)
+        }
+
+        let draw = |horizontal, vertical| {
+            let mut canvas = Canvas::new(200, 100).unwrap();
+            canvas.font(font::fixture_font());
+            canvas.font_size(20.0);
+            canvas.text_align(TextAlign { horizontal, vertical });
+            canvas.fill_text("HH", Point::new(100.0, 50.0));
+            ink_bounds(&canvas)
+        };
+
+        let (l, _, r, b) = draw(HorizontalAlign::Left, VerticalAlign::Baseline);
+        assert!(l >= 100 && b <= 50);
+        let (_, _, r_right, _) = draw(HorizontalAlign::Right, VerticalAlign::Baseline);
+        assert!(r_right <= 100);
+        let (l_center, _, r_center, _) = draw(HorizontalAlign::Center, VerticalAlign::Baseline);
+        assert!(((l_center + r_center) as f32 / 2.0 - 100.0).abs() <= 2.0);
+        assert_eq!(r_center - l_center, r - l);
+
+        let (_, t_top, _, _) = draw(HorizontalAlign::Left, VerticalAlign::Top);
+        assert!(t_top >= 50);
+        let (_, _, _, b_bottom) = draw(HorizontalAlign::Left, VerticalAlign::Bottom);
+        assert!(b_bottom <= 50);
+        let (_, t_mid, _, b_mid) = draw(HorizontalAlign::Left, VerticalAlign::Middle);
+        assert!(t_mid < 50 && b_mid > 45);
+    }
+
+    #[test]
+    fn test_fill_text_with_registered_font() {
+        let data = include_bytes!("../../tests/fixtures/FixtureSans-Regular.ttf");
+        let font = Canvas::register_font(data).and_then(|id| id.font()).unwrap();
+
+        let mut canvas = Canvas::new(60, 30).unwrap();
+        canvas.font(font);
+        canvas.font_size(20.0);
+        canvas.fill_text("Hi", Point::new(5.0, 22.0));
+
+        let inked = (0..60).flat_map(|x| (0..30).map(move |y| (x, y)))
+            .filter(|&(x, y)| pixel(&canvas, x, y).3 > 0)
+            .count();
+        assert!(inked > 20);
+    }
+
+    #[test]
+    fn test_stroke_text_outlines_glyphs() {
+        let font = font::fixture_font();
+        let draw = |stroke: bool| {
+            let mut canvas = Canvas::new(80, 80).unwrap();
+            canvas.font(font.clone());
+            canvas.font_size(80.0);
+            canvas.stroke_style(Color::new(1.0, 0.0, 0.0, 1.0));
+            if stroke {
+                canvas.stroke_text("I", Point::new(10.0, 70.0));
+            } else {
+                canvas.fill_text("I", Point::new(10.0, 70.0));
+            }
+            canvas
+        };
+
+        // The stem is solid when filled, but hollow when outlined in red
+        let filled = draw(false);
+        let stroked = draw(true);
+        let stem: Vec<u32> = (0..80).filter(|&x| pixel(&filled, x, 40).3 == 255).collect();
+        assert!(stem.len() >= 4, "stem {stem:?}");
+        let middle = stem[stem.len() / 2];
+        assert_eq!(pixel(&stroked, middle, 40).3, 0);
+        assert!((0..80).any(|x| pixel(&stroked, x, 40).0 > 200));
+    }
+
+    #[test]
+    fn test_font_weight_selects_nearest_face() {
+        let font = font::fixture_font();
+        let mut canvas = Canvas::new(10, 10).unwrap();
+        canvas.font(font);
+        canvas.font_size(20.0);
+        let regular = canvas.measure_text("Bold").width;
+
+        canvas.save();
+        canvas.font_weight(700);
+        let bold = canvas.measure_text("Bold").width;
+        // The fixture has no black weight, so the nearest (bold) is used
+        canvas.font_weight(900);
+        assert_eq!(canvas.measure_text("Bold").width, bold);
+        canvas.restore();
+
+        assert!(bold > regular);
+        assert_eq!(canvas.measure_text("Bold").width, regular);
+    }
+
+    #[test]
+    fn test_fill_text_shares_cached_face_and_outlines() {
+        let mut canvas = Canvas::new(200, 40).unwrap();
+        canvas.font_size(14.0);
+        // Use a registered face: other tests registering fonts may drop
+        // fallback lookups from the cache
+        let font = font::fixture_font();
+        let key = FaceKey::from_font(&font);
+        canvas.font(font);
+
+        // First use loads the face and outlines the glyphs
+        const TEXT: &str = "Cached text 0123456789";
+        canvas.fill_text(TEXT, Point::new(0.0, 20.0));
+        let face = font::cached_face(&key).unwrap();
+        let outlines = || -> Vec<_> {
+            TEXT.chars()
+                .filter_map(|c| face.face().glyph_index(c))
+                .filter_map(|id| face.glyph_path(id.0, 14.0))
+                .collect()
+        };
+        let before = outlines();
+        assert_eq!(before.len(), TEXT.chars().filter(|c| *c != ' ').count());
+
+        canvas.fill_text(TEXT, Point::new(0.0, 20.0));
+        canvas.measure_text(TEXT);
+
+        // Later draws share the same face and outlines
+        assert!(Arc::ptr_eq(&font::cached_face(&key).unwrap(), &face));
+        assert!(before.iter().zip(outlines()).all(|(a, b)| Arc::ptr_eq(a, &b)));
+    }
+
+    #[test]
+    fn test_clip_stack() {
+        let mut canvas = Canvas::new(20, 20).unwrap();
+        canvas.fill_style(Color::rgb(1.0, 0.0, 0.0));
+
+        canvas.push_clip(Rect::new(0.0, 0.0, 10.0, 10.0));
+        canvas.save();
+        canvas.translate(Point::new(5.0, 5.0));
+        canvas.clip(Rect::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(canvas.clip_rect(), Some(Rect::new(5.0, 5.0, 10.0, 10.0)));
+        canvas.restore();
+        assert_eq!(canvas.clip_rect(), Some(Rect::new(0.0, 0.0, 10.0, 10.0)));
+
+        canvas.fill_rect(Rect::new(0.0, 0.0, 20.0, 20.0));
+        canvas.pop_clip();
+        assert_eq!(canvas.clip_rect(), None);
+
+        assert_eq!(pixel(&canvas, 5, 5).3, 255);
+        assert_eq!(pixel(&canvas, 15, 5).3, 0);
+        assert_eq!(pixel(&canvas, 5, 15).3, 0);
+    }
+
+    #[test]
+    fn test_arc_follows_circle() {
+        let mut canvas = Canvas::new(10, 10).unwrap();
+        let center = Point::new(50.0, 50.0);
+        let radius = 40.0;
+        canvas.begin_path();
+        canvas.arc(center, radius, 0.3, 5.5, false);
+        let path = canvas.path_builder.take().unwrap().finish().unwrap();
+
+        // Sample every cubic segment and check it stays on the circle
+        let mut last = tiny_skia::Point::zero();
+        let mut cubics = 0;
+        for segment in path.segments() {
+            match segment {
+                tiny_skia::PathSegment::MoveTo(p) => last = p,
+                tiny_skia::PathSegment::CubicTo(c1, c2, p) => {
+                    cubics += 1;
+                    for i in 0..=16 {
+                        let t = i as f32 / 16.0;
+                        let u = 1.0 - t;
+                        let x = u * u * u * last.x + 3.0 * u * u * t * c1.x + 3.0 * u * t * t * c2.x + t * t * t * p.x;
+                        let y = u * u * u * last.y + 3.0 * u * u * t * c1.y + 3.0 * u * t * t * c2.y + t * t * t * p.y;
+                        let d = Point::new(x, y).distance_to(center);
+                        assert!((d - radius).abs() < 1.0, "point {} off the circle", d);
+                    }
+                    last = p;
+                }
+                other => panic!("unexpected segment {:?}", other),
+            }
+        }
+        assert_eq!(cubics, 4);
+
+        // Ends at the requested angle
+        assert!((last.x - (50.0 + radius * 5.5f32.cos())).abs() < 0.01);
+        assert!((last.y - (50.0 + radius * 5.5f32.sin())).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_draw_image_scales_and_premultiplies() {
+        let mut canvas = Canvas::new(4, 4).unwrap();
+        canvas.image_sampling(ImageSampling::Nearest);
+
+        // 2x1 image: opaque red, half-transparent white
+        let data = [255, 0, 0, 255, 255, 255, 255, 128];
+        canvas.draw_image(&data, 2, 1, Rect::new(0.0, 0.0, 4.0, 4.0));
+
+        assert_eq!(pixel(&canvas, 0, 0), (255, 0, 0, 255));
+        assert_eq!(pixel(&canvas, 1, 3), (255, 0, 0, 255));
+        let premultiplied = canvas.pixmap().pixel(3, 3).unwrap();
+        assert_eq!((premultiplied.red(), premultiplied.alpha()), (128, 128));
+
+        // Too little data draws nothing
+        let mut canvas = Canvas::new(4, 4).unwrap();
+        canvas.draw_image(&data[..4], 2, 1, Rect::new(0.0, 0.0, 4.0, 4.0));
+        assert_eq!(pixel(&canvas, 0, 0).3, 0);
+    }
+
+    #[test]
+    fn test_linear_gradient_fill() {
+        let mut canvas = Canvas::new(100, 10).unwrap();
+        let mut grad = LinearGradient::new(Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+        grad.add_stop(1.0, Color::rgb(0.0, 0.0, 1.0));
+        grad.add_stop(0.0, Color::rgb(1.0, 0.0, 0.0));
+
+        canvas.begin_path();
+        canvas.add_rect(Rect::new(0.0, 0.0, 100.0, 10.0));
+        canvas.fill_linear_gradient(&grad);
+
+        let (r, _, b, _) = pixel(&canvas, 1, 5);
+        assert!(r > 240 && b < 15);
+        let (r, _, b, _) = pixel(&canvas, 98, 5);
+        assert!(b > 240 && r < 15);
+    }
+
+    #[test]
+    fn test_gradient_stop_edge_cases() {
+        let mut canvas = Canvas::new(10, 10).unwrap();
+
+        // No stops paints nothing
+        let grad = LinearGradient::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        canvas.begin_path();
+        canvas.add_rect(Rect::new(0.0, 0.0, 10.0, 10.0));
+        canvas.fill_linear_gradient(&grad);
+        assert_eq!(pixel(&canvas, 5, 5).3, 0);
+
+        // A single stop fills solid
+        let mut grad = RadialGradient::new(Point::new(5.0, 5.0), 0.0, 5.0);
+        grad.add_stop(0.5, Color::rgb(0.0, 1.0, 0.0));
+        canvas.begin_path();
+        canvas.add_rect(Rect::new(0.0, 0.0, 10.0, 10.0));
+        canvas.fill_radial_gradient(&grad);
+        assert_eq!(pixel(&canvas, 0, 0), (0, 255, 0, 255));
+    }
+
+    #[test]
+    fn test_conic_gradient_fill() {
+        let mut canvas = Canvas::new(100, 100).unwrap();
+        let mut grad = ConicGradient::new(Point::new(50.0, 50.0), -std::f32::consts::FRAC_PI_2);
+        grad.add_stop(0.0, Color::rgb(1.0, 0.0, 0.0));
+        grad.add_stop(1.0, Color::rgb(0.0, 0.0, 1.0));
+
+        // Clockwise from the top: right is a quarter turn, left three quarters
+        let right = grad.color_at(0.0);
+        assert!((right.red - 0.75).abs() < 1e-4 && (right.blue - 0.25).abs() < 1e-4);
+
+        canvas.scale(2.0, 2.0);
+        grad.center = Point::new(25.0, 25.0);
+        canvas.begin_path();
+        canvas.add_rect(Rect::new(0.0, 0.0, 50.0, 50.0));
+        canvas.fill_conic_gradient(&grad);
+
+        let (r, _, b, _) = pixel(&canvas, 51, 2);
+        assert!(r > 240 && b < 15);
+        let (r, _, b, _) = pixel(&canvas, 2, 50);
+        assert!((55..75).contains(&r) && (180..200).contains(&b));
+        let (r, _, b, _) = pixel(&canvas, 98, 50);
+        assert!((180..200).contains(&r) && (55..75).contains(&b));
+    }
+
+    #[test]
+    fn test_even_odd_fill_leaves_holes() {
+        let mut canvas = Canvas::new(40, 20).unwrap();
+        let ring = |canvas: &mut Canvas, x: f32| {
+            canvas.begin_path();
+            canvas.add_circle(Circle::new(Point::new(x, 10.0), 9.0));
+            canvas.add_circle(Circle::new(Point::new(x, 10.0), 4.0));
+            canvas.fill();
+        };
+
+        // Both circles wind the same way, so only even-odd punches the hole
+        ring(&mut canvas, 10.0);
+        canvas.save();
+        canvas.fill_rule(FillRule::EvenOdd);
+        ring(&mut canvas, 30.0);
+        assert_eq!(pixel(&canvas, 10, 10).3, 255);
+        assert_eq!(pixel(&canvas, 30, 10).3, 0);
+        assert_eq!(pixel(&canvas, 30, 3).3, 255);
+
+        canvas.restore();
+        canvas.fill_style(Color::new(1.0, 0.0, 0.0, 1.0));
+        ring(&mut canvas, 30.0);
+        assert_eq!(pixel(&canvas, 30, 10), (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn test_line_dash() {
+        let mut canvas = Canvas::new(40, 10).unwrap();
+        let line = |canvas: &mut Canvas, y: f32| {
+            canvas.begin_path();
+            canvas.move_to(Point::new(0.0, y));
+            canvas.line_to(Point::new(40.0, y));
+            canvas.stroke();
+        };
+        canvas.line_width(2.0);
+
+        // Dashes and gaps alternate every 4 pixels; an odd pattern repeats
+        canvas.save();
+        canvas.line_dash(&[4.0], 0.0);
+        line(&mut canvas, 2.0);
+        assert_eq!(pixel(&canvas, 2, 2).3, 255);
+        assert_eq!(pixel(&canvas, 6, 2).3, 0);
+        assert_eq!(pixel(&canvas, 10, 2).3, 255);
+
+        // Restoring the state makes strokes solid again, as does an empty pattern
+        canvas.restore();
+        line(&mut canvas, 5.0);
+        assert_eq!(pixel(&canvas, 6, 5).3, 255);
+        canvas.line_dash(&[4.0, 4.0], 2.0);
+        canvas.line_dash(&[], 0.0);
+        line(&mut canvas, 8.0);
+        assert_eq!(pixel(&canvas, 6, 8).3, 255);
+    }
+
+    #[test]
+    fn test_fill_shadow_blurs_edges() {
+        let mut canvas = Canvas::new(100, 100).unwrap();
+        canvas.fill_style(Color::rgb(0.0, 0.0, 0.0));
+        canvas.fill_shadow(Rect::new(30.0, 30.0, 70.0, 70.0), 0.0, 12.0);
+
+        // Solid well inside, fading across the edge, clear well outside
+        assert_eq!(pixel(&canvas, 50, 50).3, 255);
+        let edge = pixel(&canvas, 30, 50).3;
+        assert!(edge > 80 && edge < 175, "edge alpha {edge}");
+        assert!(pixel(&canvas, 24, 50).3 < edge && pixel(&canvas, 24, 50).3 > 0);
+        assert_eq!(pixel(&canvas, 10, 50).3, 0);
+
+        // No blur leaves a hard edge
+        let mut canvas = Canvas::new(100, 100).unwrap();
+        canvas.fill_shadow(Rect::new(30.0, 30.0, 70.0, 70.0), 0.0, 0.0);
+        assert_eq!(pixel(&canvas, 30, 50).3, 255);
+        assert_eq!(pixel(&canvas, 29, 50).3, 0);
+    }
+
+    #[test]
+    fn test_save_png() {
+        let mut canvas = Canvas::new(4, 3).unwrap();
+        canvas.fill_style(Color::new(0.0, 0.0, 1.0, 1.0));
+        canvas.fill_rect(Rect::new(0.0, 0.0, 2.0, 3.0));
+
+        let path = std::env::temp_dir().join(format!("mkgraphic-save-png-{}.png", std::process::id()));
+        canvas.save_png(&path).unwrap();
+        let saved = ::image::open(&path).unwrap().into_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved.dimensions(), (4, 3));
+        assert_eq!(saved.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(saved.get_pixel(3, 0).0, [0, 0, 0, 0]);
+
+        assert!(canvas.save_png(std::env::temp_dir().join("missing-dir/x.png")).is_err());
+    }
+
+    #[test]
+    fn test_pixel_read_back() {
+        let mut canvas = Canvas::new(4, 3).unwrap();
+        canvas.fill_style(Color::new(1.0, 0.0, 0.0, 0.4));
+        canvas.fill_rect(Rect::new(0.0, 0.0, 2.0, 3.0));
+
+        // Read back unpremultiplied, up to 8-bit rounding
+        let c = canvas.pixel_at(1, 1).unwrap();
+        assert_eq!(c.to_rgba_u8(), (255, 0, 0, 102));
+        assert_eq!(canvas.pixel_at(3, 0), Some(Color::new(0.0, 0.0, 0.0, 0.0)));
+        assert_eq!(canvas.pixel_at(4, 0), None);
+
+        let region = canvas.read_region(Rect::new(1.5, -5.0, 10.0, 1.0));
+        assert_eq!(region.len(), 3);
+        assert_eq!(region[0], c);
+        assert_eq!(region[1].alpha, 0.0);
+        assert!(canvas.read_region(Rect::new(5.0, 0.0, 8.0, 3.0)).is_empty());
+    }
+}
@@ -61,6 +61,12 @@ impl Circle {
         self.center.distance_squared_to(p) <= self.radius * self.radius
     }
 
+    /// Returns the distance from the center to a point.
+    #[inline]
+    pub fn distance_to_center(&self, p: Point) -> f32 {
+        self.center.distance_to(p)
+    }
+
     /// Returns true if this circle intersects with another circle.
     #[inline]
     pub fn intersects(&self, other: &Circle) -> bool {
@@ -121,6 +127,17 @@ mod tests {
         assert!(!c.contains(Point::new(10.0, 10.0)));
     }
 
+    #[test]
+    fn test_circle_distance_to_center() {
+        let c = Circle::from_coords(1.0, 2.0, 10.0);
+        assert_eq!(c.distance_to_center(Point::new(4.0, -2.0)), 5.0);
+        assert_eq!(c.distance_to_center(c.center), 0.0);
+
+        // A point on the edge is inside
+        assert_eq!(c.distance_to_center(Point::new(1.0, 12.0)), c.radius);
+        assert!(c.contains(Point::new(1.0, 12.0)));
+    }
+
     #[test]
     fn test_circle_bounds() {
         let c = Circle::from_coords(10.0, 20.0, 5.0);
@@ -68,6 +68,38 @@ impl Color {
         }
     }
 
+    /// Parses a hex color string: `#RGB`, `#RRGGBB` or `#RRGGBBAA`.
+    ///
+    /// The leading `#` is optional. Returns `None` if the string is malformed.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u32::from_str_radix(digits, 16).ok();
+        match digits.len() {
+            3 => value.map(|v| {
+                // Expand each nibble: 0xRGB -> 0xRRGGBB
+                let (r, g, b) = ((v >> 8) & 0xf, (v >> 4) & 0xf, v & 0xf);
+                Self::from_rgb_u32((r * 0x11) << 16 | (g * 0x11) << 8 | (b * 0x11))
+            }),
+            6 => value.map(Self::from_rgb_u32),
+            8 => value.map(Self::from_rgba_u32),
+            _ => None,
+        }
+    }
+
+    /// Formats the color as `#RRGGBB`, or `#RRGGBBAA` if it isn't opaque.
+    pub fn to_hex(self) -> String {
+        let byte = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
+        let (r, g, b, a) = (byte(self.red), byte(self.green), byte(self.blue), byte(self.alpha));
+        if a == 255 {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
     /// Returns a new color with the given opacity (alpha).
     #[inline]
     pub const fn with_alpha(self, alpha: f32) -> Self {
@@ -103,6 +135,103 @@ impl Color {
         ((r as u32) << 24) | ((g as u32) << 16) | ((b as u32) << 8) | (a as u32)
     }
 
+    /// Creates an opaque color from hue (degrees), saturation and lightness (0.0 to 1.0).
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let s = saturation.clamp(0.0, 1.0);
+        let l = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue_chroma(hue, chroma, l - chroma / 2.0)
+    }
+
+    /// Converts to hue (degrees, 0 to 360), saturation and lightness (0.0 to 1.0).
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let chroma = max - min;
+        let l = (max + min) / 2.0;
+        let s = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (self.hue(max, chroma), s, l)
+    }
+
+    /// Creates an opaque color from hue (degrees), saturation and value (0.0 to 1.0).
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+        let chroma = v * s;
+        Self::from_hue_chroma(hue, chroma, v - chroma)
+    }
+
+    /// Converts to hue (degrees, 0 to 360), saturation and value (0.0 to 1.0).
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let chroma = max - min;
+        let s = if max == 0.0 { 0.0 } else { chroma / max };
+        (self.hue(max, chroma), s, max)
+    }
+
+    /// Returns the hue in degrees given the largest component and the chroma.
+    fn hue(self, max: f32, chroma: f32) -> f32 {
+        if chroma == 0.0 {
+            return 0.0;
+        }
+        let h = if max == self.red {
+            ((self.green - self.blue) / chroma).rem_euclid(6.0)
+        } else if max == self.green {
+            (self.blue - self.red) / chroma + 2.0
+        } else {
+            (self.red - self.green) / chroma + 4.0
+        };
+        h * 60.0
+    }
+
+    /// Builds a color from hue (degrees), chroma and the amount added to each component.
+    fn from_hue_chroma(hue: f32, chroma: f32, m: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Self::rgb(r + m, g + m, b + m)
+    }
+
+    /// Returns a new color with HSL saturation increased by `amount` (0.0 to 1.0).
+    pub fn saturate(self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s + amount, l).with_alpha(self.alpha)
+    }
+
+    /// Returns a new color with HSL saturation decreased by `amount` (0.0 to 1.0).
+    pub fn desaturate(self, amount: f32) -> Self {
+        self.saturate(-amount)
+    }
+
+    /// Returns a new color with HSL lightness increased by `amount` (0.0 to 1.0).
+    pub fn lighten(self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount).with_alpha(self.alpha)
+    }
+
+    /// Returns a new color with HSL lightness decreased by `amount` (0.0 to 1.0).
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Returns a new color with its hue rotated by the given number of degrees.
+    pub fn rotate_hue(self, degrees: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h + degrees, s, l).with_alpha(self.alpha)
+    }
+
     /// Linearly interpolates between two colors.
     #[inline]
     pub fn lerp(self, other: Color, t: f32) -> Self {
@@ -113,8 +242,55 @@ impl Color {
             alpha: self.alpha + (other.alpha - self.alpha) * t,
         }
     }
+
+    /// Composites this color over `backdrop` (Porter-Duff source-over).
+    ///
+    /// Both colors have straight alpha, and so does the result:
+    /// `a = a_s + a_b (1 - a_s)`, and each channel is
+    /// `(c_s a_s + c_b a_b (1 - a_s)) / a`.
+    pub fn blend_over(self, backdrop: Color) -> Self {
+        let alpha = self.alpha + backdrop.alpha * (1.0 - self.alpha);
+        if alpha <= 0.0 {
+            return Self { alpha: 0.0, ..self };
+        }
+        let channel = |s: f32, b: f32| (s * self.alpha + b * backdrop.alpha * (1.0 - self.alpha)) / alpha;
+        Self {
+            red: channel(self.red, backdrop.red),
+            green: channel(self.green, backdrop.green),
+            blue: channel(self.blue, backdrop.blue),
+            alpha,
+        }
+    }
+
+    /// Returns the relative luminance as defined by WCAG 2: 0.0 for black
+    /// to 1.0 for white. Alpha is ignored.
+    pub fn relative_luminance(self) -> f32 {
+        // Undo the sRGB transfer curve before weighting the channels
+        let linear = |c: f32| {
+            let c = c.clamp(0.0, 1.0);
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * linear(self.red) + 0.7152 * linear(self.green) + 0.0722 * linear(self.blue)
+    }
+
+    /// Returns the WCAG contrast ratio between two colors, from 1.0 (none)
+    /// to 21.0 (black on white). Text wants at least 4.5.
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns black or white, whichever contrasts more with this color
+    /// when used as a background.
+    pub fn readable_text_color(self) -> Color {
+        let (black, white) = (colors::BLACK, colors::WHITE);
+        if self.contrast_ratio(black) >= self.contrast_ratio(white) { black } else { white }
+    }
 }
 
+/// Adds the red, green and blue channels, keeping the alpha of `self`.
+///
+/// This is channel arithmetic, not blending; see [`Color::blend_over`].
 impl Add for Color {
     type Output = Self;
 
@@ -123,11 +299,12 @@ impl Add for Color {
             red: self.red + other.red,
             green: self.green + other.green,
             blue: self.blue + other.blue,
-            alpha: self.alpha + other.alpha * (1.0 - self.alpha),
+            alpha: self.alpha,
         }
     }
 }
 
+/// Subtracts the red, green and blue channels, keeping the alpha of `self`.
 impl Sub for Color {
     type Output = Self;
 
@@ -136,7 +313,7 @@ impl Sub for Color {
             red: self.red - other.red,
             green: self.green - other.green,
             blue: self.blue - other.blue,
-            alpha: self.alpha + other.alpha * (1.0 - self.alpha),
+            alpha: self.alpha,
         }
     }
 }
@@ -318,6 +495,68 @@ mod tests {
         assert!((c.blue - 0.251).abs() < 0.01);
     }
 
+    #[test]
+    fn test_color_from_hex() {
+        assert_eq!(Color::from_hex("#1e90ff"), Some(Color::from_rgb_u32(0x1e90ff)));
+        assert_eq!(Color::from_hex("1E90FF"), Some(Color::from_rgb_u32(0x1e90ff)));
+        assert_eq!(Color::from_hex("#f80"), Some(Color::from_rgb_u32(0xff8800)));
+        assert_eq!(Color::from_hex("ff000080"), Some(Color::from_rgba_u32(0xff000080)));
+
+        assert_eq!(Color::from_hex(""), None);
+        assert_eq!(Color::from_hex("#"), None);
+        assert_eq!(Color::from_hex("#12345"), None);
+        assert_eq!(Color::from_hex("#gg0000"), None);
+        assert_eq!(Color::from_hex("#+12"), None);
+        assert_eq!(Color::from_hex("##123456"), None);
+
+        assert_eq!(Color::from_rgb_u32(0x1e90ff).to_hex(), "#1e90ff");
+        assert_eq!(Color::from_rgba_u32(0xff000080).to_hex(), "#ff000080");
+    }
+
+    #[test]
+    fn test_hsl_hsv_round_trip() {
+        // Each channel must survive the round trip to within one u8 step
+        fn assert_close(a: Color, b: Color) {
+            let (a, b) = (a.to_rgba_u8(), b.to_rgba_u8());
+            for (x, y) in [(a.0, b.0), (a.1, b.1), (a.2, b.2)] {
+                assert!(x.abs_diff(y) <= 1, "{:?} != {:?}", a, b);
+            }
+        }
+
+        for rgb in [0x000000, 0xffffff, 0xff0000, 0x1e90ff, 0x808000, 0x3c1e5a, 0xfa8072, 0x00ff7f] {
+            let c = Color::from_rgb_u32(rgb);
+            let (h, s, l) = c.to_hsl();
+            assert_close(Color::from_hsl(h, s, l), c);
+            let (h, s, v) = c.to_hsv();
+            assert_close(Color::from_hsv(h, s, v), c);
+        }
+
+        let (h, s, l) = Color::from_rgb_u32(0x1e90ff).to_hsl();
+        assert!((h - 209.6).abs() < 0.1);
+        assert!((s - 1.0).abs() < 0.01);
+        assert!((l - 0.559).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hue_and_saturation_adjustment() {
+        let red = colors::RED.with_alpha(0.5);
+        let green = red.rotate_hue(120.0);
+        let (r, g, b, a) = green.to_rgba_u8();
+        assert!(r <= 1 && g >= 254 && b <= 1 && a == 127);
+        let same = red.rotate_hue(-240.0);
+        assert!((same.green - green.green).abs() < 1e-4 && (same.red - green.red).abs() < 1e-4);
+
+        let gray = red.desaturate(1.0);
+        assert_eq!(gray.red, gray.green);
+        assert_eq!(gray.green, gray.blue);
+        assert!((gray.saturate(0.5).to_hsl().1 - 0.5).abs() < 1e-4);
+
+        let lighter = red.lighten(0.25);
+        assert!((lighter.to_hsl().2 - 0.75).abs() < 1e-4 && lighter.alpha == 0.5);
+        assert!((lighter.darken(0.25).red - red.red).abs() < 1e-4);
+        assert_eq!(red.lighten(1.0).to_rgba_u8().0, 255);
+    }
+
     #[test]
     fn test_color_to_u8() {
         let c = Color::new(1.0, 0.5, 0.25, 1.0);
@@ -337,4 +576,40 @@ mod tests {
         assert!((gray.green - 0.5).abs() < 0.01);
         assert!((gray.blue - 0.5).abs() < 0.01);
     }
+
+    #[test]
+    fn test_blend_and_channel_arithmetic() {
+        // Half white over opaque black is opaque mid gray
+        let gray = colors::WHITE.with_alpha(0.5).blend_over(colors::BLACK);
+        assert_eq!(gray, Color::new(0.5, 0.5, 0.5, 1.0));
+
+        // Two half covers leave a quarter uncovered, and the color is
+        // weighted by how much of each shows
+        let mixed = Color::new(1.0, 0.0, 0.0, 0.5).blend_over(Color::new(0.0, 0.0, 1.0, 0.5));
+        assert_eq!(mixed, Color::new(2.0 / 3.0, 0.0, 1.0 / 3.0, 0.75));
+        assert_eq!(Color::new(1.0, 1.0, 1.0, 0.0).blend_over(colors::BLACK.with_alpha(0.0)).alpha, 0.0);
+
+        let a = Color::new(0.5, 0.5, 0.5, 0.8);
+        let b = Color::new(0.25, 0.0, 0.5, 0.2);
+        assert_eq!(a + b, Color::new(0.75, 0.5, 1.0, 0.8));
+        assert_eq!(a - b, Color::new(0.25, 0.5, 0.0, 0.8));
+    }
+
+    #[test]
+    fn test_contrast() {
+        assert_eq!(colors::BLACK.relative_luminance(), 0.0);
+        assert!((colors::WHITE.relative_luminance() - 1.0).abs() < 1e-5);
+        assert!((colors::BLACK.contrast_ratio(colors::WHITE) - 21.0).abs() < 1e-4);
+        assert_eq!(colors::WHITE.contrast_ratio(colors::BLACK), colors::BLACK.contrast_ratio(colors::WHITE));
+        assert_eq!(colors::BLUE.contrast_ratio(colors::BLUE), 1.0);
+
+        // #777 on white is just under the 4.5 WCAG AA threshold
+        let ratio = Color::from_rgb_u32(0x777777).contrast_ratio(colors::WHITE);
+        assert!((ratio - 4.48).abs() < 0.01, "ratio {ratio}");
+
+        assert_eq!(colors::YELLOW.readable_text_color(), colors::BLACK);
+        assert_eq!(colors::BLUE.readable_text_color(), colors::WHITE);
+        assert_eq!(colors::gray(90).readable_text_color(), colors::BLACK);
+        assert_eq!(colors::gray(20).readable_text_color(), colors::WHITE);
+    }
 }
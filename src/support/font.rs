@@ -1,6 +1,8 @@
 //! Font handling and text metrics.
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 /// Font weight.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -32,10 +34,25 @@ impl FontWeight {
             FontWeight::Black => 900,
         }
     }
+
+    /// Returns the named weight nearest to a numeric weight value.
+    pub fn from_value(value: u16) -> Self {
+        match value {
+            0..=149 => FontWeight::Thin,
+            150..=249 => FontWeight::ExtraLight,
+            250..=349 => FontWeight::Light,
+            350..=449 => FontWeight::Regular,
+            450..=549 => FontWeight::Medium,
+            550..=649 => FontWeight::SemiBold,
+            650..=749 => FontWeight::Bold,
+            750..=849 => FontWeight::ExtraBold,
+            _ => FontWeight::Black,
+        }
+    }
 }
 
 /// Font style (normal or italic).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum FontStyle {
     #[default]
     Normal,
@@ -140,6 +157,15 @@ impl Font {
     pub fn italic(self) -> Self {
         self.with_style(FontStyle::Italic)
     }
+
+    /// Loads a font file into the shared font database and returns a font
+    /// describing its first face.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        let data = std::fs::read(path)?;
+        register_font(&data)
+            .and_then(|id| id.font())
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "no usable font face"))
+    }
 }
 
 impl Default for Font {
@@ -207,6 +233,283 @@ impl Default for FontDatabase {
     }
 }
 
+/// Returns the process-wide font database, seeded with the system fonts.
+///
+/// Application fonts registered with [`register_font`] or
+/// [`Font::from_file`] are added here too.
+pub(crate) fn shared_font_db() -> &'static RwLock<FontDatabase> {
+    static FONT_DB: OnceLock<RwLock<FontDatabase>> = OnceLock::new();
+    FONT_DB.get_or_init(|| RwLock::new(FontDatabase::with_system_fonts()))
+}
+
+/// Identifies a face loaded into the shared font database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontId(fontdb::ID);
+
+impl FontId {
+    /// Returns a font descriptor matching this face's family, weight and style.
+    pub fn font(&self) -> Option<Font> {
+        let db = shared_font_db().read().unwrap();
+        let info = db.inner().face(self.0)?;
+        let (family, _) = info.families.first()?;
+        Some(Font {
+            family: family.clone(),
+            weight: FontWeight::from_value(info.weight.0),
+            style: match info.style {
+                fontdb::Style::Normal => FontStyle::Normal,
+                fontdb::Style::Italic => FontStyle::Italic,
+                fontdb::Style::Oblique => FontStyle::Oblique,
+            },
+            stretch: FontStretch::default(),
+        })
+    }
+}
+
+/// Loads font data into the shared font database.
+///
+/// Returns the id of the first face in the data, or `None` if the data
+/// contains no usable face. Font collections register all their faces.
+pub fn register_font(data: &[u8]) -> Option<FontId> {
+    let ids = shared_font_db()
+        .write()
+        .unwrap()
+        .inner_mut()
+        .load_font_source(fontdb::Source::Binary(Arc::new(data.to_vec())));
+    let id = ids.first().copied()?;
+    invalidate_fallback_faces();
+    Some(FontId(id))
+}
+
+/// Key identifying a face in the face cache: a family with a numeric
+/// weight (100-900) and a style.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct FaceKey {
+    family: String,
+    weight: u16,
+    style: FontStyle,
+}
+
+impl FaceKey {
+    pub(crate) fn new(family: &str, weight: u16, style: FontStyle) -> Self {
+        Self {
+            family: family.to_string(),
+            weight,
+            style,
+        }
+    }
+
+    pub(crate) fn from_font(font: &Font) -> Self {
+        Self::new(&font.family, font.weight.value(), font.style)
+    }
+}
+
+/// Number of font sizes a face keeps glyph outlines for. Drawing at
+/// another size drops the outlines of the least recently drawn one.
+const GLYPH_CACHE_SIZES: usize = 8;
+
+/// Glyph outlines by glyph id, per font size bits, least recently drawn
+/// size first.
+type GlyphPaths = Mutex<Vec<(u32, HashMap<u16, Option<Arc<tiny_skia::Path>>>)>>;
+
+/// A parsed face and the font data it borrows, together with the outlines
+/// of the glyphs drawn from it.
+pub(crate) struct CachedFace {
+    /// Borrows from `_data`, so it is declared first to be dropped first.
+    face: rustybuzz::Face<'static>,
+    _data: Arc<[u8]>,
+    /// True if the requested family wasn't found and a default face was used.
+    fallback: bool,
+    /// Glyph outlines for the most recently drawn font sizes, in pixels
+    /// with the origin on the baseline. `None` marks glyphs without an
+    /// outline.
+    glyph_paths: GlyphPaths,
+}
+
+impl CachedFace {
+    /// Parses the face at `index` in the font data.
+    fn new(data: Arc<[u8]>, index: u32, fallback: bool) -> Option<Self> {
+        // SAFETY: the bytes live in the Arc's heap allocation, which never
+        // moves and outlives the face because `_data` is dropped after it.
+        // The 'static lifetime never escapes: `face()` ties it to `&self`.
+        let bytes: &'static [u8] = unsafe { &*Arc::as_ptr(&data) };
+        let face = rustybuzz::Face::from_slice(bytes, index)?;
+        Some(Self {
+            face,
+            _data: data,
+            fallback,
+            glyph_paths: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Returns the shaping face.
+    pub(crate) fn face(&self) -> &rustybuzz::Face<'_> {
+        &self.face
+    }
+
+    /// Returns true if the requested family wasn't found.
+    pub(crate) fn fallback(&self) -> bool {
+        self.fallback
+    }
+
+    /// Returns the outline of a glyph at the given font size, shared with
+    /// the cache.
+    pub(crate) fn glyph_path(&self, glyph_id: u16, font_size: f32) -> Option<Arc<tiny_skia::Path>> {
+        let size = font_size.to_bits();
+        let mut sizes = self.glyph_paths.lock().unwrap();
+        let paths = match sizes.iter().position(|(cached, _)| *cached == size) {
+            Some(i) => sizes.remove(i).1,
+            None if sizes.len() == GLYPH_CACHE_SIZES => {
+                sizes.remove(0);
+                HashMap::new()
+            }
+            None => HashMap::new(),
+        };
+        sizes.push((size, paths));
+        let paths = &mut sizes.last_mut().unwrap().1;
+        if let Some(path) = paths.get(&glyph_id) {
+            return path.clone();
+        }
+
+        let scale = font_size / self.face.units_per_em() as f32;
+        let mut builder = GlyphOutlineBuilder {
+            path: tiny_skia::PathBuilder::new(),
+            scale,
+        };
+        let path = self
+            .face
+            .outline_glyph(ttf_parser::GlyphId(glyph_id), &mut builder)
+            .and_then(|_| builder.path.finish())
+            .map(Arc::new);
+
+        paths.insert(glyph_id, path.clone());
+        path
+    }
+}
+
+/// Converts glyph outlines from font units (y up) to pixels (y down).
+struct GlyphOutlineBuilder {
+    path: tiny_skia::PathBuilder,
+    scale: f32,
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.move_to(x * self.scale, -y * self.scale);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.line_to(x * self.scale, -y * self.scale);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.path.quad_to(x1 * self.scale, -y1 * self.scale, x * self.scale, -y * self.scale);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.path.cubic_to(
+            x1 * self.scale, -y1 * self.scale,
+            x2 * self.scale, -y2 * self.scale,
+            x * self.scale, -y * self.scale,
+        );
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+type FaceCache = RwLock<HashMap<FaceKey, Option<Arc<CachedFace>>>>;
+
+fn face_cache() -> &'static FaceCache {
+    static FACE_CACHE: OnceLock<FaceCache> = OnceLock::new();
+    FACE_CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Drops cached lookups that newly registered faces may now satisfy: failed
+/// lookups and lookups that fell back to the default face.
+fn invalidate_fallback_faces() {
+    face_cache()
+        .write()
+        .unwrap()
+        .retain(|_, face| face.as_ref().is_some_and(|face| !face.fallback));
+}
+
+/// Returns the cached face for the given key, loading it on first use.
+///
+/// Failed lookups are cached too, so a missing font is only queried once
+/// (until new fonts are registered).
+pub(crate) fn cached_face(key: &FaceKey) -> Option<Arc<CachedFace>> {
+    let cache = face_cache();
+
+    if let Some(face) = cache.read().unwrap().get(key) {
+        return face.clone();
+    }
+
+    let mut cache = cache.write().unwrap();
+    // Another thread may have loaded it while we waited for the lock
+    if let Some(face) = cache.get(key) {
+        return face.clone();
+    }
+
+    let face = load_face(key).map(Arc::new);
+    cache.insert(key.clone(), face.clone());
+    face
+}
+
+/// Queries the font database and parses the matching face.
+///
+/// Within a family, fontdb picks the nearest available weight and style
+/// (CSS font matching), so a missing weight still yields a face.
+fn load_face(key: &FaceKey) -> Option<CachedFace> {
+    let family = match key.family.as_str() {
+        "sans-serif" => fontdb::Family::SansSerif,
+        "serif" => fontdb::Family::Serif,
+        "monospace" => fontdb::Family::Monospace,
+        name => fontdb::Family::Name(name),
+    };
+    let mut query = fontdb::Query {
+        families: &[family],
+        weight: fontdb::Weight(key.weight),
+        stretch: fontdb::Stretch::Normal,
+        style: match key.style {
+            FontStyle::Normal => fontdb::Style::Normal,
+            FontStyle::Italic => fontdb::Style::Italic,
+            FontStyle::Oblique => fontdb::Style::Oblique,
+        },
+    };
+
+    let db = shared_font_db().read().unwrap();
+    let db = db.inner();
+    // Unknown families fall back to the default sans-serif face
+    let mut fallback = false;
+    let id = match db.query(&query) {
+        Some(id) => id,
+        None => {
+            fallback = true;
+            query.families = &[fontdb::Family::SansSerif];
+            db.query(&query)?
+        }
+    };
+    // The face keeps its own copy of the data, freed when it leaves the cache
+    let (data, index) = db.with_face_data(id, |data, index| (Arc::<[u8]>::from(data), index))?;
+    CachedFace::new(data, index, fallback)
+}
+
+/// Registers the fixture fonts shipped under `tests/fixtures` and returns
+/// the regular face. The bold face is registered alongside it.
+#[cfg(test)]
+pub(crate) fn fixture_font() -> Font {
+    static FIXTURE: OnceLock<Font> = OnceLock::new();
+    FIXTURE
+        .get_or_init(|| {
+            register_font(include_bytes!("../../tests/fixtures/FixtureSans-Bold.ttf")).unwrap();
+            register_font(include_bytes!("../../tests/fixtures/FixtureSans-Regular.ttf"))
+                .and_then(|id| id.font())
+                .unwrap()
+        })
+        .clone()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,5 +526,61 @@ mod tests {
     fn test_font_weight_values() {
         assert_eq!(FontWeight::Regular.value(), 400);
         assert_eq!(FontWeight::Bold.value(), 700);
+        assert_eq!(FontWeight::from_value(400), FontWeight::Regular);
+        assert_eq!(FontWeight::from_value(680), FontWeight::Bold);
+    }
+
+    #[test]
+    fn test_register_font() {
+        assert_eq!(register_font(b"not a font"), None);
+
+        let font = fixture_font();
+        assert_eq!(font.family(), "Fixture Sans");
+        assert_eq!(font.weight(), FontWeight::Regular);
+        assert!(cached_face(&FaceKey::from_font(&font)).is_some_and(|face| !face.fallback()));
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/FixtureSans-Bold.ttf");
+        let bold = Font::from_file(path).unwrap();
+        assert_eq!(bold.family(), "Fixture Sans");
+        assert_eq!(bold.weight(), FontWeight::Bold);
+    }
+
+    #[test]
+    fn test_invalidated_faces_free_their_data() {
+        let data: Arc<[u8]> = Arc::from(&include_bytes!("../../tests/fixtures/FixtureSans-Regular.ttf")[..]);
+        let face = Arc::new(CachedFace::new(data, 0, true).unwrap());
+        let data = Arc::downgrade(&face._data);
+        let key = FaceKey::new("Invalidated Fallback", 400, FontStyle::Normal);
+        face_cache().write().unwrap().insert(key.clone(), Some(face));
+
+        invalidate_fallback_faces();
+        assert!(!face_cache().read().unwrap().contains_key(&key));
+        assert!(data.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_glyph_outlines_kept_for_recent_sizes() {
+        let data: Arc<[u8]> = Arc::from(&include_bytes!("../../tests/fixtures/FixtureSans-Regular.ttf")[..]);
+        let face = CachedFace::new(data, 0, false).unwrap();
+        let glyph = face.face().glyph_index('A').unwrap().0;
+        let outline = |size: f32| face.glyph_path(glyph, size).unwrap();
+
+        // The same size hits the cache
+        let first = outline(10.0);
+        assert!(Arc::ptr_eq(&first, &outline(10.0)));
+
+        // Redrawing a size keeps it as others come and go
+        for i in 1..GLYPH_CACHE_SIZES * 2 {
+            outline(10.0 + i as f32);
+            assert!(Arc::ptr_eq(&first, &outline(10.0)));
+        }
+        assert_eq!(face.glyph_paths.lock().unwrap().len(), GLYPH_CACHE_SIZES);
+
+        // The least recently drawn size is outlined again
+        let dropped = outline(12.0);
+        for i in 0..GLYPH_CACHE_SIZES {
+            outline(100.0 + i as f32);
+        }
+        assert!(!Arc::ptr_eq(&dropped, &outline(12.0)));
     }
 }
@@ -9,6 +9,7 @@
 //! - [`canvas`]: 2D drawing context abstraction
 //! - [`font`]: Font handling and text metrics
 //! - [`theme`]: Theming and styling constants
+//! - [`animation`]: Animated value transitions
 
 pub mod point;
 pub mod rect;
@@ -18,6 +19,7 @@ pub mod canvas;
 pub mod font;
 pub mod theme;
 pub mod payload;
+pub mod animation;
 
 pub use point::{Point, Extent, Axis};
 pub use rect::Rect;
@@ -25,4 +27,4 @@ pub use color::Color;
 pub use circle::Circle;
 pub use canvas::Canvas;
 pub use font::Font;
-pub use theme::Theme;
+pub use theme::{Theme, ThemeColor};
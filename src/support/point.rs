@@ -70,6 +70,37 @@ impl Point {
         let dy = self.y - other.y;
         dx * dx + dy * dy
     }
+
+    /// Returns the length of the point taken as a vector from the origin.
+    #[inline]
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns a vector of length 1 in the same direction, or zero for the
+    /// zero vector.
+    #[inline]
+    pub fn normalized(self) -> Self {
+        let length = self.length();
+        if length > 0.0 {
+            self / length
+        } else {
+            Self::zero()
+        }
+    }
+
+    /// Returns the dot product with another vector.
+    #[inline]
+    pub fn dot(self, other: Point) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Interpolates linearly towards another point; `t` of 0 gives this
+    /// point and 1 gives `other`.
+    #[inline]
+    pub fn lerp(self, other: Point, t: f32) -> Self {
+        self + (other - self) * t
+    }
 }
 
 impl Index<Axis> for Point {
@@ -326,6 +357,19 @@ mod tests {
         assert_eq!(p1 * 2.0, Point::new(2.0, 4.0));
     }
 
+    #[test]
+    fn test_vector_math() {
+        let v = Point::new(3.0, 4.0);
+        assert_eq!(v.length(), 5.0);
+        assert_eq!(Point::zero().distance_to(v), 5.0);
+        assert_eq!(v.normalized(), Point::new(0.6, 0.8));
+        assert_eq!(Point::zero().normalized(), Point::zero());
+        assert_eq!(v.dot(Point::new(-4.0, 3.0)), 0.0);
+        assert_eq!(v.dot(Point::new(1.0, 2.0)), 11.0);
+        assert_eq!(Point::zero().lerp(v, 0.5), Point::new(1.5, 2.0));
+        assert_eq!(v.lerp(Point::zero(), 1.0), Point::zero());
+    }
+
     #[test]
     fn test_point_index() {
         let mut p = Point::new(1.0, 2.0);
@@ -187,6 +187,36 @@ impl Rect {
         self.inset(-x_expand, -y_expand)
     }
 
+    /// Grows the rectangle by `dx` on the left and right and `dy` on the
+    /// top and bottom.
+    ///
+    /// Unlike [`Rect::expand`], negative amounts that shrink the rectangle
+    /// past empty aren't collapsed to zero.
+    #[inline]
+    pub fn inflate(self, dx: f32, dy: f32) -> Self {
+        Self {
+            left: self.left - dx,
+            top: self.top - dy,
+            right: self.right + dx,
+            bottom: self.bottom + dy,
+        }
+    }
+
+    /// Insets each side by its own amount (shrinks it).
+    ///
+    /// Like [`Rect::inset`], an inset that leaves nothing gives a zero
+    /// rectangle.
+    #[inline]
+    pub fn inset_sides(self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        let r = Self {
+            left: self.left + left,
+            top: self.top + top,
+            right: self.right - right,
+            bottom: self.bottom - bottom,
+        };
+        if r.is_valid() { r } else { Self::zero() }
+    }
+
     /// Returns the area of the rectangle.
     #[inline]
     pub fn area(&self) -> f32 {
@@ -254,6 +284,17 @@ impl Rect {
             None
         }
     }
+
+    /// Returns the smallest rectangle enclosing both rectangles.
+    #[inline]
+    pub fn union(&self, other: Rect) -> Rect {
+        Rect {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
 }
 
 /// Returns true if two rectangles intersect.
@@ -279,12 +320,7 @@ pub fn intersection(a: &Rect, b: &Rect) -> Option<Rect> {
 
 /// Returns the union (bounding box) of two rectangles.
 pub fn union(a: &Rect, b: &Rect) -> Rect {
-    Rect {
-        left: a.left.min(b.left),
-        top: a.top.min(b.top),
-        right: a.right.max(b.right),
-        bottom: a.bottom.max(b.bottom),
-    }
+    a.union(*b)
 }
 
 /// Centers a rectangle within an enclosing rectangle.
@@ -395,4 +431,28 @@ mod tests {
         let b = Rect::new(200.0, 200.0, 300.0, 300.0);
         assert!(intersection(&a, &b).is_none());
     }
+
+    #[test]
+    fn test_union_of_separate_rects() {
+        let a = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let b = Rect::new(200.0, 200.0, 300.0, 300.0);
+        assert!(a.intersection(b).is_none());
+        assert_eq!(a.union(b), Rect::new(0.0, 0.0, 300.0, 300.0));
+        assert_eq!(union(&a, &b), a.union(b));
+
+        // Rects that only share an edge don't overlap either
+        let c = Rect::new(100.0, 0.0, 200.0, 100.0);
+        assert!(a.intersection(c).is_none());
+        assert_eq!(a.union(c), Rect::new(0.0, 0.0, 200.0, 100.0));
+    }
+
+    #[test]
+    fn test_inflate_and_inset_sides() {
+        let r = Rect::new(10.0, 20.0, 110.0, 70.0);
+        assert_eq!(r.inflate(5.0, 2.0), Rect::new(5.0, 18.0, 115.0, 72.0));
+        assert_eq!(r.inflate(-5.0, -2.0), Rect::new(15.0, 22.0, 105.0, 68.0));
+        assert_eq!(r.inset_sides(1.0, 2.0, 3.0, 4.0), Rect::new(11.0, 22.0, 107.0, 66.0));
+        assert_eq!(r.inset_sides(60.0, 0.0, 60.0, 0.0), Rect::zero());
+        assert_eq!(r.union(Rect::new(0.0, 30.0, 20.0, 40.0)), Rect::new(0.0, 20.0, 110.0, 70.0));
+    }
 }
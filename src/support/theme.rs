@@ -327,22 +327,151 @@ impl Theme {
             default_icon_size: 1.0,
         }
     }
+
+    /// Returns whether this is a dark theme, judged by its panel color.
+    pub fn is_dark(&self) -> bool {
+        self.panel_color.to_hsl().2 < 0.5
+    }
+
+    /// Sets the accent color, used for thumbs, gauges, active tabs and
+    /// selections, and recomputes the highlight colors derived from it.
+    pub fn with_accent(mut self, accent: Color) -> Self {
+        // Dark themes use a muted accent for highlights so text stays readable
+        let hilite = if self.is_dark() {
+            accent.desaturate(0.25).darken(0.15)
+        } else {
+            accent
+        };
+
+        self.slider_thumb_color = accent;
+        self.dial_indicator_color = accent;
+        self.dial_gauge_color = accent;
+        self.active_tab_color = accent;
+        self.frame_hilite_color = hilite;
+        self.menu_item_hilite_color = hilite;
+        self.tab_hilite_color = hilite;
+        self.text_box_hilite_color = accent.with_alpha(0.7);
+        self.selection_hilite_color = accent.with_alpha(if self.is_dark() { 0.4 } else { 0.3 });
+        self
+    }
+
+    /// Sets the panel color and recomputes the frame, button, menu and
+    /// background colors layered on top of it.
+    pub fn with_panel_color(mut self, panel: Color) -> Self {
+        // Raised surfaces are lighter in dark themes and darker in light ones
+        let raise = |amount: f32| {
+            if panel.to_hsl().2 < 0.5 {
+                panel.lighten(amount)
+            } else {
+                panel.darken(amount)
+            }
+        };
+
+        self.panel_color = panel;
+        self.frame_color = raise(0.03);
+        self.element_background_color = raise(0.03);
+        self.menu_background_color = raise(0.05);
+        self.default_button_color = raise(0.08);
+        self.icon_button_color = raise(0.08);
+        self.tooltip_color = raise(0.12);
+        self.inactive_tab_color = raise(0.03).with_alpha(0.8);
+        self
+    }
+
+    /// Sets the text color and recomputes the colors derived from it,
+    /// such as the caret, placeholder and slider label colors.
+    pub fn with_text_color(mut self, text: Color) -> Self {
+        self.label_font_color = text;
+        self.heading_font_color = text;
+        self.menu_font_color = text;
+        self.icon_color = text;
+        self.dial_color = text;
+        self.text_box_font_color = text;
+        self.text_box_caret_color = text;
+        self.text_box_idle_color = text.with_alpha(0.5);
+        self.slider_labels_color = text.with_alpha(0.8);
+        self
+    }
 }
 
-use std::sync::RwLock;
+use std::sync::{LazyLock, RwLock};
 
-static CURRENT_THEME: RwLock<Option<Theme>> = RwLock::new(None);
+static CURRENT_THEME: LazyLock<RwLock<Theme>> = LazyLock::new(|| RwLock::new(Theme::default()));
 
 /// Returns a reference to the current theme.
 pub fn get_theme() -> Theme {
-    CURRENT_THEME
-        .read()
-        .unwrap()
-        .clone()
-        .unwrap_or_else(Theme::default)
+    CURRENT_THEME.read().unwrap().clone()
 }
 
 /// Sets the current theme.
+///
+/// Elements look their [`ThemeColor`]s up when they draw, so the new theme
+/// shows on the next redraw.
 pub fn set_theme(theme: Theme) {
-    *CURRENT_THEME.write().unwrap() = Some(theme);
+    *CURRENT_THEME.write().unwrap() = theme;
+}
+
+/// An element color that either follows the current theme or is fixed.
+#[derive(Clone, Copy)]
+pub enum ThemeColor {
+    /// Picks the color from the current theme each time it is used.
+    Theme(fn(&Theme) -> Color),
+    /// A color set explicitly, which theme changes leave alone.
+    Fixed(Color),
+}
+
+impl ThemeColor {
+    /// Returns the color under the current theme.
+    pub fn get(&self) -> Color {
+        match self {
+            Self::Theme(pick) => pick(&CURRENT_THEME.read().unwrap()),
+            Self::Fixed(color) => *color,
+        }
+    }
+}
+
+impl From<Color> for ThemeColor {
+    fn from(color: Color) -> Self {
+        Self::Fixed(color)
+    }
+}
+
+impl std::fmt::Debug for ThemeColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Theme(_) => write!(f, "ThemeColor::Theme({:?})", self.get()),
+            Self::Fixed(color) => write!(f, "ThemeColor::Fixed({color:?})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_accent_recomputes_derived_colors() {
+        let accent = Color::from_rgb_u8(200, 80, 40);
+        let theme = Theme::dark().with_accent(accent);
+        assert_eq!(theme.slider_thumb_color, accent);
+        assert_eq!(theme.selection_hilite_color, accent.with_alpha(0.4));
+
+        // Highlights keep the accent's hue but are toned down on dark panels
+        let (hue, saturation, lightness) = theme.frame_hilite_color.to_hsl();
+        let (accent_hue, accent_saturation, accent_lightness) = accent.to_hsl();
+        assert!((hue - accent_hue).abs() < 0.5);
+        assert!(saturation < accent_saturation && lightness < accent_lightness);
+        assert_eq!(Theme::light().with_accent(accent).frame_hilite_color, accent);
+    }
+
+    #[test]
+    fn test_with_panel_color_raises_surfaces() {
+        let dark = Theme::light().with_panel_color(Color::from_rgb_u8(20, 20, 30));
+        assert!(dark.is_dark());
+        assert!(dark.default_button_color.to_hsl().2 > dark.panel_color.to_hsl().2);
+
+        let light = Theme::dark().with_panel_color(Color::from_rgb_u8(235, 235, 240));
+        assert!(!light.is_dark());
+        assert!(light.default_button_color.to_hsl().2 < light.panel_color.to_hsl().2);
+    }
 }
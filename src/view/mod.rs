@@ -3,11 +3,20 @@
 //! This module provides the View abstraction which represents a drawable surface
 //! and handles user input events.
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
 use crate::support::point::{Point, Extent};
 use crate::support::rect::Rect;
 use crate::support::canvas::Canvas;
-use crate::element::{ElementPtr, ViewLimits};
+use crate::element::{Element, ElementPtr, ViewLimits, FocusRequest};
+use crate::element::context::Context;
+
+pub mod testing;
+
+pub use testing::TestView;
 
 /// Mouse button kind.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -47,6 +56,67 @@ impl MouseButton {
     }
 }
 
+/// How close together two presses must be to count as a double-click.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoubleClickThreshold {
+    /// Maximum time between the presses.
+    pub time: Duration,
+    /// Maximum pointer travel between the presses, on either axis.
+    pub distance: f32,
+}
+
+impl Default for DoubleClickThreshold {
+    fn default() -> Self {
+        Self {
+            time: Duration::from_millis(400),
+            distance: 4.0,
+        }
+    }
+}
+
+static DOUBLE_CLICK_THRESHOLD: RwLock<Option<DoubleClickThreshold>> = RwLock::new(None);
+
+/// Returns the threshold the hosts use to count multi-clicks.
+pub fn double_click_threshold() -> DoubleClickThreshold {
+    DOUBLE_CLICK_THRESHOLD.read().unwrap().unwrap_or_default()
+}
+
+/// Sets the threshold the hosts use to count multi-clicks.
+pub fn set_double_click_threshold(threshold: DoubleClickThreshold) {
+    *DOUBLE_CLICK_THRESHOLD.write().unwrap() = Some(threshold);
+}
+
+/// Counts repeated presses of a button into multi-clicks, for the
+/// [`click_count`](MouseButton::click_count) of mouse events.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClickCounter {
+    /// Button, time and position of the last press
+    last_press: Option<(MouseButtonKind, Duration, Point)>,
+    count: i32,
+}
+
+impl ClickCounter {
+    /// Registers a press at `time`, read from any monotonic clock, and
+    /// returns its click count.
+    pub(crate) fn press(&mut self, button: MouseButtonKind, time: Duration, pos: Point) -> i32 {
+        let threshold = double_click_threshold();
+        let repeat = self.last_press.is_some_and(|(last, last_time, last_pos)| {
+            last == button
+                && time.checked_sub(last_time).is_some_and(|dt| dt <= threshold.time)
+                && (pos.x - last_pos.x).abs() <= threshold.distance
+                && (pos.y - last_pos.y).abs() <= threshold.distance
+        });
+        self.count = if repeat { self.count + 1 } else { 1 };
+        self.last_press = Some((button, time, pos));
+        self.count
+    }
+
+    /// Returns the click count of the last press, for its release.
+    pub(crate) fn count(&self) -> i32 {
+        self.count.max(1)
+    }
+}
+
 /// Key codes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum KeyCode {
@@ -117,7 +187,7 @@ pub enum CursorTracking {
 }
 
 /// Cursor type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum CursorType {
     #[default]
     Arrow,
@@ -136,6 +206,11 @@ pub struct DropInfo {
 }
 
 impl DropInfo {
+    /// Key of the dropped file paths in [`data`](Self::data), one per line.
+    pub const FILES: &'static str = "files";
+    /// Key of dropped text in [`data`](Self::data).
+    pub const TEXT: &'static str = "text";
+
     /// Creates a new drop info.
     pub fn new(pos: Point) -> Self {
         Self {
@@ -143,6 +218,31 @@ impl DropInfo {
             data: HashMap::new(),
         }
     }
+
+    /// Adds dropped file paths.
+    pub fn with_paths<P: AsRef<Path>>(mut self, paths: impl IntoIterator<Item = P>) -> Self {
+        let paths: Vec<String> = paths.into_iter().map(|p| p.as_ref().to_string_lossy().into_owned()).collect();
+        if !paths.is_empty() {
+            self.data.insert(Self::FILES.to_string(), paths.join("\n"));
+        }
+        self
+    }
+
+    /// Adds dropped text.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.data.insert(Self::TEXT.to_string(), text.into());
+        self
+    }
+
+    /// Returns the dropped file paths.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.data.get(Self::FILES).map_or_else(Vec::new, |paths| paths.lines().map(PathBuf::from).collect())
+    }
+
+    /// Returns the dropped text, if any.
+    pub fn text(&self) -> Option<&str> {
+        self.data.get(Self::TEXT).map(String::as_str)
+    }
 }
 
 /// Modifier key flags.
@@ -207,6 +307,10 @@ pub struct View {
     scale: f32,
     content: Option<ElementPtr>,
     is_focus: bool,
+    /// Area invalidated since the host last repainted.
+    dirty: Cell<Option<Rect>>,
+    /// Keyboard modifiers held during the event being handled.
+    modifiers: Cell<i32>,
 }
 
 impl View {
@@ -218,6 +322,8 @@ impl View {
             scale: 1.0,
             content: None,
             is_focus: false,
+            dirty: Cell::new(None),
+            modifiers: Cell::new(0),
         }
     }
 
@@ -236,6 +342,19 @@ impl View {
         self.bounds = Rect::from_origin_size(Point::zero(), size);
     }
 
+    /// Returns the keyboard modifiers held during the event being handled.
+    ///
+    /// Clicks and keys carry their modifiers with them; this is for events
+    /// that don't, such as scrolling.
+    pub fn modifiers(&self) -> i32 {
+        self.modifiers.get()
+    }
+
+    /// Sets the keyboard modifiers held during the event being handled.
+    pub fn set_modifiers(&self, modifiers: i32) {
+        self.modifiers.set(modifiers);
+    }
+
     /// Returns the current cursor position.
     pub fn cursor_pos(&self) -> Point {
         self.cursor_pos
@@ -274,12 +393,26 @@ impl View {
 
     /// Triggers a refresh of the entire view.
     pub fn refresh(&self) {
-        // Platform-specific implementation would trigger redraw
+        self.refresh_area(self.bounds);
     }
 
     /// Triggers a refresh of a specific area.
+    ///
+    /// Areas accumulate until the host collects them with
+    /// [`take_dirty`](Self::take_dirty) and repaints just that region.
     pub fn refresh_area(&self, area: Rect) {
-        // Platform-specific implementation would trigger partial redraw
+        let Some(area) = area.intersection(self.bounds) else {
+            return;
+        };
+        self.dirty.set(Some(match self.dirty.get() {
+            Some(dirty) => crate::support::rect::union(&dirty, &area),
+            None => area,
+        }));
+    }
+
+    /// Returns the area invalidated since the last call, if any.
+    pub fn take_dirty(&self) -> Option<Rect> {
+        self.dirty.take()
     }
 }
 
@@ -309,7 +442,12 @@ impl BaseView for View {
     }
 
     fn key(&mut self, k: KeyInfo) -> bool {
-        // Dispatch to content
+        // Tab and Shift-Tab walk the focus chain
+        if k.key == KeyCode::Tab && k.action != KeyAction::Release {
+            if let Some(content) = &self.content {
+                return move_focus(content.as_ref(), k.modifiers & modifiers::SHIFT == 0);
+            }
+        }
         false
     }
 
@@ -337,26 +475,176 @@ impl BaseView for View {
 
     fn poll(&mut self) {
         // Process any pending async tasks
+        if let Some(content) = &self.content {
+            if content.handle_poll() {
+                self.refresh();
+            }
+        }
+    }
+}
+
+/// Moves keyboard focus to the next (or previous) focusable element of
+/// `content` in tree order, wrapping around at either end.
+///
+/// Returns true if an element took focus.
+pub fn move_focus(content: &dyn Element, forward: bool) -> bool {
+    if content.handle_focus_next(forward) {
+        return true;
+    }
+
+    // Focus ran off the end (or nothing was focused): start over
+    let req = if forward { FocusRequest::FromTop } else { FocusRequest::FromBottom };
+    content.handle_begin_focus(req)
+}
+
+/// Sends a mouse button event to `content` as a window does.
+///
+/// A press first takes the focus from whatever holds it, so the element
+/// under the mouse can claim it while handling the click. Returns true if
+/// the event was handled.
+pub fn dispatch_click(content: &dyn Element, ctx: &Context, btn: MouseButton) -> bool {
+    refresh_on_focus_change(content, ctx, || {
+        if btn.down {
+            content.clear_focus();
+        }
+        content.handle_click(ctx, btn)
+    })
+}
+
+/// Sends a key event to `content` as a window does.
+///
+/// A Tab press the content doesn't handle moves the focus, backwards if
+/// Shift is held. Returns true if the event was handled.
+pub fn dispatch_key(content: &dyn Element, ctx: &Context, k: KeyInfo) -> bool {
+    refresh_on_focus_change(content, ctx, || {
+        content.handle_key(ctx, k)
+            || (k.key == KeyCode::Tab
+                && k.action != KeyAction::Release
+                && move_focus(content, k.modifiers & modifiers::SHIFT == 0))
+    })
+}
+
+/// Runs `dispatch` and refreshes the whole view if it moved the focus.
+///
+/// Elements gaining or losing the focus change how they look without
+/// refreshing themselves, and the event may have changed others that
+/// don't either, so repainting only the refreshed areas would leave them
+/// stale.
+fn refresh_on_focus_change<R>(content: &dyn Element, ctx: &Context, dispatch: impl FnOnce() -> R) -> R {
+    let before = content.focus_bounds(ctx);
+    let result = dispatch();
+    if content.focus_bounds(ctx) != before {
+        ctx.view.refresh();
     }
+    result
+}
+
+/// Draws `content` into a new pixmap of the given size, without a window.
+///
+/// The content fills the pixmap, as it would a window of that size, over
+/// a transparent background. Tests can check the pixels or save the
+/// pixmap as a PNG snapshot. Returns `None` if the size is empty.
+pub fn render_to_pixmap(content: &dyn Element, size: Extent) -> Option<tiny_skia::Pixmap> {
+    render_to_pixmap_scaled(content, size, 1.0)
+}
+
+/// Draws `content` as [`render_to_pixmap`] does, at `scale` pixels per
+/// point, as on a high-density display.
+///
+/// The content is laid out at `size` in points; the pixmap is `size *
+/// scale` pixels.
+pub fn render_to_pixmap_scaled(content: &dyn Element, size: Extent, scale: f32) -> Option<tiny_skia::Pixmap> {
+    let mut canvas = Canvas::new((size.x * scale).ceil() as u32, (size.y * scale).ceil() as u32)?;
+    canvas.scale(scale, scale);
+    let canvas = RefCell::new(canvas);
+    let mut view = View::new(size);
+    view.set_scale(scale);
+    content.draw(&Context::new(&view, &canvas, Rect::from_origin_size(Point::zero(), size)));
+    Some(canvas.into_inner().into_pixmap())
+}
+
+/// Returns the cursor shape to show with the mouse over `p`.
+///
+/// The topmost element under `p` picks the shape; the arrow is shown
+/// where no element asks for one.
+pub fn cursor_type_at(content: &dyn Element, ctx: &Context, p: Point) -> CursorType {
+    content
+        .hit_test(ctx, p, true, false)
+        .and_then(|hit| {
+            let bounds = content.bounds_of_element(ctx, hit)?;
+            hit.cursor_type(&ctx.with_bounds(bounds), p)
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "macos"))]
+thread_local! {
+    /// Clipboard for hosts without a system one. Events are handled on the
+    /// UI thread, so cut and paste within the app share it.
+    static LOCAL_CLIPBOARD: RefCell<String> = const { RefCell::new(String::new()) };
 }
 
 /// Gets the clipboard contents.
 pub fn clipboard() -> String {
-    // Platform-specific implementation
-    String::new()
+    #[cfg(target_os = "macos")]
+    {
+        crate::host::get_clipboard()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        LOCAL_CLIPBOARD.with(|clipboard| clipboard.borrow().clone())
+    }
 }
 
 /// Sets the clipboard contents.
 pub fn set_clipboard(text: &str) {
-    // Platform-specific implementation
+    #[cfg(target_os = "macos")]
+    crate::host::set_clipboard(text);
+    #[cfg(not(target_os = "macos"))]
+    LOCAL_CLIPBOARD.with(|clipboard| *clipboard.borrow_mut() = text.to_string());
 }
 
 /// Sets the cursor type.
 pub fn set_cursor(cursor: CursorType) {
-    // Platform-specific implementation
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    crate::host::set_cursor(cursor);
 }
 
 /// Returns the scroll direction preference (1.0 or -1.0).
 pub fn scroll_direction() -> Point {
     Point::new(1.0, 1.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::decoration::background;
+    use crate::element::margin::margin;
+    use crate::support::color::Color;
+
+    #[test]
+    fn test_render_to_pixmap() {
+        let content = margin(10.0, background(Color::new(1.0, 0.0, 0.0, 1.0), 0.0, crate::element::empty()));
+        let pixmap = render_to_pixmap(&content, Extent::new(40.0, 30.0)).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (40, 30));
+
+        // The margin stays transparent around the filled background
+        let inside = pixmap.pixel(20, 15).unwrap();
+        assert_eq!((inside.red(), inside.green(), inside.alpha()), (255, 0, 255));
+        assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+        assert!(render_to_pixmap(&content, Extent::new(0.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn test_render_to_pixmap_scaled() {
+        let content = margin(10.0, background(Color::new(1.0, 0.0, 0.0, 1.0), 0.0, crate::element::empty()));
+        let pixmap = render_to_pixmap_scaled(&content, Extent::new(40.0, 30.0), 2.0).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (80, 60));
+
+        // The layout is in points, so the margin is 20 pixels wide
+        assert_eq!(pixmap.pixel(19, 30).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(20, 30).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(59, 30).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(60, 30).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Synthetic input for testing elements without a window.
+//!
+//! A [`TestView`] holds an element at a fixed size and feeds it events the
+//! way a host window would, so tests can check that interactions have the
+//! intended effect.
+
+use std::cell::RefCell;
+use crate::support::point::{Point, Extent};
+use crate::support::rect::Rect;
+use crate::support::canvas::Canvas;
+use crate::element::{ElementPtr, FocusRequest};
+use crate::element::context::{BasicContext, Context};
+use super::{
+    View, ViewLimits, MouseButton, DropInfo, CursorTracking, MouseButtonKind, KeyCode, KeyAction, KeyInfo, TextInfo, CursorType,
+    render_to_pixmap, cursor_type_at, dispatch_click, dispatch_key,
+};
+
+/// An element driven by synthetic events.
+///
+/// Events reach the content as they would from a window of the given
+/// size: clicks and keys go to the root, which passes them down.
+pub struct TestView {
+    content: ElementPtr,
+    size: Extent,
+    view: View,
+    /// Events don't draw, so a single pixel is enough to back the context
+    canvas: RefCell<Canvas>,
+}
+
+impl TestView {
+    /// Creates a test view showing `content` at the given size.
+    pub fn new(content: ElementPtr, size: Extent) -> Self {
+        Self {
+            content,
+            size,
+            view: View::new(size),
+            canvas: RefCell::new(Canvas::new(1, 1).expect("1x1 canvas")),
+        }
+    }
+
+    /// Returns the content.
+    pub fn content(&self) -> &ElementPtr {
+        &self.content
+    }
+
+    /// Calls `f` with a context spanning the view, for checks the event
+    /// methods don't cover.
+    pub fn with_context<R>(&self, f: impl FnOnce(&Context) -> R) -> R {
+        f(&Context::new(&self.view, &self.canvas, Rect::from_origin_size(Point::zero(), self.size)))
+    }
+
+    /// Returns the layout limits of the content.
+    pub fn limits(&self) -> ViewLimits {
+        self.content.limits(&BasicContext::new(&self.view, &self.canvas))
+    }
+
+    /// Returns the area the content refreshed since the last call, if any.
+    pub fn take_dirty(&self) -> Option<Rect> {
+        self.view.take_dirty()
+    }
+
+    /// Sends a mouse button event, clearing the focus on a press as a
+    /// window does. Returns true if it was handled.
+    pub fn mouse(&self, btn: MouseButton) -> bool {
+        self.with_context(|ctx| dispatch_click(self.content.as_ref(), ctx, btn))
+    }
+
+    /// Presses the left button at `p`. Returns true if handled.
+    pub fn press(&self, p: Point) -> bool {
+        self.mouse(MouseButton::new(true, MouseButtonKind::Left, p))
+    }
+
+    /// Moves the mouse to `p` with the left button held.
+    pub fn drag_to(&self, p: Point) {
+        self.with_context(|ctx| self.content.handle_drag(ctx, MouseButton::new(true, MouseButtonKind::Left, p)));
+    }
+
+    /// Releases the left button at `p`. Returns true if handled.
+    pub fn release(&self, p: Point) -> bool {
+        self.mouse(MouseButton::new(false, MouseButtonKind::Left, p))
+    }
+
+    /// Presses and releases the left button at `p`.
+    ///
+    /// Returns true if either event was handled.
+    pub fn click_at(&self, p: Point) -> bool {
+        let down = self.press(p);
+        let up = self.release(p);
+        down || up
+    }
+
+    /// Clicks the left button twice at `p`, counting the second press as a
+    /// double-click.
+    pub fn double_click_at(&self, p: Point) -> bool {
+        let first = self.click_at(p);
+        let mut btn = MouseButton::new(true, MouseButtonKind::Left, p);
+        btn.click_count = 2;
+        let down = self.mouse(btn);
+        btn.down = false;
+        let up = self.mouse(btn);
+        first || down || up
+    }
+
+    /// Presses the left button at `from`, drags it to `to`, and releases it.
+    pub fn drag(&self, from: Point, to: Point) {
+        self.press(from);
+        self.drag_to(to);
+        self.release(to);
+    }
+
+    /// Scrolls by `dir` with the mouse at `p`. Returns true if handled.
+    pub fn scroll(&self, dir: Point, p: Point) -> bool {
+        self.scroll_with_modifiers(dir, p, 0)
+    }
+
+    /// Scrolls by `dir` with the mouse at `p` while holding `mods`.
+    pub fn scroll_with_modifiers(&self, dir: Point, p: Point, mods: i32) -> bool {
+        self.view.set_modifiers(mods);
+        self.with_context(|ctx| self.content.handle_scroll(ctx, dir, p))
+    }
+
+    /// Pinches by `scale_delta` around `center`. Returns true if handled.
+    pub fn magnify(&self, scale_delta: f32, center: Point) -> bool {
+        self.with_context(|ctx| self.content.handle_magnify(ctx, scale_delta, center))
+    }
+
+    /// Drags `info` over the content. Elements under it highlight
+    /// themselves while the drag is over them.
+    pub fn track_drop(&self, info: &DropInfo, status: CursorTracking) {
+        self.with_context(|ctx| self.content.handle_track_drop(ctx, info, status));
+    }
+
+    /// Drops `info` on the content. Returns true if an element took it.
+    pub fn perform_drop(&self, info: &DropInfo) -> bool {
+        self.with_context(|ctx| self.content.handle_drop(ctx, info))
+    }
+
+    /// Presses and releases `key` with the given modifiers.
+    ///
+    /// An unhandled Tab moves the focus, as in a window. Returns true if
+    /// the press was handled.
+    pub fn key(&self, key: KeyCode, mods: i32) -> bool {
+        let info = |action| KeyInfo { key, action, modifiers: mods };
+        let handled = self.with_context(|ctx| dispatch_key(self.content.as_ref(), ctx, info(KeyAction::Press)));
+        self.with_context(|ctx| dispatch_key(self.content.as_ref(), ctx, info(KeyAction::Release)));
+        handled
+    }
+
+    /// Types `text`, one character at a time, into the focused element.
+    ///
+    /// Returns true if every character was handled.
+    pub fn type_text(&self, text: &str) -> bool {
+        text.chars().fold(true, |handled, codepoint| {
+            let info = TextInfo { codepoint, modifiers: 0 };
+            self.with_context(|ctx| self.content.handle_text(ctx, info)) && handled
+        })
+    }
+
+    /// Moves the focus to the first element that takes it.
+    pub fn focus_first(&self) -> bool {
+        self.content.handle_begin_focus(FocusRequest::FromTop)
+    }
+
+    /// Moves the mouse to `p` with no button held.
+    ///
+    /// Returns true if the content responded.
+    pub fn hover(&self, p: Point) -> bool {
+        self.with_context(|ctx| self.content.handle_cursor(ctx, p, CursorTracking::Hovering))
+    }
+
+    /// Moves the mouse out of the view from `p`.
+    pub fn leave(&self, p: Point) -> bool {
+        self.with_context(|ctx| self.content.handle_cursor(ctx, p, CursorTracking::Leaving))
+    }
+
+    /// Returns the cursor shape shown with the mouse over `p`.
+    pub fn cursor_at(&self, p: Point) -> CursorType {
+        self.with_context(|ctx| cursor_type_at(self.content.as_ref(), ctx, p))
+    }
+
+    /// Runs one idle tick. Returns true if the content wants a redraw.
+    pub fn poll(&self) -> bool {
+        self.content.handle_poll()
+    }
+
+    /// Draws the content into a new pixmap of the view's size.
+    pub fn render(&self) -> Option<tiny_skia::Pixmap> {
+        render_to_pixmap(self.content.as_ref(), self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::element::share;
+    use crate::element::button::button;
+    use crate::element::text_box::{text_box, TextBox};
+    use crate::element::tile::VTile;
+
+    #[test]
+    fn test_click_and_type() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let sink = clicks.clone();
+        let field = share(text_box());
+        let tile = VTile::from_vec(vec![
+            share(button("OK").on_click(move || { sink.fetch_add(1, Ordering::SeqCst); })),
+            field.clone(),
+        ]);
+        let view = TestView::new(share(tile), Extent::new(200.0, 60.0));
+
+        view.click_at(Point::new(100.0, 10.0));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+
+        // Clicking the text box focuses it, so typing goes there
+        view.click_at(Point::new(100.0, 50.0));
+        assert!(field.has_focus());
+        assert!(view.type_text("hi"));
+        view.key(KeyCode::Backspace, 0);
+        assert!(view.type_text("ey"));
+        assert_eq!(field.as_any().downcast_ref::<TextBox>().unwrap().get_text(), "hey");
+
+        // Clicking elsewhere takes the focus away
+        view.click_at(Point::new(100.0, 10.0));
+        assert!(!field.has_focus());
+        assert!(!view.type_text("!"));
+        assert_eq!(view.cursor_at(Point::new(100.0, 50.0)), CursorType::IBeam);
+        assert!(view.render().is_some());
+    }
+
+    #[test]
+    fn test_focus_change_repaints_view() {
+        use crate::element::segmented::segmented_control;
+        use crate::element::size::fixed_size;
+
+        let field = share(fixed_size(200.0, 30.0, text_box()));
+        let control = share(fixed_size(200.0, 30.0, segmented_control(["A", "B"])));
+        let view = TestView::new(share(VTile::from_vec(vec![field.clone(), control])), Extent::new(200.0, 60.0));
+
+        // With nothing focused, only the control's refresh is repainted
+        view.click_at(Point::new(150.0, 45.0));
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 30.0, 200.0, 60.0)));
+
+        view.click_at(Point::new(100.0, 15.0));
+        assert!(field.has_focus());
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 0.0, 200.0, 60.0)));
+
+        // The field losing the focus doesn't refresh it, so the control
+        // refreshing its own area mustn't limit the repaint to that
+        view.click_at(Point::new(50.0, 45.0));
+        assert!(!field.has_focus());
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 0.0, 200.0, 60.0)));
+
+        // Tabbing back in repaints too
+        assert!(view.key(KeyCode::Tab, 0));
+        assert!(field.has_focus());
+        assert_eq!(view.take_dirty(), Some(Rect::new(0.0, 0.0, 200.0, 60.0)));
+    }
+}
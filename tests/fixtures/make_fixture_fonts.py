@@ -0,0 +1,161 @@
+#!/usr/bin/env python3
+"""Writes the fixture fonts used by the text rendering tests.
+
+Fixture Sans covers printable ASCII, e-acute and a combining acute accent
+with one solid box per glyph, so tests
+can tell where text was inked without depending on the fonts installed on
+the machine. The bold face has wider boxes and advances. Run from this
+directory to regenerate FixtureSans-Regular.ttf and FixtureSans-Bold.ttf.
+"""
+
+import struct
+
+UNITS_PER_EM = 1000
+ASCENT = 800
+DESCENT = -200
+CAP_HEIGHT = 700
+FIRST_CHAR = 0x20
+LAST_CHAR = 0x7E
+EXTRA_CHARS = "\u00e9\u0301"
+
+NARROW = "Iijl.,:;!|'"
+WIDE = "MWmw@"
+
+
+def advance(char, bold):
+    if char == "\u0301":
+        return 0
+    width = 300 if char in NARROW else 900 if char in WIDE else 600
+    return width + (100 if bold else 0)
+
+
+def box_glyph(left, right, top):
+    """A simple glyph with one rectangular contour, wound clockwise."""
+    points = [(left, 0), (left, top), (right, top), (right, 0)]
+    data = struct.pack(">hhhhh", 1, left, 0, right, top)
+    data += struct.pack(">HH", len(points) - 1, 0)
+    data += bytes([0x01] * len(points))
+    previous = (0, 0)
+    xs, ys = b"", b""
+    for x, y in points:
+        xs += struct.pack(">h", x - previous[0])
+        ys += struct.pack(">h", y - previous[1])
+        previous = (x, y)
+    return data + xs + ys
+
+
+def checksum(data):
+    data += b"\0" * (-len(data) % 4)
+    return sum(struct.unpack(">%dI" % (len(data) // 4), data)) & 0xFFFFFFFF
+
+
+def name_table(family, subfamily):
+    names = {
+        1: family,
+        2: subfamily,
+        4: "%s %s" % (family, subfamily),
+        6: ("%s-%s" % (family, subfamily)).replace(" ", ""),
+    }
+    records, strings = b"", b""
+    for name_id, text in names.items():
+        encoded = text.encode("utf-16-be")
+        records += struct.pack(">HHHHHH", 3, 1, 0x409, name_id, len(encoded), len(strings))
+        strings += encoded
+    return struct.pack(">HHH", 0, len(names), 6 + len(records)) + records + strings
+
+
+def cmap_table(chars):
+    # A format 4 segment per run of consecutive characters, glyphs from 1 on
+    starts, ends, deltas = [], [], []
+    for glyph, char in enumerate(map(ord, chars), 1):
+        if ends and char == ends[-1] + 1:
+            ends[-1] = char
+        else:
+            starts.append(char)
+            ends.append(char)
+            deltas.append((glyph - char) & 0xFFFF)
+    starts.append(0xFFFF)
+    ends.append(0xFFFF)
+    deltas.append(1)
+    seg_count = len(ends)
+    entry_selector = seg_count.bit_length() - 1
+    search_range = 2 << entry_selector
+    subtable = struct.pack(">HHH", 4, 16 + seg_count * 8, 0)
+    subtable += struct.pack(">HHHH", seg_count * 2, search_range, entry_selector, seg_count * 2 - search_range)
+    subtable += struct.pack(">%dH" % seg_count, *ends) + b"\0\0"
+    subtable += struct.pack(">%dH" % seg_count, *starts)
+    subtable += struct.pack(">%dH" % seg_count, *deltas)
+    subtable += struct.pack(">%dH" % seg_count, *([0] * seg_count))
+    return struct.pack(">HHHHI", 0, 1, 3, 1, 12) + subtable
+
+
+def build(bold):
+    chars = [chr(c) for c in range(FIRST_CHAR, LAST_CHAR + 1)] + list(EXTRA_CHARS)
+    metrics = [(500, 50)]
+    glyphs = [box_glyph(50, 450, CAP_HEIGHT)]
+    for char in chars:
+        width = advance(char, bold)
+        if char == " ":
+            metrics.append((width, 0))
+            glyphs.append(b"")
+        elif char == "\u0301":
+            # The accent hangs over the preceding letter
+            metrics.append((0, -400))
+            glyphs.append(box_glyph(-400, -200, CAP_HEIGHT + 200))
+        else:
+            inset = 50 if char in NARROW else 100
+            metrics.append((width, inset))
+            glyphs.append(box_glyph(inset, width - inset, CAP_HEIGHT))
+    min_lsb = min(lsb for _, lsb in metrics)
+
+    glyf, offsets = b"", []
+    for glyph in glyphs:
+        offsets.append(len(glyf))
+        glyf += glyph + b"\0" * (-len(glyph) % 4)
+    offsets.append(len(glyf))
+    num_glyphs = len(glyphs)
+    max_advance = max(width for width, _ in metrics)
+
+    tables = {}
+    tables[b"head"] = struct.pack(
+        ">IIIIHHqqhhhhHHhhh", 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0x000B, UNITS_PER_EM,
+        0, 0, min_lsb, 0, max_advance, CAP_HEIGHT + 200, 1 if bold else 0, 8, 2, 1, 0)
+    tables[b"hhea"] = struct.pack(
+        ">IhhhHhhhhhh4hhH", 0x00010000, ASCENT, DESCENT, 0, max_advance, min_lsb, 0, max_advance,
+        1, 0, 0, 0, 0, 0, 0, 0, num_glyphs)
+    tables[b"maxp"] = struct.pack(">IHHHHHHHHHHHHHH", 0x00010000, num_glyphs, 4, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0)
+    tables[b"hmtx"] = b"".join(struct.pack(">Hh", width, lsb) for width, lsb in metrics)
+    tables[b"loca"] = struct.pack(">%dI" % len(offsets), *offsets)
+    tables[b"glyf"] = glyf
+    tables[b"cmap"] = cmap_table(chars)
+    tables[b"name"] = name_table("Fixture Sans", "Bold" if bold else "Regular")
+    tables[b"OS/2"] = struct.pack(
+        ">HhHHH8hhhh10s4I4sHHHhhhHHIIhhHHH", 4, 600, 700 if bold else 400, 5, 0,
+        *([0] * 8), 50, 250, 0, bytes(10), 1, 0, 0, 0, b"MKFX", 0x20 if bold else 0x40,
+        FIRST_CHAR, LAST_CHAR, ASCENT, DESCENT, 0, ASCENT, -DESCENT, 1, 0, 500, CAP_HEIGHT,
+        0, 0x20, 1)
+    tables[b"post"] = struct.pack(">IIhhIIIII", 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+    tags = sorted(tables)
+    entry_selector = len(tags).bit_length() - 1
+    search_range = 16 << entry_selector
+    header = struct.pack(">IHHHH", 0x00010000, len(tags), search_range, entry_selector,
+                         len(tags) * 16 - search_range)
+    offset = len(header) + 16 * len(tags)
+    directory, body = b"", b""
+    for tag in tags:
+        data = tables[tag]
+        directory += struct.pack(">4sIII", tag, checksum(data), offset + len(body), len(data))
+        body += data + b"\0" * (-len(data) % 4)
+    font = bytearray(header + directory + body)
+
+    # The head checksum adjustment makes the whole file sum to a magic number
+    head_offset = offset + sum(len(tables[t]) + (-len(tables[t]) % 4) for t in tags[:tags.index(b"head")])
+    struct.pack_into(">I", font, head_offset + 8, (0xB1B0AFBA - checksum(bytes(font))) & 0xFFFFFFFF)
+    return bytes(font)
+
+
+if __name__ == "__main__":
+    for bold, name in [(False, "FixtureSans-Regular.ttf"), (True, "FixtureSans-Bold.ttf")]:
+        with open(name, "wb") as f:
+            f.write(build(bold))